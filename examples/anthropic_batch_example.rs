@@ -0,0 +1,52 @@
+// Import required modules from the LLM library for Anthropic integration
+use std::time::Duration;
+
+use llm::{
+    builder::LLMBuilder,               // Builder pattern components
+    chat::{BatchRequest, ChatMessage}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Anthropic API key from environment variable or use test key as fallback
+    let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap_or("anthro-key".into());
+
+    // Build the Anthropic client itself, since message batches are specific to Anthropic
+    let anthropic = LLMBuilder::new()
+        .api_key(api_key) // Set the API key
+        .model("claude-3-5-haiku-20241022") // Use a small model
+        .max_tokens(50) // Keep the replies short
+        .system("Classify the sentiment of the review as positive, negative or neutral.")
+        .build_anthropic()?;
+
+    // One request per review, identified by its position
+    let reviews = ["Great product!", "Broke after a day.", "It's fine."];
+    let requests: Vec<BatchRequest> = reviews
+        .iter()
+        .enumerate()
+        .map(|(i, review)| {
+            BatchRequest::new(
+                format!("review-{}", i),
+                vec![ChatMessage::user().content(*review).build()],
+            )
+        })
+        .collect();
+
+    // Submit the requests and wait for the batch, which takes up to 24 hours
+    let batch = anthropic.create_batch(&requests).await?;
+    println!("Created batch {} ({:?})", batch.id, batch.processing_status);
+    let batch = anthropic
+        .wait_for_batch(&batch.id, Duration::from_secs(60))
+        .await?;
+    println!("Batch ended: {:?}", batch.request_counts);
+
+    // Print the replies, matched to the reviews by their custom IDs
+    for result in anthropic.batch_results(&batch).await? {
+        match result.response {
+            Ok(response) => println!("{}: {}", result.custom_id, response),
+            Err(e) => eprintln!("{} failed: {}", result.custom_id, e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// Import required modules from the LLM library for Anthropic integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{ChatMessage, ContentPart, ImageMime, Tool}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Anthropic API key from environment variable or use test key as fallback
+    let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap_or("anthro-key".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Anthropic) // Use Anthropic (Claude) as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("claude-sonnet-4-20250514") // Use a model supporting computer use
+        .max_tokens(1024) // Limit response length
+        .build()
+        .expect("Failed to build LLM");
+
+    // Tools defined by Anthropic: a 1024x768 desktop and a shell
+    let tools = vec![Tool::computer(1024, 768, None), Tool::bash()];
+
+    let mut messages = vec![ChatMessage::user()
+        .content("What is shown on my screen?")
+        .build()];
+
+    // Answer the tool calls until the model replies with text only
+    for _ in 0..5 {
+        let response = llm.chat_with_tools(&messages, Some(&tools)).await?;
+        let Some(tool_calls) = response.tool_calls() else {
+            println!("Final answer:\n{}", response);
+            return Ok(());
+        };
+
+        messages.push(
+            ChatMessage::assistant()
+                .tool_use(tool_calls.clone())
+                .build(),
+        );
+        for call in &tool_calls {
+            println!(
+                "Calling {} with {}",
+                call.function.name, call.function.arguments
+            );
+            let result = ChatMessage::user();
+            let result = match (call.function.name.as_str(), std::fs::read("screenshot.png")) {
+                // Screenshots are sent back as images, read here from a file
+                ("computer", Ok(screenshot)) if call.function.arguments.contains("screenshot") => {
+                    result.tool_result_parts(
+                        &call.id,
+                        &call.function.name,
+                        vec![ContentPart::Image((ImageMime::PNG, screenshot))],
+                    )
+                }
+                // Anything else is reported as a failure the model can react to
+                _ => result.tool_error(
+                    &call.id,
+                    &call.function.name,
+                    "This action is not available in this example",
+                ),
+            };
+            messages.push(result.build());
+        }
+    }
+
+    Ok(())
+}
@@ -1,9 +1,7 @@
-use std::fs;
-
 // Import required modules from the LLM library for Anthropic integration
 use llm::{
     builder::{LLMBackend, LLMBuilder}, // Builder pattern components
-    chat::{ChatMessage, ImageMime},    // Chat-related structures
+    chat::ChatMessage,                 // Chat-related structures
 };
 
 #[tokio::main]
@@ -23,14 +21,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build()
         .expect("Failed to build LLM (Anthropic)");
 
-    let content = fs::read("./examples/image001.jpg").expect("The dummy.pdf file should exist");
-
     // Prepare conversation history with example message about Rust concurrency
     let messages = vec![
         ChatMessage::user()
             .content("What is in this image?")
             .build(),
-        ChatMessage::user().image(ImageMime::JPEG, content).build(),
+        // Read the image and detect its format from the file contents
+        ChatMessage::user()
+            .image_from_path("./examples/image001.jpg")?
+            .build(),
     ];
 
     // Send chat request and handle the response
@@ -0,0 +1,45 @@
+use std::fs;
+
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{AudioMime, ChatMessage},    // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Path of a WAV recording, passed as the first argument
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: audio_input_example <file.wav>");
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-audio-preview") // Use a model accepting audio input
+        .max_tokens(512) // Limit response length
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    let audio = fs::read(path)?;
+
+    // Ask a question about the recording
+    let messages = vec![
+        ChatMessage::user()
+            .content("Summarize this recording.")
+            .build(),
+        ChatMessage::user().audio(AudioMime::WAV, audio).build(),
+    ];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("OpenAI chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
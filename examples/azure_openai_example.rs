@@ -0,0 +1,38 @@
+// Import required modules from the LLM library for Azure OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get the resource endpoint and API key from environment variables
+    let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT")
+        .unwrap_or("https://my-resource.openai.azure.com".into());
+    let api_key = std::env::var("AZURE_OPENAI_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::AzureOpenAI) // Use Azure OpenAI as the LLM provider
+        .base_url(endpoint) // Endpoint of the Azure OpenAI resource
+        .api_key(api_key) // Set the resource's API key
+        .deployment("gpt-4o-mini") // Deployment serving the requests
+        .api_version("2024-10-21") // Version of the Azure OpenAI API
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .build()
+        .expect("Failed to build LLM (Azure OpenAI)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Tell me that you are running on Azure")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+use serde_json::json;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client with a tool and the handler that executes it
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with tool support
+        .max_tokens(512) // Limit response length
+        .function_with_handler(
+            "get_time",
+            "Get the current time in a timezone",
+            json!({
+                "type": "object",
+                "properties": {
+                    "timezone": { "type": "string", "description": "IANA timezone name" }
+                },
+                "required": ["timezone"]
+            }),
+            |args| async move {
+                let timezone = args["timezone"].as_str().unwrap_or("UTC");
+                Ok(format!(
+                    r#"{{"timezone": "{}", "time": "14:05"}}"#,
+                    timezone
+                ))
+            },
+        )
+        .build()
+        .expect("Failed to build LLM");
+
+    // The provider advertises the registered tools
+    for tool in llm.tools().unwrap_or_default() {
+        println!("Tool: {}", tool.function.name);
+    }
+
+    let messages = vec![ChatMessage::user()
+        .content("What time is it in Tokyo?")
+        .build()];
+
+    // Tool calls are executed automatically before the final answer is returned
+    match llm.chat(&messages).await {
+        Ok(response) => println!("Final answer:\n{}", response),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+// Import required modules from the LLM library for OpenAI integration
+use std::time::Duration;
+
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{CancellationToken, ChatMessage, ChatOptions}, // Chat-related structures
+    error::LLMError,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o") // Use GPT-4o model
+        .max_tokens(2048) // Allow a long response
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    let messages = vec![ChatMessage::user()
+        .content("Write a very long essay about the history of computing")
+        .build()];
+
+    // Cancel the request from another task, as a UI "stop" button would
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        canceller.cancel();
+    });
+
+    let options = ChatOptions::new().cancel_token(token);
+    match llm.chat_with_options(&messages, None, &options).await {
+        Ok(response) => println!("Response:\n{}", response),
+        Err(LLMError::Cancelled) => println!("Request cancelled"),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! Example demonstrating a prompt chain whose final step streams its output
+//!
+//! The first step runs buffered because its answer feeds the second step's template;
+//! the second step's tokens are printed as they arrive.
+
+use std::io::Write;
+
+use llm::{
+    builder::{LLMBackend, LLMBuilder},
+    chain::{ChainStepBuilder, ChainStepMode, PromptChain},
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize the LLM with OpenAI backend and configuration
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI)
+        .api_key(std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into()))
+        .model("gpt-4o")
+        .max_tokens(500)
+        .temperature(0.7)
+        .build()?;
+
+    let chain_result = PromptChain::new(&*llm)
+        // Step 1: Choose a topic (buffered)
+        .step(
+            ChainStepBuilder::new("topic", "Suggest an interesting Rust feature to explain. Answer with a few words only.", ChainStepMode::Chat)
+                .build()
+        )
+        // Step 2: Explain it (streamed)
+        .step(
+            ChainStepBuilder::new("explanation", "Explain {{topic}} with a short code example.", ChainStepMode::Chat)
+                .build()
+        )
+        .run_stream_with(|chunk| {
+            print!("{}", chunk);
+            let _ = std::io::stdout().flush();
+        })
+        .await?;
+
+    println!("\n\nChain results: {:?}", chain_result);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+// Import required modules from the LLM library for Cohere integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Cohere API key from environment variable or use test key as fallback
+    let api_key = std::env::var("COHERE_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Cohere) // Use Cohere as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("command-a-03-2025") // Use Command A
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .system("Answer concisely") // Sent as Cohere's preamble
+        .connector("web-search") // Ground the replies with web search
+        .embedding_input_type("search_query") // Embed texts as search queries
+        .build()
+        .expect("Failed to build LLM (Cohere)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("What is the tallest building in the world?")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    // Embed texts with embed-english-v3.0
+    match llm
+        .embed(vec!["Hello".to_string(), "World".to_string()])
+        .await
+    {
+        Ok(embeddings) => println!("Embedding dimensions: {}", embeddings[0].len()),
+        Err(e) => eprintln!("Embedding error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::Conversation,                // Conversation with managed history
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use GPT-4o mini
+        .max_tokens(256) // Limit response length
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    // The conversation records every turn, so each request carries the full history
+    let mut conversation = Conversation::new(llm);
+    for question in [
+        "Suggest a name for a grey cat.",
+        "Why did you pick it?",
+        "Give me two more in the same style.",
+    ] {
+        println!("> {}", question);
+        match conversation.say(question).await {
+            Ok(reply) => println!("{}\n", reply),
+            Err(e) => eprintln!("Chat error: {}", e),
+        }
+    }
+
+    println!("History holds {} messages", conversation.messages().len());
+
+    Ok(())
+}
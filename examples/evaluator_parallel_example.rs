@@ -45,6 +45,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             role: ChatRole::User,
             message_type: Default::default(),
             content: "Explique-moi la théorie de la relativité d'Einstein".to_string(),
+            metadata: Default::default(),
         }
     ];
     
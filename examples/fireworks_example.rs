@@ -0,0 +1,44 @@
+// Import required modules from the LLM library for Fireworks integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Fireworks API key from environment variable or use test key as fallback
+    let api_key = std::env::var("FIREWORKS_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Fireworks) // Use Fireworks as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("accounts/fireworks/models/llama-v3p1-70b-instruct") // Use Llama 3.1 70B
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .grammar("root ::= \"yes\" | \"no\"") // Constrain the reply with a GBNF grammar
+        .build()
+        .expect("Failed to build LLM (Fireworks)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Is Rust memory safe? Answer yes or no")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    // Embed texts with nomic-embed-text-v1.5
+    match llm
+        .embed(vec!["Hello".to_string(), "Bonjour".to_string()])
+        .await
+    {
+        Ok(embeddings) => println!("Embedding dimensions: {}", embeddings[0].len()),
+        Err(e) => eprintln!("Embedding error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// Import required modules from the LLM library for Google Gemini integration
+use std::time::Duration;
+
+use llm::{
+    backends::google::GoogleFileState, // Processing state of uploaded files
+    builder::LLMBuilder,               // Builder pattern components
+    chat::{ChatMessage, ChatProvider, ContentPart}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Google API key from environment variable or use test key as fallback
+    let api_key = std::env::var("GOOGLE_API_KEY").unwrap_or("google-key".into());
+
+    // Build the Google client itself, since the Files API is specific to Google
+    let google = LLMBuilder::new()
+        .api_key(api_key) // Set the API key
+        .model("gemini-2.0-flash") // Use a model reading PDFs and videos
+        .max_tokens(1024) // Limit response length
+        .build_google()?;
+
+    // Upload the document once, then wait for Gemini to process it (videos take longest)
+    let pdf = std::fs::read("examples/dummy.pdf")?;
+    let file = google
+        .upload_file("dummy.pdf", &pdf, "application/pdf")
+        .await?;
+    println!("Uploaded {} ({} bytes)", file.name, file.size_bytes);
+    let file = google
+        .wait_for_file(&file.name, Duration::from_secs(5))
+        .await?;
+    if file.state != GoogleFileState::Active {
+        println!("Processing failed: {:?}", file.state);
+        return Ok(());
+    }
+
+    // Reference the file by its URI instead of sending its bytes
+    let messages = vec![ChatMessage::user()
+        .parts(vec![
+            ContentPart::File(file.uri.clone()),
+            ContentPart::Text("Summarize this document in three sentences.".to_string()),
+        ])
+        .build()];
+    let response = google.chat(&messages).await?;
+    println!("{}", response.text().unwrap_or_default());
+
+    // Files expire after 48 hours, but can be deleted sooner
+    google.delete_file(&file.name).await?;
+
+    Ok(())
+}
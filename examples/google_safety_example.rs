@@ -0,0 +1,49 @@
+// Import required modules from the LLM library for Google Gemini integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Google API key from environment variable or use test key as fallback
+    let api_key = std::env::var("GOOGLE_API_KEY").unwrap_or("google-key".into());
+
+    // Initialize the client, relaxing the filter for a security training course
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Google) // Use Google as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gemini-2.0-flash") // Use a fast Gemini model
+        .max_tokens(512) // Limit response length
+        .safety_setting("HARM_CATEGORY_DANGEROUS_CONTENT", "BLOCK_ONLY_HIGH")
+        .safety_setting("HARM_CATEGORY_HARASSMENT", "BLOCK_ONLY_HIGH")
+        .build()
+        .expect("Failed to build LLM (Google)");
+
+    let messages = vec![ChatMessage::user()
+        .content("How do phishing emails usually trick people into clicking links?")
+        .build()];
+
+    let response = llm.chat(&messages).await?;
+
+    // A blocked reply has no text, so report why instead of printing nothing
+    match response.refusal() {
+        Some(reason) => println!("No reply: {}", reason),
+        None => println!(
+            "Google Gemini response:\n{}",
+            response.text().unwrap_or_default()
+        ),
+    }
+
+    // Print how the filter rated the content
+    for rating in response.safety_ratings().unwrap_or_default() {
+        println!(
+            "{}: {}{}",
+            rating.category,
+            rating.probability,
+            if rating.blocked { " (blocked)" } else { "" }
+        );
+    }
+
+    Ok(())
+}
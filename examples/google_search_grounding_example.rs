@@ -0,0 +1,56 @@
+// Import required modules from the LLM library for Google Gemini integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{ChatMessage, Tool},         // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Google API key from environment variable or use test key as fallback
+    let api_key = std::env::var("GOOGLE_API_KEY").unwrap_or("google-key".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Google) // Use Google as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gemini-2.0-flash") // Use a model supporting Google Search
+        .max_tokens(1024) // Limit response length
+        .build()
+        .expect("Failed to build LLM (Google)");
+
+    let messages = vec![ChatMessage::user()
+        .content("Who won the most recent Formula 1 world championship?")
+        .build()];
+
+    // Let Gemini search Google before answering
+    let tools = [Tool::google_search()];
+    let response = llm.chat_with_tools(&messages, Some(&tools)).await?;
+    let text = response.text().unwrap_or_default();
+    println!("{}\n", text);
+
+    let Some(grounding) = response.grounding() else {
+        println!("The model answered without searching");
+        return Ok(());
+    };
+    println!("Searched for: {:?}", grounding.queries);
+
+    // Print each grounded statement with the numbers of its sources
+    for support in &grounding.supports {
+        let sources: Vec<String> = support
+            .source_indices
+            .iter()
+            .map(|i| format!("[{}]", i + 1))
+            .collect();
+        println!("\"{}\" {}", support.text, sources.join(""));
+    }
+    for (i, source) in grounding.sources.iter().enumerate() {
+        println!(
+            "[{}] {} - {}",
+            i + 1,
+            source.title.as_deref().unwrap_or("untitled"),
+            source.url
+        );
+    }
+
+    Ok(())
+}
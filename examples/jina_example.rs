@@ -0,0 +1,53 @@
+// Import required builder types from llm
+use llm::backends::{jina::Jina, HttpOptions};
+use llm::builder::{LLMBackend, LLMBuilder};
+use llm::rerank::RerankProvider;
+
+/// Example demonstrating retrieval with Jina AI's embeddings and reranker
+///
+/// This example shows how to:
+/// - Configure a Jina provider with an embedding task and dimensions
+/// - Embed documents for an embedding search
+/// - Rerank candidate documents against a query
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = std::env::var("JINA_API_KEY").unwrap_or("TESTKEY".to_string());
+
+    let documents = vec![
+        "The Eiffel Tower is in Paris.".to_string(),
+        "Rust guarantees memory safety without a garbage collector.".to_string(),
+        "Python is garbage collected.".to_string(),
+    ];
+
+    // Embed the documents for retrieval, shortened to 256 values
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Jina)
+        .api_key(api_key.clone())
+        .model("jina-embeddings-v3")
+        .embedding_input_type("retrieval.passage") // Jina's embedding task
+        .embedding_dimensions(256)
+        .build()?;
+    let vectors = llm.embed(documents.clone()).await?;
+    println!(
+        "Embedded {} documents of {} values",
+        vectors.len(),
+        vectors[0].len()
+    );
+
+    // Rerank the documents against a query, keeping the two most relevant
+    let reranker = Jina::new(
+        api_key,
+        None,
+        None,
+        None,
+        None,
+        None,
+        HttpOptions::default(),
+    );
+    let query = "Which language is memory safe?";
+    for result in reranker.rerank(query, &documents, Some(2)).await? {
+        println!("{:.3} {}", result.relevance_score, documents[result.index]);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+    llm_tool,                          // Tool declaration macro
+    tool_enabled_llm::ToolEnabledLLM,  // Automatic tool execution
+    LLMProvider,
+};
+
+/// Get the current weather in a city
+///
+/// # Arguments
+///
+/// * `city` - Name of the city
+/// * `unit` - Temperature unit, "celsius" or "fahrenheit"
+#[llm_tool]
+async fn get_weather(city: String, unit: Option<String>) -> Result<String, std::io::Error> {
+    let unit = unit.unwrap_or_else(|| "celsius".to_string());
+    Ok(format!(
+        r#"{{"city": "{}", "temperature": 18, "unit": "{}"}}"#,
+        city, unit
+    ))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with tool support
+        .build()
+        .expect("Failed to build LLM");
+
+    // The macro generated get_weather_tool() from the function signature and docs
+    let llm = ToolEnabledLLM::new(llm).register(get_weather_tool());
+    println!("Tool definition: {:?}", llm.tools());
+
+    let messages = vec![ChatMessage::user()
+        .content("What is the weather in Berlin?")
+        .build()];
+
+    match llm.chat_auto(&messages).await {
+        Ok(response) => println!("Final answer:\n{}", response),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+// Import required modules from the LLM library for LM Studio integration
+use llm::{
+    backends::lmstudio::list_models,   // Model discovery
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{ChatMessage, ChatOptions},  // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // List the models downloaded in LM Studio (start its server from the Developer tab)
+    let models = list_models(None).await?;
+    for model in &models {
+        println!("{} ({}, {})", model.id, model.model_type, model.state);
+    }
+    let chat_models: Vec<&str> = models
+        .iter()
+        .filter(|model| model.model_type != "embeddings")
+        .map(|model| model.id.as_str())
+        .collect();
+    let Some(first) = chat_models.first() else {
+        eprintln!("No chat model downloaded in LM Studio");
+        return Ok(());
+    };
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::LMStudio) // Use LM Studio's local server
+        .model(*first) // Use the first downloaded chat model
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .build()
+        .expect("Failed to build LLM (LM Studio)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Explain the difference between a process and a thread")
+        .build()];
+
+    // Ask every chat model in turn; LM Studio loads each one on demand
+    for model in chat_models {
+        let options = ChatOptions::new().model(model);
+        match llm.chat_with_options(&messages, None, &options).await {
+            Ok(text) => println!("{}:\n{}\n", model, text),
+            Err(e) => eprintln!("{}: chat error: {}", model, e),
+        }
+    }
+
+    Ok(())
+}
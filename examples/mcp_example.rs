@@ -0,0 +1,53 @@
+// Import required modules from the LLM library for OpenAI integration
+use std::sync::Arc;
+
+use llm::{
+    builder::{LLMBackend, LLMBuilder},
+    chat::ChatMessage,                // Chat-related structures
+    mcp::{McpClient, StdioTransport}, // MCP client and stdio transport
+    tool_enabled_llm::ToolEnabledLLM, // Automatic tool execution
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Start the MCP reference server as a child process and perform the handshake
+    let transport =
+        StdioTransport::spawn("npx", ["-y", "@modelcontextprotocol/server-everything"])?;
+    let mcp = Arc::new(McpClient::connect(transport).await?);
+    println!("Connected to {}", mcp.server_info());
+
+    // Show the tools exposed by the server
+    for tool in mcp.list_tools().await? {
+        println!("- {}: {}", tool.name, tool.description.unwrap_or_default());
+    }
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with tool support
+        .max_tokens(512) // Limit response length
+        .build()
+        .expect("Failed to build LLM");
+
+    // Register every server tool; their handlers dispatch calls back to the server
+    let mut llm = ToolEnabledLLM::new(llm);
+    for tool in mcp.executable_tools().await? {
+        llm = llm.register(tool);
+    }
+
+    let messages = vec![ChatMessage::user()
+        .content("Use the add tool to compute 19 + 23.")
+        .build()];
+
+    // Run the call-tool/feed-result loop until the model answers
+    match llm.chat_auto(&messages).await {
+        Ok(response) => println!("Final answer:\n{}", response),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// Import required modules from the LLM library for Mistral integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Mistral API key from environment variable or use test key as fallback
+    let api_key = std::env::var("MISTRAL_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Mistral) // Use Mistral as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("mistral-small-latest") // Use Mistral Small
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .safe_prompt(true) // Prepend Mistral's safety prompt
+        .random_seed(42) // Sample reproducibly
+        .build()
+        .expect("Failed to build LLM (Mistral)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Explain the difference between a process and a thread")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    // Embed texts with mistral-embed
+    match llm.embed(vec!["Hello".to_string(), "Bonjour".to_string()]).await {
+        Ok(embeddings) => println!("Embedding dimensions: {}", embeddings[0].len()),
+        Err(e) => eprintln!("Embedding error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+// Import required modules from the LLM library for Moonshot integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Moonshot API key from environment variable or use test key as fallback
+    let api_key = std::env::var("MOONSHOT_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Moonshot) // Use Moonshot (Kimi) as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("moonshot-v1-128k") // Use the 128k-token context window
+        .max_tokens(1024) // Limit response length
+        .temperature(0.3) // Control response randomness (0.0-1.0)
+        .context_file("README.md") // Read a document as context of the conversation
+        .build()
+        .expect("Failed to build LLM (Moonshot)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Summarize the document in three bullet points.")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
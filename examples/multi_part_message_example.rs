@@ -0,0 +1,44 @@
+use std::fs;
+
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{ChatMessage, ContentPart, ImageMime}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with vision support
+        .max_tokens(512) // Limit response length
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    let image = fs::read("./examples/image001.jpg").expect("The image001.jpg file should exist");
+
+    // Send text and several images in a single message
+    let messages = vec![ChatMessage::user()
+        .parts(vec![
+            ContentPart::Text("Compare these two images.".to_string()),
+            ContentPart::Image((ImageMime::JPEG, image)),
+            ContentPart::ImageURL(
+                "https://upload.wikimedia.org/wikipedia/commons/4/47/PNG_transparency_demonstration_1.png"
+                    .to_string(),
+            ),
+        ])
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("OpenAI chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use GPT-4o-mini model
+        .temperature(1.0) // Sample varied candidates
+        .n(3) // Ask for three candidate replies
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    let messages = vec![ChatMessage::user()
+        .content("Suggest a name for a coffee shop run by cats.")
+        .build()];
+
+    // Send chat request and print every candidate
+    match llm.chat(&messages).await {
+        Ok(response) => {
+            for (index, choice) in response.choices().iter().enumerate() {
+                println!(
+                    "Candidate {}: {}",
+                    index + 1,
+                    choice.text.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
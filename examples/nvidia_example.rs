@@ -0,0 +1,34 @@
+// Import required modules from the LLM library for NVIDIA NIM integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get NVIDIA NIM API key from environment variable or use test key as fallback
+    let api_key = std::env::var("NVIDIA_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Nvidia) // Use NVIDIA NIM as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("meta/llama-3.1-70b-instruct") // Use Llama 3.1 70B on build.nvidia.com
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .build()
+        .expect("Failed to build LLM (NVIDIA NIM)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Explain the difference between a process and a thread")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+// Import required modules from the LLM library
+use std::io::Write;
+
+use futures::StreamExt;
+use llm::{
+    builder::{LLMBackend, LLMBuilder},
+    completion::CompletionRequest,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Ollama server URL from environment variable or use default localhost
+    let base_url = std::env::var("OLLAMA_URL").unwrap_or("http://127.0.0.1:11434".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Ollama) // Use Ollama as the LLM backend
+        .base_url(base_url) // Set the Ollama server URL
+        .model("qwen2.5-coder:1.5b")
+        .build()
+        .expect("Failed to build LLM (Ollama)");
+
+    // Ask for a code completion and print the fragments as they arrive
+    let req = CompletionRequest::new("fn fibonacci(n: u64) -> u64 {");
+    let mut stream = llm.complete_stream(&req).await?;
+    while let Some(fragment) = stream.next().await {
+        print!("{}", fragment?);
+        std::io::stdout().flush()?;
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{AudioMime, ChatMessage, ContentPart}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize an audio model answering with speech as well as text
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-audio-preview") // Use an audio model
+        .audio_output("alloy", "wav") // Speak the reply with the "alloy" voice, as WAV
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    // Send a question along with a recording, when one is given
+    let mut parts = vec![ContentPart::Text(
+        "Answer the question in the recording, or tell a short joke if there is none.".to_string(),
+    )];
+    if let Some(path) = std::env::args().nth(1) {
+        parts.push(ContentPart::Audio((AudioMime::MP3, std::fs::read(path)?)));
+    }
+    let messages = vec![ChatMessage::user().parts(parts).build()];
+
+    // Save the spoken reply and print its transcript
+    match llm.chat(&messages).await {
+        Ok(response) => match response.audio() {
+            Some(audio) => {
+                std::fs::write("reply.wav", &audio.data)?;
+                println!("Saved reply.wav: {}", audio.transcript.unwrap_or_default());
+            }
+            None => println!("Chat response:\n{}", response),
+        },
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Import required modules from the LLM library for OpenAI integration
+use std::time::Duration;
+
+use llm::{
+    backends::openai::BatchStatus,     // Batch job status
+    builder::LLMBuilder,               // Builder pattern components
+    chat::{BatchRequest, ChatMessage}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Build the OpenAI client itself, since batches are specific to OpenAI
+    let openai = LLMBuilder::new()
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a small model
+        .max_tokens(50) // Keep the replies short
+        .system("Classify the sentiment of the review as positive, negative or neutral.")
+        .build_openai()?;
+
+    // One request per review, identified by its position
+    let reviews = ["Great product!", "Broke after a day.", "It's fine."];
+    let requests: Vec<BatchRequest> = reviews
+        .iter()
+        .enumerate()
+        .map(|(i, review)| {
+            BatchRequest::new(
+                format!("review-{}", i),
+                vec![ChatMessage::user().content(*review).build()],
+            )
+        })
+        .collect();
+
+    // Upload the requests and wait for the job, which takes up to 24 hours
+    let batch = openai.create_batch(&requests).await?;
+    println!("Created batch {} ({:?})", batch.id, batch.status);
+    let batch = openai
+        .wait_for_batch(&batch.id, Duration::from_secs(60))
+        .await?;
+    if batch.status != BatchStatus::Completed {
+        println!("Batch ended as {:?}: {:?}", batch.status, batch.errors);
+        return Ok(());
+    }
+
+    // Print the replies, matched to the reviews by their custom IDs
+    for result in openai.batch_results(&batch).await? {
+        match result.response {
+            Ok(response) => println!("{}: {}", result.custom_id, response),
+            Err(e) => eprintln!("{} failed: {}", result.custom_id, e),
+        }
+    }
+
+    Ok(())
+}
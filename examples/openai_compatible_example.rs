@@ -0,0 +1,41 @@
+// Import required modules from the LLM library
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Point at any OpenAI-compatible server, e.g. vLLM started with `vllm serve <model>`
+    let base_url =
+        std::env::var("OPENAI_COMPATIBLE_BASE_URL").unwrap_or("http://localhost:8000/v1".into());
+
+    // Initialize and configure the LLM client
+    let mut builder = LLMBuilder::new()
+        .backend(LLMBackend::OpenAICompatible) // Use an OpenAI-compatible server
+        .base_url(base_url) // Set the server's API root
+        .model("Qwen/Qwen2.5-7B-Instruct") // Use a model the server serves
+        .max_tokens(512) // Limit response length
+        .temperature(0.7); // Control response randomness (0.0-1.0)
+
+    // Local servers often need no key; gateways like LiteLLM usually do
+    if let Ok(api_key) = std::env::var("OPENAI_COMPATIBLE_API_KEY") {
+        builder = builder.api_key(api_key);
+    }
+    let llm = builder
+        .build()
+        .expect("Failed to build LLM (OpenAI-compatible)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("Explain the difference between a process and a thread")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::LLMBuilder,                            // Builder pattern components
+    chat::{ChatMessage, ChatProvider, ContentPart}, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Build the OpenAI client itself, since files are specific to OpenAI
+    let openai = LLMBuilder::new()
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model reading PDFs
+        .build_openai()?;
+
+    // Upload the document once, to reference it in several messages
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or("examples/dummy.pdf".to_string());
+    let file = openai
+        .upload_file("document.pdf", &std::fs::read(path)?, "user_data")
+        .await?;
+    println!("Uploaded {} ({} bytes)", file.id, file.bytes);
+
+    // Ask questions about the document by its ID
+    for question in ["Summarize the document.", "What is its title?"] {
+        let message = ChatMessage::user()
+            .parts(vec![
+                ContentPart::File(file.id.clone()),
+                ContentPart::Text(question.to_string()),
+            ])
+            .build();
+        println!("{}\n{}\n", question, openai.chat(&[message]).await?);
+    }
+
+    // List the stored documents, then delete the uploaded one
+    for stored in openai.list_files(Some("user_data")).await? {
+        println!("Stored: {} {}", stored.id, stored.filename);
+    }
+    openai.delete_file(&file.id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::LLMBuilder,               // Builder pattern components
+    chat::{ChatMessage, ChatProvider}, // Chat-related structures
+    moderation::ModerationProvider,    // Content screening
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Build the OpenAI client itself, which both screens and answers the messages
+    let openai = LLMBuilder::new()
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a small model for the replies
+        .build_openai()?;
+
+    let inputs = [
+        "What is the boiling point of water at high altitude?",
+        "Tell me how to hurt my neighbour without getting caught.",
+    ];
+    for input in inputs {
+        // Screen the user's message before answering it
+        let verdict = &openai.moderate(&[input.to_string()]).await?[0];
+        if verdict.flagged {
+            println!("Refused \"{}\": {:?}", input, verdict.flagged_categories());
+            continue;
+        }
+
+        let reply = openai
+            .chat(&[ChatMessage::user().content(input).build()])
+            .await?;
+        println!("{}\n{}\n", input, reply);
+    }
+
+    Ok(())
+}
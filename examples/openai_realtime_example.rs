@@ -0,0 +1,42 @@
+// Import required modules from the LLM library for OpenAI integration
+use futures::StreamExt;
+use llm::{
+    backends::openai::RealtimeEvent, // Realtime session events
+    builder::LLMBuilder,             // Builder pattern components
+};
+use serde_json::json;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Build the OpenAI client itself, since realtime sessions are specific to OpenAI
+    let openai = LLMBuilder::new()
+        .api_key(api_key) // Set the API key
+        .system("You are a concise assistant.") // Sent as the session's instructions
+        .build_openai()?;
+
+    // Open a session answering with text only, then ask a question
+    let (sender, mut events) = openai.connect_realtime(None).await?;
+    sender
+        .update_session(json!({"output_modalities": ["text"]}))
+        .await?;
+    sender.send_text("Name three moons of Jupiter.").await?;
+    sender.create_response().await?;
+
+    // Print the reply as it streams in, then close the session
+    while let Some(event) = events.next().await {
+        match event? {
+            RealtimeEvent::TextDelta { delta, .. } => print!("{}", delta),
+            RealtimeEvent::ResponseDone { usage, .. } => {
+                println!("\n\nUsage: {:?}", usage);
+                sender.close().await?;
+            }
+            RealtimeEvent::Error { message, .. } => eprintln!("Error: {}", message),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
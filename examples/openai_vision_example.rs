@@ -3,7 +3,7 @@ use std::fs;
 // Import required modules from the LLM library for OpenAI integration
 use llm::{
     builder::{LLMBackend, LLMBuilder}, // Builder pattern components
-    chat::{ChatMessage, ImageMime},    // Chat-related structures
+    chat::{ChatMessage, ImageDetail, ImageMime}, // Chat-related structures
 };
 
 #[tokio::main]
@@ -26,7 +26,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Prepare conversation history with example messages
     let messages = vec![
-        ChatMessage::user().image_url("https://media.istockphoto.com/id/1443562748/fr/photo/mignon-chat-gingembre.jpg?s=612x612&w=0&k=20&c=ygNVVnqLk9V8BWu4VQ0D21u7-daIyHUoyKlCcx3K1E8=").build(),
+        ChatMessage::user().image_url("https://media.istockphoto.com/id/1443562748/fr/photo/mignon-chat-gingembre.jpg?s=612x612&w=0&k=20&c=ygNVVnqLk9V8BWu4VQ0D21u7-daIyHUoyKlCcx3K1E8=").image_detail(ImageDetail::Low).build(), // Read at low resolution, for fewer tokens
         ChatMessage::user().image(ImageMime::JPEG, content).build(),
         ChatMessage::user().content("What is in this image (image 1 and 2)?").build(),
     ];
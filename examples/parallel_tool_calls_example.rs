@@ -0,0 +1,69 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{FunctionBuilder, LLMBackend, LLMBuilder, ParamBuilder},
+    chat::ChatMessage, // Chat-related structures
+    ToolCall,
+};
+
+/// Pretends to look up the weather for the city named in the call arguments
+async fn get_weather(call: &ToolCall) -> String {
+    let args: serde_json::Value =
+        serde_json::from_str(&call.function.arguments).unwrap_or_default();
+    let city = args["city"].as_str().unwrap_or("unknown").to_string();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    format!(r#"{{"city": "{}", "temperature_c": 18}}"#, city)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with tool support
+        .parallel_tool_calls(true) // Allow several tool calls per response
+        .function(
+            FunctionBuilder::new("get_weather")
+                .description("Get the current weather in a city")
+                .param(
+                    ParamBuilder::new("city")
+                        .type_of("string")
+                        .description("Name of the city"),
+                )
+                .required(vec!["city".to_string()]),
+        )
+        .build()
+        .expect("Failed to build LLM");
+
+    let mut messages = vec![ChatMessage::user()
+        .content("What is the weather in Tokyo, Paris and Lima?")
+        .build()];
+
+    let response = llm.chat_with_tools(&messages, llm.tools()).await?;
+    let tool_calls = response.tool_calls().unwrap_or_default();
+    println!("The model requested {} tool calls", tool_calls.len());
+
+    // Execute every call concurrently; join_all keeps the results in call order
+    let outputs = futures::future::join_all(tool_calls.iter().map(get_weather)).await;
+
+    messages.push(
+        ChatMessage::assistant()
+            .tool_use(tool_calls.clone())
+            .build(),
+    );
+    for (call, output) in tool_calls.iter().zip(outputs) {
+        messages.push(
+            ChatMessage::user()
+                .tool_result(&call.id, &call.function.name, output)
+                .build(),
+        );
+    }
+
+    let response = llm.chat_with_tools(&messages, llm.tools()).await?;
+    println!("Final answer:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// Import required modules from the LLM library for Perplexity integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Perplexity API key from environment variable or use test key as fallback
+    let api_key = std::env::var("PERPLEXITY_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Perplexity) // Use Perplexity as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("sonar") // Use Sonar, which answers from a web search
+        .max_tokens(512) // Limit response length
+        .build()
+        .expect("Failed to build LLM (Perplexity)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("What are the latest stable features of Rust?")
+        .build()];
+
+    // Send chat request and print the reply with its sources
+    match llm.chat(&messages).await {
+        Ok(response) => {
+            println!("Chat response:\n{}", response);
+            for (i, citation) in response.citations().unwrap_or_default().iter().enumerate() {
+                let title = citation.title.as_deref().unwrap_or("untitled");
+                println!("[{}] {} - {}", i + 1, title, citation.url);
+            }
+        }
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
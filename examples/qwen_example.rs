@@ -0,0 +1,44 @@
+// Import required modules from the LLM library for Qwen integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::ChatMessage,                 // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get Qwen API key from environment variable or use test key as fallback
+    let api_key = std::env::var("DASHSCOPE_API_KEY").unwrap_or("TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::Qwen) // Use Qwen as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("qwen-plus") // Use Qwen Plus
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .enable_search(true) // Ground the reply with a web search
+        .build()
+        .expect("Failed to build LLM (Qwen)");
+
+    // Prepare conversation history with example messages
+    let messages = vec![ChatMessage::user()
+        .content("What happened in the news today?")
+        .build()];
+
+    // Send chat request and handle the response
+    match llm.chat(&messages).await {
+        Ok(text) => println!("Chat response:\n{}", text),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    // Embed texts with text-embedding-v3
+    match llm
+        .embed(vec!["Hello".to_string(), "Bonjour".to_string()])
+        .await
+    {
+        Ok(embeddings) => println!("Embedding dimensions: {}", embeddings[0].len()),
+        Err(e) => eprintln!("Embedding error: {}", e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{FunctionBuilder, LLMBackend, LLMBuilder, ParamBuilder},
+    chat::ChatMessage,                // Chat-related structures
+    tool_enabled_llm::ToolEnabledLLM, // Automatic tool execution
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with tool support
+        .max_tokens(512) // Limit response length
+        .build()
+        .expect("Failed to build LLM");
+
+    // Register tools with the handlers that execute them
+    let llm = ToolEnabledLLM::new(llm)
+        .tool(
+            FunctionBuilder::new("get_weather")
+                .description("Get the current weather in a city")
+                .param(
+                    ParamBuilder::new("city")
+                        .type_of("string")
+                        .description("Name of the city"),
+                )
+                .required(vec!["city".to_string()]),
+            |args| async move {
+                let city = args["city"].as_str().unwrap_or("unknown");
+                Ok(format!(r#"{{"city": "{}", "temperature_c": 18}}"#, city))
+            },
+        )
+        .max_iterations(5); // Stop after 5 model calls
+
+    let messages = vec![ChatMessage::user()
+        .content("Is it warmer in Tokyo or in Paris right now?")
+        .build()];
+
+    // Run the call-tool/feed-result loop until the model answers
+    match llm.chat_auto(&messages).await {
+        Ok(response) => println!("Final answer:\n{}", response),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
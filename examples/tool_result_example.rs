@@ -0,0 +1,66 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{FunctionBuilder, LLMBackend, LLMBuilder, ParamBuilder},
+    chat::ChatMessage, // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client with a single weather tool
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use a model with tool support
+        .max_tokens(512) // Limit response length
+        .function(
+            FunctionBuilder::new("get_weather")
+                .description("Get the current weather in a city")
+                .param(
+                    ParamBuilder::new("city")
+                        .type_of("string")
+                        .description("Name of the city"),
+                )
+                .required(vec!["city".to_string()]),
+        )
+        .build()
+        .expect("Failed to build LLM");
+
+    let mut messages = vec![ChatMessage::user()
+        .content("What is the weather in Tokyo?")
+        .build()];
+
+    // First turn: the model asks for the tool
+    let response = llm.chat_with_tools(&messages, llm.tools()).await?;
+    let Some(tool_calls) = response.tool_calls() else {
+        println!("No tool call:\n{}", response);
+        return Ok(());
+    };
+
+    // Replay the tool calls, then send back one result per call
+    messages.push(
+        ChatMessage::assistant()
+            .tool_use(tool_calls.clone())
+            .build(),
+    );
+    for call in &tool_calls {
+        println!(
+            "Calling {} with {}",
+            call.function.name, call.function.arguments
+        );
+        let output = r#"{"temperature_c": 18, "conditions": "light rain"}"#;
+        messages.push(
+            ChatMessage::user()
+                .tool_result(&call.id, &call.function.name, output)
+                .build(),
+        );
+    }
+
+    // Second turn: the model answers using the tool output
+    let response = llm.chat_with_tools(&messages, llm.tools()).await?;
+    println!("Final answer:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Import required modules from the LLM library for OpenAI integration
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{ChatMessage, StructuredChat, StructuredOutputFormat}, // Chat-related structures
+};
+use serde::Deserialize;
+
+/// The shape of the reply, matching the JSON schema below
+#[derive(Debug, Deserialize)]
+struct Student {
+    name: String,
+    age: u32,
+    is_student: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get OpenAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    let schema = r#"
+    {
+        "name": "Student",
+        "schema": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" },
+                "is_student": { "type": "boolean" }
+            },
+            "required": ["name", "age", "is_student"],
+            "additionalProperties": false
+        },
+        "strict": true
+    }
+"#;
+    let schema: StructuredOutputFormat = serde_json::from_str(schema)?;
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::OpenAI) // Use OpenAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("gpt-4o-mini") // Use GPT-4o-mini model
+        .system("Generate random students as example data.")
+        .schema(schema) // Constrain the reply to the schema
+        .build()
+        .expect("Failed to build LLM (OpenAI)");
+
+    let messages = vec![ChatMessage::user()
+        .content("Generate a random student")
+        .build()];
+
+    // Send chat request and parse the reply into a Student
+    match llm.chat_structured::<Student>(&messages).await {
+        Ok(student) => println!(
+            "{} is {} years old (student: {})",
+            student.name, student.age, student.is_student
+        ),
+        Err(e) => eprintln!("Chat error: {}", e),
+    }
+
+    Ok(())
+}
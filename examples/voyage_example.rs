@@ -0,0 +1,51 @@
+// Import required builder types from llm
+use llm::builder::{LLMBackend, LLMBuilder};
+
+/// Example demonstrating how to generate embeddings using Voyage AI's API
+///
+/// This example shows how to:
+/// - Configure a Voyage provider with an input type and output dimension
+/// - Embed a query and the documents it is compared with
+/// - Rank the documents by cosine similarity to the query
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = std::env::var("VOYAGE_API_KEY").unwrap_or("TESTKEY".to_string());
+
+    // Documents and queries are embedded with a different input type
+    let documents = LLMBuilder::new()
+        .backend(LLMBackend::Voyage)
+        .api_key(api_key.clone())
+        .model("voyage-3.5")
+        .embedding_input_type("document")
+        .embedding_dimensions(512) // Shorten the vectors to 512 values
+        .build()?;
+    let queries = LLMBuilder::new()
+        .backend(LLMBackend::Voyage)
+        .api_key(api_key)
+        .model("voyage-3.5")
+        .embedding_input_type("query")
+        .embedding_dimensions(512)
+        .build()?;
+
+    let texts = vec![
+        "The Eiffel Tower is in Paris.".to_string(),
+        "Rust guarantees memory safety without a garbage collector.".to_string(),
+    ];
+    let document_vectors = documents.embed(texts.clone()).await?;
+    let query_vector = queries
+        .embed(vec!["Which language is memory safe?".to_string()])
+        .await?
+        .remove(0);
+
+    // Rank the documents by similarity to the query
+    let cosine = |a: &[f32], b: &[f32]| {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm(a) * norm(b))
+    };
+    for (text, vector) in texts.iter().zip(&document_vectors) {
+        println!("{:.3} {}", cosine(&query_vector, vector), text);
+    }
+
+    Ok(())
+}
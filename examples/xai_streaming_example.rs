@@ -0,0 +1,52 @@
+// Import required modules from the LLM library for xAI integration
+use std::io::Write;
+
+use futures::StreamExt;
+use llm::{
+    builder::{LLMBackend, LLMBuilder}, // Builder pattern components
+    chat::{ChatMessage, StreamChunk},  // Chat-related structures
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Get xAI API key from environment variable or use test key as fallback
+    let api_key = std::env::var("XAI_API_KEY").unwrap_or("sk-TESTKEY".into());
+
+    // Initialize and configure the LLM client
+    let llm = LLMBuilder::new()
+        .backend(LLMBackend::XAI) // Use xAI as the LLM provider
+        .api_key(api_key) // Set the API key
+        .model("grok-2-latest") // Use Grok-2 model
+        .max_tokens(512) // Limit response length
+        .temperature(0.7) // Control response randomness (0.0-1.0)
+        .build()
+        .expect("Failed to build LLM (xAI)");
+
+    let messages = vec![ChatMessage::user()
+        .content("Write a haiku about the ocean")
+        .build()];
+
+    // Consume the stream directly
+    let mut stream = llm.chat_stream(&messages).await?;
+    while let Some(chunk) = stream.next().await {
+        match chunk? {
+            StreamChunk::Text(text) => print!("{}", text),
+            StreamChunk::Done { usage, .. } => println!("\n\nUsage: {:?}", usage),
+            _ => {}
+        }
+        std::io::stdout().flush()?;
+    }
+
+    // Or let the callback API print tokens and assemble the full response
+    let response = llm
+        .chat_stream_with(&messages, &mut |chunk| {
+            if let StreamChunk::Text(text) = chunk {
+                print!("{}", text);
+                let _ = std::io::stdout().flush();
+            }
+        })
+        .await?;
+    println!("\n\nFull response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+//! Procedural macros for the `llm` crate.
+//!
+//! These macros are re-exported by `llm` behind its `macros` feature and should be used
+//! through it, since the generated code refers to items in `llm`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Error, Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, Meta,
+    Pat, PathArguments, Type,
+};
+
+/// Declares a tool from an async function.
+///
+/// Alongside the function, the macro generates a `<name>_tool()` function returning an
+/// `llm::tool_enabled_llm::ExecutableTool`. It holds the tool definition sent to the model
+/// and an executor that deserializes the call arguments and runs the function.
+///
+/// - The tool description is the function's doc comment, up to an `# Arguments` section.
+/// - Argument descriptions come from `` * `name` - description `` lines in that section.
+/// - Arguments must be owned, deserializable types. `Option<T>` arguments are optional.
+/// - The function must return a `Result` whose `Ok` value implements `ToString` and
+///   whose `Err` value implements `Display`.
+///
+/// ```ignore
+/// use llm::llm_tool;
+///
+/// /// Get the current weather in a city
+/// ///
+/// /// # Arguments
+/// ///
+/// /// * `city` - Name of the city
+/// /// * `days` - Number of forecast days
+/// #[llm_tool]
+/// async fn get_weather(city: String, days: Option<u32>) -> Result<String, std::io::Error> {
+///     Ok(format!("Sunny in {} for {} days", city, days.unwrap_or(1)))
+/// }
+///
+/// let llm = ToolEnabledLLM::new(provider).register(get_weather_tool());
+/// ```
+#[proc_macro_attribute]
+pub fn llm_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            proc_macro2::Span::call_site(),
+            "#[llm_tool] does not take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let func = parse_macro_input!(item as ItemFn);
+    match expand(func) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// A JSON schema type derived from a Rust argument type.
+struct JsonType {
+    /// The JSON type name
+    name: &'static str,
+    /// The JSON type of array items, for `Vec<T>`
+    items: Option<&'static str>,
+    /// Whether the argument is an `Option<T>`
+    optional: bool,
+}
+
+fn expand(func: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &func.sig;
+    if sig.asyncness.is_none() {
+        return Err(Error::new_spanned(
+            sig.fn_token,
+            "#[llm_tool] functions must be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "#[llm_tool] functions cannot be generic",
+        ));
+    }
+
+    let fn_name = &sig.ident;
+    let name = fn_name.to_string();
+    let tool_fn = format_ident!("{}_tool", fn_name);
+    let vis = &func.vis;
+    let (description, arg_docs) = parse_docs(&func.attrs);
+
+    let mut fields = Vec::new();
+    let mut types = Vec::new();
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+
+    for input in &sig.inputs {
+        let arg = match input {
+            FnArg::Typed(arg) => arg,
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(
+                    receiver,
+                    "#[llm_tool] cannot be used on methods",
+                ))
+            }
+        };
+        let ident = match arg.pat.as_ref() {
+            Pat::Ident(pat) => &pat.ident,
+            other => {
+                return Err(Error::new_spanned(
+                    other,
+                    "#[llm_tool] arguments must be plain identifiers",
+                ))
+            }
+        };
+        if let Type::Reference(reference) = arg.ty.as_ref() {
+            return Err(Error::new_spanned(
+                reference,
+                "#[llm_tool] arguments must be owned types",
+            ));
+        }
+
+        let arg_name = ident.to_string();
+        let json_type = json_type(&arg.ty);
+        let property_type = json_type.name;
+        let arg_description = arg_docs
+            .iter()
+            .find(|(name, _)| *name == arg_name)
+            .map(|(_, doc)| doc.clone())
+            .unwrap_or_default();
+        let items = match json_type.items {
+            Some(item_type) => quote! {
+                Some(Box::new(::llm::chat::ParameterProperty {
+                    property_type: #item_type.to_string(),
+                    description: String::new(),
+                    items: None,
+                    enum_list: None,
+                }))
+            },
+            None => quote! { None },
+        };
+
+        properties.push(quote! {
+            (
+                #arg_name.to_string(),
+                ::llm::chat::ParameterProperty {
+                    property_type: #property_type.to_string(),
+                    description: #arg_description.to_string(),
+                    items: #items,
+                    enum_list: None,
+                },
+            )
+        });
+        if !json_type.optional {
+            required.push(arg_name);
+        }
+        fields.push(ident.clone());
+        types.push(arg.ty.as_ref().clone());
+    }
+
+    Ok(quote! {
+        #func
+
+        #[doc = concat!("Returns the executable tool generated for [`", #name, "`].")]
+        #vis fn #tool_fn() -> ::llm::tool_enabled_llm::ExecutableTool {
+            #[derive(::llm::__private::serde::Deserialize)]
+            #[serde(crate = "::llm::__private::serde")]
+            struct Args {
+                #( #fields: #types, )*
+            }
+
+            let tool = ::llm::chat::Tool {
+                tool_type: "function".to_string(),
+                function: ::llm::chat::FunctionTool {
+                    name: #name.to_string(),
+                    description: #description.to_string(),
+                    parameters: ::llm::chat::ParametersSchema {
+                        schema_type: "object".to_string(),
+                        properties: ::std::collections::HashMap::from([#( #properties ),*]),
+                        required: vec![#( #required.to_string() ),*],
+                    },
+                },
+                settings: ::std::default::Default::default(),
+            };
+
+            ::llm::tool_enabled_llm::ExecutableTool::new(
+                tool,
+                |args: ::llm::__private::serde_json::Value| async move {
+                    let Args { #( #fields ),* } = ::llm::__private::serde_json::from_value(args)
+                        .map_err(|e| ::llm::error::LLMError::JsonError(e.to_string()))?;
+                    match #fn_name(#( #fields ),*).await {
+                        Ok(output) => Ok(output.to_string()),
+                        Err(e) => Err(::llm::error::LLMError::ProviderError(e.to_string())),
+                    }
+                },
+            )
+        }
+    })
+}
+
+/// Splits the doc comment into the tool description and per-argument descriptions.
+fn parse_docs(attrs: &[Attribute]) -> (String, Vec<(String, String)>) {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(doc), ..
+                }) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let mut description = Vec::new();
+    let mut args = Vec::new();
+    let mut in_arguments = false;
+    for line in lines {
+        if line.starts_with('#') {
+            in_arguments = line.trim_start_matches('#').trim() == "Arguments";
+            continue;
+        }
+        if in_arguments {
+            if let Some(arg) = parse_arg_doc(&line) {
+                args.push(arg);
+            }
+        } else if !line.is_empty() || !description.is_empty() {
+            description.push(line);
+        }
+    }
+
+    let description = description.join(" ").trim().to_string();
+    (description, args)
+}
+
+/// Parses a `` * `name` - description `` argument line.
+fn parse_arg_doc(line: &str) -> Option<(String, String)> {
+    let rest = line
+        .strip_prefix('*')
+        .or_else(|| line.strip_prefix('-'))?
+        .trim();
+    let rest = rest.strip_prefix('`')?;
+    let (name, rest) = rest.split_once('`')?;
+    let description = rest.trim_start().trim_start_matches(['-', ':']).trim();
+    Some((name.to_string(), description.to_string()))
+}
+
+/// Maps a Rust type to the JSON schema type of its values.
+fn json_type(ty: &Type) -> JsonType {
+    let (ident, inner) = match last_segment(ty) {
+        Some(segment) => segment,
+        None => {
+            return JsonType {
+                name: "object",
+                items: None,
+                optional: false,
+            }
+        }
+    };
+
+    match (ident.as_str(), inner) {
+        ("Option", Some(inner)) => JsonType {
+            optional: true,
+            ..json_type(inner)
+        },
+        ("Vec", Some(inner)) => JsonType {
+            name: "array",
+            items: Some(json_type(inner).name),
+            optional: false,
+        },
+        (ident, _) => JsonType {
+            name: scalar_type(ident),
+            items: None,
+            optional: false,
+        },
+    }
+}
+
+fn scalar_type(ident: &str) -> &'static str {
+    match ident {
+        "String" | "str" | "char" => "string",
+        "bool" => "boolean",
+        "f32" | "f64" => "number",
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => "integer",
+        _ => "object",
+    }
+}
+
+/// Returns the last path segment of a type and its first generic type argument.
+fn last_segment(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let inner = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+    Some((segment.ident.to_string(), inner))
+}
@@ -1,13 +1,22 @@
+use std::convert::Infallible;
+
 use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
+use futures::{stream, StreamExt};
 use uuid::Uuid;
 
-use super::types::{ChatRequest, ChatResponse, Choice, Message};
+use super::types::{
+    ChatCompletionChunk, ChatRequest, ChatResponse, Choice, ChunkChoice, Delta, Message,
+};
 use super::ServerState;
-use crate::chat::{ChatMessage, ChatRole};
+use crate::chat::{ChatMessage, ChatRole, ChatStream, StreamChunk};
 use crate::{
     chain::{MultiChainStepBuilder, MultiChainStepMode, MultiPromptChain},
     chat::MessageType,
@@ -39,6 +48,11 @@ use crate::{
 /// # Model Specification
 /// Models must be specified in the format "provider:model_name" (e.g. "openai:gpt-4", "anthropic:claude-2")
 ///
+/// # Streaming
+/// When the request sets `"stream": true`, the provider's streamed chunks are proxied as
+/// server-sent events in OpenAI's `chat.completion.chunk` format, terminated by `data: [DONE]`.
+/// Streaming is only available for simple chat completions, not chain requests.
+///
 /// # Response Format
 /// Returns a standardized chat completion response containing:
 /// - Unique ID for the completion
@@ -50,7 +64,7 @@ pub async fn handle_chat(
     State(state): State<ServerState>,
     headers: HeaderMap,
     Json(req): Json<ChatRequest>,
-) -> Result<Json<ChatResponse>, (StatusCode, String)> {
+) -> Result<Response, (StatusCode, String)> {
     if let Some(key) = &state.auth_key {
         let auth_header = headers.get("Authorization").ok_or((
             StatusCode::UNAUTHORIZED,
@@ -70,7 +84,15 @@ pub async fn handle_chat(
     }
 
     if !req.steps.is_empty() {
-        return handle_chain_request(state, req).await;
+        if req.stream {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Streaming is not supported for chain requests".to_string(),
+            ));
+        }
+        return handle_chain_request(state, req)
+            .await
+            .map(IntoResponse::into_response);
     }
 
     let messages: Vec<ChatMessage> = req
@@ -79,12 +101,15 @@ pub async fn handle_chat(
         .into_iter()
         .map(|msg| ChatMessage {
             role: match msg.role.as_str() {
+                "system" => ChatRole::System,
                 "user" => ChatRole::User,
                 "assistant" => ChatRole::Assistant,
+                "tool" => ChatRole::Tool,
                 _ => ChatRole::User,
             },
             message_type: MessageType::Text,
             content: msg.content,
+            metadata: Default::default(),
         })
         .collect();
 
@@ -100,6 +125,14 @@ pub async fn handle_chat(
         format!("Unknown provider: {}", provider_id),
     ))?;
 
+    if req.stream {
+        let chunks = provider
+            .chat_stream(&messages)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        return Ok(stream_chat_response(chunks, model_name.to_string()).into_response());
+    }
+
     let response = provider
         .chat(&messages)
         .await
@@ -108,10 +141,7 @@ pub async fn handle_chat(
     Ok(Json(ChatResponse {
         id: format!("chatcmpl-{}", Uuid::new_v4()),
         object: "chat.completion".to_string(),
-        created: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+        created: unix_time(),
         model: model_name.to_string(),
         choices: vec![Choice {
             index: 0,
@@ -121,7 +151,88 @@ pub async fn handle_chat(
             },
             finish_reason: "stop".to_string(),
         }],
-    }))
+    })
+    .into_response())
+}
+
+/// Converts a provider's chunk stream into server-sent events in OpenAI's chunk format.
+///
+/// The first event announces the assistant role, each text chunk becomes a content delta,
+/// and the stream ends with a finish-reason event followed by `[DONE]`. An error raised
+/// mid-stream is sent as an `error` object and closes the stream, without `[DONE]`.
+fn stream_chat_response(
+    chunks: ChatStream,
+    model: String,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let id = format!("chatcmpl-{}", Uuid::new_v4());
+    let created = unix_time();
+
+    let make_event = move |delta: Delta, finish_reason: Option<String>| {
+        let chunk = ChatCompletionChunk {
+            id: id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created,
+            model: model.clone(),
+            choices: vec![ChunkChoice {
+                index: 0,
+                delta,
+                finish_reason,
+            }],
+        };
+        Event::default().data(serde_json::to_string(&chunk).unwrap_or_default())
+    };
+
+    let first = make_event(
+        Delta {
+            role: Some("assistant".to_string()),
+            content: None,
+        },
+        None,
+    );
+
+    // `None` marks the end of the provider's stream, answered with `[DONE]`; an error ends
+    // the stream right after its error event.
+    let events = chunks
+        .map(Some)
+        .chain(stream::once(futures::future::ready(None)))
+        .scan(false, move |failed, chunk| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            let event = match chunk {
+                Some(Ok(StreamChunk::Text(text))) => Some(make_event(
+                    Delta {
+                        role: None,
+                        content: Some(text),
+                    },
+                    None,
+                )),
+                Some(Ok(StreamChunk::Done { finish_reason, .. })) => Some(make_event(
+                    Delta::default(),
+                    Some(
+                        finish_reason
+                            .map(|reason| reason.as_str().to_string())
+                            .unwrap_or_else(|| "stop".to_string()),
+                    ),
+                )),
+                Some(Ok(_)) => None,
+                Some(Err(e)) => {
+                    *failed = true;
+                    Some(Event::default().data(
+                        serde_json::json!({ "error": { "message": e.to_string() } }).to_string(),
+                    ))
+                }
+                None => Some(Event::default().data("[DONE]")),
+            };
+            futures::future::ready(Some(event))
+        })
+        .filter_map(futures::future::ready);
+
+    let stream = stream::once(futures::future::ready(first))
+        .chain(events)
+        .map(Ok);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
 /// Handles multi-step chain requests by orchestrating message flow through multiple models.
@@ -234,10 +345,7 @@ async fn handle_chain_request(
     Ok(Json(ChatResponse {
         id: format!("chatcmpl-{}", Uuid::new_v4()),
         object: "chat.completion".to_string(),
-        created: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+        created: unix_time(),
         model: provider_ids.join(",").to_string(),
         choices: vec![Choice {
             index: 0,
@@ -249,3 +357,70 @@ async fn handle_chain_request(
         }],
     }))
 }
+
+/// Serves the metrics of a registry in the Prometheus text exposition format.
+///
+/// The route is not authenticated, so scrapers need no API key.
+#[cfg(feature = "metrics")]
+pub async fn handle_metrics(metrics: crate::metrics::Metrics) -> impl IntoResponse {
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        metrics.render(),
+    )
+}
+
+/// Returns the current Unix time in seconds, or 0 if the clock is set before the epoch.
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::LLMError;
+
+    async fn body(chunks: Vec<Result<StreamChunk, LLMError>>) -> String {
+        let response = stream_chat_response(Box::pin(stream::iter(chunks)), "model".to_string())
+            .into_response();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn stream_ends_with_done() {
+        let body = body(vec![
+            Ok(StreamChunk::Text("Hello".to_string())),
+            Ok(StreamChunk::Done {
+                finish_reason: None,
+                usage: None,
+            }),
+        ])
+        .await;
+        assert!(body.contains("\"content\":\"Hello\""));
+        assert!(body.contains("\"finish_reason\":\"stop\""));
+        assert!(body.trim_end().ends_with("data: [DONE]"));
+    }
+
+    #[tokio::test]
+    async fn stream_ends_after_error_without_done() {
+        let body = body(vec![
+            Ok(StreamChunk::Text("Hel".to_string())),
+            Err(LLMError::ProviderError("overloaded".to_string())),
+            Ok(StreamChunk::Text("lo".to_string())),
+        ])
+        .await;
+        assert!(body.contains("\"content\":\"Hel\""));
+        assert!(body
+            .trim_end()
+            .ends_with("{\"error\":{\"message\":\"Provider Error: overloaded\"}}"));
+        assert!(!body.contains("\"content\":\"lo\""));
+        assert!(!body.contains("[DONE]"));
+    }
+}
@@ -13,7 +13,7 @@ use tower_http::cors::CorsLayer;
 use crate::chain::LLMRegistry;
 use handlers::handle_chat;
 
-pub use types::{ChatRequest, ChatResponse, Message};
+pub use types::{ChatCompletionChunk, ChatRequest, ChatResponse, ChunkChoice, Delta, Message};
 
 /// Main server struct that manages LLM registry and authentication
 pub struct Server {
@@ -21,6 +21,9 @@ pub struct Server {
     llms: Arc<LLMRegistry>,
     /// Optional authentication key for API requests
     pub auth_key: Option<String>,
+    /// Metrics served on `/metrics`
+    #[cfg(feature = "metrics")]
+    metrics: Option<crate::metrics::Metrics>,
 }
 
 /// Internal server state shared between request handlers
@@ -41,6 +44,8 @@ impl Server {
         Self {
             llms: Arc::new(llms),
             auth_key: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
 
@@ -53,13 +58,19 @@ impl Server {
     /// * `Ok(())` if server starts successfully
     /// * `Err(LLMError)` if server fails to start
     pub async fn run(self, addr: &str) -> Result<(), crate::error::LLMError> {
-        let app = Router::new()
-            .route("/v1/chat/completions", axum::routing::post(handle_chat))
-            .layer(CorsLayer::permissive())
-            .with_state(ServerState {
-                llms: self.llms,
-                auth_key: self.auth_key,
-            });
+        let app = Router::new().route("/v1/chat/completions", axum::routing::post(handle_chat));
+        #[cfg(feature = "metrics")]
+        let app = match self.metrics {
+            Some(metrics) => app.route(
+                "/metrics",
+                axum::routing::get(move || handlers::handle_metrics(metrics.clone())),
+            ),
+            None => app,
+        };
+        let app = app.layer(CorsLayer::permissive()).with_state(ServerState {
+            llms: self.llms,
+            auth_key: self.auth_key,
+        });
 
         let listener = tokio::net::TcpListener::bind(addr)
             .await
@@ -80,4 +91,14 @@ impl Server {
         self.auth_key = Some(key.into());
         self
     }
+
+    /// Serves the Prometheus metrics of a registry on `/metrics`
+    ///
+    /// # Arguments
+    /// * `metrics` - Registry shared with the providers of the LLM registry
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: crate::metrics::Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
 }
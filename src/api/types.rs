@@ -19,6 +19,9 @@ pub struct ChatRequest {
     /// Optional max tokens parameter
     #[serde(default)]
     pub max_tokens: Option<u32>,
+    /// Whether to stream the response as server-sent events
+    #[serde(default)]
+    pub stream: bool,
 }
 
 /// Chain step configuration for multi-step processing
@@ -75,3 +78,40 @@ pub struct Choice {
     /// Reason why the model stopped generating
     pub finish_reason: String,
 }
+
+/// Streamed chunk of a chat completion, in OpenAI's `chat.completion.chunk` format
+#[derive(Serialize)]
+pub struct ChatCompletionChunk {
+    /// Identifier shared by every chunk of the completion
+    pub id: String,
+    /// Object type identifier
+    pub object: String,
+    /// Unix timestamp when the completion was created
+    pub created: u64,
+    /// Name of the model that generated the completion
+    pub model: String,
+    /// Deltas for each choice
+    pub choices: Vec<ChunkChoice>,
+}
+
+/// Single choice delta in a streamed chunk
+#[derive(Serialize)]
+pub struct ChunkChoice {
+    /// Index of this choice in the list
+    pub index: usize,
+    /// Incremental message content
+    pub delta: Delta,
+    /// Reason why the model stopped generating, set on the last chunk
+    pub finish_reason: Option<String>,
+}
+
+/// Incremental message content in a streamed chunk
+#[derive(Serialize, Default)]
+pub struct Delta {
+    /// Role of the message sender, set on the first chunk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Fragment of the generated content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
@@ -0,0 +1,239 @@
+//! Anthropic's Message Batches API, running many messages requests offline at half the
+//! price.
+//!
+//! The requests are submitted together and processed within 24 hours. Once the batch has
+//! ended, its results hold one reply per request, matched to the requests by their
+//! custom IDs since the replies come in no particular order.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    backends::send_request,
+    chat::{BatchRequest, BatchResult, ChatResponse},
+    error::LLMError,
+};
+
+use super::{tool_betas, Anthropic, AnthropicCompleteResponse};
+
+/// Processing status of a message batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageBatchStatus {
+    /// The requests are being processed
+    InProgress,
+    /// The batch is being cancelled
+    Canceling,
+    /// Every request has a result
+    Ended,
+}
+
+/// Number of requests of a message batch, by outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct MessageBatchRequestCounts {
+    pub processing: u32,
+    pub succeeded: u32,
+    pub errored: u32,
+    pub canceled: u32,
+    pub expired: u32,
+}
+
+/// A message batch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MessageBatch {
+    /// ID of the batch, to poll or cancel it
+    pub id: String,
+    pub processing_status: MessageBatchStatus,
+    #[serde(default)]
+    pub request_counts: MessageBatchRequestCounts,
+    /// Creation time, as an RFC 3339 timestamp
+    #[serde(default)]
+    pub created_at: String,
+    /// Time the last request was processed, as an RFC 3339 timestamp
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    /// Time the unprocessed requests expire at, as an RFC 3339 timestamp
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    /// URL of the results, once the batch has ended
+    #[serde(default)]
+    pub results_url: Option<String>,
+}
+
+impl MessageBatch {
+    /// Returns whether every request of the batch has a result.
+    pub fn is_done(&self) -> bool {
+        self.processing_status == MessageBatchStatus::Ended
+    }
+}
+
+/// A line of the results of a message batch.
+#[derive(Deserialize)]
+struct MessageBatchResultLine {
+    custom_id: String,
+    result: MessageBatchOutcome,
+}
+
+/// Outcome of one of the requests of a message batch.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MessageBatchOutcome {
+    Succeeded { message: Value },
+    Errored { error: Value },
+    Canceled,
+    Expired,
+}
+
+impl Anthropic {
+    /// Submits the requests as a message batch processing them within 24 hours.
+    ///
+    /// The requests are built with the client's settings, like [`ChatProvider::chat`]
+    /// requests; the replies are read with [`Anthropic::batch_results`] once the batch
+    /// has ended.
+    ///
+    /// [`ChatProvider::chat`]: crate::chat::ChatProvider::chat
+    pub async fn create_batch(&self, requests: &[BatchRequest]) -> Result<MessageBatch, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Anthropic API key".to_string()));
+        }
+
+        let betas = tool_betas(
+            requests
+                .iter()
+                .filter_map(|request| request.tools.as_ref())
+                .flatten(),
+        );
+        let requests = requests
+            .iter()
+            .map(|request| {
+                Ok(json!({
+                    "custom_id": request.custom_id,
+                    "params": self.chat_body(
+                        &request.messages,
+                        request.tools.as_deref(),
+                        false,
+                        &request.options,
+                    )?,
+                }))
+            })
+            .collect::<Result<Vec<_>, LLMError>>()?;
+        let mut request = self
+            .request(
+                reqwest::Method::POST,
+                format!("{}/messages/batches", self.base_url),
+            )
+            .json(&json!({ "requests": requests }));
+        if let Some(betas) = betas {
+            request = request.header("anthropic-beta", betas);
+        }
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Returns the current state of a message batch.
+    pub async fn batch(&self, batch_id: &str) -> Result<MessageBatch, LLMError> {
+        let request = self.request(
+            reqwest::Method::GET,
+            format!("{}/messages/batches/{}", self.base_url, batch_id),
+        );
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Cancels a message batch. The requests already processed keep their replies.
+    pub async fn cancel_batch(&self, batch_id: &str) -> Result<MessageBatch, LLMError> {
+        let request = self.request(
+            reqwest::Method::POST,
+            format!("{}/messages/batches/{}/cancel", self.base_url, batch_id),
+        );
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Polls a message batch until it has ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_id` - ID of the batch
+    /// * `poll_interval` - Time waited between two polls
+    pub async fn wait_for_batch(
+        &self,
+        batch_id: &str,
+        poll_interval: Duration,
+    ) -> Result<MessageBatch, LLMError> {
+        loop {
+            let batch = self.batch(batch_id).await?;
+            if batch.is_done() {
+                return Ok(batch);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Downloads and parses the replies of an ended message batch.
+    pub async fn batch_results(&self, batch: &MessageBatch) -> Result<Vec<BatchResult>, LLMError> {
+        let url = batch.results_url.clone().ok_or_else(|| {
+            LLMError::InvalidRequest(format!("Message batch {} has not ended", batch.id))
+        })?;
+        let request = self.request(reqwest::Method::GET, url);
+        let content = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let line: MessageBatchResultLine =
+                    serde_json::from_str(line).map_err(|e| LLMError::JsonError(e.to_string()))?;
+                Ok(BatchResult {
+                    custom_id: line.custom_id,
+                    response: batch_response(line.result),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Converts the outcome of one of the requests of a message batch into a chat response.
+fn batch_response(outcome: MessageBatchOutcome) -> Result<Box<dyn ChatResponse>, LLMError> {
+    match outcome {
+        MessageBatchOutcome::Succeeded { message } => {
+            let mut response: AnthropicCompleteResponse =
+                serde_json::from_value(message.clone())
+                    .map_err(|e| LLMError::JsonError(e.to_string()))?;
+            response.raw = message;
+            Ok(Box::new(response))
+        }
+        MessageBatchOutcome::Errored { error } => {
+            // The error is wrapped in an error response: {"type": "error", "error": {...}}.
+            let error = if error["error"].is_object() {
+                &error["error"]
+            } else {
+                &error
+            };
+            let message = error["message"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            Err(LLMError::ProviderError(match error["type"].as_str() {
+                Some(error_type) => format!("{}: {}", error_type, message),
+                None => message,
+            }))
+        }
+        MessageBatchOutcome::Canceled => Err(LLMError::Cancelled),
+        MessageBatchOutcome::Expired => Err(LLMError::ProviderError(
+            "The batch expired before the request was processed".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,874 @@
+//! Anthropic API client implementation for chat and completion functionality.
+//!
+//! This module provides integration with Anthropic's Claude models through their API.
+
+use std::collections::HashMap;
+
+use crate::{
+    backends::{
+        http_client, json_with_raw, probe, request_body, request_id_header, send_request,
+        HttpOptions,
+    },
+    chat::{
+        with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatRole,
+        ContentPart, FinishReason, ImageMime, MessageType, ParametersSchema, RequestTimer,
+        ResponseMetadata, ResponseTiming, Tool, ToolChoice, Usage,
+    },
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
+    FunctionCall, ToolCall,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+mod batch;
+
+pub use batch::{MessageBatch, MessageBatchRequestCounts, MessageBatchStatus};
+
+/// Client for interacting with Anthropic's API.
+///
+/// Provides methods for chat and completion requests using Anthropic's models.
+#[derive(Debug)]
+pub struct Anthropic {
+    pub api_key: String,
+    pub model: String,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    pub timeout_seconds: u64,
+    pub system: String,
+    pub stream: bool,
+    pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
+    pub tools: Option<Vec<Tool>>,
+    pub tool_choice: Option<ToolChoice>,
+    pub reasoning: bool,
+    pub thinking_budget_tokens: Option<u32>,
+    pub base_url: String,
+    pub extra_params: Map<String, Value>,
+    pub retry: Option<RetryPolicy>,
+    pub logging: Option<RequestLogging>,
+    client: Client,
+}
+
+/// Anthropic-specific tool format that matches their API structure
+#[derive(Serialize, Debug)]
+struct AnthropicTool<'a> {
+    /// Versioned type of a tool defined by Anthropic, absent for custom tools
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    tool_type: Option<&'a str>,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_schema: Option<&'a ParametersSchema>,
+    /// Settings of a tool defined by Anthropic
+    #[serde(flatten)]
+    settings: &'a Map<String, Value>,
+}
+
+/// Configuration for the thinking feature
+#[derive(Serialize, Debug)]
+struct ThinkingConfig {
+    #[serde(rename = "type")]
+    thinking_type: String,
+    budget_tokens: u32,
+}
+
+/// Request payload for Anthropic's messages API endpoint.
+#[derive(Serialize, Debug)]
+struct AnthropicCompleteRequest<'a> {
+    messages: Vec<AnthropicMessage<'a>>,
+    model: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<AnthropicTool<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
+}
+
+/// Individual message in an Anthropic chat conversation.
+#[derive(Serialize, Debug)]
+struct AnthropicMessage<'a> {
+    role: &'a str,
+    content: Vec<MessageContent<'a>>,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct MessageContent<'a> {
+    #[serde(rename = "type")]
+    message_type: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<ImageSource<'a>>,
+    /// ID of a `tool_use` block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a str>,
+    /// Tool name of a `tool_use` block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    /// Tool input of a `tool_use` block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input: Option<Value>,
+    /// ID of the `tool_use` block a `tool_result` block answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_use_id: Option<&'a str>,
+    /// Output of a `tool_result` block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<ToolResultContent<'a>>,
+    /// Whether the tool of a `tool_result` block failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_error: Option<bool>,
+}
+
+/// Output of a `tool_result` block, as text or as text and image blocks.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum ToolResultContent<'a> {
+    Text(&'a str),
+    Blocks(Vec<MessageContent<'a>>),
+}
+
+/// Source of an image or document block, inline or downloaded by Anthropic.
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ImageSource<'a> {
+    Base64 { media_type: &'a str, data: String },
+    Url { url: &'a str },
+}
+
+/// Response from Anthropic's messages API endpoint.
+#[derive(Deserialize, Debug)]
+struct AnthropicCompleteResponse {
+    content: Vec<AnthropicContent>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
+}
+
+/// Token usage reported by Anthropic's messages API.
+#[derive(Deserialize, Debug)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u32,
+    #[serde(default)]
+    output_tokens: u32,
+}
+
+/// Content block within an Anthropic API response.
+#[derive(Serialize, Deserialize, Debug)]
+struct AnthropicContent {
+    text: Option<String>,
+    #[serde(rename = "type")]
+    content_type: Option<String>,
+    thinking: Option<String>,
+    name: Option<String>,
+    input: Option<Value>,
+    id: Option<String>,
+}
+
+impl std::fmt::Display for AnthropicCompleteResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for content in self.content.iter() {
+            match content.content_type {
+                Some(ref t) if t == "tool_use" => write!(
+                    f,
+                    "{{\n \"name\": {}, \"input\": {}\n}}",
+                    content.name.clone().unwrap_or_default(),
+                    content.input.clone().unwrap_or_default()
+                )?,
+                Some(ref t) if t == "thinking" => {
+                    write!(f, "{}", content.thinking.clone().unwrap_or_default())?
+                }
+                _ => write!(
+                    f,
+                    "{}",
+                    self.content
+                        .iter()
+                        .map(|c| c.text.clone().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ChatResponse for AnthropicCompleteResponse {
+    fn usage(&self) -> Option<Usage> {
+        self.usage.as_ref().map(|usage| Usage {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+            total_tokens: usage.input_tokens + usage.output_tokens,
+            cached_prompt_tokens: None,
+        })
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("anthropic".to_string()),
+            model: self.model.clone(),
+            response_id: self.id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: None,
+            timing: self.timing,
+        }
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw)
+    }
+
+    fn text(&self) -> Option<String> {
+        Some(
+            self.content
+                .iter()
+                .filter_map(|c| {
+                    if c.content_type == Some("text".to_string()) || c.content_type.is_none() {
+                        c.text.clone()
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    fn refusal(&self) -> Option<String> {
+        (self.stop_reason.as_deref() == Some("refusal")).then(|| {
+            self.text()
+                .filter(|text| !text.is_empty())
+                .unwrap_or_else(|| "The model declined to answer".to_string())
+        })
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.stop_reason.as_deref().map(FinishReason::from_provider)
+    }
+
+    fn thinking(&self) -> Option<String> {
+        self.content
+            .iter()
+            .find(|c| c.content_type == Some("thinking".to_string()))
+            .and_then(|c| c.thinking.clone())
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        let tool_calls: Vec<ToolCall> = self
+            .content
+            .iter()
+            .filter(|c| c.content_type.as_deref() == Some("tool_use"))
+            .map(|c| ToolCall {
+                id: c.id.clone().unwrap_or_default(),
+                call_type: "function".to_string(),
+                function: FunctionCall {
+                    name: c.name.clone().unwrap_or_default(),
+                    arguments: c
+                        .input
+                        .as_ref()
+                        .map(|input| input.to_string())
+                        .unwrap_or_else(|| "{}".to_string()),
+                },
+            })
+            .collect();
+
+        if tool_calls.is_empty() {
+            None
+        } else {
+            Some(tool_calls)
+        }
+    }
+}
+
+impl Anthropic {
+    /// Creates a new Anthropic client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Anthropic API key for authentication
+    /// * `model` - Model identifier (defaults to "claude-3-sonnet-20240229")
+    /// * `max_tokens` - Maximum tokens in response (defaults to 300)
+    /// * `temperature` - Sampling temperature (defaults to 0.7)
+    /// * `timeout_seconds` - Request timeout in seconds (defaults to 30)
+    /// * `system` - System prompt (defaults to "You are a helpful assistant.")
+    /// * `stream` - Whether to stream responses (defaults to false)
+    /// * `tools` - Function tools the model may call
+    /// * `tool_choice` - How the model should choose between the tools (defaults to auto)
+    /// * `thinking_budget_tokens` - Budget tokens for thinking (optional)
+    /// * `http` - HTTP settings, such as the base URL (defaults to
+    ///   `https://api.anthropic.com/v1`), headers, proxy and retry policy
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        api_key: impl Into<String>,
+        model: Option<String>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+        timeout_seconds: Option<u64>,
+        system: Option<String>,
+        stream: Option<bool>,
+        top_p: Option<f32>,
+        top_k: Option<u32>,
+        tools: Option<Vec<Tool>>,
+        tool_choice: Option<ToolChoice>,
+        reasoning: Option<bool>,
+        thinking_budget_tokens: Option<u32>,
+        http: HttpOptions,
+    ) -> Self {
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
+        Self {
+            api_key: api_key.into(),
+            model: model.unwrap_or_else(|| "claude-3-sonnet-20240229".to_string()),
+            max_tokens: max_tokens.unwrap_or(300),
+            temperature: temperature.unwrap_or(0.7),
+            system: system.unwrap_or_else(|| "You are a helpful assistant.".to_string()),
+            timeout_seconds: timeout_seconds.unwrap_or(30),
+            stream: stream.unwrap_or(false),
+            top_p,
+            top_k,
+            tools,
+            tool_choice,
+            reasoning: reasoning.unwrap_or(false),
+            thinking_budget_tokens,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://api.anthropic.com/v1".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
+        }
+    }
+
+    /// Starts an authenticated request to a URL of the API.
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+    }
+
+    /// Builds the JSON body of a messages request, applying the per-request overrides and
+    /// the extra parameters.
+    fn chat_body(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        stream: bool,
+        options: &ChatOptions,
+    ) -> Result<Value, LLMError> {
+        // Anthropic takes instructions only as a top-level system prompt, so system messages
+        // from the conversation are appended to it.
+        let system = messages
+            .iter()
+            .filter(|m| m.role == ChatRole::System)
+            .fold(self.system.clone(), |system, m| {
+                format!("{}\n\n{}", system, m.content)
+            });
+
+        let anthropic_messages: Vec<AnthropicMessage> = messages
+            .iter()
+            .filter(|m| m.role != ChatRole::System)
+            .map(|m| {
+                Ok(AnthropicMessage {
+                    role: match (&m.role, &m.message_type) {
+                        (_, MessageType::ToolResult { .. }) => "user",
+                        (_, MessageType::ToolUse(_)) => "assistant",
+                        (ChatRole::System | ChatRole::User | ChatRole::Tool, _) => "user",
+                        (ChatRole::Assistant, _) => "assistant",
+                    },
+                    content: match &m.message_type {
+                        MessageType::Text => vec![MessageContent {
+                            message_type: Some("text"),
+                            text: Some(&m.content),
+                            ..Default::default()
+                        }],
+                        MessageType::Pdf(raw_bytes) => vec![pdf_block(raw_bytes)],
+                        MessageType::Audio(_) => return Err(audio_unsupported()),
+                        MessageType::Image((image_mime, raw_bytes)) => {
+                            vec![image_block(image_mime, raw_bytes)]
+                        }
+                        MessageType::ImageURL(url) => vec![image_url_block(url)],
+                        MessageType::Parts(parts) => {
+                            parts.iter().map(content_part).collect::<Result<_, _>>()?
+                        }
+                        MessageType::ToolUse(calls) => {
+                            let text = (!m.content.is_empty()).then(|| MessageContent {
+                                message_type: Some("text"),
+                                text: Some(&m.content),
+                                ..Default::default()
+                            });
+                            text.into_iter()
+                                .chain(calls.iter().map(|call| {
+                                    MessageContent {
+                                        message_type: Some("tool_use"),
+                                        id: Some(&call.id),
+                                        name: Some(&call.function.name),
+                                        input: Some(
+                                            serde_json::from_str(&call.function.arguments)
+                                                .unwrap_or_else(|_| {
+                                                    Value::Object(Default::default())
+                                                }),
+                                        ),
+                                        ..Default::default()
+                                    }
+                                }))
+                                .collect()
+                        }
+                        MessageType::ToolResult {
+                            tool_call_id,
+                            content,
+                            parts,
+                            is_error,
+                            ..
+                        } => vec![MessageContent {
+                            message_type: Some("tool_result"),
+                            tool_use_id: Some(tool_call_id),
+                            content: Some(if parts.is_empty() {
+                                ToolResultContent::Text(content)
+                            } else {
+                                ToolResultContent::Blocks(
+                                    parts.iter().map(content_part).collect::<Result<_, _>>()?,
+                                )
+                            }),
+                            is_error: is_error.then_some(true),
+                            ..Default::default()
+                        }],
+                    },
+                })
+            })
+            .collect::<Result<_, LLMError>>()?;
+
+        let anthropic_tools = tools.map(|t| {
+            t.iter()
+                .map(|tool| {
+                    // The tools defined by Anthropic have a known schema, and only a type,
+                    // a name and their settings.
+                    let function = tool.is_function();
+                    AnthropicTool {
+                        tool_type: (!function).then_some(tool.tool_type.as_str()),
+                        name: &tool.function.name,
+                        description: function.then_some(tool.function.description.as_str()),
+                        input_schema: function.then_some(&tool.function.parameters),
+                        settings: &tool.settings,
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let tool_choice = if anthropic_tools.is_some() {
+            let choice = match self.tool_choice.clone().unwrap_or_default() {
+                ToolChoice::Auto => HashMap::from([("type".to_string(), "auto".to_string())]),
+                ToolChoice::None => HashMap::from([("type".to_string(), "none".to_string())]),
+                ToolChoice::Required => HashMap::from([("type".to_string(), "any".to_string())]),
+                ToolChoice::Function(name) => HashMap::from([
+                    ("type".to_string(), "tool".to_string()),
+                    ("name".to_string(), name),
+                ]),
+            };
+            Some(choice)
+        } else {
+            None
+        };
+
+        let thinking = if self.reasoning {
+            Some(ThinkingConfig {
+                thinking_type: "enabled".to_string(),
+                budget_tokens: self.thinking_budget_tokens.unwrap_or(16000),
+            })
+        } else {
+            None
+        };
+
+        let req_body = AnthropicCompleteRequest {
+            messages: anthropic_messages,
+            model: &self.model,
+            max_tokens: Some(options.max_tokens.unwrap_or(self.max_tokens)),
+            temperature: Some(options.temperature.unwrap_or(self.temperature)),
+            system: Some(&system),
+            stream: Some(stream),
+            top_p: options.top_p.or(self.top_p),
+            top_k: self.top_k,
+            stop_sequences: options.stop.clone(),
+            tools: anthropic_tools,
+            tool_choice,
+            thinking,
+        };
+        request_body(&req_body, &self.extra_params)
+    }
+
+    /// Builds and sends a chat request, applying the per-request overrides.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Anthropic API key".to_string()));
+        }
+
+        let mut request = self
+            .request(reqwest::Method::POST, format!("{}/messages", self.base_url))
+            .json(&self.chat_body(messages, tools, self.stream, options)?);
+        if let Some(betas) = tool_betas(tools.into_iter().flatten()) {
+            request = request.header("anthropic-beta", betas);
+        }
+
+        if let Some(timeout) = options.timeout.or((self.timeout_seconds > 0)
+            .then(|| std::time::Duration::from_secs(self.timeout_seconds)))
+        {
+            request = request.timeout(timeout);
+        }
+
+        let mut timer = RequestTimer::start();
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        timer.first_byte();
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (AnthropicCompleteResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.timing = Some(timer.finish("anthropic", &self.model));
+        json_resp.request_id = request_id;
+        Ok(Box::new(json_resp))
+    }
+}
+
+/// Returns the `anthropic-beta` header value enabling the tools defined by Anthropic that
+/// are still in beta, if any of the tools is.
+fn tool_betas<'a>(tools: impl IntoIterator<Item = &'a Tool>) -> Option<String> {
+    let mut betas: Vec<String> = Vec::new();
+    for tool in tools {
+        let beta = match tool.tool_type.as_str() {
+            // The first versions of the text editor and bash tools came with computer use.
+            "computer_20241022" | "text_editor_20241022" | "bash_20241022" => {
+                "computer-use-2024-10-22".to_string()
+            }
+            // computer_YYYYMMDD needs computer-use-YYYY-MM-DD.
+            tool_type => match tool_type.strip_prefix("computer_") {
+                Some(date) if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) => {
+                    format!("computer-use-{}-{}-{}", &date[..4], &date[4..6], &date[6..])
+                }
+                _ => continue,
+            },
+        };
+        if !betas.contains(&beta) {
+            betas.push(beta);
+        }
+    }
+    (!betas.is_empty()).then(|| betas.join(","))
+}
+
+/// Error returned for audio clips, which Anthropic does not accept.
+fn audio_unsupported() -> LLMError {
+    LLMError::InvalidRequest("Anthropic does not support audio input".to_string())
+}
+
+/// An image block holding the image's bytes.
+fn image_block<'a>(image_mime: &ImageMime, raw_bytes: &[u8]) -> MessageContent<'a> {
+    MessageContent {
+        message_type: Some("image"),
+        source: Some(ImageSource::Base64 {
+            media_type: image_mime.mime_type(),
+            data: BASE64.encode(raw_bytes),
+        }),
+        ..Default::default()
+    }
+}
+
+/// An image block pointing to an image Anthropic downloads.
+fn image_url_block(url: &str) -> MessageContent<'_> {
+    MessageContent {
+        message_type: Some("image"),
+        source: Some(ImageSource::Url { url }),
+        ..Default::default()
+    }
+}
+
+/// A document block holding a PDF's bytes.
+fn pdf_block<'a>(raw_bytes: &[u8]) -> MessageContent<'a> {
+    MessageContent {
+        message_type: Some("document"),
+        source: Some(ImageSource::Base64 {
+            media_type: "application/pdf",
+            data: BASE64.encode(raw_bytes),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Converts a part of a multi-part message into an Anthropic content block.
+fn content_part(part: &ContentPart) -> Result<MessageContent<'_>, LLMError> {
+    Ok(match part {
+        ContentPart::Text(text) => MessageContent {
+            message_type: Some("text"),
+            text: Some(text),
+            ..Default::default()
+        },
+        ContentPart::Image((image_mime, raw_bytes)) => image_block(image_mime, raw_bytes),
+        ContentPart::ImageURL(url) => image_url_block(url),
+        ContentPart::Pdf(raw_bytes) => pdf_block(raw_bytes),
+        ContentPart::Audio(_) => return Err(audio_unsupported()),
+        ContentPart::File(_) => {
+            return Err(LLMError::InvalidRequest(
+                "Anthropic does not support file IDs, send the document bytes instead".to_string(),
+            ))
+        }
+    })
+}
+
+#[async_trait]
+impl ChatProvider for Anthropic {
+    /// Sends a chat request to Anthropic's API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Slice of chat messages representing the conversation
+    /// * `tools` - Optional slice of tools to use in the chat
+    ///
+    /// # Returns
+    ///
+    /// The model's response text or an error
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, tools, options)).await
+    }
+
+    /// Sends a chat request to Anthropic's API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    ///
+    /// # Returns
+    ///
+    /// The provider's response text or an error
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_with_tools(messages, None).await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Anthropic {
+    /// Sends a completion request to Anthropic's API.
+    ///
+    /// Converts the completion request into a chat message format.
+    async fn complete(&self, _req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Anthropic {
+    async fn embed(&self, _text: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        Err(LLMError::ProviderError(
+            "Embedding not supported".to_string(),
+        ))
+    }
+}
+
+#[async_trait]
+impl crate::LLMProvider for Anthropic {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.tools.as_deref()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        probe(self.request(reqwest::Method::GET, format!("{}/models", self.base_url))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        builder::{LLMBackend, LLMBuilder},
+        chat::AudioMime,
+    };
+
+    fn client(tools: Option<Vec<Tool>>, tool_choice: Option<ToolChoice>) -> Anthropic {
+        Anthropic::new(
+            "key",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            tools,
+            tool_choice,
+            None,
+            None,
+            HttpOptions::default(),
+        )
+    }
+
+    fn weather_tool() -> Tool {
+        Tool::from_value(json!({
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "description": "Get the current weather in a city",
+                "parameters": { "type": "object", "properties": {} }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn tool_choice_is_only_sent_with_tools() {
+        let llm = client(
+            Some(vec![weather_tool()]),
+            Some(ToolChoice::Function("get_weather".to_string())),
+        );
+        let messages = [ChatMessage::user().content("Hello").build()];
+
+        let body = llm
+            .chat_body(&messages, None, false, &ChatOptions::default())
+            .unwrap();
+        assert!(body.get("tool_choice").is_none());
+        assert!(body.get("tools").is_none());
+
+        let tools = [weather_tool()];
+        let body = llm
+            .chat_body(&messages, Some(&tools), false, &ChatOptions::default())
+            .unwrap();
+        assert_eq!(
+            body["tool_choice"],
+            json!({ "type": "tool", "name": "get_weather" })
+        );
+    }
+
+    #[test]
+    fn sends_image_urls_and_pdfs_as_source_blocks() {
+        let messages = [
+            ChatMessage::user()
+                .parts(vec![
+                    ContentPart::Text("Compare these".to_string()),
+                    ContentPart::ImageURL("https://example.com/cat.png".to_string()),
+                ])
+                .build(),
+            ChatMessage::user()
+                .image_url("https://example.com/dog.png")
+                .build(),
+            ChatMessage::user().pdf(b"%PDF".to_vec()).build(),
+        ];
+        let body = client(None, None)
+            .chat_body(&messages, None, false, &ChatOptions::default())
+            .unwrap();
+        assert_eq!(
+            body["messages"][0]["content"][1],
+            json!({
+                "type": "image",
+                "source": { "type": "url", "url": "https://example.com/cat.png" }
+            })
+        );
+        assert_eq!(
+            body["messages"][1]["content"][0],
+            json!({
+                "type": "image",
+                "source": { "type": "url", "url": "https://example.com/dog.png" }
+            })
+        );
+        assert_eq!(
+            body["messages"][2]["content"][0],
+            json!({
+                "type": "document",
+                "source": {
+                    "type": "base64",
+                    "media_type": "application/pdf",
+                    "data": "JVBERg=="
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_audio_input() {
+        let llm = LLMBuilder::new()
+            .backend(LLMBackend::Anthropic)
+            .api_key("key")
+            .base_url("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        for message in [
+            ChatMessage::user()
+                .audio(AudioMime::WAV, vec![0; 16])
+                .build(),
+            ChatMessage::user()
+                .parts(vec![
+                    ContentPart::Text("Transcribe this".to_string()),
+                    ContentPart::Audio((AudioMime::WAV, vec![0; 16])),
+                ])
+                .build(),
+        ] {
+            assert!(matches!(
+                llm.chat(&[message]).await,
+                Err(LLMError::InvalidRequest(_))
+            ));
+        }
+    }
+}
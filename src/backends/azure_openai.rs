@@ -0,0 +1,115 @@
+//! Azure OpenAI client implementation for chat and embedding functionality.
+//!
+//! Azure serves OpenAI models from deployments of a resource: requests go to
+//! `{endpoint}/openai/deployments/{deployment}/...` with an `api-version` query parameter,
+//! and are authenticated with the resource's API key in an `api-key` header or with a
+//! Microsoft Entra ID (Azure AD) token. The request and response bodies are OpenAI's, so
+//! this client drives an [`OpenAI`] client configured for Azure.
+
+use async_trait::async_trait;
+
+use crate::{
+    backends::{
+        openai::{forward_to_inner, OpenAI},
+        probe,
+    },
+    chat::Tool,
+    health::HealthStatus,
+    LLMProvider,
+};
+
+/// `api-version` sent when none is configured.
+pub const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// Credentials of an Azure OpenAI resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AzureAuth {
+    /// API key of the resource, sent in the `api-key` header
+    ApiKey(String),
+    /// Microsoft Entra ID (Azure AD) access token, sent as a bearer token
+    AdToken(String),
+}
+
+/// Client for interacting with a deployment of an Azure OpenAI resource.
+pub struct AzureOpenAI {
+    /// Endpoint of the resource (e.g. `https://my-resource.openai.azure.com`)
+    endpoint: String,
+    /// Name of the deployment serving the requests
+    deployment: String,
+    /// `api-version` query parameter sent with every request
+    api_version: String,
+    /// OpenAI client sending the requests to the deployment
+    inner: OpenAI,
+}
+
+impl AzureOpenAI {
+    /// Creates a client for a deployment from an OpenAI client holding the generation
+    /// settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - Endpoint of the resource (e.g. `https://my-resource.openai.azure.com`)
+    /// * `deployment` - Name of the deployment serving the requests
+    /// * `api_version` - `api-version` query parameter (defaults to [`DEFAULT_API_VERSION`])
+    /// * `auth` - Credentials of the resource
+    /// * `openai` - OpenAI client with the generation settings; its API key and base URL
+    ///   are replaced
+    pub fn new(
+        endpoint: impl Into<String>,
+        deployment: impl Into<String>,
+        api_version: Option<String>,
+        auth: AzureAuth,
+        mut openai: OpenAI,
+    ) -> Self {
+        let endpoint = endpoint.into().trim_end_matches('/').to_string();
+        let deployment = deployment.into();
+        let api_version = api_version.unwrap_or_else(|| DEFAULT_API_VERSION.to_string());
+        openai.base_url = format!("{}/openai/deployments/{}", endpoint, deployment);
+        openai.provider = "azure";
+        openai.api_version = Some(api_version.clone());
+        (openai.api_key, openai.api_key_header) = match auth {
+            AzureAuth::ApiKey(key) => (key, Some("api-key")),
+            AzureAuth::AdToken(token) => (token, None),
+        };
+        Self {
+            endpoint,
+            deployment,
+            api_version,
+            inner: openai,
+        }
+    }
+
+    /// Returns the endpoint of the resource.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Returns the name of the deployment serving the requests.
+    pub fn deployment(&self) -> &str {
+        &self.deployment
+    }
+
+    /// Returns the `api-version` query parameter sent with every request.
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+}
+
+forward_to_inner!(AzureOpenAI; chat, completion, embedding);
+
+#[async_trait]
+impl LLMProvider for AzureOpenAI {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    /// Lists the models of the resource, which checks the endpoint and credentials but
+    /// not the deployment.
+    async fn validate(&self) -> HealthStatus {
+        probe(self.inner.request(
+            reqwest::Method::GET,
+            format!("{}/openai/models", self.endpoint),
+        ))
+        .await
+    }
+}
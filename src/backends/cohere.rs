@@ -0,0 +1,657 @@
+//! Cohere API client implementation for chat and embedding functionality.
+//!
+//! This module provides integration with Cohere's Command models through their chat API,
+//! and with their Embed models. Cohere's chat API takes the latest message apart from the
+//! chat history, a preamble instead of a system message, and tool results as a separate
+//! field, and can ground replies with connectors such as web search.
+
+use std::collections::HashMap;
+
+use crate::{
+    backends::{
+        check_no_audio, http_client, json_with_raw, probe, request_body, request_id_header,
+        send_request, HttpOptions,
+    },
+    chat::{
+        with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatRole,
+        FinishReason, MessageType, ParameterProperty, RequestTimer, ResponseMetadata,
+        ResponseTiming, StructuredOutputFormat, Tool, Usage,
+    },
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
+    FunctionCall, LLMProvider, ToolCall,
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Model computing embeddings when the configured model is a chat model.
+pub const EMBEDDING_MODEL: &str = "embed-english-v3.0";
+
+/// Client for interacting with Cohere's API.
+pub struct Cohere {
+    pub api_key: String,
+    pub model: String,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub system: Option<String>,
+    pub timeout_seconds: Option<u64>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
+    pub tools: Option<Vec<Tool>>,
+    /// JSON schema for structured output
+    pub structured_output: Option<StructuredOutputFormat>,
+    /// IDs of the connectors grounding the replies (e.g. "web-search")
+    pub connectors: Vec<String>,
+    /// Kind of text embedded (e.g. "search_document" or "search_query")
+    pub embedding_input_type: Option<String>,
+    /// Base URL of the API, without a trailing slash
+    pub base_url: String,
+    /// Extra parameters merged into the chat request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
+    client: Client,
+}
+
+/// Request payload for Cohere's chat API endpoint.
+#[derive(Serialize, Debug)]
+struct CohereChatRequest<'a> {
+    model: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    chat_history: Vec<CohereMessage<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    preamble: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    connectors: Vec<CohereConnector<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<CohereTool<'a>>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_results: Vec<CohereToolResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+}
+
+/// Earlier turn of the conversation.
+#[derive(Serialize, Debug)]
+struct CohereMessage<'a> {
+    role: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<CohereToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_results: Option<Vec<CohereToolResult>>,
+}
+
+/// Connector grounding the reply.
+#[derive(Serialize, Debug)]
+struct CohereConnector<'a> {
+    id: &'a str,
+}
+
+/// Tool definition in Cohere's format.
+#[derive(Serialize, Debug)]
+struct CohereTool<'a> {
+    name: &'a str,
+    description: &'a str,
+    parameter_definitions: HashMap<&'a str, CohereParameter<'a>>,
+}
+
+/// Parameter of a tool, typed with Python type names.
+#[derive(Serialize, Debug)]
+struct CohereParameter<'a> {
+    description: &'a str,
+    #[serde(rename = "type")]
+    parameter_type: String,
+    required: bool,
+}
+
+/// Tool call, which Cohere identifies by name and parameters.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CohereToolCall {
+    name: String,
+    #[serde(default)]
+    parameters: Value,
+}
+
+/// Output of a tool call.
+#[derive(Serialize, Debug)]
+struct CohereToolResult {
+    call: CohereToolCall,
+    outputs: Vec<Value>,
+}
+
+/// Response from Cohere's chat API endpoint.
+#[derive(Deserialize, Debug)]
+struct CohereChatResponse {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    generation_id: Option<String>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<CohereToolCall>>,
+    #[serde(default)]
+    meta: Option<CohereMeta>,
+    /// The original response document
+    #[serde(skip)]
+    raw: Value,
+    /// The model that served the request
+    #[serde(skip)]
+    model: String,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
+}
+
+/// Metadata of a chat response.
+#[derive(Deserialize, Debug)]
+struct CohereMeta {
+    #[serde(default)]
+    tokens: Option<CohereTokens>,
+    #[serde(default)]
+    billed_units: Option<CohereTokens>,
+}
+
+/// Token counts, which Cohere reports as numbers that may be fractional.
+#[derive(Deserialize, Debug)]
+struct CohereTokens {
+    #[serde(default)]
+    input_tokens: f64,
+    #[serde(default)]
+    output_tokens: f64,
+}
+
+#[derive(Serialize)]
+struct CohereEmbedRequest<'a> {
+    model: &'a str,
+    texts: Vec<String>,
+    input_type: &'a str,
+    embedding_types: [&'static str; 1],
+}
+
+#[derive(Deserialize, Debug)]
+struct CohereEmbedResponse {
+    embeddings: CohereEmbeddings,
+}
+
+#[derive(Deserialize, Debug)]
+struct CohereEmbeddings {
+    float: Vec<Vec<f32>>,
+}
+
+impl std::fmt::Display for CohereChatResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for tool_call in self.tool_calls().unwrap_or_default() {
+            write!(f, "{}", tool_call)?;
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+impl ChatResponse for CohereChatResponse {
+    fn text(&self) -> Option<String> {
+        (!self.text.is_empty()).then(|| self.text.clone())
+    }
+
+    /// Returns the tool calls, given IDs from their position ("call_0", ...) since Cohere
+    /// doesn't identify them.
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        let calls = self.tool_calls.as_ref().filter(|calls| !calls.is_empty())?;
+        Some(
+            calls
+                .iter()
+                .enumerate()
+                .map(|(i, call)| ToolCall {
+                    id: format!("call_{}", i),
+                    call_type: "function".to_string(),
+                    function: FunctionCall {
+                        name: call.name.clone(),
+                        arguments: call.parameters.to_string(),
+                    },
+                })
+                .collect(),
+        )
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        if self
+            .tool_calls
+            .as_ref()
+            .is_some_and(|calls| !calls.is_empty())
+        {
+            return Some(FinishReason::ToolCalls);
+        }
+        self.finish_reason
+            .as_deref()
+            .map(FinishReason::from_provider)
+    }
+
+    fn raw(&self) -> Option<&Value> {
+        Some(&self.raw)
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        let meta = self.meta.as_ref()?;
+        let tokens = meta.tokens.as_ref().or(meta.billed_units.as_ref())?;
+        let prompt_tokens = tokens.input_tokens as u32;
+        let completion_tokens = tokens.output_tokens as u32;
+        Some(Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cached_prompt_tokens: None,
+        })
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("cohere".to_string()),
+            model: Some(self.model.clone()),
+            response_id: self.generation_id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: None,
+            timing: self.timing,
+        }
+    }
+}
+
+/// Returns the Python type name Cohere expects for a JSON schema type.
+fn parameter_type(property: &ParameterProperty) -> String {
+    match property.property_type.as_str() {
+        "string" => "str".to_string(),
+        "integer" => "int".to_string(),
+        "number" => "float".to_string(),
+        "boolean" => "bool".to_string(),
+        "array" => match &property.items {
+            Some(items) => format!("List[{}]", parameter_type(items)),
+            None => "List".to_string(),
+        },
+        "object" => "Dict".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Converts a tool into Cohere's format.
+fn cohere_tool(tool: &Tool) -> CohereTool<'_> {
+    let parameters = &tool.function.parameters;
+    CohereTool {
+        name: &tool.function.name,
+        description: &tool.function.description,
+        parameter_definitions: parameters
+            .properties
+            .iter()
+            .map(|(name, property)| {
+                (
+                    name.as_str(),
+                    CohereParameter {
+                        description: &property.description,
+                        parameter_type: parameter_type(property),
+                        required: parameters.required.contains(name),
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Converts a tool call into Cohere's format.
+fn cohere_call(call: &ToolCall) -> CohereToolCall {
+    CohereToolCall {
+        name: call.function.name.clone(),
+        parameters: serde_json::from_str(&call.function.arguments).unwrap_or_else(|_| json!({})),
+    }
+}
+
+/// Converts the output of a tool into Cohere's format, which takes a list of objects.
+fn cohere_outputs(content: &str) -> Vec<Value> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(Value::Array(items)) if items.iter().all(Value::is_object) => items,
+        Ok(object @ Value::Object(_)) => vec![object],
+        _ => vec![json!({ "result": content })],
+    }
+}
+
+/// Converts a tool result into Cohere's format, which repeats the call it answers, found
+/// by ID among the calls made so far.
+fn cohere_result(
+    calls: &HashMap<&str, &ToolCall>,
+    message: &ChatMessage,
+) -> Option<CohereToolResult> {
+    let MessageType::ToolResult {
+        tool_call_id,
+        name,
+        content,
+        ..
+    } = &message.message_type
+    else {
+        return None;
+    };
+    Some(CohereToolResult {
+        call: calls
+            .get(tool_call_id.as_str())
+            .map(|call| cohere_call(call))
+            .unwrap_or_else(|| CohereToolCall {
+                name: name.clone(),
+                parameters: json!({}),
+            }),
+        outputs: cohere_outputs(content),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+impl Cohere {
+    /// Creates a new Cohere client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Cohere API key
+    /// * `model` - Model to use (defaults to "command-a-03-2025")
+    /// * `max_tokens` - Maximum tokens to generate
+    /// * `temperature` - Sampling temperature
+    /// * `timeout_seconds` - Request timeout in seconds
+    /// * `system` - System prompt, sent as the preamble
+    /// * `top_p` - Top-p sampling parameter
+    /// * `top_k` - Top-k sampling parameter
+    /// * `tools` - Function tools the model may call
+    /// * `json_schema` - JSON schema for structured output
+    /// * `connectors` - IDs of the connectors grounding the replies (e.g. "web-search")
+    /// * `embedding_input_type` - Kind of text embedded (defaults to "search_document")
+    /// * `http` - HTTP settings, such as the base URL (defaults to
+    ///   `https://api.cohere.com/v1`), headers, proxy and retry policy
+    pub fn new(
+        api_key: impl Into<String>,
+        model: Option<String>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+        timeout_seconds: Option<u64>,
+        system: Option<String>,
+        top_p: Option<f32>,
+        top_k: Option<u32>,
+        tools: Option<Vec<Tool>>,
+        json_schema: Option<StructuredOutputFormat>,
+        connectors: Vec<String>,
+        embedding_input_type: Option<String>,
+        http: HttpOptions,
+    ) -> Self {
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
+        Self {
+            api_key: api_key.into(),
+            model: model.unwrap_or("command-a-03-2025".to_string()),
+            max_tokens,
+            temperature,
+            system,
+            timeout_seconds,
+            top_p,
+            top_k,
+            tools,
+            structured_output: json_schema,
+            connectors,
+            embedding_input_type,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://api.cohere.com/v1".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
+        }
+    }
+
+    /// Builds and sends a chat request, applying the per-request overrides.
+    ///
+    /// The last message is sent as the message, or, when the conversation ends with tool
+    /// results, as the tool results of the calls they answer.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Cohere API key".to_string()));
+        }
+        check_no_audio(messages, "Cohere")?;
+
+        // Leading system messages join the configured system prompt in the preamble.
+        let leading = messages
+            .iter()
+            .take_while(|m| m.role == ChatRole::System)
+            .count();
+        let preamble: Vec<&str> = self
+            .system
+            .as_deref()
+            .into_iter()
+            .chain(messages[..leading].iter().map(|m| m.content.as_str()))
+            .collect();
+        let messages = &messages[leading..];
+
+        // Results of the calls made in the last turn are sent apart from the history.
+        let trailing = messages
+            .iter()
+            .rev()
+            .take_while(|m| matches!(m.message_type, MessageType::ToolResult { .. }))
+            .count();
+        let (history, message) = match (trailing, messages.split_last()) {
+            (0, Some((last, history))) => (history, last.content.as_str()),
+            _ => (&messages[..messages.len() - trailing], ""),
+        };
+
+        let mut calls: HashMap<&str, &ToolCall> = HashMap::new();
+        let mut chat_history = Vec::new();
+        for m in history {
+            if let MessageType::ToolUse(tool_calls) = &m.message_type {
+                // Later calls reuse the IDs of earlier turns, so keep the latest.
+                calls.extend(tool_calls.iter().map(|call| (call.id.as_str(), call)));
+            }
+            chat_history.push(match (&m.role, &m.message_type) {
+                (_, MessageType::ToolUse(tool_calls)) => CohereMessage {
+                    role: "CHATBOT",
+                    message: (!m.content.is_empty()).then_some(m.content.as_str()),
+                    tool_calls: Some(tool_calls.iter().map(cohere_call).collect()),
+                    tool_results: None,
+                },
+                (_, MessageType::ToolResult { .. }) => CohereMessage {
+                    role: "TOOL",
+                    message: None,
+                    tool_calls: None,
+                    tool_results: cohere_result(&calls, m).map(|result| vec![result]),
+                },
+                (ChatRole::System, _) => CohereMessage {
+                    role: "SYSTEM",
+                    message: Some(&m.content),
+                    tool_calls: None,
+                    tool_results: None,
+                },
+                (ChatRole::Assistant, _) => CohereMessage {
+                    role: "CHATBOT",
+                    message: Some(&m.content),
+                    tool_calls: None,
+                    tool_results: None,
+                },
+                (ChatRole::User | ChatRole::Tool, _) => CohereMessage {
+                    role: "USER",
+                    message: Some(&m.content),
+                    tool_calls: None,
+                    tool_results: None,
+                },
+            });
+        }
+        let tool_results = messages[messages.len() - trailing..]
+            .iter()
+            .filter_map(|m| cohere_result(&calls, m))
+            .collect();
+
+        let body = CohereChatRequest {
+            model: &self.model,
+            message,
+            chat_history,
+            preamble: (!preamble.is_empty()).then(|| preamble.join("\n\n")),
+            max_tokens: options.max_tokens.or(self.max_tokens),
+            temperature: options.temperature.or(self.temperature),
+            p: options.top_p.or(self.top_p),
+            k: self.top_k,
+            seed: options.seed,
+            stop_sequences: options.stop.clone(),
+            connectors: self
+                .connectors
+                .iter()
+                .map(|id| CohereConnector { id })
+                .collect(),
+            tools: tools.map(|tools| tools.iter().map(cohere_tool).collect()),
+            tool_results,
+            response_format: self
+                .structured_output
+                .as_ref()
+                .map(|format| match &format.schema {
+                    Some(schema) => json!({"type": "json_object", "schema": schema}),
+                    None => json!({"type": "json_object"}),
+                }),
+        };
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&request_body(&body, &self.extra_params)?);
+
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
+        }
+
+        let mut timer = RequestTimer::start();
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        timer.first_byte();
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (CohereChatResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.model = self.model.clone();
+        json_resp.timing = Some(timer.finish("cohere", &self.model));
+        json_resp.request_id = request_id;
+
+        Ok(Box::new(json_resp))
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Cohere {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, tools, options)).await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Cohere {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        let message = ChatMessage::user().content(req.prompt.clone()).build();
+        let response = self.chat(&[message]).await?;
+        Ok(CompletionResponse {
+            text: response.text().unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Cohere {
+    /// Embeds texts with the configured model if it is an embedding model, and with
+    /// [`EMBEDDING_MODEL`] otherwise.
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Cohere API key".to_string()));
+        }
+
+        let body = CohereEmbedRequest {
+            model: if self.model.starts_with("embed") {
+                &self.model
+            } else {
+                EMBEDDING_MODEL
+            },
+            texts: input,
+            input_type: self
+                .embedding_input_type
+                .as_deref()
+                .unwrap_or("search_document"),
+            embedding_types: ["float"],
+        };
+
+        let request = self
+            .client
+            .post(format!("{}/embed", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let json_resp: CohereEmbedResponse = resp.json().await?;
+        Ok(json_resp.embeddings.float)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for Cohere {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.tools.as_deref()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.client
+                .get(format!("{}/models", self.base_url))
+                .bearer_auth(&self.api_key),
+        )
+        .await
+    }
+}
@@ -1,19 +1,32 @@
 //! DeepSeek API client implementation for chat and completion functionality.
 //!
-//! This module provides integration with DeepSeek's models through their API.
+//! This module provides integration with DeepSeek's models through their API. The
+//! reasoning of deepseek-reasoner is returned by [`ChatResponse::thinking`], and the
+//! prompt tokens read from DeepSeek's context cache are counted in
+//! [`Usage::cached_prompt_tokens`].
 
-use crate::chat::{ChatResponse, Tool};
+use crate::chat::{
+    ChatResponse, FinishReason, RequestTimer, ResponseMetadata, ResponseTiming, Tool, Usage,
+};
 #[cfg(feature = "deepseek")]
 use crate::{
-    chat::{ChatMessage, ChatProvider, ChatRole},
+    backends::{
+        check_no_audio, http_client, json_with_raw, probe, request_body, request_id_header,
+        send_request, HttpOptions,
+    },
+    chat::{with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatRole},
     completion::{CompletionProvider, CompletionRequest, CompletionResponse},
     embedding::EmbeddingProvider,
     error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
     LLMProvider,
 };
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::ToolCall;
 
@@ -25,6 +38,10 @@ pub struct DeepSeek {
     pub system: Option<String>,
     pub timeout_seconds: Option<u64>,
     pub stream: Option<bool>,
+    pub base_url: String,
+    pub extra_params: Map<String, Value>,
+    pub retry: Option<RetryPolicy>,
+    pub logging: Option<RequestLogging>,
     client: Client,
 }
 
@@ -39,13 +56,36 @@ struct DeepSeekChatRequest<'a> {
     model: &'a str,
     messages: Vec<DeepSeekChatMessage<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
     stream: bool,
 }
 
 #[derive(Deserialize, Debug)]
 struct DeepSeekChatResponse {
     choices: Vec<DeepSeekChatChoice>,
+    #[serde(default)]
+    usage: Option<DeepSeekUsage>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    system_fingerprint: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
 }
 
 impl std::fmt::Display for DeepSeekChatResponse {
@@ -57,29 +97,86 @@ impl std::fmt::Display for DeepSeekChatResponse {
 #[derive(Deserialize, Debug)]
 struct DeepSeekChatChoice {
     message: DeepSeekChatMsg,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 struct DeepSeekChatMsg {
-    content: String,
+    #[serde(default)]
+    content: Option<String>,
+    /// Reasoning of deepseek-reasoner, returned apart from the answer
+    #[serde(default)]
+    reasoning_content: Option<String>,
 }
+
+/// Token usage reported by DeepSeek, which splits the prompt tokens into those read from
+/// its context cache and the others.
+#[derive(Deserialize, Debug)]
+struct DeepSeekUsage {
+    #[serde(default)]
+    prompt_tokens: u32,
+    #[serde(default)]
+    completion_tokens: u32,
+    #[serde(default)]
+    total_tokens: u32,
+    #[serde(default)]
+    prompt_cache_hit_tokens: Option<u32>,
+}
+
 impl ChatResponse for DeepSeekChatResponse {
-    fn text(&self) -> Option<String> {
-        self.choices.first().and_then(|c| {
-            if c.message.content.is_empty() {
-                None
-            } else {
-                Some(c.message.content.clone())
-            }
+    fn usage(&self) -> Option<Usage> {
+        self.usage.as_ref().map(|usage| Usage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            cached_prompt_tokens: usage.prompt_cache_hit_tokens,
         })
     }
 
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("deepseek".to_string()),
+            model: self.model.clone(),
+            response_id: self.id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: self.system_fingerprint.clone(),
+            timing: self.timing,
+        }
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw)
+    }
+
+    fn text(&self) -> Option<String> {
+        self.choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .filter(|content| !content.is_empty())
+    }
+
+    fn thinking(&self) -> Option<String> {
+        self.choices
+            .first()
+            .and_then(|c| c.message.reasoning_content.clone())
+            .filter(|reasoning| !reasoning.is_empty())
+    }
+
     fn tool_calls(&self) -> Option<Vec<ToolCall>> {
         None
     }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.choices
+            .first()
+            .and_then(|c| c.finish_reason.as_deref())
+            .map(FinishReason::from_provider)
+    }
 }
 
 impl DeepSeek {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         api_key: impl Into<String>,
         model: Option<String>,
@@ -88,11 +185,17 @@ impl DeepSeek {
         timeout_seconds: Option<u64>,
         system: Option<String>,
         stream: Option<bool>,
+        http: HttpOptions,
     ) -> Self {
-        let mut builder = Client::builder();
-        if let Some(sec) = timeout_seconds {
-            builder = builder.timeout(std::time::Duration::from_secs(sec));
-        }
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
         Self {
             api_key: api_key.into(),
             model: model.unwrap_or("deepseek-chat".to_string()),
@@ -101,33 +204,35 @@ impl DeepSeek {
             system,
             timeout_seconds,
             stream,
-            client: builder.build().expect("Failed to build reqwest Client"),
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://api.deepseek.com/v1".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
         }
     }
-}
 
-#[async_trait]
-impl ChatProvider for DeepSeek {
-    /// Sends a chat request to DeepSeek's API.
-    ///
-    /// # Arguments
-    ///
-    /// * `messages` - The conversation history as a slice of chat messages
-    ///
-    /// # Returns
-    ///
-    /// The provider's response text or an error
-    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+    /// Builds and sends a chat request, applying the per-request overrides.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
         if self.api_key.is_empty() {
             return Err(LLMError::AuthError("Missing DeepSeek API key".to_string()));
         }
+        check_no_audio(messages, "DeepSeek")?;
 
         let mut deepseek_msgs: Vec<DeepSeekChatMessage> = messages
             .iter()
             .map(|m| DeepSeekChatMessage {
                 role: match m.role {
+                    ChatRole::System => "system",
                     ChatRole::User => "user",
                     ChatRole::Assistant => "assistant",
+                    ChatRole::Tool => "user",
                 },
                 content: &m.content,
             })
@@ -146,28 +251,67 @@ impl ChatProvider for DeepSeek {
         let body = DeepSeekChatRequest {
             model: &self.model,
             messages: deepseek_msgs,
-            temperature: self.temperature,
+            max_tokens: options.max_tokens.or(self.max_tokens),
+            temperature: options.temperature.or(self.temperature),
+            top_p: options.top_p,
+            stop: options.stop.clone(),
             stream: self.stream.unwrap_or(false),
         };
 
         let mut request = self
             .client
-            .post("https://api.deepseek.com/v1/chat/completions")
+            .post(format!("{}/chat/completions", self.base_url))
             .bearer_auth(&self.api_key)
-            .json(&body);
+            .json(&request_body(&body, &self.extra_params)?);
 
-        if let Some(timeout) = self.timeout_seconds {
-            request = request.timeout(std::time::Duration::from_secs(timeout));
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
         }
 
-        let resp = request.send().await?.error_for_status()?;
+        let mut timer = RequestTimer::start();
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        timer.first_byte();
 
-        let json_resp: DeepSeekChatResponse = resp.json().await?;
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (DeepSeekChatResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.timing = Some(timer.finish("deepseek", &self.model));
+        json_resp.request_id = request_id;
 
         Ok(Box::new(json_resp))
     }
+}
+
+#[async_trait]
+impl ChatProvider for DeepSeek {
+    /// Sends a chat request to DeepSeek's API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    ///
+    /// # Returns
+    ///
+    /// The provider's response text or an error
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, &ChatOptions::default()).await
+    }
 
-    /// Sends a chat request to DeepSeek's API with tools.
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, options)).await
+    }
+
+    /// Sends a chat request to DeepSeek's API. Tools are not sent yet.
     ///
     /// # Arguments
     ///
@@ -179,10 +323,10 @@ impl ChatProvider for DeepSeek {
     /// The provider's response text or an error
     async fn chat_with_tools(
         &self,
-        _messages: &[ChatMessage],
+        messages: &[ChatMessage],
         _tools: Option<&[Tool]>,
     ) -> Result<Box<dyn ChatResponse>, LLMError> {
-        todo!()
+        self.send_chat(messages, &ChatOptions::default()).await
     }
 }
 
@@ -204,4 +348,14 @@ impl EmbeddingProvider for DeepSeek {
     }
 }
 
-impl LLMProvider for DeepSeek {}
+#[async_trait]
+impl LLMProvider for DeepSeek {
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.client
+                .get(format!("{}/models", self.base_url))
+                .bearer_auth(&self.api_key),
+        )
+        .await
+    }
+}
@@ -0,0 +1,65 @@
+//! Fireworks AI client implementation for chat and embedding functionality.
+//!
+//! Fireworks serves open models through OpenAI's chat completions API, tool calls and
+//! embeddings included, so this client drives an [`OpenAI`] client pointed at Fireworks.
+//! Besides JSON mode and JSON schemas, Fireworks can constrain replies with a GBNF
+//! grammar (grammar mode), sent as a `grammar` response format.
+
+use serde_json::json;
+
+use crate::backends::openai::{forward_to_inner, OpenAI};
+
+/// Base URL of Fireworks' API.
+pub const BASE_URL: &str = "https://api.fireworks.ai/inference/v1";
+
+/// Model used when none is configured.
+pub const DEFAULT_MODEL: &str = "accounts/fireworks/models/llama-v3p1-70b-instruct";
+
+/// Model computing embeddings when the configured model is a chat model.
+pub const EMBEDDING_MODEL: &str = "nomic-ai/nomic-embed-text-v1.5";
+
+/// Client for interacting with Fireworks AI's API.
+///
+/// Texts are embedded with the configured model if it is an embedding model, and with
+/// [`EMBEDDING_MODEL`] otherwise.
+pub struct Fireworks {
+    /// GBNF grammar the replies are constrained to
+    grammar: Option<String>,
+    /// OpenAI client sending the requests to Fireworks
+    inner: OpenAI,
+}
+
+impl Fireworks {
+    /// Creates a Fireworks client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the API key, model and generation settings, pointed
+    ///   at [`BASE_URL`] or a compatible server
+    /// * `grammar` - GBNF grammar the replies are constrained to, replacing any structured
+    ///   output format
+    pub fn new(mut openai: OpenAI, grammar: Option<String>) -> Self {
+        openai.provider = "fireworks";
+        if !openai.model.contains("embed") {
+            openai.embedding_model = Some(EMBEDDING_MODEL.to_string());
+        }
+        if let Some(grammar) = &grammar {
+            openai.structured_output = None;
+            openai.extra_params.insert(
+                "response_format".to_string(),
+                json!({"type": "grammar", "grammar": grammar}),
+            );
+        }
+        Self {
+            grammar,
+            inner: openai,
+        }
+    }
+
+    /// Returns the GBNF grammar the replies are constrained to.
+    pub fn grammar(&self) -> Option<&str> {
+        self.grammar.as_deref()
+    }
+}
+
+forward_to_inner!(Fireworks);
@@ -0,0 +1,220 @@
+//! Gemini's Files API, storing media too large to send inline.
+//!
+//! Inline data is limited to about 20 MB per request, which rules out most videos and long
+//! PDFs. An uploaded file is sent to the model as a [`ContentPart::File`] part holding its
+//! URI instead, and is kept for 48 hours. Videos are processed after the upload, and can
+//! only be referenced once [`GoogleFile::state`] is [`GoogleFileState::Active`].
+//!
+//! [`ContentPart::File`]: crate::chat::ContentPart::File
+
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+
+use crate::{backends::send_request, error::LLMError};
+
+use super::Google;
+
+/// Processing state of an uploaded file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GoogleFileState {
+    /// The state was not reported
+    #[default]
+    StateUnspecified,
+    /// The file is being processed and cannot be referenced yet
+    Processing,
+    /// The file can be referenced in messages
+    Active,
+    /// Processing the file failed
+    Failed,
+}
+
+/// A file uploaded to Gemini.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GoogleFile {
+    /// Resource name of the file ("files/..."), to get or delete it
+    pub name: String,
+    /// Name given to the file at upload
+    #[serde(rename = "displayName", default)]
+    pub display_name: Option<String>,
+    /// Media type of the file
+    #[serde(rename = "mimeType", default)]
+    pub mime_type: String,
+    /// Size of the file, in bytes
+    #[serde(rename = "sizeBytes", default, deserialize_with = "int64")]
+    pub size_bytes: u64,
+    /// Upload time, as an RFC 3339 timestamp
+    #[serde(rename = "createTime", default)]
+    pub create_time: Option<String>,
+    /// Time the file is deleted at, as an RFC 3339 timestamp
+    #[serde(rename = "expirationTime", default)]
+    pub expiration_time: Option<String>,
+    /// URI of the file, to reference it in messages
+    #[serde(default)]
+    pub uri: String,
+    /// Processing state of the file
+    #[serde(default)]
+    pub state: GoogleFileState,
+}
+
+/// Response wrapping an uploaded file.
+#[derive(Deserialize)]
+struct GoogleFileResponse {
+    file: GoogleFile,
+}
+
+/// A page of the list of files.
+#[derive(Deserialize)]
+struct GoogleFileList {
+    #[serde(default)]
+    files: Vec<GoogleFile>,
+    #[serde(rename = "nextPageToken", default)]
+    next_page_token: Option<String>,
+}
+
+/// Deserializes an int64, which the API sends as a string.
+fn int64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Int64 {
+        Number(u64),
+        Text(String),
+    }
+
+    match Int64::deserialize(deserializer)? {
+        Int64::Number(value) => Ok(value),
+        Int64::Text(text) => text.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+impl Google {
+    /// Uploads a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `display_name` - Name of the file
+    /// * `bytes` - Content of the file
+    /// * `mime_type` - Media type of the file (e.g. "application/pdf" or "video/mp4")
+    pub async fn upload_file(
+        &self,
+        display_name: &str,
+        bytes: &[u8],
+        mime_type: &str,
+    ) -> Result<GoogleFile, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Google API key".to_string()));
+        }
+
+        // Uploads go to the same API version under /upload, with the resumable protocol:
+        // the first request describes the file and returns the URL the bytes are sent to.
+        let upload_url = match self.base_url.rsplit_once('/') {
+            Some((host, version)) => format!("{}/upload/{}/files", host, version),
+            None => format!("{}/upload/files", self.base_url),
+        };
+        let request = self
+            .request(reqwest::Method::POST, upload_url)
+            .header("X-Goog-Upload-Protocol", "resumable")
+            .header("X-Goog-Upload-Command", "start")
+            .header("X-Goog-Upload-Header-Content-Length", bytes.len())
+            .header("X-Goog-Upload-Header-Content-Type", mime_type)
+            .json(&json!({ "file": { "display_name": display_name } }));
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        let session_url = resp
+            .headers()
+            .get("x-goog-upload-url")
+            .and_then(|url| url.to_str().ok())
+            .ok_or_else(|| {
+                LLMError::ProviderError("No upload URL in the upload response".to_string())
+            })?
+            .to_string();
+
+        let request = self
+            .client
+            .post(session_url)
+            .header("X-Goog-Upload-Offset", 0)
+            .header("X-Goog-Upload-Command", "upload, finalize")
+            .body(bytes.to_vec());
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        let uploaded: GoogleFileResponse = resp.json().await?;
+        Ok(uploaded.file)
+    }
+
+    /// Lists the uploaded files.
+    pub async fn list_files(&self) -> Result<Vec<GoogleFile>, LLMError> {
+        let mut files = Vec::new();
+        let mut page_token = None;
+        loop {
+            let mut request = self
+                .request(reqwest::Method::GET, format!("{}/files", self.base_url))
+                .query(&[("pageSize", "100")]);
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token)]);
+            }
+            let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+                .await?
+                .error_for_status()?;
+            let page: GoogleFileList = resp.json().await?;
+            files.extend(page.files);
+            page_token = page.next_page_token.filter(|token| !token.is_empty());
+            if page_token.is_none() {
+                return Ok(files);
+            }
+        }
+    }
+
+    /// Returns the details of an uploaded file.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Resource name of the file ("files/...")
+    pub async fn file(&self, name: &str) -> Result<GoogleFile, LLMError> {
+        let request = self.request(reqwest::Method::GET, format!("{}/{}", self.base_url, name));
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Polls an uploaded file until it is no longer processing, then returns it. Its
+    /// state tells whether processing succeeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Resource name of the file ("files/...")
+    /// * `poll_interval` - Time waited between two polls
+    pub async fn wait_for_file(
+        &self,
+        name: &str,
+        poll_interval: Duration,
+    ) -> Result<GoogleFile, LLMError> {
+        loop {
+            let file = self.file(name).await?;
+            if file.state != GoogleFileState::Processing {
+                return Ok(file);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Deletes an uploaded file.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Resource name of the file ("files/...")
+    pub async fn delete_file(&self, name: &str) -> Result<(), LLMError> {
+        let request = self.request(
+            reqwest::Method::DELETE,
+            format!("{}/{}", self.base_url, name),
+        );
+        send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,952 @@
+//! Google Gemini API client implementation for chat and completion functionality.
+//!
+//! This module provides integration with Google's Gemini models through their API.
+//! It implements chat, completion and embedding capabilities via the Gemini API.
+//!
+//! # Features
+//! - Chat conversations with system prompts and message history
+//! - Text completion requests
+//! - Configuration options for temperature, tokens, top_p, top_k etc.
+//! - Streaming support
+//!
+//! # Example
+//! ```no_run
+//! use llm::backends::{google::Google, HttpOptions};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//! let client = Google::new(
+//!     "your-api-key",
+//!     None, // Use default model
+//!     Some(1000), // Max tokens
+//!     Some(0.7), // Temperature
+//!     None, // Default timeout
+//!     None, // No system prompt
+//!     None, // No streaming
+//!     None, // Default top_p
+//!     None, // Default top_k
+//!     None, // No structured output
+//!     None, // Single candidate
+//!     None, // Default safety settings
+//!     HttpOptions::default(), // Default base URL, no proxy or retries
+//! );
+//!
+//! let messages = vec![
+//!     ChatMessage::user().content("Hello!").build()
+//! ];
+//!
+//! let response = client.chat(&messages).await.unwrap();
+//! println!("{}", response);
+//! }
+//! ```
+
+use crate::{
+    backends::{
+        http_client, json_with_raw, probe, request_body, request_id_header, send_request,
+        HttpOptions,
+    },
+    chat::{
+        with_cancellation, ChatChoice, ChatMessage, ChatOptions, ChatProvider, ChatResponse,
+        ChatRole, Citation, ContentPart, FinishReason, Grounding, GroundingSupport, MessageType,
+        RequestTimer, ResponseMetadata, ResponseTiming, SafetyRating, SafetySetting,
+        StructuredOutputFormat, Tool, Usage,
+    },
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
+    LLMProvider,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::ToolCall;
+
+mod files;
+
+pub use files::{GoogleFile, GoogleFileState};
+
+/// Client for interacting with Google's Gemini API.
+///
+/// This struct holds the configuration and state needed to make requests to the Gemini API.
+/// It implements the [`ChatProvider`], [`CompletionProvider`], and [`EmbeddingProvider`] traits.
+pub struct Google {
+    /// API key for authentication with Google's API
+    pub api_key: String,
+    /// Model identifier (e.g. "gemini-1.5-flash")
+    pub model: String,
+    /// Maximum number of tokens to generate in responses
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature between 0.0 and 1.0
+    pub temperature: Option<f32>,
+    /// Optional system prompt to set context
+    pub system: Option<String>,
+    /// Request timeout in seconds
+    pub timeout_seconds: Option<u64>,
+    /// Whether to stream responses
+    pub stream: Option<bool>,
+    /// Top-p sampling parameter
+    pub top_p: Option<f32>,
+    /// Top-k sampling parameter
+    pub top_k: Option<u32>,
+    /// JSON schema for structured output
+    pub json_schema: Option<StructuredOutputFormat>,
+    /// Number of candidate replies to generate per request
+    pub n: Option<u32>,
+    /// Thresholds of the content filter, replacing Gemini's defaults
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    /// HTTP client for making API requests
+    pub base_url: String,
+    /// Extra parameters merged into the chat request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
+    client: Client,
+}
+
+/// Request body for chat completions
+#[derive(Serialize)]
+struct GoogleChatRequest<'a> {
+    /// List of conversation messages
+    contents: Vec<GoogleChatContent<'a>>,
+    /// Optional generation parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GoogleGenerationConfig>,
+    /// Thresholds of the content filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    safety_settings: Option<&'a [SafetySetting]>,
+    /// Tools defined by Google, each an object named after its type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<Value>>,
+}
+
+/// Individual message in a chat conversation
+#[derive(Serialize)]
+struct GoogleChatContent<'a> {
+    /// Role of the message sender ("user", "model", or "system")
+    role: &'a str,
+    /// Content parts of the message
+    parts: Vec<GoogleContentPart<'a>>,
+}
+
+/// Text content within a chat message
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum GoogleContentPart<'a> {
+    /// The actual text content
+    Text(&'a str),
+    InlineData(GoogleInlineData),
+    /// A function call previously requested by the model
+    FunctionCall(GoogleFunctionCall<'a>),
+    /// The output of a function call
+    FunctionResponse(GoogleFunctionResponse<'a>),
+    /// A file uploaded with the Files API
+    FileData(GoogleFileData<'a>),
+}
+
+/// Reference to an uploaded file
+#[derive(Serialize)]
+struct GoogleFileData<'a> {
+    /// URI of the file
+    file_uri: &'a str,
+}
+
+/// Function call replayed in the conversation history
+#[derive(Serialize)]
+struct GoogleFunctionCall<'a> {
+    /// Name of the called function
+    name: &'a str,
+    /// Arguments the function was called with
+    args: Value,
+}
+
+/// Function output sent back to the model
+#[derive(Serialize)]
+struct GoogleFunctionResponse<'a> {
+    /// Name of the called function
+    name: &'a str,
+    /// Output of the function, as a JSON object
+    response: Value,
+}
+
+#[derive(Serialize)]
+struct GoogleInlineData {
+    mime_type: String,
+    data: String,
+}
+
+/// Configuration parameters for text generation
+#[derive(Serialize)]
+struct GoogleGenerationConfig {
+    /// Maximum tokens to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    /// Top-p sampling parameter
+    #[serde(skip_serializing_if = "Option::is_none", rename = "topP")]
+    top_p: Option<f32>,
+    /// Top-k sampling parameter
+    #[serde(skip_serializing_if = "Option::is_none", rename = "topK")]
+    top_k: Option<u32>,
+    /// Number of candidates to generate
+    #[serde(skip_serializing_if = "Option::is_none", rename = "candidateCount")]
+    candidate_count: Option<u32>,
+    /// Sequences ending the generation
+    #[serde(skip_serializing_if = "Option::is_none", rename = "stopSequences")]
+    stop_sequences: Option<Vec<String>>,
+    /// Seed for deterministic sampling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    /// The MIME type of the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<GoogleResponseMimeType>,
+    /// A schema for structured output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_schema: Option<Value>,
+}
+
+/// Response from the chat completion API
+#[derive(Deserialize, Debug)]
+struct GoogleChatResponse {
+    /// Generated completion candidates, absent when the prompt was blocked
+    #[serde(default)]
+    candidates: Vec<GoogleCandidate>,
+    /// Feedback on the prompt, explaining why it was blocked
+    #[serde(rename = "promptFeedback", default)]
+    prompt_feedback: Option<GooglePromptFeedback>,
+    /// Token usage of the request
+    #[serde(rename = "usageMetadata", default)]
+    usage_metadata: Option<GoogleUsageMetadata>,
+    /// ID of the response
+    #[serde(rename = "responseId", default)]
+    response_id: Option<String>,
+    /// Version of the model that served the request
+    #[serde(rename = "modelVersion", default)]
+    model_version: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
+}
+
+impl std::fmt::Display for GoogleChatResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Token usage reported by the Gemini API
+#[derive(Deserialize, Debug)]
+struct GoogleUsageMetadata {
+    /// Tokens in the prompt
+    #[serde(rename = "promptTokenCount", default)]
+    prompt_token_count: u32,
+    /// Tokens across the generated candidates
+    #[serde(rename = "candidatesTokenCount", default)]
+    candidates_token_count: u32,
+    /// Tokens of the whole request
+    #[serde(rename = "totalTokenCount", default)]
+    total_token_count: u32,
+}
+
+/// Feedback on a chat prompt
+#[derive(Deserialize, Debug)]
+struct GooglePromptFeedback {
+    /// Why the prompt was blocked, if it was
+    #[serde(rename = "blockReason", default)]
+    block_reason: Option<String>,
+    /// Ratings of the prompt by the content filter
+    #[serde(rename = "safetyRatings", default)]
+    safety_ratings: Vec<SafetyRating>,
+}
+
+/// Individual completion candidate
+#[derive(Deserialize, Debug)]
+struct GoogleCandidate {
+    /// Content of the candidate response, absent when the filter blocked it
+    #[serde(default)]
+    content: GoogleResponseContent,
+    /// Why generation stopped
+    #[serde(rename = "finishReason", default)]
+    finish_reason: Option<String>,
+    /// Ratings of the candidate by the content filter
+    #[serde(rename = "safetyRatings", default)]
+    safety_ratings: Vec<SafetyRating>,
+    /// How the candidate is grounded in Google Search results
+    #[serde(rename = "groundingMetadata", default)]
+    grounding_metadata: Option<GoogleGroundingMetadata>,
+}
+
+/// Searches grounding a candidate, with their results
+#[derive(Deserialize, Debug)]
+struct GoogleGroundingMetadata {
+    /// Queries the model searched for
+    #[serde(rename = "webSearchQueries", default)]
+    web_search_queries: Vec<String>,
+    /// Results of the searches
+    #[serde(rename = "groundingChunks", default)]
+    grounding_chunks: Vec<GoogleGroundingChunk>,
+    /// Parts of the candidate, with the results supporting them
+    #[serde(rename = "groundingSupports", default)]
+    grounding_supports: Vec<GoogleGroundingSupport>,
+    /// Search suggestions to display with the candidate
+    #[serde(rename = "searchEntryPoint", default)]
+    search_entry_point: Option<GoogleSearchEntryPoint>,
+}
+
+/// A search result
+#[derive(Deserialize, Debug)]
+struct GoogleGroundingChunk {
+    /// The web page found, for Google Search results
+    #[serde(default)]
+    web: Option<GoogleWebSource>,
+}
+
+/// A web page found by a search
+#[derive(Deserialize, Debug, Default, Clone)]
+struct GoogleWebSource {
+    #[serde(default)]
+    uri: String,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+/// A part of a candidate, with the search results supporting it
+#[derive(Deserialize, Debug)]
+struct GoogleGroundingSupport {
+    #[serde(default)]
+    segment: GoogleSegment,
+    /// Positions of the supporting results in the grounding chunks
+    #[serde(rename = "groundingChunkIndices", default)]
+    grounding_chunk_indices: Vec<usize>,
+    #[serde(rename = "confidenceScores", default)]
+    confidence_scores: Vec<f32>,
+}
+
+/// A part of a candidate's text
+#[derive(Deserialize, Debug, Default)]
+struct GoogleSegment {
+    #[serde(rename = "startIndex", default)]
+    start_index: usize,
+    #[serde(rename = "endIndex", default)]
+    end_index: usize,
+    #[serde(default)]
+    text: String,
+}
+
+/// Search suggestions to display with a grounded candidate
+#[derive(Deserialize, Debug)]
+struct GoogleSearchEntryPoint {
+    /// HTML and CSS of the suggestions
+    #[serde(rename = "renderedContent", default)]
+    rendered_content: Option<String>,
+}
+
+impl GoogleGroundingMetadata {
+    /// Converts the metadata, keeping one source per chunk since the supports refer to the
+    /// chunks by position.
+    fn grounding(&self) -> Grounding {
+        Grounding {
+            queries: self.web_search_queries.clone(),
+            sources: self
+                .grounding_chunks
+                .iter()
+                .map(|chunk| {
+                    let web = chunk.web.clone().unwrap_or_default();
+                    Citation {
+                        url: web.uri,
+                        title: web.title,
+                        date: None,
+                    }
+                })
+                .collect(),
+            supports: self
+                .grounding_supports
+                .iter()
+                .map(|support| GroundingSupport {
+                    text: support.segment.text.clone(),
+                    start_index: support.segment.start_index,
+                    end_index: support.segment.end_index,
+                    source_indices: support.grounding_chunk_indices.clone(),
+                    confidence_scores: support.confidence_scores.clone(),
+                })
+                .collect(),
+            search_entry_point: self
+                .search_entry_point
+                .as_ref()
+                .and_then(|entry_point| entry_point.rendered_content.clone()),
+        }
+    }
+}
+
+impl GoogleCandidate {
+    /// Returns why the content filter blocked the candidate, if it did.
+    fn block_reason(&self) -> Option<String> {
+        let reason = self.finish_reason.as_deref()?;
+        (FinishReason::from_provider(reason) == FinishReason::ContentFilter)
+            .then(|| format!("Response blocked: {}", reason))
+    }
+}
+
+/// Content block within a response
+#[derive(Deserialize, Debug, Default)]
+struct GoogleResponseContent {
+    /// Parts making up the content
+    #[serde(default)]
+    parts: Vec<GoogleResponsePart>,
+}
+
+impl ChatResponse for GoogleChatResponse {
+    fn usage(&self) -> Option<Usage> {
+        self.usage_metadata.as_ref().map(|usage| Usage {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+            total_tokens: usage.total_token_count,
+            cached_prompt_tokens: None,
+        })
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("google".to_string()),
+            model: self.model_version.clone(),
+            response_id: self.response_id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: None,
+            timing: self.timing,
+        }
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw)
+    }
+
+    fn text(&self) -> Option<String> {
+        self.candidates
+            .first()
+            .map(|c| c.content.parts.iter().map(|p| p.text.clone()).collect())
+    }
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        None
+    }
+
+    fn refusal(&self) -> Option<String> {
+        self.prompt_feedback
+            .as_ref()
+            .and_then(|feedback| feedback.block_reason.as_ref())
+            .map(|reason| format!("Prompt blocked: {}", reason))
+            .or_else(|| {
+                self.candidates
+                    .first()
+                    .and_then(GoogleCandidate::block_reason)
+            })
+    }
+
+    fn safety_ratings(&self) -> Option<Vec<SafetyRating>> {
+        let ratings = match self.candidates.first() {
+            Some(candidate) => &candidate.safety_ratings,
+            None => &self.prompt_feedback.as_ref()?.safety_ratings,
+        };
+        (!ratings.is_empty()).then(|| ratings.clone())
+    }
+
+    fn grounding(&self) -> Option<Grounding> {
+        self.candidates
+            .first()?
+            .grounding_metadata
+            .as_ref()
+            .map(GoogleGroundingMetadata::grounding)
+    }
+
+    fn citations(&self) -> Option<Vec<Citation>> {
+        let sources: Vec<Citation> = self
+            .grounding()?
+            .sources
+            .into_iter()
+            .filter(|source| !source.url.is_empty())
+            .collect();
+        (!sources.is_empty()).then_some(sources)
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.candidates
+            .first()
+            .and_then(|c| c.finish_reason.as_deref())
+            .map(FinishReason::from_provider)
+    }
+
+    fn choices(&self) -> Vec<ChatChoice> {
+        self.candidates
+            .iter()
+            .map(|c| ChatChoice {
+                text: Some(c.content.parts.iter().map(|p| p.text.clone()).collect()),
+                tool_calls: None,
+                finish_reason: c.finish_reason.as_deref().map(FinishReason::from_provider),
+                refusal: c.block_reason(),
+                logprobs: None,
+            })
+            .collect()
+    }
+}
+
+/// Individual part of response content
+#[derive(Deserialize, Debug)]
+struct GoogleResponsePart {
+    /// Text content of this part
+    #[serde(default)]
+    text: String,
+}
+
+/// MIME type of the response
+#[derive(Deserialize, Debug, Serialize)]
+enum GoogleResponseMimeType {
+    /// Plain text response
+    #[serde(rename = "text/plain")]
+    PlainText,
+    /// JSON response
+    #[serde(rename = "application/json")]
+    Json,
+    /// ENUM as a string response in the response candidates.
+    #[serde(rename = "text/x.enum")]
+    Enum,
+}
+
+/// Request body for embedding content
+#[derive(Serialize)]
+struct GoogleEmbeddingRequest<'a> {
+    model: &'a str,
+    content: GoogleEmbeddingContent<'a>,
+}
+
+#[derive(Serialize)]
+struct GoogleEmbeddingContent<'a> {
+    parts: Vec<GoogleContentPart<'a>>,
+}
+
+/// Response from the embedding API
+#[derive(Deserialize)]
+struct GoogleEmbeddingResponse {
+    embedding: GoogleEmbedding,
+}
+
+#[derive(Deserialize)]
+struct GoogleEmbedding {
+    values: Vec<f32>,
+}
+
+impl Google {
+    /// Creates a new Google Gemini client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Google API key for authentication
+    /// * `model` - Model identifier (defaults to "gemini-1.5-flash")
+    /// * `max_tokens` - Maximum tokens in response
+    /// * `temperature` - Sampling temperature between 0.0 and 1.0
+    /// * `timeout_seconds` - Request timeout in seconds
+    /// * `system` - System prompt to set context
+    /// * `stream` - Whether to stream responses
+    /// * `top_p` - Top-p sampling parameter
+    /// * `top_k` - Top-k sampling parameter
+    /// * `json_schema` - JSON schema for structured output
+    /// * `n` - Number of candidate replies to generate per request
+    /// * `safety_settings` - Thresholds of the content filter, replacing Gemini's defaults
+    /// * `http` - HTTP settings, such as the base URL (defaults to
+    ///   `https://generativelanguage.googleapis.com/v1beta`), headers, proxy and retry policy
+    ///
+    /// # Returns
+    ///
+    /// A new `Google` client instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        api_key: impl Into<String>,
+        model: Option<String>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+        timeout_seconds: Option<u64>,
+        system: Option<String>,
+        stream: Option<bool>,
+        top_p: Option<f32>,
+        top_k: Option<u32>,
+        json_schema: Option<StructuredOutputFormat>,
+        n: Option<u32>,
+        safety_settings: Option<Vec<SafetySetting>>,
+        http: HttpOptions,
+    ) -> Self {
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
+        Self {
+            api_key: api_key.into(),
+            model: model.unwrap_or_else(|| "gemini-1.5-flash".to_string()),
+            max_tokens,
+            temperature,
+            system,
+            timeout_seconds,
+            stream,
+            top_p,
+            top_k,
+            json_schema,
+            n,
+            safety_settings,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://generativelanguage.googleapis.com/v1beta".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
+        }
+    }
+
+    /// Starts an authenticated request to a URL of the API.
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .query(&[("key", &self.api_key)])
+    }
+
+    /// Builds and sends a chat request, applying the per-request overrides.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Google API key".to_string()));
+        }
+
+        let mut chat_contents = Vec::with_capacity(messages.len());
+
+        // Add system message if present
+        if let Some(system) = &self.system {
+            chat_contents.push(GoogleChatContent {
+                role: "user",
+                parts: vec![GoogleContentPart::Text(system)],
+            });
+        }
+
+        // Add conversation messages in pairs to maintain context
+        for msg in messages {
+            chat_contents.push(GoogleChatContent {
+                role: match (&msg.role, &msg.message_type) {
+                    (_, MessageType::ToolResult { .. }) => "user",
+                    (_, MessageType::ToolUse(_)) => "model",
+                    // System messages are sent as user turns, like the system prompt.
+                    (ChatRole::System | ChatRole::User | ChatRole::Tool, _) => "user",
+                    (ChatRole::Assistant, _) => "model",
+                },
+                parts: match &msg.message_type {
+                    MessageType::Text => vec![GoogleContentPart::Text(&msg.content)],
+                    MessageType::Image((image_mime, raw_bytes)) => {
+                        vec![GoogleContentPart::InlineData(GoogleInlineData {
+                            mime_type: image_mime.mime_type().to_string(),
+                            data: BASE64.encode(raw_bytes),
+                        })]
+                    }
+                    MessageType::ImageURL(_) => return Err(image_url_unsupported()),
+                    MessageType::Parts(parts) => parts
+                        .iter()
+                        .map(content_part)
+                        .collect::<Result<Vec<_>, _>>()?,
+                    MessageType::Audio((audio_mime, raw_bytes)) => {
+                        vec![GoogleContentPart::InlineData(GoogleInlineData {
+                            mime_type: audio_mime.mime_type().to_string(),
+                            data: BASE64.encode(raw_bytes),
+                        })]
+                    }
+                    MessageType::Pdf(raw_bytes) => {
+                        vec![GoogleContentPart::InlineData(GoogleInlineData {
+                            mime_type: "application/pdf".to_string(),
+                            data: BASE64.encode(raw_bytes),
+                        })]
+                    }
+                    MessageType::ToolUse(calls) => calls
+                        .iter()
+                        .map(|call| {
+                            GoogleContentPart::FunctionCall(GoogleFunctionCall {
+                                name: &call.function.name,
+                                args: serde_json::from_str(&call.function.arguments)
+                                    .unwrap_or_else(|_| Value::Object(Default::default())),
+                            })
+                        })
+                        .collect(),
+                    MessageType::ToolResult { name, content, .. } => {
+                        // Gemini expects the response to be an object, so wrap anything else.
+                        let response = match serde_json::from_str::<Value>(content) {
+                            Ok(value @ Value::Object(_)) => value,
+                            _ => json!({ "content": content }),
+                        };
+                        vec![GoogleContentPart::FunctionResponse(
+                            GoogleFunctionResponse { name, response },
+                        )]
+                    }
+                },
+            });
+        }
+
+        let max_tokens = options.max_tokens.or(self.max_tokens);
+        let temperature = options.temperature.or(self.temperature);
+        let top_p = options.top_p.or(self.top_p);
+
+        // Remove generation_config if empty to avoid validation errors
+        let generation_config = if max_tokens.is_none()
+            && temperature.is_none()
+            && top_p.is_none()
+            && self.top_k.is_none()
+            && self.n.is_none()
+            && options.stop.is_none()
+            && options.seed.is_none()
+            && self.json_schema.is_none()
+        {
+            None
+        } else {
+            // If json_schema and json_schema.schema are not None, use json_schema.schema as the response schema and set response_mime_type to JSON
+            // Google's API doesn't need the schema to have a "name" field, so we can just use the schema directly.
+            let (response_mime_type, response_schema) = if let Some(json_schema) = &self.json_schema
+            {
+                if let Some(schema) = &json_schema.schema {
+                    // If the schema has an "additionalProperties" field (as required by OpenAI), remove it as Google's API doesn't support it
+                    let mut schema = schema.clone();
+                    if let Some(obj) = schema.as_object_mut() {
+                        obj.remove("additionalProperties");
+                    }
+                    (Some(GoogleResponseMimeType::Json), Some(schema))
+                } else {
+                    (None, None)
+                }
+            } else {
+                (None, None)
+            };
+
+            Some(GoogleGenerationConfig {
+                max_output_tokens: max_tokens,
+                temperature,
+                top_p,
+                top_k: self.top_k,
+                candidate_count: self.n,
+                stop_sequences: options.stop.clone(),
+                seed: options.seed,
+                response_mime_type,
+                response_schema,
+            })
+        };
+
+        // Google's own tools, such as {"google_search": {}}. Function tools are not sent.
+        let google_tools: Vec<Value> = tools
+            .into_iter()
+            .flatten()
+            .filter(|tool| !tool.is_function())
+            .map(|tool| json!({ tool.tool_type.as_str(): tool.settings }))
+            .collect();
+
+        let req_body = GoogleChatRequest {
+            contents: chat_contents,
+            generation_config,
+            safety_settings: options
+                .safety_settings
+                .as_deref()
+                .or(self.safety_settings.as_deref()),
+            tools: (!google_tools.is_empty()).then_some(google_tools),
+        };
+
+        let url = format!(
+            "{base_url}/models/{model}:generateContent?key={key}",
+            base_url = self.base_url,
+            model = self.model,
+            key = self.api_key
+        );
+
+        let mut request = self
+            .client
+            .post(&url)
+            .json(&request_body(&req_body, &self.extra_params)?);
+
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
+        }
+
+        let mut timer = RequestTimer::start();
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        timer.first_byte();
+
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (GoogleChatResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.timing = Some(timer.finish("google", &self.model));
+        json_resp.request_id = request_id;
+        Ok(Box::new(json_resp))
+    }
+}
+
+/// Error returned for image URLs, which Gemini does not download.
+fn image_url_unsupported() -> LLMError {
+    LLMError::InvalidRequest(
+        "Google does not support image URLs, send the image bytes instead".to_string(),
+    )
+}
+
+/// Converts a part of a multi-part message into a Gemini content part.
+fn content_part(part: &ContentPart) -> Result<GoogleContentPart<'_>, LLMError> {
+    match part {
+        ContentPart::Text(text) => Ok(GoogleContentPart::Text(text)),
+        ContentPart::Image((image_mime, raw_bytes)) => {
+            Ok(GoogleContentPart::InlineData(GoogleInlineData {
+                mime_type: image_mime.mime_type().to_string(),
+                data: BASE64.encode(raw_bytes),
+            }))
+        }
+        ContentPart::Pdf(raw_bytes) => Ok(GoogleContentPart::InlineData(GoogleInlineData {
+            mime_type: "application/pdf".to_string(),
+            data: BASE64.encode(raw_bytes),
+        })),
+        ContentPart::Audio((audio_mime, raw_bytes)) => {
+            Ok(GoogleContentPart::InlineData(GoogleInlineData {
+                mime_type: audio_mime.mime_type().to_string(),
+                data: BASE64.encode(raw_bytes),
+            }))
+        }
+        ContentPart::ImageURL(_) => Err(image_url_unsupported()),
+        ContentPart::File(uri) => Ok(GoogleContentPart::FileData(GoogleFileData {
+            file_uri: uri,
+        })),
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Google {
+    /// Sends a chat request to Google's Gemini API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Slice of chat messages representing the conversation
+    ///
+    /// # Returns
+    ///
+    /// The model's response text or an error
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, None, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, tools, options)).await
+    }
+
+    /// Sends a chat request to Google's Gemini API with tools.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    /// * `tools` - Optional slice of tools to use in the chat
+    ///
+    /// # Returns
+    ///
+    /// The provider's response text or an error
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Google {
+    /// Performs a completion request using the chat endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - Completion request parameters
+    ///
+    /// # Returns
+    ///
+    /// The completion response or an error
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        let chat_message = ChatMessage::user().content(req.prompt.clone()).build();
+        if let Some(text) = self.chat(&[chat_message]).await?.text() {
+            Ok(CompletionResponse { text })
+        } else {
+            Err(LLMError::ProviderError(
+                "No answer returned by Google".to_string(),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Google {
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Google API key".to_string()));
+        }
+
+        let mut embeddings = Vec::new();
+
+        // Process each text separately as Gemini API accepts one text at a time
+        for text in texts {
+            let req_body = GoogleEmbeddingRequest {
+                model: "models/text-embedding-004",
+                content: GoogleEmbeddingContent {
+                    parts: vec![GoogleContentPart::Text(&text)],
+                },
+            };
+
+            let url = format!(
+                "{}/models/text-embedding-004:embedContent?key={}",
+                self.base_url, self.api_key
+            );
+
+            let request = self.client.post(&url).json(&req_body);
+            let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+                .await?
+                .error_for_status()?;
+
+            let embedding_resp: GoogleEmbeddingResponse = resp.json().await?;
+            embeddings.push(embedding_resp.embedding.values);
+        }
+
+        Ok(embeddings)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for Google {
+    async fn validate(&self) -> HealthStatus {
+        probe(self.request(reqwest::Method::GET, format!("{}/models", self.base_url))).await
+    }
+}
@@ -3,15 +3,26 @@
 //! This module provides integration with Groq's LLM models through their API.
 
 use crate::{
-    chat::{ChatMessage, ChatProvider, ChatResponse, ChatRole, Tool},
+    backends::{
+        check_no_audio, http_client, json_with_raw, probe, request_body, request_id_header,
+        send_request, HttpOptions,
+    },
+    chat::{
+        with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatRole,
+        FinishReason, RequestTimer, ResponseMetadata, ResponseTiming, Tool, Usage,
+    },
     completion::{CompletionProvider, CompletionRequest, CompletionResponse},
     embedding::EmbeddingProvider,
     error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
     LLMProvider, ToolCall,
 };
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 /// Client for interacting with Groq's API.
 pub struct Groq {
@@ -24,6 +35,10 @@ pub struct Groq {
     pub stream: Option<bool>,
     pub top_p: Option<f32>,
     pub top_k: Option<u32>,
+    pub base_url: String,
+    pub extra_params: Map<String, Value>,
+    pub retry: Option<RetryPolicy>,
+    pub logging: Option<RequestLogging>,
     client: Client,
 }
 
@@ -46,16 +61,39 @@ struct GroqChatRequest<'a> {
     top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
 }
 
 #[derive(Deserialize, Debug)]
 struct GroqChatResponse {
     choices: Vec<GroqChatChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    system_fingerprint: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
 }
 
 #[derive(Deserialize, Debug)]
 struct GroqChatChoice {
     message: GroqChatMsg,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -70,6 +108,25 @@ impl std::fmt::Display for GroqChatResponse {
 }
 
 impl ChatResponse for GroqChatResponse {
+    fn usage(&self) -> Option<Usage> {
+        self.usage.clone()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("groq".to_string()),
+            model: self.model.clone(),
+            response_id: self.id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: self.system_fingerprint.clone(),
+            timing: self.timing,
+        }
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw)
+    }
+
     fn text(&self) -> Option<String> {
         self.choices.first().and_then(|c| {
             if c.message.content.is_empty() {
@@ -81,7 +138,14 @@ impl ChatResponse for GroqChatResponse {
     }
 
     fn tool_calls(&self) -> Option<Vec<ToolCall>> {
-        todo!()
+        None
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.choices
+            .first()
+            .and_then(|c| c.finish_reason.as_deref())
+            .map(FinishReason::from_provider)
     }
 }
 
@@ -98,11 +162,17 @@ impl Groq {
         stream: Option<bool>,
         top_p: Option<f32>,
         top_k: Option<u32>,
+        http: HttpOptions,
     ) -> Self {
-        let mut builder = Client::builder();
-        if let Some(sec) = timeout_seconds {
-            builder = builder.timeout(std::time::Duration::from_secs(sec));
-        }
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
         Self {
             api_key: api_key.into(),
             model: model.unwrap_or("llama-3.3-70b-versatile".to_string()),
@@ -113,24 +183,35 @@ impl Groq {
             stream,
             top_p,
             top_k,
-            client: builder.build().expect("Failed to build reqwest Client"),
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://api.groq.com/openai/v1".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
         }
     }
-}
 
-#[async_trait]
-impl ChatProvider for Groq {
-    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+    /// Builds and sends a chat request, applying the per-request overrides.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
         if self.api_key.is_empty() {
             return Err(LLMError::AuthError("Missing Groq API key".to_string()));
         }
+        check_no_audio(messages, "Groq")?;
 
         let mut groq_msgs: Vec<GroqChatMessage> = messages
             .iter()
             .map(|m| GroqChatMessage {
                 role: match m.role {
+                    ChatRole::System => "system",
                     ChatRole::User => "user",
                     ChatRole::Assistant => "assistant",
+                    ChatRole::Tool => "user",
                 },
                 content: &m.content,
             })
@@ -149,36 +230,65 @@ impl ChatProvider for Groq {
         let body = GroqChatRequest {
             model: &self.model,
             messages: groq_msgs,
-            max_tokens: self.max_tokens,
-            temperature: self.temperature,
+            max_tokens: options.max_tokens.or(self.max_tokens),
+            temperature: options.temperature.or(self.temperature),
             stream: self.stream.unwrap_or(false),
-            top_p: self.top_p,
+            top_p: options.top_p.or(self.top_p),
             top_k: self.top_k,
+            stop: options.stop.clone(),
+            seed: options.seed,
         };
 
         let mut request = self
             .client
-            .post("https://api.groq.com/openai/v1/chat/completions")
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Content-Type", "application/json")
             .bearer_auth(&self.api_key)
-            .json(&body);
+            .json(&request_body(&body, &self.extra_params)?);
 
-        if let Some(timeout) = self.timeout_seconds {
-            request = request.timeout(std::time::Duration::from_secs(timeout));
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
         }
 
-        let resp = request.send().await?.error_for_status()?;
-        let json_resp: GroqChatResponse = resp.json().await?;
+        let mut timer = RequestTimer::start();
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        timer.first_byte();
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (GroqChatResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.timing = Some(timer.finish("groq", &self.model));
+        json_resp.request_id = request_id;
 
         Ok(Box::new(json_resp))
     }
+}
+
+#[async_trait]
+impl ChatProvider for Groq {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, &ChatOptions::default()).await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, options)).await
+    }
 
     async fn chat_with_tools(
         &self,
-        _messages: &[ChatMessage],
+        messages: &[ChatMessage],
         _tools: Option<&[Tool]>,
     ) -> Result<Box<dyn ChatResponse>, LLMError> {
-        todo!()
+        self.send_chat(messages, &ChatOptions::default()).await
     }
 }
 
@@ -200,4 +310,49 @@ impl EmbeddingProvider for Groq {
     }
 }
 
-impl LLMProvider for Groq {}
+#[async_trait]
+impl LLMProvider for Groq {
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.client
+                .get(format!("{}/models", self.base_url))
+                .bearer_auth(&self.api_key),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        builder::{LLMBackend, LLMBuilder},
+        chat::{AudioMime, ChatMessage, ContentPart},
+        error::LLMError,
+    };
+
+    #[tokio::test]
+    async fn rejects_audio_input() {
+        let llm = LLMBuilder::new()
+            .backend(LLMBackend::Groq)
+            .api_key("key")
+            .base_url("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        for message in [
+            ChatMessage::user()
+                .audio(AudioMime::WAV, vec![0; 16])
+                .build(),
+            ChatMessage::user()
+                .parts(vec![
+                    ContentPart::Text("Transcribe this".to_string()),
+                    ContentPart::Audio((AudioMime::WAV, vec![0; 16])),
+                ])
+                .build(),
+        ] {
+            assert!(matches!(
+                llm.chat(&[message]).await,
+                Err(LLMError::InvalidRequest(_))
+            ));
+        }
+    }
+}
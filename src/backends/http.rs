@@ -0,0 +1,265 @@
+//! HTTP plumbing shared by the backends that call a provider's API: building clients and
+//! request bodies, sending requests with retries and rate-limit handling, and health
+//! probes.
+
+// Each backend uses only some of these helpers, so builds with a few backends leave some
+// unused.
+#![cfg_attr(not(feature = "full"), allow(dead_code))]
+
+/// Reads a JSON response body, returning both the parsed response and the original
+/// document so provider-specific fields stay reachable.
+pub(crate) async fn json_with_raw<T: serde::de::DeserializeOwned>(
+    resp: reqwest::Response,
+) -> Result<(T, serde_json::Value), crate::error::LLMError> {
+    let raw: serde_json::Value = resp.json().await?;
+    let parsed = serde_json::from_value(raw.clone())
+        .map_err(|e| crate::error::LLMError::JsonError(e.to_string()))?;
+    Ok((parsed, raw))
+}
+
+/// Returns the provider's ID of an HTTP request, from its request-ID header.
+pub(crate) fn request_id_header(resp: &reqwest::Response) -> Option<String> {
+    ["x-request-id", "request-id"]
+        .iter()
+        .find_map(|name| resp.headers().get(*name)?.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Returns the HTTP client a backend sends its requests with: the client supplied by the
+/// user, or a new one configured with the timeout, default headers and proxy.
+pub(crate) fn http_client(
+    client: Option<reqwest::Client>,
+    timeout_seconds: Option<u64>,
+    headers: Option<reqwest::header::HeaderMap>,
+    proxy: Option<reqwest::Proxy>,
+) -> reqwest::Client {
+    if let Some(client) = client {
+        return client;
+    }
+    client_builder(timeout_seconds, headers, proxy)
+        .build()
+        .expect("Failed to build reqwest Client")
+}
+
+/// Returns a builder of HTTP clients configured with the timeout, default headers and
+/// proxy.
+pub(crate) fn client_builder(
+    timeout_seconds: Option<u64>,
+    headers: Option<reqwest::header::HeaderMap>,
+    proxy: Option<reqwest::Proxy>,
+) -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder();
+    if let Some(sec) = timeout_seconds {
+        builder = builder.timeout(std::time::Duration::from_secs(sec));
+    }
+    if let Some(headers) = headers {
+        builder = builder.default_headers(headers);
+    }
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    builder
+}
+
+/// Serializes a request body and merges the user's extra parameters into it.
+///
+/// Nested objects are merged key by key; any other value replaces the one in the body.
+pub(crate) fn request_body(
+    body: &impl serde::Serialize,
+    extra_params: &serde_json::Map<String, serde_json::Value>,
+) -> Result<serde_json::Value, crate::error::LLMError> {
+    let mut body =
+        serde_json::to_value(body).map_err(|e| crate::error::LLMError::JsonError(e.to_string()))?;
+    merge_params(&mut body, extra_params);
+    Ok(body)
+}
+
+fn merge_params(
+    target: &mut serde_json::Value,
+    params: &serde_json::Map<String, serde_json::Value>,
+) {
+    let Some(target) = target.as_object_mut() else {
+        return;
+    };
+    for (key, value) in params {
+        match (target.get_mut(key), value) {
+            (Some(existing @ serde_json::Value::Object(_)), serde_json::Value::Object(nested)) => {
+                merge_params(existing, nested)
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Sends a request, resending it while the retry policy allows it.
+///
+/// Every attempt is logged if logging is enabled. The last response is returned whatever its status, for the caller to check, except for
+/// rate limits, which are returned as [`crate::error::LLMError::RateLimited`]. Rate-limited
+/// requests are retried after the delay the provider asks for, or not at all if it exceeds
+/// the policy's longest delay. Requests whose body cannot be cloned are sent once.
+pub(crate) async fn send_request(
+    mut request: reqwest::RequestBuilder,
+    retry: Option<&crate::retry::RetryPolicy>,
+    logging: Option<&crate::logging::RequestLogging>,
+) -> Result<reqwest::Response, crate::error::LLMError> {
+    use crate::retry::RetryOn;
+
+    let Some(policy) = retry else {
+        return rate_limit_error(send(request, logging).await?).await;
+    };
+    let mut attempt = 1;
+    loop {
+        let next = match request.try_clone() {
+            Some(next) if attempt < policy.max_attempts => next,
+            _ => return rate_limit_error(send(request, logging).await?).await,
+        };
+
+        let result = send(request, logging).await;
+        let class = match &result {
+            Ok(resp) if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                Some(RetryOn::RateLimit)
+            }
+            Ok(resp) if resp.status().is_server_error() => Some(RetryOn::ServerError),
+            Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => {
+                Some(RetryOn::Transport)
+            }
+            _ => None,
+        };
+        let delay = match (&result, class) {
+            (_, Some(class)) if !policy.retries(class) => None,
+            (Ok(resp), Some(RetryOn::RateLimit)) => match retry_after(resp.headers()) {
+                Some(delay) => Some(delay).filter(|delay| *delay <= policy.max_delay),
+                None => Some(policy.delay(attempt)),
+            },
+            (_, Some(_)) => Some(policy.delay(attempt)),
+            (_, None) => None,
+        };
+        let Some(delay) = delay else {
+            return rate_limit_error(result?).await;
+        };
+
+        tokio::time::sleep(delay).await;
+        request = next;
+        attempt += 1;
+    }
+}
+
+/// Sends a request once, logging the exchange if logging is enabled.
+async fn send(
+    request: reqwest::RequestBuilder,
+    logging: Option<&crate::logging::RequestLogging>,
+) -> reqwest::Result<reqwest::Response> {
+    let Some(logging) = logging else {
+        return request.send().await;
+    };
+    let (client, request) = request.build_split();
+    logging.send(&client, request?).await
+}
+
+/// Turns a rate-limited response into [`crate::error::LLMError::RateLimited`], passing
+/// other responses through.
+async fn rate_limit_error(
+    resp: reqwest::Response,
+) -> Result<reqwest::Response, crate::error::LLMError> {
+    if resp.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Ok(resp);
+    }
+    let retry_after = retry_after(resp.headers());
+    let body = resp.text().await.unwrap_or_default();
+    Err(crate::error::LLMError::RateLimited {
+        message: if body.trim().is_empty() {
+            "429 Too Many Requests".to_string()
+        } else {
+            body
+        },
+        retry_after,
+    })
+}
+
+/// Returns how long a rate-limited client should wait, from the `Retry-After` header in
+/// seconds, or failing that the longest of the provider's rate-limit reset headers.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+    let header = |name: &str| headers.get(name)?.to_str().ok();
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return std::time::Duration::try_from_secs_f64(ms / 1000.0).ok();
+    }
+    if let Some(secs) = header("retry-after").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return std::time::Duration::try_from_secs_f64(secs).ok();
+    }
+    [
+        "x-ratelimit-reset",
+        "x-ratelimit-reset-requests",
+        "x-ratelimit-reset-tokens",
+    ]
+    .iter()
+    .filter_map(|name| parse_reset(header(name)?))
+    .max()
+}
+
+/// Parses a rate-limit reset delay, either plain seconds or a duration such as `1m30s`
+/// or `250ms`.
+fn parse_reset(value: &str) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return std::time::Duration::try_from_secs_f64(secs).ok();
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let (number, tail) = rest.split_at(split);
+        let unit_len = tail
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        let number: f64 = number.parse().ok()?;
+        total += number
+            * match unit {
+                "h" => 3600.0,
+                "m" => 60.0,
+                "s" => 1.0,
+                "ms" => 0.001,
+                _ => return None,
+            };
+        rest = tail;
+    }
+    std::time::Duration::try_from_secs_f64(total).ok()
+}
+
+/// Sends a health check request and classifies the provider's answer.
+pub(crate) async fn probe(request: reqwest::RequestBuilder) -> crate::health::HealthStatus {
+    use crate::health::HealthStatus;
+
+    let start = std::time::Instant::now();
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(e) => return HealthStatus::Unavailable(e.to_string()),
+    };
+    let latency = start.elapsed();
+    let status = resp.status();
+    if status.is_success() {
+        return HealthStatus::Healthy { latency };
+    }
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return HealthStatus::RateLimited {
+            retry_after: retry_after(resp.headers()),
+        };
+    }
+
+    let body = resp.text().await.unwrap_or_default();
+    let message = if body.trim().is_empty() {
+        status.to_string()
+    } else {
+        format!("{}: {}", status, body)
+    };
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            HealthStatus::Unauthorized(message)
+        }
+        _ => HealthStatus::Unavailable(message),
+    }
+}
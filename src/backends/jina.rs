@@ -0,0 +1,269 @@
+//! Jina AI client implementation for embedding and reranking functionality.
+//!
+//! Jina serves embedding and reranker models for retrieval. `jina-embeddings-v3` adapts
+//! its embeddings to a task (e.g. "retrieval.query" or "retrieval.passage") and can
+//! shorten them to a requested dimension, and the rerankers implement
+//! [`RerankProvider`]. The chat and completion methods return an error.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    backends::{http_client, probe, request_body, send_request, HttpOptions},
+    chat::{ChatMessage, ChatProvider, ChatResponse, ChatStream, Tool},
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    rerank::{RerankProvider, RerankResult},
+    retry::RetryPolicy,
+    LLMProvider,
+};
+
+/// Base URL of Jina's API.
+pub const BASE_URL: &str = "https://api.jina.ai/v1";
+
+/// Model used for embeddings when none is configured.
+pub const DEFAULT_MODEL: &str = "jina-embeddings-v3";
+
+/// Model used for reranking when none is configured.
+pub const RERANK_MODEL: &str = "jina-reranker-v2-base-multilingual";
+
+/// Client for interacting with Jina AI's API.
+pub struct Jina {
+    pub api_key: String,
+    pub model: String,
+    /// Reranker model ranking documents
+    pub rerank_model: String,
+    pub timeout_seconds: Option<u64>,
+    /// Task the embeddings are adapted to (e.g. "retrieval.query" or "text-matching")
+    pub task: Option<String>,
+    /// Length of the returned vectors, for the models supporting several
+    pub dimensions: Option<u32>,
+    /// Base URL of the API, without a trailing slash
+    pub base_url: String,
+    /// Extra parameters merged into the embedding and rerank request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
+    client: Client,
+}
+
+/// Request payload for Jina's embeddings endpoint.
+#[derive(Serialize, Debug)]
+struct JinaEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+}
+
+/// Response of Jina's embeddings endpoint.
+#[derive(Deserialize, Debug)]
+struct JinaEmbedResponse {
+    data: Vec<JinaEmbedding>,
+}
+
+/// Embedding of one of the input texts.
+#[derive(Deserialize, Debug)]
+struct JinaEmbedding {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// Request payload for Jina's rerank endpoint.
+#[derive(Serialize, Debug)]
+struct JinaRerankRequest<'a> {
+    model: &'a str,
+    query: &'a str,
+    documents: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_n: Option<usize>,
+    return_documents: bool,
+}
+
+/// Response of Jina's rerank endpoint.
+#[derive(Deserialize, Debug)]
+struct JinaRerankResponse {
+    results: Vec<RerankResult>,
+}
+
+/// Error returned by the chat and completion methods.
+fn chat_unsupported() -> LLMError {
+    LLMError::ProviderError(
+        "Jina AI only serves embeddings and reranking; use another backend for chat and completion"
+            .to_string(),
+    )
+}
+
+impl Jina {
+    /// Creates a new Jina client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Jina API key
+    /// * `model` - Embedding model to use (defaults to [`DEFAULT_MODEL`])
+    /// * `rerank_model` - Reranker model to use (defaults to [`RERANK_MODEL`])
+    /// * `timeout_seconds` - Request timeout in seconds
+    /// * `task` - Task the embeddings are adapted to: "retrieval.query",
+    ///   "retrieval.passage", "text-matching", "classification" or "separation"
+    /// * `dimensions` - Length of the returned vectors (e.g. 256, 512 or 1024)
+    /// * `http` - HTTP settings, such as the base URL (defaults to [`BASE_URL`]), headers,
+    ///   proxy and retry policy
+    pub fn new(
+        api_key: impl Into<String>,
+        model: Option<String>,
+        rerank_model: Option<String>,
+        timeout_seconds: Option<u64>,
+        task: Option<String>,
+        dimensions: Option<u32>,
+        http: HttpOptions,
+    ) -> Self {
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
+        Self {
+            api_key: api_key.into(),
+            model: model.unwrap_or(DEFAULT_MODEL.to_string()),
+            rerank_model: rerank_model.unwrap_or(RERANK_MODEL.to_string()),
+            timeout_seconds,
+            task,
+            dimensions,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| BASE_URL.to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Jina {
+    async fn chat_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        Err(chat_unsupported())
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        Err(chat_unsupported())
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Jina {
+    async fn complete(&self, _req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        Err(chat_unsupported())
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Jina {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Jina API key".to_string()));
+        }
+
+        let body = JinaEmbedRequest {
+            model: &self.model,
+            input: &input,
+            task: self.task.as_deref(),
+            dimensions: self.dimensions,
+        };
+        let request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&request_body(&body, &self.extra_params)?);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let mut json_resp: JinaEmbedResponse = resp.json().await?;
+        json_resp.data.sort_by_key(|embedding| embedding.index);
+        Ok(json_resp
+            .data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl RerankProvider for Jina {
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: Option<usize>,
+    ) -> Result<Vec<RerankResult>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Jina API key".to_string()));
+        }
+
+        let body = JinaRerankRequest {
+            model: &self.rerank_model,
+            query,
+            documents,
+            top_n,
+            return_documents: false,
+        };
+        let request = self
+            .client
+            .post(format!("{}/rerank", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&request_body(&body, &self.extra_params)?);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let json_resp: JinaRerankResponse = resp.json().await?;
+        Ok(json_resp.results)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for Jina {
+    fn tools(&self) -> Option<&[Tool]> {
+        None
+    }
+
+    /// Embeds a one-word text, since Jina has no endpoint listing its models.
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.client
+                .post(format!("{}/embeddings", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&JinaEmbedRequest {
+                    model: &self.model,
+                    input: &["ping".to_string()],
+                    task: None,
+                    dimensions: None,
+                }),
+        )
+        .await
+    }
+}
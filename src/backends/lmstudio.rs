@@ -0,0 +1,113 @@
+//! LM Studio client implementation for chat and embedding functionality.
+//!
+//! LM Studio's local server serves the models downloaded in the desktop app through
+//! OpenAI's chat completions and embeddings APIs, so this client drives an [`OpenAI`]
+//! client pointed at it. The server loads a model on demand when a request names it, so
+//! [`ChatOptions::model`] switches the loaded model per request, and [`list_models`] lists
+//! the downloaded models with their state.
+//!
+//! [`ChatOptions::model`]: crate::chat::ChatOptions::model
+
+use serde::Deserialize;
+
+use crate::{
+    backends::openai::{forward_to_inner, OpenAI},
+    error::LLMError,
+};
+
+/// Base URL of LM Studio's local server.
+pub const BASE_URL: &str = "http://localhost:1234/v1";
+
+/// A model downloaded in LM Studio.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LMStudioModel {
+    /// Identifier of the model, to pass as the model of requests
+    pub id: String,
+    /// Kind of model: "llm", "vlm" (vision) or "embeddings"
+    #[serde(rename = "type")]
+    pub model_type: String,
+    /// Publisher of the model (e.g. "lmstudio-community")
+    #[serde(default)]
+    pub publisher: Option<String>,
+    /// Architecture of the model (e.g. "llama")
+    #[serde(default)]
+    pub arch: Option<String>,
+    /// Format of the weights (e.g. "gguf" or "mlx")
+    #[serde(default)]
+    pub compatibility_type: Option<String>,
+    /// Quantization of the weights (e.g. "Q4_K_M")
+    #[serde(default)]
+    pub quantization: Option<String>,
+    /// Whether the model is "loaded" or "not-loaded"
+    pub state: String,
+    /// Largest context window the model supports, in tokens
+    #[serde(default)]
+    pub max_context_length: Option<u32>,
+}
+
+impl LMStudioModel {
+    /// Returns whether the model is loaded in memory.
+    pub fn is_loaded(&self) -> bool {
+        self.state == "loaded"
+    }
+}
+
+#[derive(Deserialize)]
+struct LMStudioModelList {
+    data: Vec<LMStudioModel>,
+}
+
+/// Lists the models downloaded in LM Studio, loaded or not.
+///
+/// # Arguments
+///
+/// * `base_url` - Base URL of the server's OpenAI API (defaults to [`BASE_URL`])
+pub async fn list_models(base_url: Option<&str>) -> Result<Vec<LMStudioModel>, LLMError> {
+    let url = models_url(base_url.unwrap_or(BASE_URL));
+    fetch_models(reqwest::Client::new().get(url)).await
+}
+
+/// Returns the URL listing the models in LM Studio's REST API, which is served next to the
+/// OpenAI API.
+fn models_url(base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let root = base_url.strip_suffix("/v1").unwrap_or(base_url);
+    format!("{}/api/v0/models", root)
+}
+
+async fn fetch_models(request: reqwest::RequestBuilder) -> Result<Vec<LMStudioModel>, LLMError> {
+    let resp = request.send().await?.error_for_status()?;
+    let list: LMStudioModelList = resp.json().await?;
+    Ok(list.data)
+}
+
+/// Client for interacting with LM Studio's local server.
+pub struct LMStudio {
+    /// OpenAI client sending the requests to the server
+    inner: OpenAI,
+}
+
+impl LMStudio {
+    /// Creates an LM Studio client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the model and generation settings, pointed at
+    ///   [`BASE_URL`] or another LM Studio server. The API key may be empty.
+    pub fn new(mut openai: OpenAI) -> Self {
+        openai.provider = "lmstudio";
+        openai.requires_key = false;
+        Self { inner: openai }
+    }
+
+    /// Lists the models downloaded on the server, loaded or not.
+    pub async fn models(&self) -> Result<Vec<LMStudioModel>, LLMError> {
+        fetch_models(
+            self.inner
+                .request(reqwest::Method::GET, models_url(&self.inner.base_url)),
+        )
+        .await
+    }
+}
+
+forward_to_inner!(LMStudio);
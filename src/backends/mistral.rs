@@ -0,0 +1,152 @@
+//! Mistral AI client implementation for chat and embedding functionality.
+//!
+//! Mistral's chat completions, tool calls and embeddings follow OpenAI's API, so this
+//! client drives an [`OpenAI`] client pointed at Mistral, leaving out the fields Mistral
+//! rejects and sending the seed as `random_seed`. Structured output without a schema
+//! enables Mistral's JSON mode, and `safe_prompt` prepends Mistral's safety prompt.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::{
+    backends::openai::{forward_to_inner, Dialect, OpenAI},
+    chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool},
+    error::LLMError,
+};
+
+/// Base URL of Mistral's API.
+pub const BASE_URL: &str = "https://api.mistral.ai/v1";
+
+/// Model used when none is configured.
+pub const DEFAULT_MODEL: &str = "mistral-small-latest";
+
+/// Model computing embeddings when the configured model is a chat model.
+pub const EMBEDDING_MODEL: &str = "mistral-embed";
+
+/// How Mistral's chat API differs from OpenAI's.
+const DIALECT: Dialect = Dialect {
+    unsupported: &[
+        "top_k",
+        "stream_options",
+        "logprobs",
+        "top_logprobs",
+        "logit_bias",
+        "reasoning_effort",
+    ],
+    renamed: &[("seed", "random_seed")],
+};
+
+/// Client for interacting with Mistral AI's API.
+///
+/// Texts are embedded with the configured model if it is an embedding model, and with
+/// [`EMBEDDING_MODEL`] otherwise.
+pub struct Mistral {
+    /// Whether Mistral's safety prompt is prepended to the conversations
+    safe_prompt: Option<bool>,
+    /// Seed sent with requests that don't set their own, for reproducible replies
+    random_seed: Option<u64>,
+    /// OpenAI client sending the requests to Mistral
+    inner: OpenAI,
+}
+
+impl Mistral {
+    /// Creates a Mistral client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the API key, model and generation settings, pointed
+    ///   at [`BASE_URL`] or a compatible server
+    /// * `safe_prompt` - Whether to prepend Mistral's safety prompt to the conversations
+    /// * `random_seed` - Seed sent with requests that don't set their own
+    pub fn new(mut openai: OpenAI, safe_prompt: Option<bool>, random_seed: Option<u64>) -> Self {
+        openai.provider = "mistral";
+        openai.dialect = DIALECT;
+        if !openai.model.contains("embed") {
+            openai.embedding_model = Some(EMBEDDING_MODEL.to_string());
+        }
+        if openai
+            .structured_output
+            .as_ref()
+            .is_some_and(|format| format.schema.is_none())
+        {
+            openai.structured_output = None;
+            openai
+                .extra_params
+                .entry("response_format")
+                .or_insert_with(|| json!({"type": "json_object"}));
+        }
+        if let Some(safe_prompt) = safe_prompt {
+            openai
+                .extra_params
+                .entry("safe_prompt")
+                .or_insert(Value::Bool(safe_prompt));
+        }
+        Self {
+            safe_prompt,
+            random_seed,
+            inner: openai,
+        }
+    }
+
+    /// Returns whether Mistral's safety prompt is prepended, if configured.
+    pub fn safe_prompt(&self) -> Option<bool> {
+        self.safe_prompt
+    }
+
+    /// Returns the seed sent with requests that don't set their own.
+    pub fn random_seed(&self) -> Option<u64> {
+        self.random_seed
+    }
+
+    /// Returns the options of a request, with the configured seed unless it sets one.
+    fn options(&self, options: &ChatOptions) -> ChatOptions {
+        let mut options = options.clone();
+        options.seed = options.seed.or(self.random_seed);
+        options
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Mistral {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.inner
+            .chat_with_options(messages, tools, &self.options(options))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.chat_stream_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, &self.options(options))
+            .await
+    }
+}
+
+forward_to_inner!(Mistral; completion, embedding, provider);
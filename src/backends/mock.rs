@@ -0,0 +1,358 @@
+//! Scriptable provider answering with canned responses, for tests.
+//!
+//! A [`MockLLM`] never touches the network: each chat request takes the next reply of its
+//! script — a text, tool calls or an error — optionally after checking the request it
+//! received, and waiting a simulated latency. It records every request, so tests can
+//! check what their code sent. Clones share the same script and records, so keep one to
+//! inspect after handing another to the builder with
+//! [`crate::builder::LLMBuilder::mock`].
+//!
+//! # Example
+//!
+//! ```
+//! use llm::backends::mock::MockLLM;
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::error::LLMError;
+//!
+//! # block_on(async {
+//! let mock = MockLLM::new()
+//!     .reply("Paris")
+//!     .expecting(|messages, _tools| assert_eq!(messages.len(), 1))
+//!     .fail(LLMError::ProviderError("overloaded".to_string()));
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::Mock)
+//!     .mock(mock.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! let messages = [ChatMessage::user().content("Capital of France?").build()];
+//! let reply = llm.chat(&messages).await.unwrap();
+//! assert_eq!(reply.text().as_deref(), Some("Paris"));
+//! assert!(llm.chat(&messages).await.is_err());
+//! assert_eq!(mock.calls().len(), 2);
+//! # });
+//! # fn block_on<F: std::future::Future>(f: F) -> F::Output {
+//! #     tokio::runtime::Runtime::new().unwrap().block_on(f)
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream;
+
+use crate::{
+    chat::{
+        with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream,
+        ResponseSnapshot, StreamChunk, Tool,
+    },
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    testing::FakeResponse,
+    tokens, LLMProvider, ToolCall,
+};
+
+/// Check run on the request a scripted reply answers.
+type Expectation = Arc<dyn Fn(&[ChatMessage], Option<&[Tool]>) + Send + Sync>;
+
+/// A scripted reply.
+struct Turn {
+    reply: Result<ResponseSnapshot, LLMError>,
+    expectation: Option<Expectation>,
+    latency: Option<Duration>,
+}
+
+/// A request received by a [`MockLLM`].
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    /// Messages of the request
+    pub messages: Vec<ChatMessage>,
+    /// Tools offered with the request
+    pub tools: Option<Vec<Tool>>,
+    /// Whether the reply was streamed
+    pub stream: bool,
+}
+
+#[derive(Default)]
+struct MockState {
+    script: VecDeque<Turn>,
+    calls: Vec<MockCall>,
+}
+
+/// Provider answering from a script of canned replies.
+///
+/// Chat requests, streams and completions take the next reply of the script; once it is
+/// exhausted they get the fallback reply if one is set, and fail otherwise. Streams send
+/// the reply text as one chunk, then its tool calls and a final chunk. Embeddings are
+/// derived from the text, so equal texts get equal vectors.
+#[derive(Clone, Default)]
+pub struct MockLLM {
+    state: Arc<Mutex<MockState>>,
+    /// Reply sent once the script is exhausted
+    fallback: Option<ResponseSnapshot>,
+    /// Delay before every reply without its own
+    latency: Option<Duration>,
+    /// Length of the embeddings
+    dimensions: Option<usize>,
+    /// Tools reported by [`LLMProvider::tools`]
+    tools: Option<Vec<Tool>>,
+}
+
+impl fmt::Debug for MockLLM {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockLLM")
+            .field("remaining", &self.remaining())
+            .field("calls", &self.calls().len())
+            .finish()
+    }
+}
+
+impl MockLLM {
+    /// Creates a mock with an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a text reply to the script.
+    pub fn reply(self, text: impl Into<String>) -> Self {
+        self.reply_response(snapshot(Some(text.into()), None))
+    }
+
+    /// Appends a reply requesting tool calls to the script.
+    pub fn reply_tool_calls(self, calls: Vec<ToolCall>) -> Self {
+        self.reply_response(snapshot(None, Some(calls)))
+    }
+
+    /// Appends a full response to the script.
+    pub fn reply_response(self, response: ResponseSnapshot) -> Self {
+        self.push(Ok(response))
+    }
+
+    /// Appends a failure to the script.
+    pub fn fail(self, error: LLMError) -> Self {
+        self.push(Err(error))
+    }
+
+    /// Checks the request answered by the last reply added, typically with assertions.
+    pub fn expecting(
+        self,
+        expectation: impl Fn(&[ChatMessage], Option<&[Tool]>) + Send + Sync + 'static,
+    ) -> Self {
+        if let Some(turn) = self.state.lock().unwrap().script.back_mut() {
+            turn.expectation = Some(Arc::new(expectation));
+        }
+        self
+    }
+
+    /// Delays the last reply added, overriding [`MockLLM::latency`].
+    pub fn after(self, latency: Duration) -> Self {
+        if let Some(turn) = self.state.lock().unwrap().script.back_mut() {
+            turn.latency = Some(latency);
+        }
+        self
+    }
+
+    /// Delays every reply, to simulate a slow provider.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Answers with a text once the script is exhausted, instead of failing.
+    pub fn fallback_reply(mut self, text: impl Into<String>) -> Self {
+        self.fallback = Some(snapshot(Some(text.into()), None));
+        self
+    }
+
+    /// Sets the length of the embeddings, 8 by default.
+    pub fn embedding_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+
+    /// Sets the tools the provider reports, as a configured backend would.
+    pub fn with_tools(mut self, tools: Option<Vec<Tool>>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Returns the requests received so far.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Returns the number of scripted replies not yet sent.
+    pub fn remaining(&self) -> usize {
+        self.state.lock().unwrap().script.len()
+    }
+
+    fn push(self, reply: Result<ResponseSnapshot, LLMError>) -> Self {
+        self.state.lock().unwrap().script.push_back(Turn {
+            reply,
+            expectation: None,
+            latency: None,
+        });
+        self
+    }
+
+    /// Records a request and returns its reply, after the simulated latency.
+    async fn next(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> Result<ResponseSnapshot, LLMError> {
+        let turn = {
+            let mut state = self.state.lock().unwrap();
+            state.calls.push(MockCall {
+                messages: messages.to_vec(),
+                tools: tools.map(<[Tool]>::to_vec),
+                stream,
+            });
+            state.script.pop_front()
+        };
+        let Some(turn) = turn else {
+            return self.fallback.clone().ok_or_else(|| {
+                LLMError::ProviderError("The mock's script has no reply left".to_string())
+            });
+        };
+        if let Some(expectation) = &turn.expectation {
+            expectation(messages, tools);
+        }
+        if let Some(latency) = turn.latency.or(self.latency) {
+            tokio::time::sleep(latency).await;
+        }
+        turn.reply
+    }
+}
+
+/// Builds the response of a scripted reply.
+fn snapshot(text: Option<String>, tool_calls: Option<Vec<ToolCall>>) -> ResponseSnapshot {
+    let completion_tokens = text
+        .as_deref()
+        .map_or(0, |text| tokens::count_tokens("", text) as u32);
+    let mut response = FakeResponse::new()
+        .usage(0, completion_tokens)
+        .served_by("mock", "mock");
+    if let Some(text) = text {
+        response = response.text(text);
+    }
+    for call in tool_calls.into_iter().flatten() {
+        response = response.with_tool_call(call);
+    }
+    response.build()
+}
+
+/// Splits a response into the chunks of a stream.
+fn chunks(response: ResponseSnapshot) -> Vec<StreamChunk> {
+    let mut chunks = Vec::new();
+    if let Some(text) = response.text.filter(|text| !text.is_empty()) {
+        chunks.push(StreamChunk::Text(text));
+    }
+    for (index, call) in response.tool_calls.into_iter().flatten().enumerate() {
+        chunks.push(StreamChunk::ToolCallDelta {
+            index,
+            id: Some(call.id),
+            name: Some(call.function.name),
+            arguments: call.function.arguments,
+        });
+    }
+    chunks.push(StreamChunk::Done {
+        finish_reason: response.finish_reason,
+        usage: response.usage,
+    });
+    chunks
+}
+
+/// Derives a unit vector from a text, hashing its characters into buckets.
+fn embedding(text: &str, dimensions: usize) -> Vec<f32> {
+    let mut vector = vec![0.0f32; dimensions.max(1)];
+    for (i, c) in text.chars().enumerate() {
+        let bucket = (c as usize).wrapping_mul(31).wrapping_add(i) % vector.len();
+        vector[bucket] += 1.0;
+    }
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+#[async_trait]
+impl LLMProvider for MockLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.tools.as_deref()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        HealthStatus::Healthy {
+            latency: self.latency.unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for MockLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        Ok(Box::new(self.next(messages, tools, false).await?))
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.chat_with_tools(messages, tools)).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        let response = self.next(messages, tools, true).await?;
+        Ok(Box::pin(stream::iter(chunks(response).into_iter().map(Ok))))
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        with_cancellation(options, self.chat_stream_with_tools(messages, tools)).await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for MockLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        let messages = [ChatMessage::user().content(req.prompt.clone()).build()];
+        let response = self.next(&messages, None, false).await?;
+        Ok(CompletionResponse {
+            text: response.text.unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MockLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        let dimensions = self.dimensions.unwrap_or(8);
+        Ok(input
+            .iter()
+            .map(|text| embedding(text, dimensions))
+            .collect())
+    }
+}
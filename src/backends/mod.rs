@@ -21,3 +21,141 @@ pub mod google;
 
 #[cfg(feature = "groq")]
 pub mod groq;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+#[cfg(feature = "jina")]
+pub mod jina;
+
+#[cfg(feature = "voyage")]
+pub mod voyage;
+
+#[cfg(feature = "moonshot")]
+pub mod moonshot;
+
+#[cfg(feature = "openai")]
+pub(crate) mod multipart;
+
+#[cfg(feature = "realtime")]
+pub(crate) mod websocket;
+
+#[cfg(feature = "qwen")]
+pub mod qwen;
+
+#[cfg(feature = "lmstudio")]
+pub mod lmstudio;
+
+#[cfg(feature = "openai-compatible")]
+pub mod openai_compatible;
+
+#[cfg(feature = "nvidia")]
+pub mod nvidia;
+
+#[cfg(feature = "perplexity")]
+pub mod perplexity;
+
+#[cfg(feature = "fireworks")]
+pub mod fireworks;
+
+#[cfg(feature = "cohere")]
+pub mod cohere;
+
+#[cfg(feature = "mistral")]
+pub mod mistral;
+
+#[cfg(feature = "azure")]
+pub mod azure_openai;
+
+#[cfg(any(
+    feature = "openai",
+    feature = "phind",
+    feature = "xai",
+    feature = "mcp"
+))]
+pub(crate) mod sse;
+
+/// HTTP settings shared by every backend: where the requests are sent, how, and what is
+/// added to them.
+///
+/// [`LLMBuilder::build`](crate::builder::LLMBuilder::build) fills it from the builder's
+/// settings; the backends' constructors take it last.
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    /// Base URL of the API, replacing the backend's default
+    pub base_url: Option<String>,
+    /// Extra HTTP headers sent with every request
+    pub headers: Option<reqwest::header::HeaderMap>,
+    /// Proxy the requests are sent through
+    pub proxy: Option<reqwest::Proxy>,
+    /// HTTP client to send the requests with, replacing the timeout, headers and proxy
+    /// settings
+    pub client: Option<reqwest::Client>,
+    /// Extra parameters merged into the request bodies
+    pub extra_params: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Policy retrying transient request failures
+    pub retry: Option<crate::retry::RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<crate::logging::RequestLogging>,
+}
+
+#[cfg(feature = "http-backend")]
+mod http;
+
+#[cfg(feature = "http-backend")]
+#[cfg_attr(not(feature = "full"), allow(unused_imports))]
+pub(crate) use http::{
+    client_builder, http_client, json_with_raw, probe, request_body, request_id_header,
+    send_request,
+};
+
+/// Rejects tool messages that are not tool results, since OpenAI-style APIs require the
+/// ID of the tool call a tool message answers.
+#[cfg(any(feature = "openai", feature = "xai"))]
+pub(crate) fn check_tool_messages(
+    messages: &[crate::chat::ChatMessage],
+) -> Result<(), crate::error::LLMError> {
+    use crate::chat::{ChatRole, MessageType};
+
+    if messages.iter().any(|m| {
+        m.role == ChatRole::Tool && !matches!(m.message_type, MessageType::ToolResult { .. })
+    }) {
+        return Err(crate::error::LLMError::InvalidRequest(
+            "Tool messages need the ID of the tool call they answer: build them with \
+             ChatMessageBuilder::tool_result"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects audio clips, which APIs taking only text would otherwise receive as empty
+/// messages.
+#[cfg(any(
+    feature = "ollama",
+    feature = "deepseek",
+    feature = "xai",
+    feature = "phind",
+    feature = "groq",
+    feature = "cohere"
+))]
+pub(crate) fn check_no_audio(
+    messages: &[crate::chat::ChatMessage],
+    provider: &str,
+) -> Result<(), crate::error::LLMError> {
+    use crate::chat::{ContentPart, MessageType};
+
+    if messages.iter().any(|m| match &m.message_type {
+        MessageType::Audio(_) => true,
+        MessageType::Parts(parts) | MessageType::ToolResult { parts, .. } => parts
+            .iter()
+            .any(|part| matches!(part, ContentPart::Audio(_))),
+        _ => false,
+    }) {
+        return Err(crate::error::LLMError::InvalidRequest(format!(
+            "{} does not support audio input",
+            provider
+        )));
+    }
+    Ok(())
+}
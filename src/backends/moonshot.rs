@@ -0,0 +1,270 @@
+//! Moonshot AI (Kimi) client implementation for chat functionality.
+//!
+//! Moonshot's chat completions and tool calls follow OpenAI's API, so this client drives
+//! an [`OpenAI`] client pointed at Moonshot. Its models read long contexts (up to 128k
+//! tokens for `moonshot-v1-128k`, with `moonshot-v1-auto` picking the window from the
+//! length of the conversation). Documents are read through Moonshot's file API: an
+//! uploaded file's extracted text is sent as a system message, which the client does for
+//! its context files before every conversation.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+
+use crate::{
+    backends::{
+        multipart::{content_type, Multipart},
+        openai::{forward_to_inner, Dialect, OpenAI},
+        send_request,
+    },
+    chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool},
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+};
+
+/// Base URL of Moonshot's API. Accounts of the China region use
+/// `https://api.moonshot.cn/v1`.
+pub const BASE_URL: &str = "https://api.moonshot.ai/v1";
+
+/// Model used when none is configured, which picks its context window from the length of
+/// the conversation.
+pub const DEFAULT_MODEL: &str = "moonshot-v1-auto";
+
+/// How Moonshot's chat API differs from OpenAI's.
+const DIALECT: Dialect = Dialect {
+    unsupported: &["top_k", "logprobs", "top_logprobs", "reasoning_effort"],
+    renamed: &[],
+};
+
+/// A file uploaded to Moonshot.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MoonshotFile {
+    /// ID of the file, to read or delete it
+    pub id: String,
+    /// Name the file was uploaded with
+    #[serde(default)]
+    pub filename: String,
+    /// Size of the file, in bytes
+    #[serde(default)]
+    pub bytes: u64,
+    /// What the file is used for ("file-extract")
+    #[serde(default)]
+    pub purpose: String,
+    /// Processing status of the file
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Extracted text of a file.
+#[derive(Deserialize)]
+struct MoonshotFileContent {
+    content: String,
+}
+
+/// Client for interacting with Moonshot AI's API.
+pub struct Moonshot {
+    /// Files whose text is sent as context before every conversation
+    pub context_files: Vec<PathBuf>,
+    /// Extracted text of the context files, read once on the first request
+    context: OnceCell<Vec<String>>,
+    /// OpenAI client sending the requests to Moonshot
+    inner: OpenAI,
+}
+
+impl Moonshot {
+    /// Creates a Moonshot client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the API key, model and generation settings, pointed
+    ///   at [`BASE_URL`] or another Moonshot region
+    /// * `context_files` - Files uploaded on the first request, whose text is then sent as
+    ///   context before every conversation
+    pub fn new(mut openai: OpenAI, context_files: Vec<PathBuf>) -> Self {
+        openai.provider = "moonshot";
+        openai.dialect = DIALECT;
+        Self {
+            context_files,
+            context: OnceCell::new(),
+            inner: openai,
+        }
+    }
+
+    /// Uploads a file for text extraction (PDF, Word, spreadsheet, image, text...).
+    pub async fn upload_file(
+        &self,
+        filename: &str,
+        bytes: &[u8],
+    ) -> Result<MoonshotFile, LLMError> {
+        let (content_type_header, body) = Multipart::new()
+            .text("purpose", "file-extract")
+            .file("file", filename, content_type(filename), bytes)
+            .finish();
+        let request = self
+            .inner
+            .request(
+                reqwest::Method::POST,
+                format!("{}/files", self.inner.base_url),
+            )
+            .header(reqwest::header::CONTENT_TYPE, content_type_header)
+            .body(body);
+        let resp = send_request(
+            request,
+            self.inner.retry.as_ref(),
+            self.inner.logging.as_ref(),
+        )
+        .await?
+        .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Returns the text Moonshot extracted from an uploaded file.
+    pub async fn file_content(&self, file_id: &str) -> Result<String, LLMError> {
+        let request = self.inner.request(
+            reqwest::Method::GET,
+            format!("{}/files/{}/content", self.inner.base_url, file_id),
+        );
+        let resp = send_request(
+            request,
+            self.inner.retry.as_ref(),
+            self.inner.logging.as_ref(),
+        )
+        .await?
+        .error_for_status()?;
+        let file: MoonshotFileContent = resp.json().await?;
+        Ok(file.content)
+    }
+
+    /// Deletes an uploaded file.
+    pub async fn delete_file(&self, file_id: &str) -> Result<(), LLMError> {
+        let request = self.inner.request(
+            reqwest::Method::DELETE,
+            format!("{}/files/{}", self.inner.base_url, file_id),
+        );
+        send_request(
+            request,
+            self.inner.retry.as_ref(),
+            self.inner.logging.as_ref(),
+        )
+        .await?
+        .error_for_status()?;
+        Ok(())
+    }
+
+    /// Uploads a file and returns the system message giving its text as context.
+    pub async fn file_message(
+        &self,
+        filename: &str,
+        bytes: &[u8],
+    ) -> Result<ChatMessage, LLMError> {
+        let file = self.upload_file(filename, bytes).await?;
+        let content = self.file_content(&file.id).await?;
+        Ok(ChatMessage::system().content(content).build())
+    }
+
+    /// Prepends the text of the context files to a conversation, uploading them on the
+    /// first request.
+    async fn with_context(&self, messages: &[ChatMessage]) -> Result<Vec<ChatMessage>, LLMError> {
+        let context = self
+            .context
+            .get_or_try_init(|| async {
+                let mut context = Vec::with_capacity(self.context_files.len());
+                for path in &self.context_files {
+                    let bytes = tokio::fs::read(path).await.map_err(|e| {
+                        LLMError::InvalidRequest(format!(
+                            "Cannot read context file {}: {}",
+                            path.display(),
+                            e
+                        ))
+                    })?;
+                    let filename = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let file = self.upload_file(&filename, &bytes).await?;
+                    context.push(self.file_content(&file.id).await?);
+                }
+                Ok::<_, LLMError>(context)
+            })
+            .await?;
+        Ok(context
+            .iter()
+            .map(|content| ChatMessage::system().content(content.clone()).build())
+            .chain(messages.iter().cloned())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Moonshot {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let messages = self.with_context(messages).await?;
+        self.inner
+            .chat_with_options(&messages, tools, options)
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.chat_stream_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        let messages = self.with_context(messages).await?;
+        self.inner
+            .chat_stream_with_options(&messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Moonshot {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        let message = ChatMessage::user().content(req.prompt.clone()).build();
+        let response = self.chat(&[message]).await?;
+        Ok(CompletionResponse {
+            text: response.text().unwrap_or_default(),
+        })
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Moonshot {
+    async fn embed(&self, _input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        Err(LLMError::ProviderError(
+            "Embedding not supported".to_string(),
+        ))
+    }
+}
+
+forward_to_inner!(Moonshot; provider);
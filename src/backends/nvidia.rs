@@ -0,0 +1,48 @@
+//! NVIDIA NIM client implementation for chat and embedding functionality.
+//!
+//! NVIDIA NIM microservices serve models through OpenAI's chat completions and embeddings
+//! APIs, both hosted on build.nvidia.com and deployed on your own infrastructure, so this
+//! client drives an [`OpenAI`] client pointed at a NIM endpoint. Models are named after
+//! their publisher (e.g. "meta/llama-3.1-70b-instruct"), and the hosted endpoint takes an
+//! `nvapi-` key as a bearer token. Self-deployed NIMs usually need no key.
+
+use crate::backends::openai::{forward_to_inner, Dialect, OpenAI};
+
+/// Base URL of the hosted NIM endpoints of build.nvidia.com.
+pub const BASE_URL: &str = "https://integrate.api.nvidia.com/v1";
+
+/// Model used when none is configured.
+pub const DEFAULT_MODEL: &str = "meta/llama-3.1-70b-instruct";
+
+/// How NIM's chat API differs from OpenAI's.
+const DIALECT: Dialect = Dialect {
+    unsupported: &["top_k", "parallel_tool_calls", "reasoning_effort"],
+    renamed: &[],
+};
+
+/// Client for interacting with NVIDIA NIM endpoints.
+pub struct Nvidia {
+    /// OpenAI client sending the requests to the NIM endpoint
+    inner: OpenAI,
+}
+
+impl Nvidia {
+    /// Creates a NIM client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the API key, model and generation settings, pointed
+    ///   at [`BASE_URL`] or a self-deployed NIM. An empty API key sends the requests
+    ///   unauthenticated.
+    /// * `embedding_input_type` - Kind of text embedded by retrieval models ("query" or
+    ///   "passage"), which they require
+    pub fn new(mut openai: OpenAI, embedding_input_type: Option<String>) -> Self {
+        openai.provider = "nvidia";
+        openai.dialect = DIALECT;
+        openai.requires_key = false;
+        openai.embedding_input_type = embedding_input_type;
+        Self { inner: openai }
+    }
+}
+
+forward_to_inner!(Nvidia);
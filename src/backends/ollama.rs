@@ -2,16 +2,28 @@
 //!
 //! This module provides integration with Ollama's local LLM server through its API.
 
+use std::collections::VecDeque;
+
 use crate::{
-    chat::{ChatMessage, ChatProvider, ChatResponse, ChatRole, StructuredOutputFormat, Tool},
-    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    backends::{
+        check_no_audio, http_client, json_with_raw, probe, request_body, send_request, HttpOptions,
+    },
+    chat::{
+        with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatRole,
+        FinishReason, RequestTimer, ResponseMetadata, ResponseTiming, StructuredOutputFormat, Tool,
+        Usage,
+    },
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream},
     embedding::EmbeddingProvider,
     error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
 };
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use crate::ToolCall;
 
@@ -31,6 +43,12 @@ pub struct Ollama {
     pub top_k: Option<u32>,
     /// JSON schema for structured output
     pub json_schema: Option<StructuredOutputFormat>,
+    /// Extra parameters merged into the chat request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
     client: Client,
 }
 
@@ -46,8 +64,16 @@ struct OllamaChatRequest<'a> {
 
 #[derive(Serialize)]
 struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
     top_p: Option<f32>,
     top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
 }
 
 /// Individual message in an Ollama chat conversation.
@@ -63,6 +89,22 @@ struct OllamaResponse {
     content: Option<String>,
     response: Option<String>,
     message: Option<OllamaChatResponseMessage>,
+    #[serde(default)]
+    done_reason: Option<String>,
+    /// Number of tokens in the prompt
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    /// Number of tokens generated
+    #[serde(default)]
+    eval_count: Option<u32>,
+    #[serde(default)]
+    model: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: Value,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
 }
 
 impl std::fmt::Display for OllamaResponse {
@@ -79,6 +121,33 @@ impl std::fmt::Display for OllamaResponse {
 }
 
 impl ChatResponse for OllamaResponse {
+    fn usage(&self) -> Option<Usage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        let prompt_tokens = self.prompt_eval_count.unwrap_or_default();
+        let completion_tokens = self.eval_count.unwrap_or_default();
+        Some(Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cached_prompt_tokens: None,
+        })
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("ollama".to_string()),
+            model: self.model.clone(),
+            timing: self.timing,
+            ..Default::default()
+        }
+    }
+
+    fn raw(&self) -> Option<&Value> {
+        Some(&self.raw)
+    }
+
     fn text(&self) -> Option<String> {
         self.content
             .as_ref()
@@ -88,7 +157,11 @@ impl ChatResponse for OllamaResponse {
     }
 
     fn tool_calls(&self) -> Option<Vec<ToolCall>> {
-        todo!()
+        None
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.done_reason.as_deref().map(FinishReason::from_provider)
     }
 }
 
@@ -107,6 +180,41 @@ struct OllamaGenerateRequest<'a> {
     stream: bool,
 }
 
+/// A single line of a streamed generate response.
+#[derive(Deserialize, Debug)]
+struct OllamaGenerateChunk {
+    #[serde(default)]
+    response: String,
+    error: Option<String>,
+}
+
+/// State carried between polls of a streamed generate response.
+struct OllamaStreamState {
+    response: Response,
+    buffer: Vec<u8>,
+    pending: VecDeque<Result<String, LLMError>>,
+    finished: bool,
+}
+
+impl OllamaStreamState {
+    /// Parses one line of newline-delimited JSON into a text fragment.
+    fn parse_line(line: &[u8]) -> Option<Result<String, LLMError>> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        match serde_json::from_str::<OllamaGenerateChunk>(line) {
+            Ok(OllamaGenerateChunk {
+                error: Some(error), ..
+            }) => Some(Err(LLMError::ProviderError(error))),
+            Ok(chunk) if chunk.response.is_empty() => None,
+            Ok(chunk) => Some(Ok(chunk.response)),
+            Err(e) => Some(Err(LLMError::JsonError(e.to_string()))),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct OllamaEmbeddingRequest {
     model: String,
@@ -146,6 +254,8 @@ impl Ollama {
     /// * `system` - System prompt
     /// * `stream` - Whether to stream responses
     /// * `json_schema` - JSON schema for structured output
+    /// * `http` - HTTP settings, such as headers, proxy and retry policy; its base URL
+    ///   replaces `base_url` when set
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         base_url: impl Into<String>,
@@ -159,13 +269,19 @@ impl Ollama {
         top_p: Option<f32>,
         top_k: Option<u32>,
         json_schema: Option<StructuredOutputFormat>,
+        http: HttpOptions,
     ) -> Self {
-        let mut builder = Client::builder();
-        if let Some(sec) = timeout_seconds {
-            builder = builder.timeout(std::time::Duration::from_secs(sec));
-        }
+        let HttpOptions {
+            base_url: base_url_override,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
         Self {
-            base_url: base_url.into(),
+            base_url: base_url_override.unwrap_or_else(|| base_url.into()),
             api_key,
             model: model.unwrap_or("llama3.1".to_string()),
             temperature,
@@ -176,33 +292,32 @@ impl Ollama {
             top_p,
             top_k,
             json_schema,
-            client: builder.build().expect("Failed to build reqwest Client"),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
         }
     }
-}
 
-#[async_trait]
-impl ChatProvider for Ollama {
-    /// Sends a chat request to Ollama's API.
-    ///
-    /// # Arguments
-    ///
-    /// * `messages` - Slice of chat messages representing the conversation
-    ///
-    /// # Returns
-    ///
-    /// The model's response text or an error
-    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+    /// Builds and sends a chat request, applying the per-request overrides.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
         if self.base_url.is_empty() {
             return Err(LLMError::InvalidRequest("Missing base_url".to_string()));
         }
+        check_no_audio(messages, "Ollama")?;
 
         let mut chat_messages: Vec<OllamaChatMessage> = messages
             .iter()
             .map(|msg| OllamaChatMessage {
                 role: match msg.role {
+                    ChatRole::System => "system",
                     ChatRole::User => "user",
                     ChatRole::Assistant => "assistant",
+                    ChatRole::Tool => "tool",
                 },
                 content: &msg.content,
             })
@@ -234,31 +349,72 @@ impl ChatProvider for Ollama {
             messages: chat_messages,
             stream: self.stream.unwrap_or(false),
             options: Some(OllamaOptions {
-                top_p: self.top_p,
+                temperature: options.temperature.or(self.temperature),
+                num_predict: options.max_tokens.or(self.max_tokens),
+                top_p: options.top_p.or(self.top_p),
                 top_k: self.top_k,
+                stop: options.stop.clone(),
+                seed: options.seed,
             }),
             format,
         };
 
         let url = format!("{}/api/chat", self.base_url);
 
-        let mut request = self.client.post(&url).json(&req_body);
+        let mut request = self
+            .client
+            .post(&url)
+            .json(&request_body(&req_body, &self.extra_params)?);
 
-        if let Some(timeout) = self.timeout_seconds {
-            request = request.timeout(std::time::Duration::from_secs(timeout));
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
         }
 
-        let resp = request.send().await?.error_for_status()?;
-        let json_resp: OllamaResponse = resp.json().await?;
+        let mut timer = RequestTimer::start();
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        timer.first_byte();
+        let (mut json_resp, raw): (OllamaResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.timing = Some(timer.finish("ollama", &self.model));
         Ok(Box::new(json_resp))
     }
+}
+
+#[async_trait]
+impl ChatProvider for Ollama {
+    /// Sends a chat request to Ollama's API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Slice of chat messages representing the conversation
+    ///
+    /// # Returns
+    ///
+    /// The model's response text or an error
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, &ChatOptions::default()).await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, options)).await
+    }
 
     async fn chat_with_tools(
         &self,
-        _messages: &[ChatMessage],
+        messages: &[ChatMessage],
         _tools: Option<&[Tool]>,
     ) -> Result<Box<dyn ChatResponse>, LLMError> {
-        todo!()
+        self.send_chat(messages, &ChatOptions::default()).await
     }
 }
 
@@ -286,11 +442,8 @@ impl CompletionProvider for Ollama {
             stream: false,
         };
 
-        let resp = self
-            .client
-            .post(&url)
-            .json(&req_body)
-            .send()
+        let request = self.client.post(&url).json(&req_body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
             .await?
             .error_for_status()?;
         let json_resp: OllamaResponse = resp.json().await?;
@@ -303,6 +456,76 @@ impl CompletionProvider for Ollama {
             ))
         }
     }
+
+    /// Sends a streaming completion request to Ollama's API.
+    ///
+    /// Ollama streams newline-delimited JSON objects, each carrying a fragment of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The completion request containing the prompt
+    ///
+    /// # Returns
+    ///
+    /// A stream of generated text fragments or an error
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        if self.base_url.is_empty() {
+            return Err(LLMError::InvalidRequest("Missing base_url".to_string()));
+        }
+        let url = format!("{}/api/generate", self.base_url);
+
+        let req_body = OllamaGenerateRequest {
+            model: self.model.clone(),
+            prompt: &req.prompt,
+            raw: true,
+            stream: true,
+        };
+
+        let request = self.client.post(&url).json(&req_body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let state = OllamaStreamState {
+            response: resp,
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            finished: false,
+        };
+
+        Ok(Box::pin(futures::stream::unfold(
+            state,
+            |mut state| async move {
+                loop {
+                    if let Some(item) = state.pending.pop_front() {
+                        return Some((item, state));
+                    }
+                    if state.finished {
+                        return None;
+                    }
+
+                    match state.response.chunk().await {
+                        Ok(Some(bytes)) => {
+                            state.buffer.extend_from_slice(&bytes);
+                            while let Some(pos) = state.buffer.iter().position(|&b| b == b'\n') {
+                                let line: Vec<u8> = state.buffer.drain(..=pos).collect();
+                                state.pending.extend(OllamaStreamState::parse_line(&line));
+                            }
+                        }
+                        Ok(None) => {
+                            state.finished = true;
+                            let rest = std::mem::take(&mut state.buffer);
+                            state.pending.extend(OllamaStreamState::parse_line(&rest));
+                        }
+                        Err(e) => {
+                            state.finished = true;
+                            return Some((Err(e.into()), state));
+                        }
+                    }
+                }
+            },
+        )))
+    }
 }
 
 #[async_trait]
@@ -318,11 +541,8 @@ impl EmbeddingProvider for Ollama {
             input: text,
         };
 
-        let resp = self
-            .client
-            .post(&url)
-            .json(&body)
-            .send()
+        let request = self.client.post(&url).json(&body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
             .await?
             .error_for_status()?;
 
@@ -331,4 +551,9 @@ impl EmbeddingProvider for Ollama {
     }
 }
 
-impl crate::LLMProvider for Ollama {}
+#[async_trait]
+impl crate::LLMProvider for Ollama {
+    async fn validate(&self) -> HealthStatus {
+        probe(self.client.get(format!("{}/api/tags", self.base_url))).await
+    }
+}
@@ -0,0 +1,289 @@
+//! OpenAI's Batch API, running many chat requests offline at half the price.
+//!
+//! The requests are written to a JSONL file, uploaded, and processed by a batch job within
+//! 24 hours. Once the job is done, its output file holds one reply per request, matched to
+//! the requests by their custom IDs since the replies come in no particular order.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+use crate::{
+    backends::send_request,
+    chat::{BatchRequest, BatchResult, ChatResponse},
+    error::LLMError,
+};
+
+use super::{OpenAI, OpenAIChatResponse};
+
+/// Endpoint the requests of a batch are sent to.
+const ENDPOINT: &str = "/v1/chat/completions";
+
+/// Processing status of a batch job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The input file is being validated
+    Validating,
+    /// The input file failed validation
+    Failed,
+    /// The requests are being processed
+    InProgress,
+    /// The results are being prepared
+    Finalizing,
+    /// The results are ready
+    Completed,
+    /// The job did not finish within its completion window
+    Expired,
+    /// The job is being cancelled
+    Cancelling,
+    /// The job was cancelled
+    Cancelled,
+}
+
+/// Number of requests of a batch job, by outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct BatchRequestCounts {
+    pub total: u32,
+    pub completed: u32,
+    pub failed: u32,
+}
+
+/// An error validating the input file of a batch job.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BatchError {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub message: String,
+    /// Line of the input file the error is on
+    #[serde(default)]
+    pub line: Option<u64>,
+}
+
+/// A batch job.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Batch {
+    /// ID of the job, to poll or cancel it
+    pub id: String,
+    pub status: BatchStatus,
+    /// ID of the uploaded file holding the requests
+    pub input_file_id: String,
+    /// ID of the file holding the successful replies, once the job is done
+    #[serde(default)]
+    pub output_file_id: Option<String>,
+    /// ID of the file holding the failed requests, once the job is done
+    #[serde(default)]
+    pub error_file_id: Option<String>,
+    #[serde(default)]
+    pub request_counts: BatchRequestCounts,
+    /// Errors validating the input file, when the job failed
+    #[serde(default, deserialize_with = "batch_errors")]
+    pub errors: Vec<BatchError>,
+    /// Creation time, in seconds since the Unix epoch
+    #[serde(default)]
+    pub created_at: u64,
+    /// Completion time, in seconds since the Unix epoch
+    #[serde(default)]
+    pub completed_at: Option<u64>,
+}
+
+impl Batch {
+    /// Returns whether the job has stopped, successfully or not.
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self.status,
+            BatchStatus::Failed
+                | BatchStatus::Completed
+                | BatchStatus::Expired
+                | BatchStatus::Cancelled
+        )
+    }
+}
+
+/// Reads the `{"data": [...]}` list of validation errors, which is null when there are none.
+fn batch_errors<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<BatchError>, D::Error> {
+    #[derive(Deserialize)]
+    struct Errors {
+        #[serde(default)]
+        data: Vec<BatchError>,
+    }
+    Ok(Option::<Errors>::deserialize(deserializer)?
+        .map(|errors| errors.data)
+        .unwrap_or_default())
+}
+
+/// A line of a batch's output or error file.
+#[derive(Deserialize)]
+struct BatchOutputLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<BatchOutputResponse>,
+    #[serde(default)]
+    error: Option<BatchOutputError>,
+}
+
+/// HTTP response to one of the requests of a batch.
+#[derive(Deserialize)]
+struct BatchOutputResponse {
+    status_code: u16,
+    #[serde(default)]
+    request_id: Option<String>,
+    #[serde(default)]
+    body: Value,
+}
+
+/// Error of a request that got no HTTP response.
+#[derive(Deserialize)]
+struct BatchOutputError {
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: String,
+}
+
+impl OpenAI {
+    /// Uploads the requests and creates a batch job processing them within 24 hours.
+    ///
+    /// The requests are built with the client's settings, like [`ChatProvider::chat`]
+    /// requests; the replies are read with [`OpenAI::batch_results`] once the job is done.
+    ///
+    /// [`ChatProvider::chat`]: crate::chat::ChatProvider::chat
+    pub async fn create_batch(&self, requests: &[BatchRequest]) -> Result<Batch, LLMError> {
+        if self.requires_key && self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing OpenAI API key".to_string()));
+        }
+
+        let mut jsonl = Vec::new();
+        for request in requests {
+            let line = json!({
+                "custom_id": request.custom_id,
+                "method": "POST",
+                "url": ENDPOINT,
+                "body": self.chat_body(
+                    &request.messages,
+                    request.tools.as_deref(),
+                    false,
+                    &request.options,
+                )?,
+            });
+            serde_json::to_writer(&mut jsonl, &line)
+                .map_err(|e| LLMError::JsonError(e.to_string()))?;
+            jsonl.push(b'\n');
+        }
+        let input_file = self.upload_file("batch.jsonl", &jsonl, "batch").await?;
+
+        let request = self
+            .request(reqwest::Method::POST, format!("{}/batches", self.base_url))
+            .json(&json!({
+                "input_file_id": input_file.id,
+                "endpoint": ENDPOINT,
+                "completion_window": "24h",
+            }));
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Returns the current state of a batch job.
+    pub async fn batch(&self, batch_id: &str) -> Result<Batch, LLMError> {
+        let request = self.request(
+            reqwest::Method::GET,
+            format!("{}/batches/{}", self.base_url, batch_id),
+        );
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Cancels a batch job. The requests already processed keep their replies.
+    pub async fn cancel_batch(&self, batch_id: &str) -> Result<Batch, LLMError> {
+        let request = self.request(
+            reqwest::Method::POST,
+            format!("{}/batches/{}/cancel", self.base_url, batch_id),
+        );
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Polls a batch job until it is done.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_id` - ID of the job
+    /// * `poll_interval` - Time waited between two polls
+    pub async fn wait_for_batch(
+        &self,
+        batch_id: &str,
+        poll_interval: Duration,
+    ) -> Result<Batch, LLMError> {
+        loop {
+            let batch = self.batch(batch_id).await?;
+            if batch.is_done() {
+                return Ok(batch);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Downloads and parses the replies of a done batch job, the successful ones first.
+    pub async fn batch_results(&self, batch: &Batch) -> Result<Vec<BatchResult>, LLMError> {
+        let mut results = Vec::new();
+        for file_id in [&batch.output_file_id, &batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let content = self.file_content(file_id).await?;
+            for line in String::from_utf8_lossy(&content)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+            {
+                let line: BatchOutputLine =
+                    serde_json::from_str(line).map_err(|e| LLMError::JsonError(e.to_string()))?;
+                results.push(BatchResult {
+                    custom_id: line.custom_id,
+                    response: self.batch_response(line.response, line.error),
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    /// Converts the response to one of the requests of a batch into a chat response.
+    fn batch_response(
+        &self,
+        response: Option<BatchOutputResponse>,
+        error: Option<BatchOutputError>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if let Some(error) = error {
+            return Err(LLMError::ProviderError(match error.code {
+                Some(code) => format!("{}: {}", code, error.message),
+                None => error.message,
+            }));
+        }
+        let response = response.ok_or_else(|| {
+            LLMError::ProviderError("Batch request has neither a response nor an error".into())
+        })?;
+        if response.status_code != 200 {
+            let message = response.body["error"]["message"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| response.body.to_string());
+            return Err(LLMError::HttpError(format!(
+                "Batch request failed with status {}: {}",
+                response.status_code, message
+            )));
+        }
+        let mut chat: OpenAIChatResponse = serde_json::from_value(response.body.clone())
+            .map_err(|e| LLMError::JsonError(e.to_string()))?;
+        chat.raw = response.body;
+        chat.request_id = response.request_id;
+        chat.provider = self.provider;
+        Ok(Box::new(chat))
+    }
+}
@@ -0,0 +1,130 @@
+//! OpenAI's Files API, storing documents to reference by ID.
+//!
+//! An uploaded file is sent to the model as a [`ContentPart::File`] part instead of its
+//! bytes, so a large PDF is uploaded once for several conversations. Batch jobs read their
+//! requests from and write their replies to files too.
+//!
+//! [`ContentPart::File`]: crate::chat::ContentPart::File
+
+use serde::Deserialize;
+
+use crate::{
+    backends::{
+        multipart::{content_type, Multipart},
+        send_request,
+    },
+    error::LLMError,
+};
+
+use super::OpenAI;
+
+/// A file uploaded to OpenAI.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OpenAIFile {
+    /// ID of the file, to reference it in messages or delete it
+    pub id: String,
+    /// Name the file was uploaded with
+    #[serde(default)]
+    pub filename: String,
+    /// Size of the file, in bytes
+    #[serde(default)]
+    pub bytes: u64,
+    /// What the file is used for ("user_data", "batch", "batch_output"...)
+    #[serde(default)]
+    pub purpose: String,
+    /// Upload time, in seconds since the Unix epoch
+    #[serde(default)]
+    pub created_at: u64,
+    /// Time the file is deleted at, in seconds since the Unix epoch, if it expires
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+/// A page of the list of files.
+#[derive(Deserialize)]
+struct OpenAIFileList {
+    data: Vec<OpenAIFile>,
+}
+
+impl OpenAI {
+    /// Uploads a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - Name of the file, whose extension gives its media type
+    /// * `bytes` - Content of the file
+    /// * `purpose` - What the file is used for: "user_data" for documents referenced in
+    ///   messages, "batch" for batch requests, "vision", "assistants" or "fine-tune"
+    pub async fn upload_file(
+        &self,
+        filename: &str,
+        bytes: &[u8],
+        purpose: &str,
+    ) -> Result<OpenAIFile, LLMError> {
+        if self.requires_key && self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing OpenAI API key".to_string()));
+        }
+
+        let (content_type_header, body) = Multipart::new()
+            .text("purpose", purpose)
+            .file("file", filename, content_type(filename), bytes)
+            .finish();
+        let request = self
+            .request(reqwest::Method::POST, format!("{}/files", self.base_url))
+            .header(reqwest::header::CONTENT_TYPE, content_type_header)
+            .body(body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Lists the uploaded files, optionally only those with the given purpose.
+    pub async fn list_files(&self, purpose: Option<&str>) -> Result<Vec<OpenAIFile>, LLMError> {
+        let mut request = self.request(reqwest::Method::GET, format!("{}/files", self.base_url));
+        if let Some(purpose) = purpose {
+            request = request.query(&[("purpose", purpose)]);
+        }
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        let list: OpenAIFileList = resp.json().await?;
+        Ok(list.data)
+    }
+
+    /// Returns the details of an uploaded file.
+    pub async fn file(&self, file_id: &str) -> Result<OpenAIFile, LLMError> {
+        let request = self.request(
+            reqwest::Method::GET,
+            format!("{}/files/{}", self.base_url, file_id),
+        );
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// Downloads the content of a file.
+    pub async fn file_content(&self, file_id: &str) -> Result<Vec<u8>, LLMError> {
+        let request = self.request(
+            reqwest::Method::GET,
+            format!("{}/files/{}/content", self.base_url, file_id),
+        );
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    /// Deletes an uploaded file.
+    pub async fn delete_file(&self, file_id: &str) -> Result<(), LLMError> {
+        let request = self.request(
+            reqwest::Method::DELETE,
+            format!("{}/files/{}", self.base_url, file_id),
+        );
+        send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Forwarding of the provider traits to a wrapped OpenAI client.
+//!
+//! Most OpenAI-compatible backends configure an [`OpenAI`](super::OpenAI) client in their
+//! constructor and send every request through it. [`forward_to_inner!`] implements the
+//! provider traits of such a backend by calling the same methods on its `inner` client,
+//! so a backend only writes the impls whose behavior differs.
+
+/// Implements provider traits for a type by forwarding every call to its `inner` field.
+///
+/// `forward_to_inner!(Client)` implements [`ChatProvider`], [`CompletionProvider`],
+/// [`EmbeddingProvider`] and [`LLMProvider`]. `forward_to_inner!(Client; chat, embedding)`
+/// implements only the listed ones, out of `chat`, `completion`, `embedding` and
+/// `provider`, leaving the others to the backend.
+///
+/// [`ChatProvider`]: crate::chat::ChatProvider
+/// [`CompletionProvider`]: crate::completion::CompletionProvider
+/// [`EmbeddingProvider`]: crate::embedding::EmbeddingProvider
+/// [`LLMProvider`]: crate::LLMProvider
+macro_rules! forward_to_inner {
+    (@chat $client:ty) => {
+        #[async_trait::async_trait]
+        impl $crate::chat::ChatProvider for $client {
+            async fn chat_with_tools(
+                &self,
+                messages: &[$crate::chat::ChatMessage],
+                tools: Option<&[$crate::chat::Tool]>,
+            ) -> Result<Box<dyn $crate::chat::ChatResponse>, $crate::error::LLMError> {
+                self.inner.chat_with_tools(messages, tools).await
+            }
+
+            async fn chat_with_options(
+                &self,
+                messages: &[$crate::chat::ChatMessage],
+                tools: Option<&[$crate::chat::Tool]>,
+                options: &$crate::chat::ChatOptions,
+            ) -> Result<Box<dyn $crate::chat::ChatResponse>, $crate::error::LLMError> {
+                self.inner.chat_with_options(messages, tools, options).await
+            }
+
+            async fn chat_stream_with_tools(
+                &self,
+                messages: &[$crate::chat::ChatMessage],
+                tools: Option<&[$crate::chat::Tool]>,
+            ) -> Result<$crate::chat::ChatStream, $crate::error::LLMError> {
+                self.inner.chat_stream_with_tools(messages, tools).await
+            }
+
+            async fn chat_stream_with_options(
+                &self,
+                messages: &[$crate::chat::ChatMessage],
+                tools: Option<&[$crate::chat::Tool]>,
+                options: &$crate::chat::ChatOptions,
+            ) -> Result<$crate::chat::ChatStream, $crate::error::LLMError> {
+                self.inner
+                    .chat_stream_with_options(messages, tools, options)
+                    .await
+            }
+        }
+    };
+    (@completion $client:ty) => {
+        #[async_trait::async_trait]
+        impl $crate::completion::CompletionProvider for $client {
+            async fn complete(
+                &self,
+                req: &$crate::completion::CompletionRequest,
+            ) -> Result<$crate::completion::CompletionResponse, $crate::error::LLMError> {
+                self.inner.complete(req).await
+            }
+
+            async fn complete_stream(
+                &self,
+                req: &$crate::completion::CompletionRequest,
+            ) -> Result<$crate::completion::CompletionStream, $crate::error::LLMError> {
+                self.inner.complete_stream(req).await
+            }
+
+            async fn complete_with_options(
+                &self,
+                req: &$crate::completion::CompletionRequest,
+                options: &$crate::chat::ChatOptions,
+            ) -> Result<$crate::completion::CompletionResponse, $crate::error::LLMError> {
+                self.inner.complete_with_options(req, options).await
+            }
+        }
+    };
+    (@embedding $client:ty) => {
+        #[async_trait::async_trait]
+        impl $crate::embedding::EmbeddingProvider for $client {
+            async fn embed(
+                &self,
+                input: Vec<String>,
+            ) -> Result<Vec<Vec<f32>>, $crate::error::LLMError> {
+                self.inner.embed(input).await
+            }
+        }
+    };
+    (@provider $client:ty) => {
+        #[async_trait::async_trait]
+        impl $crate::LLMProvider for $client {
+            fn tools(&self) -> Option<&[$crate::chat::Tool]> {
+                self.inner.tools()
+            }
+
+            async fn validate(&self) -> $crate::health::HealthStatus {
+                self.inner.validate().await
+            }
+        }
+    };
+    ($client:ty; $($part:ident),+ $(,)?) => {
+        $($crate::backends::openai::forward_to_inner!(@$part $client);)+
+    };
+    ($client:ty) => {
+        $crate::backends::openai::forward_to_inner!($client; chat, completion, embedding, provider);
+    };
+}
+
+pub(crate) use forward_to_inner;
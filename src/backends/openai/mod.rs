@@ -0,0 +1,1356 @@
+//! OpenAI API client implementation for chat and completion functionality.
+//!
+//! This module provides integration with OpenAI's GPT models through their API.
+
+#[cfg(feature = "openai")]
+use crate::{
+    backends::{
+        check_tool_messages, http_client, json_with_raw, probe, request_body, request_id_header,
+        send_request,
+        sse::{chat_chunk_stream, SseEvent, StreamEnd},
+        HttpOptions,
+    },
+    chat::{
+        AudioOutput, AudioReply, ChatMessage, ChatProvider, ChatRole, ChatStream, ContentPart,
+        ImageDetail, MessageType, StreamChunk, StreamedResponse, StructuredOutputFormat, Usage,
+    },
+    chat::{Tool, ToolChoice},
+    completion::{
+        text_stream, CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+    },
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    moderation::{ModerationProvider, ModerationResult},
+    retry::RetryPolicy,
+    LLMProvider,
+};
+use crate::{
+    chat::{
+        timed_stream, with_cancellation, with_stream_cancellation, ChatChoice, ChatOptions,
+        ChatResponse, FinishReason, RequestTimer, ResponseMetadata, ResponseTiming, TokenLogprob,
+    },
+    ToolCall,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+mod batch;
+mod files;
+#[cfg(any(
+    feature = "azure",
+    feature = "mistral",
+    feature = "fireworks",
+    feature = "perplexity",
+    feature = "nvidia",
+    feature = "openai-compatible",
+    feature = "lmstudio",
+    feature = "qwen",
+    feature = "moonshot"
+))]
+mod forward;
+#[cfg(feature = "realtime")]
+mod realtime;
+
+pub use batch::{Batch, BatchError, BatchRequestCounts, BatchStatus};
+pub use files::OpenAIFile;
+#[cfg(any(
+    feature = "azure",
+    feature = "mistral",
+    feature = "fireworks",
+    feature = "perplexity",
+    feature = "nvidia",
+    feature = "openai-compatible",
+    feature = "lmstudio",
+    feature = "qwen",
+    feature = "moonshot"
+))]
+pub(crate) use forward::forward_to_inner;
+#[cfg(feature = "realtime")]
+pub use realtime::{RealtimeEvent, RealtimeEventStream, RealtimeSender, REALTIME_MODEL};
+
+/// Model screening texts when no moderation model is configured.
+pub const MODERATION_MODEL: &str = "omni-moderation-latest";
+
+/// Settings of OpenAI's chat requests beyond those shared with the other backends, applied
+/// to every request of a client.
+#[derive(Debug, Clone, Default)]
+pub struct OpenAIParams {
+    /// How the model should choose between the tools
+    pub tool_choice: Option<ToolChoice>,
+    /// Whether the model may request several tool calls at once
+    pub parallel_tool_calls: Option<bool>,
+    /// Number of candidate replies to generate per request
+    pub n: Option<u32>,
+    /// Whether to return log probabilities of the reply tokens
+    pub logprobs: Option<bool>,
+    /// Number of most likely alternatives to return for each token
+    pub top_logprobs: Option<u8>,
+    /// Penalty on tokens proportional to how often they appeared
+    pub frequency_penalty: Option<f32>,
+    /// Penalty on tokens that already appeared
+    pub presence_penalty: Option<f32>,
+    /// Sequences that end the generation when produced
+    pub stop: Option<Vec<String>>,
+    /// Seed for deterministic sampling
+    pub seed: Option<u64>,
+    /// Bias added to the likelihood of tokens, by token ID
+    pub logit_bias: Option<HashMap<u32, f32>>,
+    /// Voice and format of spoken replies, for audio models
+    pub audio_output: Option<AudioOutput>,
+}
+
+/// Client for interacting with OpenAI's API.
+///
+/// Provides methods for chat and completion requests using OpenAI's models.
+pub struct OpenAI {
+    pub api_key: String,
+    pub model: String,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub system: Option<String>,
+    pub timeout_seconds: Option<u64>,
+    pub stream: Option<bool>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
+    pub tools: Option<Vec<Tool>>,
+    pub tool_choice: Option<ToolChoice>,
+    /// Whether the model may request several tool calls in one response
+    pub parallel_tool_calls: Option<bool>,
+    /// Embedding parameters
+    pub embedding_encoding_format: Option<String>,
+    pub embedding_dimensions: Option<u32>,
+    pub reasoning_effort: Option<String>,
+    /// JSON schema for structured output
+    pub structured_output: Option<StructuredOutputFormat>,
+    /// Number of candidate replies to generate per request
+    pub n: Option<u32>,
+    /// Whether to return log probabilities of the reply tokens
+    pub logprobs: Option<bool>,
+    /// Number of most likely alternatives to return for each token
+    pub top_logprobs: Option<u8>,
+    /// Penalty on tokens proportional to how often they already appeared (-2.0 to 2.0)
+    pub frequency_penalty: Option<f32>,
+    /// Penalty on tokens that already appeared at all (-2.0 to 2.0)
+    pub presence_penalty: Option<f32>,
+    /// Sequences that end the generation when produced
+    pub stop: Option<Vec<String>>,
+    /// Seed for deterministic sampling
+    pub seed: Option<u64>,
+    /// Bias added to the likelihood of tokens, by token ID (-100 to 100)
+    pub logit_bias: Option<HashMap<u32, f32>>,
+    /// Voice and format of spoken replies, for audio models answering with audio
+    pub audio_output: Option<AudioOutput>,
+    /// Base URL of the API, without a trailing slash
+    pub base_url: String,
+    /// Extra parameters merged into the chat request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
+    /// Name of the provider reported with the responses
+    pub(crate) provider: &'static str,
+    /// `api-version` query parameter sent with every request
+    pub(crate) api_version: Option<String>,
+    /// Header carrying the API key, or `None` to send it as a bearer token
+    pub(crate) api_key_header: Option<&'static str>,
+    /// Differences of the API from OpenAI's
+    pub(crate) dialect: Dialect,
+    /// Model screening texts for harmful content (defaults to [`MODERATION_MODEL`])
+    pub moderation_model: Option<String>,
+    /// Model computing embeddings, if it differs from the chat model
+    pub(crate) embedding_model: Option<String>,
+    /// Kind of text embedded, sent as `input_type` by APIs that embed queries and
+    /// documents differently
+    pub(crate) embedding_input_type: Option<String>,
+    /// Whether requests fail without an API key, rather than being sent unauthenticated
+    pub(crate) requires_key: bool,
+    client: Client,
+    /// Client opening realtime sessions, restricted to HTTP/1.1 for the WebSocket upgrade
+    #[cfg(feature = "realtime")]
+    realtime_client: Client,
+}
+
+/// Differences of an OpenAI-compatible API from OpenAI's, applied to chat request bodies
+/// before the extra parameters are merged in.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Dialect {
+    /// Fields the API rejects, which are left out
+    pub unsupported: &'static [&'static str],
+    /// Fields the API names differently, as (OpenAI name, API name)
+    pub renamed: &'static [(&'static str, &'static str)],
+}
+
+impl Dialect {
+    /// Rewrites a chat request body for the API.
+    fn apply(&self, body: &mut Value) {
+        let Some(body) = body.as_object_mut() else {
+            return;
+        };
+        for field in self.unsupported {
+            body.remove(*field);
+        }
+        for (from, to) in self.renamed {
+            if let Some(value) = body.remove(*from) {
+                body.insert(to.to_string(), value);
+            }
+        }
+    }
+}
+
+/// Individual message in an OpenAI chat conversation.
+#[derive(Serialize, Debug)]
+struct OpenAIChatMessage<'a> {
+    #[allow(dead_code)]
+    role: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<Vec<MessageContent<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<&'a [ToolCall]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<&'a str>,
+}
+
+#[derive(Serialize, Debug)]
+struct MessageContent<'a> {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    message_type: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_url: Option<ImageUrlContent<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<FileContent<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_audio: Option<InputAudioContent>,
+}
+
+/// Individual image message in an OpenAI chat conversation.
+#[derive(Serialize, Debug)]
+struct ImageUrlContent<'a> {
+    url: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<ImageDetail>,
+}
+
+/// Audio clip attached to an OpenAI chat message.
+#[derive(Serialize, Debug)]
+struct InputAudioContent {
+    data: String,
+    format: &'static str,
+}
+
+/// File attached to an OpenAI chat message, inline or by the ID of an uploaded file.
+#[derive(Serialize, Debug)]
+struct FileContent<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_id: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct OpenAIEmbeddingRequest {
+    model: String,
+    input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_type: Option<String>,
+}
+
+/// Request payload for OpenAI's chat API endpoint.
+#[derive(Serialize, Debug)]
+struct OpenAIChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<OpenAIChatMessage<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAIStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logprobs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_logprobs: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logit_bias: Option<&'a HashMap<u32, f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modalities: Option<[&'static str; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio: Option<&'a AudioOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<Tool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parallel_tool_calls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<OpenAIResponseFormat>,
+}
+
+/// Options controlling a streamed response.
+#[derive(Serialize, Debug)]
+struct OpenAIStreamOptions {
+    include_usage: bool,
+}
+
+/// Response from OpenAI's chat API endpoint.
+#[derive(Deserialize, Debug)]
+struct OpenAIChatResponse {
+    choices: Vec<OpenAIChatChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    system_fingerprint: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
+    /// Name of the provider that served the request
+    #[serde(skip)]
+    provider: &'static str,
+}
+
+/// Individual choice within an OpenAI chat API response.
+#[derive(Deserialize, Debug)]
+struct OpenAIChatChoice {
+    message: OpenAIChatMsg,
+    #[serde(default)]
+    finish_reason: Option<String>,
+    /// Log probabilities of the reply tokens, if requested
+    #[serde(default)]
+    logprobs: Option<OpenAIChoiceLogprobs>,
+}
+
+/// Log probabilities attached to a choice.
+#[derive(Deserialize, Debug)]
+struct OpenAIChoiceLogprobs {
+    #[serde(default)]
+    content: Option<Vec<TokenLogprob>>,
+}
+
+/// Message content within an OpenAI chat API response.
+#[derive(Deserialize, Debug)]
+struct OpenAIChatMsg {
+    #[allow(dead_code)]
+    role: String,
+    content: Option<String>,
+    tool_calls: Option<Vec<ToolCall>>,
+    #[serde(default)]
+    refusal: Option<String>,
+    /// Spoken reply of an audio model
+    #[serde(default)]
+    audio: Option<OpenAIAudioReply>,
+}
+
+/// Spoken reply of an audio model, its audio base64-encoded.
+#[derive(Deserialize, Debug)]
+struct OpenAIAudioReply {
+    #[serde(default)]
+    id: Option<String>,
+    data: String,
+    #[serde(default)]
+    transcript: Option<String>,
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+/// A single chunk of a streamed chat response.
+#[derive(Deserialize, Debug)]
+struct OpenAIStreamChunk {
+    #[serde(default)]
+    choices: Vec<OpenAIStreamChoice>,
+    usage: Option<Usage>,
+}
+
+/// Individual choice within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct OpenAIStreamChoice {
+    delta: OpenAIStreamDelta,
+    finish_reason: Option<String>,
+}
+
+/// Incremental message content within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct OpenAIStreamDelta {
+    content: Option<String>,
+    tool_calls: Option<Vec<OpenAIToolCallDelta>>,
+}
+
+/// Fragment of a tool call within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct OpenAIToolCallDelta {
+    index: usize,
+    id: Option<String>,
+    function: Option<OpenAIFunctionDelta>,
+}
+
+/// Fragment of a function call within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct OpenAIFunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIEmbeddingData {
+    embedding: Vec<f32>,
+}
+/// Request payload for OpenAI's moderation endpoint.
+#[derive(Serialize, Debug)]
+struct OpenAIModerationRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// Response of OpenAI's moderation endpoint.
+#[derive(Deserialize, Debug)]
+struct OpenAIModerationResponse {
+    results: Vec<ModerationResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIEmbeddingResponse {
+    data: Vec<OpenAIEmbeddingData>,
+}
+
+/// An object specifying the format that the model must output.
+///Setting to `{ "type": "json_schema", "json_schema": {...} }` enables Structured Outputs which ensures the model will match your supplied JSON schema. Learn more in the [Structured Outputs guide](https://platform.openai.com/docs/guides/structured-outputs).
+/// Setting to `{ "type": "json_object" }` enables the older JSON mode, which ensures the message the model generates is valid JSON. Using `json_schema` is preferred for models that support it.
+#[derive(Deserialize, Debug, Serialize, PartialEq)]
+enum OpenAIResponseType {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "json_schema")]
+    JsonSchema,
+    #[serde(rename = "json_object")]
+    JsonObject,
+}
+
+#[derive(Deserialize, Debug, Serialize, PartialEq)]
+struct OpenAIResponseFormat {
+    #[serde(rename = "type")]
+    response_type: OpenAIResponseType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    json_schema: Option<StructuredOutputFormat>,
+}
+
+impl From<StructuredOutputFormat> for OpenAIResponseFormat {
+    /// Modify the schema to ensure that it meets OpenAI's requirements.
+    fn from(structured_response_format: StructuredOutputFormat) -> Self {
+        // It's possible to pass a StructuredOutputJsonSchema without an actual schema.
+        // In this case, just pass the StructuredOutputJsonSchema object without modifying it.
+        match structured_response_format.schema {
+            None => OpenAIResponseFormat {
+                response_type: OpenAIResponseType::JsonSchema,
+                json_schema: Some(structured_response_format),
+            },
+            Some(mut schema) => {
+                // Although [OpenAI's specifications](https://platform.openai.com/docs/guides/structured-outputs?api-mode=chat#additionalproperties-false-must-always-be-set-in-objects) say that the "additionalProperties" field is required, my testing shows that it is not.
+                // Just to be safe, add it to the schema if it is missing.
+                schema = if schema.get("additionalProperties").is_none() {
+                    schema["additionalProperties"] = json!(false);
+                    schema
+                } else {
+                    schema
+                };
+                OpenAIResponseFormat {
+                    response_type: OpenAIResponseType::JsonSchema,
+                    json_schema: Some(StructuredOutputFormat {
+                        name: structured_response_format.name,
+                        description: structured_response_format.description,
+                        schema: Some(schema),
+                        strict: structured_response_format.strict,
+                    }),
+                }
+            }
+        }
+    }
+}
+
+impl ChatResponse for OpenAIChatResponse {
+    fn usage(&self) -> Option<Usage> {
+        self.usage.clone()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some(self.provider.to_string()),
+            model: self.model.clone(),
+            response_id: self.id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: self.system_fingerprint.clone(),
+            timing: self.timing,
+        }
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw)
+    }
+
+    /// Returns the reply text, or the transcript of a spoken reply.
+    fn text(&self) -> Option<String> {
+        let message = &self.choices.first()?.message;
+        message.content.clone().or_else(|| {
+            message
+                .audio
+                .as_ref()
+                .and_then(|audio| audio.transcript.clone())
+        })
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        self.choices
+            .first()
+            .and_then(|c| c.message.tool_calls.clone())
+    }
+
+    fn audio(&self) -> Option<AudioReply> {
+        let audio = self.choices.first()?.message.audio.as_ref()?;
+        Some(AudioReply {
+            id: audio.id.clone(),
+            data: BASE64.decode(&audio.data).ok()?,
+            transcript: audio.transcript.clone(),
+            expires_at: audio.expires_at,
+        })
+    }
+
+    fn logprobs(&self) -> Option<Vec<TokenLogprob>> {
+        self.choices
+            .first()
+            .and_then(|c| c.logprobs.as_ref())
+            .and_then(|l| l.content.clone())
+    }
+
+    fn refusal(&self) -> Option<String> {
+        self.choices.first().and_then(|c| c.message.refusal.clone())
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.choices
+            .first()
+            .and_then(|c| c.finish_reason.as_deref())
+            .map(FinishReason::from_provider)
+    }
+
+    fn choices(&self) -> Vec<ChatChoice> {
+        self.choices
+            .iter()
+            .map(|c| ChatChoice {
+                text: c.message.content.clone(),
+                tool_calls: c.message.tool_calls.clone(),
+                finish_reason: c.finish_reason.as_deref().map(FinishReason::from_provider),
+                refusal: c.message.refusal.clone(),
+                logprobs: c.logprobs.as_ref().and_then(|l| l.content.clone()),
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for OpenAIChatResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (
+            &self.choices.first().unwrap().message.content,
+            &self.choices.first().unwrap().message.tool_calls,
+        ) {
+            (Some(content), Some(tool_calls)) => {
+                for tool_call in tool_calls {
+                    write!(f, "{}", tool_call)?;
+                }
+                write!(f, "{}", content)
+            }
+            (Some(content), None) => write!(f, "{}", content),
+            (None, Some(tool_calls)) => {
+                for tool_call in tool_calls {
+                    write!(f, "{}", tool_call)?;
+                }
+                Ok(())
+            }
+            (None, None) => write!(
+                f,
+                "{}",
+                self.text().or_else(|| self.refusal()).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Converts a part of a multi-part message into an OpenAI content part, its images read
+/// at the given detail level.
+fn content_part(part: &ContentPart, detail: Option<ImageDetail>) -> MessageContent<'_> {
+    let (message_type, text, image_url, file, input_audio) = match part {
+        ContentPart::Text(text) => ("text", Some(text.as_str()), None, None, None),
+        ContentPart::Image((image_mime, raw_bytes)) => (
+            "image_url",
+            None,
+            Some(ImageUrlContent {
+                url: Cow::Owned(format!(
+                    "data:{};base64,{}",
+                    image_mime.mime_type(),
+                    BASE64.encode(raw_bytes)
+                )),
+                detail,
+            }),
+            None,
+            None,
+        ),
+        ContentPart::ImageURL(url) => (
+            "image_url",
+            None,
+            Some(ImageUrlContent {
+                url: Cow::Borrowed(url),
+                detail,
+            }),
+            None,
+            None,
+        ),
+        ContentPart::Pdf(raw_bytes) => ("file", None, None, Some(pdf_file(raw_bytes)), None),
+        ContentPart::File(file_id) => (
+            "file",
+            None,
+            None,
+            Some(FileContent {
+                filename: None,
+                file_data: None,
+                file_id: Some(file_id),
+            }),
+            None,
+        ),
+        ContentPart::Audio((audio_mime, raw_bytes)) => (
+            "input_audio",
+            None,
+            None,
+            None,
+            Some(InputAudioContent {
+                data: BASE64.encode(raw_bytes),
+                format: audio_mime.format(),
+            }),
+        ),
+    };
+    MessageContent {
+        message_type: Some(message_type),
+        text,
+        image_url,
+        file,
+        input_audio,
+    }
+}
+
+/// A file holding a PDF's bytes.
+fn pdf_file<'a>(raw_bytes: &[u8]) -> FileContent<'a> {
+    FileContent {
+        filename: Some("document.pdf"),
+        file_data: Some(format!(
+            "data:application/pdf;base64,{}",
+            BASE64.encode(raw_bytes)
+        )),
+        file_id: None,
+    }
+}
+
+impl OpenAI {
+    /// Creates a new OpenAI client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - OpenAI API key
+    /// * `model` - Model to use (defaults to "gpt-3.5-turbo")
+    /// * `max_tokens` - Maximum tokens to generate
+    /// * `temperature` - Sampling temperature
+    /// * `timeout_seconds` - Request timeout in seconds
+    /// * `system` - System prompt
+    /// * `stream` - Whether to stream responses
+    /// * `tools` - Function tools the model may call
+    /// * `json_schema` - JSON schema for structured output
+    /// * `params` - OpenAI-specific request settings (tool choice, candidates, log
+    ///   probabilities, penalties, audio replies...)
+    /// * `http` - HTTP settings, such as the base URL (defaults to
+    ///   `https://api.openai.com/v1`), headers, proxy and retry policy
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        api_key: impl Into<String>,
+        model: Option<String>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+        timeout_seconds: Option<u64>,
+        system: Option<String>,
+        stream: Option<bool>,
+        top_p: Option<f32>,
+        top_k: Option<u32>,
+        embedding_encoding_format: Option<String>,
+        embedding_dimensions: Option<u32>,
+        tools: Option<Vec<Tool>>,
+        reasoning_effort: Option<String>,
+        json_schema: Option<StructuredOutputFormat>,
+        params: OpenAIParams,
+        http: HttpOptions,
+    ) -> Self {
+        let OpenAIParams {
+            tool_choice,
+            parallel_tool_calls,
+            n,
+            logprobs,
+            top_logprobs,
+            frequency_penalty,
+            presence_penalty,
+            stop,
+            seed,
+            logit_bias,
+            audio_output,
+        } = params;
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
+        #[cfg(feature = "realtime")]
+        let realtime_client = crate::backends::websocket::http_client(
+            client.clone(),
+            timeout_seconds,
+            headers.clone(),
+            proxy.clone(),
+        );
+        Self {
+            api_key: api_key.into(),
+            model: model.unwrap_or("gpt-3.5-turbo".to_string()),
+            max_tokens,
+            temperature,
+            system,
+            timeout_seconds,
+            stream,
+            top_p,
+            top_k,
+            tools,
+            tool_choice,
+            parallel_tool_calls,
+            embedding_encoding_format,
+            embedding_dimensions,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            provider: "openai",
+            api_version: None,
+            api_key_header: None,
+            dialect: Dialect::default(),
+            moderation_model: None,
+            embedding_model: None,
+            embedding_input_type: None,
+            requires_key: true,
+            client: http_client(client, timeout_seconds, headers, proxy),
+            #[cfg(feature = "realtime")]
+            realtime_client,
+            reasoning_effort,
+            structured_output: json_schema,
+            n,
+            logprobs,
+            top_logprobs,
+            frequency_penalty,
+            presence_penalty,
+            stop,
+            seed,
+            logit_bias,
+            audio_output,
+        }
+    }
+
+    /// Starts an authenticated request to a URL of the API.
+    pub(crate) fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        self.authorize(self.client.request(method, url))
+    }
+
+    /// Adds the API key and version to a request.
+    fn authorize(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(version) = &self.api_version {
+            request = request.query(&[("api-version", version)]);
+        }
+        if self.api_key.is_empty() {
+            return request;
+        }
+        match self.api_key_header {
+            Some(header) => request.header(header, &self.api_key),
+            None => request.bearer_auth(&self.api_key),
+        }
+    }
+
+    /// Builds the JSON body of a chat request, with the dialect and extra parameters
+    /// applied.
+    fn chat_body(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        stream: bool,
+        options: &ChatOptions,
+    ) -> Result<Value, LLMError> {
+        check_tool_messages(messages)?;
+        // Clone the messages to have an owned mutable vector.
+        let mut messages = messages.to_vec();
+
+        let mut openai_msgs: Vec<OpenAIChatMessage> = messages
+            .iter_mut() // Use mutable iterator to allow content modification.
+            .map(|m| OpenAIChatMessage {
+                role: match (&m.role, &m.message_type) {
+                    (_, MessageType::ToolResult { .. }) => "tool",
+                    (ChatRole::System, _) => "system",
+                    (ChatRole::User, _) => "user",
+                    (ChatRole::Assistant, _) => "assistant",
+                    (ChatRole::Tool, _) => "tool",
+                },
+                name: m.metadata.name.as_deref(),
+                tool_calls: match &m.message_type {
+                    MessageType::ToolUse(calls) => Some(calls.as_slice()),
+                    _ => None,
+                },
+                tool_call_id: match &m.message_type {
+                    MessageType::ToolResult { tool_call_id, .. } => Some(tool_call_id.as_str()),
+                    _ => None,
+                },
+                content: match &m.message_type {
+                    MessageType::Text => Some(vec![MessageContent {
+                        message_type: Some("text"),
+                        text: Some(&m.content),
+                        image_url: None,
+                        file: None,
+                        input_audio: None,
+                    }]),
+                    MessageType::Image((image_mime, raw_bytes)) => Some(vec![MessageContent {
+                        message_type: Some("image_url"),
+                        text: None,
+                        image_url: Some(ImageUrlContent {
+                            url: {
+                                let base64_image = BASE64.encode(raw_bytes);
+                                // Modify the content: m is now mutable thanks to iter_mut()
+                                m.content = format!(
+                                    "data:{};base64,{}",
+                                    image_mime.mime_type(),
+                                    base64_image
+                                );
+                                Cow::Borrowed(&m.content)
+                            },
+                            detail: m.metadata.image_detail.or(options.image_detail),
+                        }),
+                        file: None,
+                        input_audio: None,
+                    }]),
+                    MessageType::Pdf(raw_bytes) => Some(vec![MessageContent {
+                        message_type: Some("file"),
+                        text: None,
+                        image_url: None,
+                        file: Some(pdf_file(raw_bytes)),
+                        input_audio: None,
+                    }]),
+                    MessageType::Audio((audio_mime, raw_bytes)) => Some(vec![MessageContent {
+                        message_type: Some("input_audio"),
+                        text: None,
+                        image_url: None,
+                        file: None,
+                        input_audio: Some(InputAudioContent {
+                            data: BASE64.encode(raw_bytes),
+                            format: audio_mime.format(),
+                        }),
+                    }]),
+                    MessageType::ImageURL(ref url) => Some(vec![MessageContent {
+                        message_type: Some("image_url"),
+                        text: None,
+                        image_url: Some(ImageUrlContent {
+                            url: Cow::Borrowed(url),
+                            detail: m.metadata.image_detail.or(options.image_detail),
+                        }),
+                        file: None,
+                        input_audio: None,
+                    }]),
+                    MessageType::Parts(parts) => Some(
+                        parts
+                            .iter()
+                            .map(|part| {
+                                content_part(part, m.metadata.image_detail.or(options.image_detail))
+                            })
+                            .collect(),
+                    ),
+                    MessageType::ToolUse(_) if m.content.is_empty() => None,
+                    MessageType::ToolUse(_) => Some(vec![MessageContent {
+                        message_type: Some("text"),
+                        text: Some(&m.content),
+                        image_url: None,
+                        file: None,
+                        input_audio: None,
+                    }]),
+                    MessageType::ToolResult { content, .. } => Some(vec![MessageContent {
+                        message_type: Some("text"),
+                        text: Some(content),
+                        image_url: None,
+                        file: None,
+                        input_audio: None,
+                    }]),
+                },
+            })
+            .collect();
+
+        if let Some(system) = &self.system {
+            openai_msgs.insert(
+                0,
+                OpenAIChatMessage {
+                    role: "system",
+                    name: None,
+                    content: Some(vec![MessageContent {
+                        message_type: Some("text"),
+                        text: Some(system),
+                        image_url: None,
+                        file: None,
+                        input_audio: None,
+                    }]),
+                    tool_calls: None,
+                    tool_call_id: None,
+                },
+            );
+        }
+
+        // Build the response format object
+        let response_format: Option<OpenAIResponseFormat> =
+            self.structured_output.clone().map(|s| s.into());
+
+        let body = OpenAIChatRequest {
+            model: options.model.as_deref().unwrap_or(&self.model),
+            messages: openai_msgs,
+            max_tokens: options.max_tokens.or(self.max_tokens),
+            temperature: options.temperature.or(self.temperature),
+            stream,
+            stream_options: stream.then_some(OpenAIStreamOptions {
+                include_usage: true,
+            }),
+            top_p: options.top_p.or(self.top_p),
+            top_k: self.top_k,
+            stop: options.stop.clone().or_else(|| self.stop.clone()),
+            seed: options.seed.or(self.seed),
+            // Streams carry a single reply.
+            n: self.n.filter(|_| !stream),
+            logprobs: self.logprobs.filter(|_| !stream),
+            top_logprobs: self.top_logprobs.filter(|_| !stream),
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+            logit_bias: self.logit_bias.as_ref(),
+            // Streams carry only text.
+            modalities: self
+                .audio_output
+                .as_ref()
+                .filter(|_| !stream)
+                .map(|_| ["text", "audio"]),
+            audio: self.audio_output.as_ref().filter(|_| !stream),
+            tools: tools.map(|t| t.to_vec()),
+            tool_choice: tools.and(self.tool_choice.clone()),
+            parallel_tool_calls: tools.and(self.parallel_tool_calls),
+            reasoning_effort: options
+                .reasoning_effort
+                .map(|effort| effort.to_string())
+                .or_else(|| self.reasoning_effort.clone()),
+            response_format,
+        };
+
+        let mut body =
+            serde_json::to_value(&body).map_err(|e| LLMError::JsonError(e.to_string()))?;
+        self.dialect.apply(&mut body);
+        request_body(&body, &self.extra_params)
+    }
+
+    /// Builds and sends a chat request, returning the raw HTTP response.
+    async fn send_chat_request(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        stream: bool,
+        options: &ChatOptions,
+    ) -> Result<reqwest::Response, LLMError> {
+        if self.requires_key && self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing OpenAI API key".to_string()));
+        }
+
+        let mut request = self
+            .request(
+                reqwest::Method::POST,
+                format!("{}/chat/completions", self.base_url),
+            )
+            .json(&self.chat_body(messages, tools, stream, options)?);
+
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
+        }
+
+        Ok(
+            send_request(request, self.retry.as_ref(), self.logging.as_ref())
+                .await?
+                .error_for_status()?,
+        )
+    }
+
+    /// Sends a chat request and parses the reply, assembling it from a stream when
+    /// streaming is enabled.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if self.stream.unwrap_or(false) {
+            let mut timer = RequestTimer::start();
+            let mut stream = self.send_chat_stream(messages, tools, options).await?;
+            let mut response = StreamedResponse::default();
+            while let Some(chunk) = stream.next().await {
+                timer.first_byte();
+                response.push(chunk?);
+            }
+            response.timing = Some(timer.timing());
+            return Ok(Box::new(response));
+        }
+
+        let mut timer = RequestTimer::start();
+        let resp = self
+            .send_chat_request(messages, tools, false, options)
+            .await?;
+        timer.first_byte();
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (OpenAIChatResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        let model = options.model.as_deref().unwrap_or(&self.model);
+        json_resp.timing = Some(timer.finish(self.provider, model));
+        json_resp.request_id = request_id;
+        json_resp.provider = self.provider;
+
+        Ok(Box::new(json_resp))
+    }
+
+    /// Sends a streaming chat request and returns its chunks.
+    async fn send_chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        let timer = RequestTimer::start();
+        let resp = self
+            .send_chat_request(messages, tools, true, options)
+            .await?;
+        Ok(timed_stream(
+            chat_chunk_stream(resp, Self::parse_stream_event),
+            timer,
+            self.provider,
+            options.model.clone().unwrap_or_else(|| self.model.clone()),
+        ))
+    }
+
+    /// Converts a single streamed event into normalized chunks.
+    fn parse_stream_event(
+        event: &SseEvent,
+        end: &mut StreamEnd,
+    ) -> Result<Vec<StreamChunk>, LLMError> {
+        let chunk: OpenAIStreamChunk =
+            serde_json::from_str(&event.data).map_err(|e| LLMError::JsonError(e.to_string()))?;
+
+        if chunk.usage.is_some() {
+            end.usage = chunk.usage;
+        }
+
+        let mut chunks = Vec::new();
+        for choice in chunk.choices {
+            if let Some(text) = choice.delta.content.filter(|t| !t.is_empty()) {
+                chunks.push(StreamChunk::Text(text));
+            }
+            for tool_call in choice.delta.tool_calls.unwrap_or_default() {
+                let (name, arguments) = match tool_call.function {
+                    Some(function) => (function.name, function.arguments.unwrap_or_default()),
+                    None => (None, String::new()),
+                };
+                chunks.push(StreamChunk::ToolCallDelta {
+                    index: tool_call.index,
+                    id: tool_call.id,
+                    name,
+                    arguments,
+                });
+            }
+            if choice.finish_reason.is_some() {
+                end.finish_reason = choice.finish_reason;
+            }
+        }
+        Ok(chunks)
+    }
+}
+
+#[async_trait]
+impl ChatProvider for OpenAI {
+    /// Sends a chat request to OpenAI's API.
+    ///
+    /// When streaming is enabled, the response is streamed and assembled before returning.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Slice of chat messages representing the conversation
+    /// * `tools` - Optional slice of tools to use in the chat
+    /// # Returns
+    ///
+    /// The model's response text or an error
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.send_chat(messages, tools, options)).await
+    }
+
+    /// Sends a streaming chat request to OpenAI's API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Slice of chat messages representing the conversation
+    /// * `tools` - Optional slice of tools to use in the chat
+    /// # Returns
+    ///
+    /// A stream of response chunks or an error
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.send_chat_stream(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        with_stream_cancellation(options, self.send_chat_stream(messages, tools, options)).await
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_with_tools(messages, None).await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for OpenAI {
+    /// Sends a completion request to OpenAI's API.
+    ///
+    /// Currently not implemented.
+    async fn complete(&self, _req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        Ok(CompletionResponse {
+            text: "OpenAI completion not implemented.".into(),
+        })
+    }
+
+    /// Streams a completion by sending the prompt as a single user message to the chat endpoint.
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        let message = ChatMessage::user().content(req.prompt.clone()).build();
+        Ok(text_stream(self.chat_stream(&[message]).await?))
+    }
+}
+
+#[cfg(feature = "openai")]
+#[async_trait]
+impl EmbeddingProvider for OpenAI {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        if self.requires_key && self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing OpenAI API key".into()));
+        }
+
+        let emb_format = self
+            .embedding_encoding_format
+            .clone()
+            .unwrap_or_else(|| "float".to_string());
+
+        let body = OpenAIEmbeddingRequest {
+            model: self
+                .embedding_model
+                .clone()
+                .unwrap_or_else(|| self.model.clone()),
+            input,
+            encoding_format: Some(emb_format),
+            dimensions: self.embedding_dimensions,
+            input_type: self.embedding_input_type.clone(),
+        };
+
+        let request = self
+            .request(
+                reqwest::Method::POST,
+                format!("{}/embeddings", self.base_url),
+            )
+            .json(&body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let json_resp: OpenAIEmbeddingResponse = resp.json().await?;
+
+        let embeddings = json_resp.data.into_iter().map(|d| d.embedding).collect();
+        Ok(embeddings)
+    }
+}
+
+#[async_trait]
+impl ModerationProvider for OpenAI {
+    async fn moderate(&self, input: &[String]) -> Result<Vec<ModerationResult>, LLMError> {
+        if self.requires_key && self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing OpenAI API key".into()));
+        }
+
+        let body = OpenAIModerationRequest {
+            model: self.moderation_model.as_deref().unwrap_or(MODERATION_MODEL),
+            input,
+        };
+        let request = self
+            .request(
+                reqwest::Method::POST,
+                format!("{}/moderations", self.base_url),
+            )
+            .json(&body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let json_resp: OpenAIModerationResponse = resp.json().await?;
+        Ok(json_resp.results)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for OpenAI {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.tools.as_deref()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        probe(self.request(reqwest::Method::GET, format!("{}/models", self.base_url))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{LLMBackend, LLMBuilder};
+
+    #[test]
+    fn sends_pdf_messages_as_files() {
+        let llm = OpenAI::new(
+            "key",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            OpenAIParams::default(),
+            HttpOptions::default(),
+        );
+        let messages = [ChatMessage::user().pdf(b"%PDF".to_vec()).build()];
+        let body = llm
+            .chat_body(&messages, None, false, &ChatOptions::default())
+            .unwrap();
+        assert_eq!(
+            body["messages"][0]["content"][0],
+            json!({
+                "type": "file",
+                "file": {
+                    "filename": "document.pdf",
+                    "file_data": "data:application/pdf;base64,JVBERg=="
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_tool_message_without_tool_call_id() {
+        let llm = LLMBuilder::new()
+            .backend(LLMBackend::OpenAI)
+            .api_key("key")
+            .base_url("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        let messages = [
+            ChatMessage::user().content("What is 6 times 7?").build(),
+            ChatMessage::tool().content("42").build(),
+        ];
+        assert!(matches!(
+            llm.chat(&messages).await,
+            Err(LLMError::InvalidRequest(_))
+        ));
+
+        let messages = [
+            ChatMessage::user().content("What is 6 times 7?").build(),
+            ChatMessage::tool()
+                .tool_result("call_1", "multiply", "42")
+                .build(),
+        ];
+        assert!(matches!(
+            llm.chat(&messages).await,
+            Err(LLMError::HttpError(_))
+        ));
+    }
+}
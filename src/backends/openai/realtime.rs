@@ -0,0 +1,332 @@
+//! OpenAI's Realtime API, holding a low-latency voice or text session over a WebSocket.
+//!
+//! A session is a conversation the client feeds with events (text messages, chunks of
+//! microphone audio, tool results) while the server streams back its own (text and audio
+//! deltas, transcripts, tool calls). The server detects the end of the user's speech by
+//! default and answers on its own; without voice activity detection, the client commits
+//! the audio and asks for a response itself.
+//!
+//! Audio is 24 kHz mono 16-bit PCM, little-endian, unless the session is configured
+//! otherwise.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use futures::Stream;
+use serde_json::{json, Map, Value};
+use tokio::sync::Mutex;
+
+use crate::{
+    backends::websocket::{self, WebSocketReader, WebSocketWriter},
+    chat::Usage,
+    error::LLMError,
+    FunctionCall, ToolCall,
+};
+
+use super::OpenAI;
+
+/// Realtime model used when none is given.
+pub const REALTIME_MODEL: &str = "gpt-realtime";
+
+/// An event sent by the server during a realtime session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RealtimeEvent {
+    /// The session was created or its configuration changed
+    SessionUpdated {
+        /// The configuration of the session
+        session: Value,
+    },
+    /// The server detected the start of the user's speech
+    SpeechStarted { item_id: String },
+    /// The server detected the end of the user's speech
+    SpeechStopped { item_id: String },
+    /// Transcript of the user's speech, if input transcription is enabled
+    InputTranscript { item_id: String, transcript: String },
+    /// A fragment of the model's text reply
+    TextDelta {
+        response_id: String,
+        item_id: String,
+        delta: String,
+    },
+    /// A chunk of the model's spoken reply
+    AudioDelta {
+        response_id: String,
+        item_id: String,
+        delta: Vec<u8>,
+    },
+    /// A fragment of the transcript of the model's spoken reply
+    TranscriptDelta {
+        response_id: String,
+        item_id: String,
+        delta: String,
+    },
+    /// The model calls a tool, whose result is sent with
+    /// [`RealtimeSender::send_tool_result`]
+    ToolCall(ToolCall),
+    /// The model finished a response
+    ResponseDone {
+        response_id: String,
+        /// How the response ended ("completed", "cancelled", "incomplete" or "failed")
+        status: String,
+        usage: Option<Usage>,
+    },
+    /// The server rejected an event or failed
+    Error {
+        code: Option<String>,
+        message: String,
+    },
+    /// Any other event, as sent by the server
+    Other(Value),
+}
+
+/// Stream of the events sent by the server during a realtime session.
+pub type RealtimeEventStream = Pin<Box<dyn Stream<Item = Result<RealtimeEvent, LLMError>> + Send>>;
+
+/// Sends client events to a realtime session. Clones send to the same session.
+#[derive(Clone)]
+pub struct RealtimeSender {
+    writer: Arc<Mutex<WebSocketWriter>>,
+}
+
+impl RealtimeSender {
+    /// Sends a client event, given as the JSON object the API documents.
+    pub async fn send_event(&self, event: Value) -> Result<(), LLMError> {
+        let text = serde_json::to_string(&event).map_err(|e| LLMError::JsonError(e.to_string()))?;
+        self.writer.lock().await.send_text(&text).await
+    }
+
+    /// Changes the configuration of the session (instructions, voice, tools, turn
+    /// detection...). The fields left out keep their value.
+    pub async fn update_session(&self, session: Value) -> Result<(), LLMError> {
+        let mut session = session;
+        if let Some(session) = session.as_object_mut() {
+            session
+                .entry("type")
+                .or_insert_with(|| Value::from("realtime"));
+        }
+        self.send_event(json!({"type": "session.update", "session": session}))
+            .await
+    }
+
+    /// Adds a text message from the user to the conversation.
+    ///
+    /// The model answers once asked with [`RealtimeSender::create_response`].
+    pub async fn send_text(&self, text: &str) -> Result<(), LLMError> {
+        self.send_event(json!({
+            "type": "conversation.item.create",
+            "item": {
+                "type": "message",
+                "role": "user",
+                "content": [{"type": "input_text", "text": text}],
+            },
+        }))
+        .await
+    }
+
+    /// Appends a chunk of the user's audio to the input buffer.
+    pub async fn append_audio(&self, audio: &[u8]) -> Result<(), LLMError> {
+        self.send_event(json!({
+            "type": "input_audio_buffer.append",
+            "audio": BASE64.encode(audio),
+        }))
+        .await
+    }
+
+    /// Adds the buffered audio to the conversation as a user message, which the server
+    /// does on its own when it detects the end of the speech.
+    pub async fn commit_audio(&self) -> Result<(), LLMError> {
+        self.send_event(json!({"type": "input_audio_buffer.commit"}))
+            .await
+    }
+
+    /// Asks the model to answer the conversation.
+    pub async fn create_response(&self) -> Result<(), LLMError> {
+        self.send_event(json!({"type": "response.create"})).await
+    }
+
+    /// Interrupts the response in progress, such as when the user starts speaking.
+    pub async fn cancel_response(&self) -> Result<(), LLMError> {
+        self.send_event(json!({"type": "response.cancel"})).await
+    }
+
+    /// Sends the result of a tool call. The model answers it once asked with
+    /// [`RealtimeSender::create_response`].
+    pub async fn send_tool_result(&self, call_id: &str, output: &str) -> Result<(), LLMError> {
+        self.send_event(json!({
+            "type": "conversation.item.create",
+            "item": {
+                "type": "function_call_output",
+                "call_id": call_id,
+                "output": output,
+            },
+        }))
+        .await
+    }
+
+    /// Closes the session. The event stream ends once the server acknowledges it.
+    pub async fn close(&self) -> Result<(), LLMError> {
+        self.writer.lock().await.close().await
+    }
+}
+
+impl OpenAI {
+    /// Opens a realtime session.
+    ///
+    /// The session starts with the client's system prompt as instructions, its tools and
+    /// the voice of its audio output, when they are set. The connection is opened with the
+    /// client's HTTP settings; a custom HTTP client must be able to speak HTTP/1.1.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Realtime model to use (defaults to [`REALTIME_MODEL`])
+    ///
+    /// # Returns
+    ///
+    /// The sender of the client events and the stream of the server events
+    pub async fn connect_realtime(
+        &self,
+        model: Option<&str>,
+    ) -> Result<(RealtimeSender, RealtimeEventStream), LLMError> {
+        if self.requires_key && self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing OpenAI API key".to_string()));
+        }
+
+        let request = self
+            .authorize(
+                self.realtime_client
+                    .get(format!("{}/realtime", self.base_url)),
+            )
+            .query(&[("model", model.unwrap_or(REALTIME_MODEL))]);
+        let (reader, writer) = websocket::connect(request).await?;
+        let sender = RealtimeSender { writer };
+
+        let session = self.realtime_session();
+        if !session.is_empty() {
+            sender.update_session(Value::Object(session)).await?;
+        }
+
+        Ok((sender, event_stream(reader)))
+    }
+
+    /// Returns the initial configuration of a realtime session from the client's
+    /// settings.
+    fn realtime_session(&self) -> Map<String, Value> {
+        let mut session = Map::new();
+        if let Some(system) = &self.system {
+            session.insert("instructions".to_string(), Value::from(system.as_str()));
+        }
+        if let Some(tools) = &self.tools {
+            // Realtime tools are flat, unlike the chat API's nested function objects.
+            let tools = tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "name": tool.function.name,
+                        "description": tool.function.description,
+                        "parameters": tool.function.parameters,
+                    })
+                })
+                .collect();
+            session.insert("tools".to_string(), Value::Array(tools));
+        }
+        if let Some(audio) = &self.audio_output {
+            session.insert(
+                "audio".to_string(),
+                json!({"output": {"voice": audio.voice}}),
+            );
+        }
+        session
+    }
+}
+
+/// Turns the messages of the connection into a stream of events, which ends after an
+/// error.
+fn event_stream(reader: WebSocketReader) -> RealtimeEventStream {
+    Box::pin(futures::stream::unfold(Some(reader), |reader| async {
+        let mut reader = reader?;
+        match reader.receive().await {
+            Ok(Some(text)) => Some((parse_event(&text), Some(reader))),
+            Ok(None) => None,
+            Err(e) => Some((Err(e), None)),
+        }
+    }))
+}
+
+/// Parses a server event.
+///
+/// The event names of the beta API are accepted along with those of the current one.
+fn parse_event(text: &str) -> Result<RealtimeEvent, LLMError> {
+    let event: Value =
+        serde_json::from_str(text).map_err(|e| LLMError::JsonError(e.to_string()))?;
+    let field = |name: &str| event[name].as_str().unwrap_or_default().to_string();
+
+    Ok(match event["type"].as_str().unwrap_or_default() {
+        "session.created" | "session.updated" => RealtimeEvent::SessionUpdated {
+            session: event["session"].clone(),
+        },
+        "input_audio_buffer.speech_started" => RealtimeEvent::SpeechStarted {
+            item_id: field("item_id"),
+        },
+        "input_audio_buffer.speech_stopped" => RealtimeEvent::SpeechStopped {
+            item_id: field("item_id"),
+        },
+        "conversation.item.input_audio_transcription.completed" => RealtimeEvent::InputTranscript {
+            item_id: field("item_id"),
+            transcript: field("transcript"),
+        },
+        "response.output_text.delta" | "response.text.delta" => RealtimeEvent::TextDelta {
+            response_id: field("response_id"),
+            item_id: field("item_id"),
+            delta: field("delta"),
+        },
+        "response.output_audio.delta" | "response.audio.delta" => RealtimeEvent::AudioDelta {
+            response_id: field("response_id"),
+            item_id: field("item_id"),
+            delta: BASE64
+                .decode(field("delta"))
+                .map_err(|e| LLMError::ProviderError(format!("Invalid audio delta: {}", e)))?,
+        },
+        "response.output_audio_transcript.delta" | "response.audio_transcript.delta" => {
+            RealtimeEvent::TranscriptDelta {
+                response_id: field("response_id"),
+                item_id: field("item_id"),
+                delta: field("delta"),
+            }
+        }
+        "response.function_call_arguments.done" => RealtimeEvent::ToolCall(ToolCall {
+            id: field("call_id"),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: field("name"),
+                arguments: field("arguments"),
+            },
+        }),
+        "response.done" => {
+            let response = &event["response"];
+            let usage = &response["usage"];
+            let tokens = |name: &str| usage[name].as_u64().unwrap_or_default() as u32;
+            RealtimeEvent::ResponseDone {
+                response_id: response["id"].as_str().unwrap_or_default().to_string(),
+                status: response["status"].as_str().unwrap_or_default().to_string(),
+                usage: usage.is_object().then(|| Usage {
+                    prompt_tokens: tokens("input_tokens"),
+                    completion_tokens: tokens("output_tokens"),
+                    total_tokens: tokens("total_tokens"),
+                    cached_prompt_tokens: usage["input_token_details"]["cached_tokens"]
+                        .as_u64()
+                        .map(|tokens| tokens as u32),
+                }),
+            }
+        }
+        "error" => RealtimeEvent::Error {
+            code: event["error"]["code"].as_str().map(str::to_string),
+            message: event["error"]["message"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        },
+        _ => RealtimeEvent::Other(event),
+    })
+}
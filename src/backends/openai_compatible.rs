@@ -0,0 +1,30 @@
+//! Client for any server implementing OpenAI's API.
+//!
+//! vLLM, LiteLLM, text-generation-webui, LocalAI and many gateways serve OpenAI's chat
+//! completions and embeddings APIs, so this client drives an [`OpenAI`] client pointed at
+//! the server's base URL (e.g. `http://localhost:8000/v1`). The API key is optional, as
+//! local servers often run without authentication.
+
+use crate::backends::openai::{forward_to_inner, OpenAI};
+
+/// Client for interacting with an OpenAI-compatible server.
+pub struct OpenAICompatible {
+    /// OpenAI client sending the requests to the server
+    inner: OpenAI,
+}
+
+impl OpenAICompatible {
+    /// Creates a client for a server from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the server's base URL, the model and the generation
+    ///   settings. An empty API key sends the requests unauthenticated.
+    pub fn new(mut openai: OpenAI) -> Self {
+        openai.provider = "openai-compatible";
+        openai.requires_key = false;
+        Self { inner: openai }
+    }
+}
+
+forward_to_inner!(OpenAICompatible);
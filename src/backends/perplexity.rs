@@ -0,0 +1,244 @@
+//! Perplexity (Sonar) client implementation for chat functionality.
+//!
+//! Perplexity's Sonar models answer from a web search, through OpenAI's chat completions
+//! API, so this client drives an [`OpenAI`] client pointed at Perplexity. The sources of a
+//! reply are returned next to the choices, as `search_results` (or, from older models, as
+//! `citations` URLs), and surfaced by [`ChatResponse::citations`].
+
+use std::fmt;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::{
+    backends::{
+        openai::{forward_to_inner, Dialect, OpenAI},
+        probe,
+    },
+    chat::{
+        AudioReply, ChatChoice, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream,
+        Citation, FinishReason, ResponseMetadata, TokenLogprob, Tool, Usage,
+    },
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    LLMProvider, ToolCall,
+};
+
+/// Base URL of Perplexity's API.
+pub const BASE_URL: &str = "https://api.perplexity.ai";
+
+/// Model used when none is configured.
+pub const DEFAULT_MODEL: &str = "sonar";
+
+/// How Perplexity's chat API differs from OpenAI's.
+const DIALECT: Dialect = Dialect {
+    unsupported: &[
+        "tools",
+        "tool_choice",
+        "parallel_tool_calls",
+        "n",
+        "logprobs",
+        "top_logprobs",
+        "logit_bias",
+        "seed",
+    ],
+    renamed: &[],
+};
+
+/// Client for interacting with Perplexity's API.
+pub struct Perplexity {
+    /// OpenAI client sending the requests to Perplexity
+    inner: OpenAI,
+}
+
+/// Chat response carrying the sources of the reply.
+#[derive(Debug)]
+struct PerplexityResponse {
+    inner: Box<dyn ChatResponse>,
+    citations: Option<Vec<Citation>>,
+}
+
+impl PerplexityResponse {
+    fn new(inner: Box<dyn ChatResponse>) -> Self {
+        let citations = inner.raw().and_then(citations);
+        Self { inner, citations }
+    }
+}
+
+/// Reads the sources of a reply from Perplexity's response document, preferring the
+/// detailed search results to the bare citation URLs.
+fn citations(raw: &Value) -> Option<Vec<Citation>> {
+    if let Some(results) = raw.get("search_results").and_then(Value::as_array) {
+        let citations: Vec<Citation> = results
+            .iter()
+            .filter_map(|result| serde_json::from_value(result.clone()).ok())
+            .collect();
+        if !citations.is_empty() {
+            return Some(citations);
+        }
+    }
+    let urls = raw.get("citations")?.as_array()?;
+    Some(
+        urls.iter()
+            .filter_map(Value::as_str)
+            .map(|url| Citation {
+                url: url.to_string(),
+                title: None,
+                date: None,
+            })
+            .collect(),
+    )
+}
+
+impl fmt::Display for PerplexityResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl ChatResponse for PerplexityResponse {
+    fn text(&self) -> Option<String> {
+        self.inner.text()
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        self.inner.tool_calls()
+    }
+
+    fn thinking(&self) -> Option<String> {
+        self.inner.thinking()
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.inner.finish_reason()
+    }
+
+    fn raw(&self) -> Option<&Value> {
+        self.inner.raw()
+    }
+
+    fn refusal(&self) -> Option<String> {
+        self.inner.refusal()
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.inner.usage()
+    }
+
+    fn logprobs(&self) -> Option<Vec<TokenLogprob>> {
+        self.inner.logprobs()
+    }
+
+    fn citations(&self) -> Option<Vec<Citation>> {
+        self.citations.clone()
+    }
+
+    fn audio(&self) -> Option<AudioReply> {
+        self.inner.audio()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        self.inner.metadata()
+    }
+
+    fn choices(&self) -> Vec<ChatChoice> {
+        self.inner.choices()
+    }
+}
+
+impl Perplexity {
+    /// Creates a Perplexity client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the API key, model and generation settings, pointed
+    ///   at [`BASE_URL`] or a compatible server. Its tools are not sent, since Perplexity
+    ///   doesn't call tools.
+    pub fn new(mut openai: OpenAI) -> Self {
+        openai.provider = "perplexity";
+        openai.dialect = DIALECT;
+        Self { inner: openai }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Perplexity {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let response = self
+            .inner
+            .chat_with_options(messages, tools, options)
+            .await?;
+        Ok(Box::new(PerplexityResponse::new(response)))
+    }
+
+    /// Streams the reply, without its sources.
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner.chat_stream_with_tools(messages, tools).await
+    }
+
+    /// Streams the reply, without its sources.
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+    }
+}
+
+forward_to_inner!(Perplexity; completion);
+
+#[async_trait]
+impl EmbeddingProvider for Perplexity {
+    async fn embed(&self, _input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        Err(LLMError::ProviderError(
+            "Embedding not supported".to_string(),
+        ))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for Perplexity {
+    fn tools(&self) -> Option<&[Tool]> {
+        None
+    }
+
+    /// Sends a one-token chat request, since Perplexity has no endpoint listing its models.
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.inner
+                .request(
+                    reqwest::Method::POST,
+                    format!("{}/chat/completions", self.inner.base_url),
+                )
+                .json(&json!({
+                    "model": self.inner.model,
+                    "messages": [{"role": "user", "content": "ping"}],
+                    "max_tokens": 1,
+                })),
+        )
+        .await
+    }
+}
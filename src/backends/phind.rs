@@ -1,22 +1,28 @@
+use crate::{
+    backends::{
+        check_no_audio, http_client, request_body, send_request, sse::sse_stream, HttpOptions,
+    },
+    chat::{ChatResponse, Tool},
+    ToolCall,
+};
 /// Implementation of the Phind LLM provider.
 /// This module provides integration with Phind's language model API.
 #[cfg(feature = "phind")]
 use crate::{
-    chat::{ChatMessage, ChatProvider, ChatRole},
+    chat::{with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatRole},
     completion::{CompletionProvider, CompletionRequest, CompletionResponse},
     embedding::EmbeddingProvider,
     error::LLMError,
+    logging::RequestLogging,
+    retry::RetryPolicy,
     LLMProvider,
 };
-use crate::{
-    chat::{ChatResponse, Tool},
-    ToolCall,
-};
 use async_trait::async_trait;
+use futures::StreamExt;
 use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::StatusCode;
 use reqwest::{Client, Response};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 
 /// Represents a Phind LLM client with configuration options.
 pub struct Phind {
@@ -38,6 +44,12 @@ pub struct Phind {
     pub top_k: Option<u32>,
     /// Base URL for the Phind API
     pub api_base_url: String,
+    /// Extra parameters merged into the chat request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
     /// HTTP client for making requests
     client: Client,
 }
@@ -75,11 +87,17 @@ impl Phind {
         stream: Option<bool>,
         top_p: Option<f32>,
         top_k: Option<u32>,
+        http: HttpOptions,
     ) -> Self {
-        let mut builder = Client::builder();
-        if let Some(sec) = timeout_seconds {
-            builder = builder.timeout(std::time::Duration::from_secs(sec));
-        }
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
         Self {
             model: model.unwrap_or_else(|| "Phind-70B".to_string()),
             max_tokens,
@@ -89,8 +107,12 @@ impl Phind {
             stream,
             top_p,
             top_k,
-            api_base_url: "https://https.extension.phind.com/agent/".to_string(),
-            client: builder.build().expect("Failed to build reqwest Client"),
+            api_base_url: base_url
+                .unwrap_or_else(|| "https://https.extension.phind.com/agent/".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
         }
     }
 
@@ -104,9 +126,8 @@ impl Phind {
         Ok(headers)
     }
 
-    /// Parses a single line from the streaming response.
-    fn parse_line(line: &str) -> Option<String> {
-        let data = line.strip_prefix("data: ")?;
+    /// Extracts the text delta from a single streamed event.
+    fn parse_event(data: &str) -> Option<String> {
         let json_value: Value = serde_json::from_str(data).ok()?;
 
         json_value
@@ -119,12 +140,16 @@ impl Phind {
             .map(String::from)
     }
 
-    /// Parses the complete streaming response into a single string.
-    fn parse_stream_response(response_text: &str) -> String {
-        response_text
-            .split('\n')
-            .filter_map(Self::parse_line)
-            .collect()
+    /// Collects the streaming response into a single string.
+    async fn collect_stream_response(response: Response) -> Result<String, LLMError> {
+        let mut events = Box::pin(sse_stream(response));
+        let mut full_text = String::new();
+        while let Some(event) = events.next().await {
+            if let Some(delta) = Self::parse_event(&event?.data) {
+                full_text.push_str(&delta);
+            }
+        }
+        Ok(full_text)
     }
 
     /// Interprets the API response and handles any errors.
@@ -135,8 +160,7 @@ impl Phind {
         let status = response.status();
         match status {
             StatusCode::OK => {
-                let response_text = response.text().await?;
-                let full_text = Self::parse_stream_response(&response_text);
+                let full_text = Self::collect_stream_response(response).await?;
                 if full_text.is_empty() {
                     Err(LLMError::ProviderError(
                         "No completion choice returned.".to_string(),
@@ -164,26 +188,21 @@ impl Phind {
             }
         }
     }
-}
 
-/// Implementation of chat functionality for Phind.
-#[async_trait]
-impl ChatProvider for Phind {
-    /// Sends a chat request to Phind's API.
-    ///
-    /// # Arguments
-    ///
-    /// * `messages` - The conversation history as a slice of chat messages
-    ///
-    /// # Returns
-    ///
-    /// The provider's response text or an error
-    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+    /// Builds and sends a chat request, applying the per-request overrides.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        check_no_audio(messages, "Phind")?;
         let mut message_history = vec![];
         for m in messages {
             let role_str = match m.role {
+                ChatRole::System => "system",
                 ChatRole::User => "user",
                 ChatRole::Assistant => "assistant",
+                ChatRole::Tool => "user",
             };
             message_history.push(json!({
                 "content": m.content,
@@ -220,18 +239,38 @@ impl ChatProvider for Phind {
             .client
             .post(&self.api_base_url)
             .headers(headers)
-            .json(&payload);
+            .json(&request_body(&payload, &self.extra_params)?);
 
-        if let Some(timeout) = self.timeout_seconds {
-            request = request.timeout(std::time::Duration::from_secs(timeout));
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
         }
 
-        let response = request.send().await?;
+        let response = send_request(request, self.retry.as_ref(), self.logging.as_ref()).await?;
 
         self.interpret_response(response).await
     }
+}
+
+/// Implementation of chat functionality for Phind.
+#[async_trait]
+impl ChatProvider for Phind {
+    /// Sends a chat request to Phind's API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    ///
+    /// # Returns
+    ///
+    /// The provider's response text or an error
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, &ChatOptions::default()).await
+    }
 
-    /// Sends a chat request to Phind's API with tools.
+    /// Sends a chat request to Phind's API. Phind has no tool calling, so tools are ignored.
     ///
     /// # Arguments
     ///
@@ -243,10 +282,19 @@ impl ChatProvider for Phind {
     /// The provider's response text or an error
     async fn chat_with_tools(
         &self,
-        _messages: &[ChatMessage],
+        messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, &ChatOptions::default()).await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
         _tools: Option<&[Tool]>,
+        options: &ChatOptions,
     ) -> Result<Box<dyn ChatResponse>, LLMError> {
-        todo!()
+        with_cancellation(options, self.send_chat(messages, options)).await
     }
 }
 
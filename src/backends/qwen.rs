@@ -0,0 +1,91 @@
+//! Qwen (Alibaba Cloud DashScope) client implementation for chat and embedding
+//! functionality.
+//!
+//! DashScope serves Qwen models through an OpenAI-compatible mode, so this client drives
+//! an [`OpenAI`] client pointed at it, leaving out the fields DashScope rejects. DashScope's
+//! own extras are sent as extra parameters: `enable_search` grounds replies with a web
+//! search, and `incremental_output` makes streamed chunks carry only the new text.
+
+use serde_json::Value;
+
+use crate::backends::openai::{forward_to_inner, Dialect, OpenAI};
+
+/// Base URL of DashScope's OpenAI-compatible mode, in the international (Singapore)
+/// region. Accounts of the China (Beijing) region use
+/// `https://dashscope.aliyuncs.com/compatible-mode/v1`.
+pub const BASE_URL: &str = "https://dashscope-intl.aliyuncs.com/compatible-mode/v1";
+
+/// Model used when none is configured.
+pub const DEFAULT_MODEL: &str = "qwen-plus";
+
+/// Model computing embeddings when the configured model is a chat model.
+pub const EMBEDDING_MODEL: &str = "text-embedding-v3";
+
+/// How DashScope's chat API differs from OpenAI's.
+const DIALECT: Dialect = Dialect {
+    unsupported: &["reasoning_effort"],
+    renamed: &[],
+};
+
+/// Client for interacting with Qwen models on DashScope.
+///
+/// Texts are embedded with the configured model if it is an embedding model, and with
+/// [`EMBEDDING_MODEL`] otherwise.
+pub struct Qwen {
+    /// Whether replies are grounded with a web search
+    enable_search: Option<bool>,
+    /// Whether streamed chunks carry only the new text rather than the whole reply so far
+    incremental_output: Option<bool>,
+    /// OpenAI client sending the requests to DashScope
+    inner: OpenAI,
+}
+
+impl Qwen {
+    /// Creates a Qwen client from an OpenAI client holding the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `openai` - OpenAI client with the API key, model and generation settings, pointed
+    ///   at [`BASE_URL`] or another DashScope region
+    /// * `enable_search` - Whether to ground the replies with a web search
+    /// * `incremental_output` - Whether streamed chunks carry only the new text
+    pub fn new(
+        mut openai: OpenAI,
+        enable_search: Option<bool>,
+        incremental_output: Option<bool>,
+    ) -> Self {
+        openai.provider = "qwen";
+        openai.dialect = DIALECT;
+        if !openai.model.contains("embedding") {
+            openai.embedding_model = Some(EMBEDDING_MODEL.to_string());
+        }
+        for (name, value) in [
+            ("enable_search", enable_search),
+            ("incremental_output", incremental_output),
+        ] {
+            if let Some(value) = value {
+                openai
+                    .extra_params
+                    .entry(name)
+                    .or_insert(Value::Bool(value));
+            }
+        }
+        Self {
+            enable_search,
+            incremental_output,
+            inner: openai,
+        }
+    }
+
+    /// Returns whether replies are grounded with a web search, if configured.
+    pub fn enable_search(&self) -> Option<bool> {
+        self.enable_search
+    }
+
+    /// Returns whether streamed chunks carry only the new text, if configured.
+    pub fn incremental_output(&self) -> Option<bool> {
+        self.incremental_output
+    }
+}
+
+forward_to_inner!(Qwen);
@@ -0,0 +1,333 @@
+//! Server-Sent Events parsing shared by the streaming backends.
+//!
+//! Providers deliver streamed responses as `text/event-stream` bodies. The network can
+//! split those bodies at arbitrary byte boundaries, so this module buffers partial lines,
+//! skips keep-alive comments, and stops at the `[DONE]` marker used by OpenAI-compatible
+//! APIs.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt};
+use reqwest::Response;
+
+use crate::{
+    chat::{ChatStream, FinishReason, StreamChunk, Usage},
+    error::LLMError,
+};
+
+/// Marker sent by OpenAI-compatible APIs as the data of the last event.
+const DONE_MARKER: &str = "[DONE]";
+
+/// A single dispatched Server-Sent Event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    /// The event type, if the server sent an `event:` field
+    pub event: Option<String>,
+    /// The event payload; multiple `data:` lines are joined with newlines
+    pub data: String,
+}
+
+/// Incremental parser turning raw body bytes into events.
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    /// Bytes of the current, not yet terminated line
+    buffer: Vec<u8>,
+    /// Event type of the event being assembled
+    event: Option<String>,
+    /// Data lines of the event being assembled
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Creates an empty parser.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk of the response body and returns the events it completed.
+    pub(crate) fn feed(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if let Some(event) = self.process_line(&String::from_utf8_lossy(&line)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flushes the event left over when the body ends without a trailing blank line.
+    pub(crate) fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+            if let Some(event) = self.process_line(line.trim_end_matches('\r')) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        // Lines starting with a colon are comments, typically used as keep-alives.
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => self.data.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        if self.data.is_empty() {
+            self.event = None;
+            return None;
+        }
+        Some(SseEvent {
+            event: self.event.take(),
+            data: std::mem::take(&mut self.data).join("\n"),
+        })
+    }
+}
+
+impl SseEvent {
+    /// Returns true if this event is the end-of-stream marker.
+    pub(crate) fn is_done(&self) -> bool {
+        self.data.trim() == DONE_MARKER
+    }
+}
+
+/// State carried between polls of an SSE stream.
+struct SseState {
+    response: Response,
+    parser: SseParser,
+    pending: VecDeque<SseEvent>,
+    finished: bool,
+}
+
+/// Turns a streaming HTTP response into a stream of events.
+///
+/// The stream ends when the body ends or when a `[DONE]` event is received; the
+/// marker itself is not yielded.
+pub(crate) fn sse_stream(
+    response: Response,
+) -> impl Stream<Item = Result<SseEvent, LLMError>> + Send {
+    let state = SseState {
+        response,
+        parser: SseParser::new(),
+        pending: VecDeque::new(),
+        finished: false,
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                if event.is_done() {
+                    return None;
+                }
+                return Some((Ok(event), state));
+            }
+            if state.finished {
+                return None;
+            }
+
+            match state.response.chunk().await {
+                Ok(Some(bytes)) => {
+                    let events = state.parser.feed(&bytes);
+                    state.pending.extend(events);
+                }
+                Ok(None) => {
+                    state.finished = true;
+                    state.pending.extend(state.parser.finish());
+                }
+                Err(e) => {
+                    state.finished = true;
+                    return Some((Err(e.into()), state));
+                }
+            }
+        }
+    })
+}
+
+/// End-of-stream metadata that providers spread over several events.
+#[derive(Debug, Default)]
+#[cfg_attr(not(any(feature = "openai", feature = "xai")), allow(dead_code))]
+pub(crate) struct StreamEnd {
+    /// The provider's reason for ending generation
+    pub finish_reason: Option<String>,
+    /// Token usage for the whole request
+    pub usage: Option<Usage>,
+}
+
+/// State carried between polls of a chat chunk stream.
+#[cfg_attr(not(any(feature = "openai", feature = "xai")), allow(dead_code))]
+struct ChunkState<F> {
+    events: Pin<Box<dyn Stream<Item = Result<SseEvent, LLMError>> + Send>>,
+    parse: F,
+    end: StreamEnd,
+    pending: VecDeque<StreamChunk>,
+    finished: bool,
+}
+
+/// Turns a streaming HTTP response into a stream of normalized chat chunks.
+///
+/// `parse` maps each event to the chunks it carries and records finish reasons and
+/// usage in the [`StreamEnd`]; a single [`StreamChunk::Done`] built from it is
+/// yielded once the body ends.
+#[cfg_attr(not(any(feature = "openai", feature = "xai")), allow(dead_code))]
+pub(crate) fn chat_chunk_stream<F>(response: Response, parse: F) -> ChatStream
+where
+    F: FnMut(&SseEvent, &mut StreamEnd) -> Result<Vec<StreamChunk>, LLMError> + Send + 'static,
+{
+    let state = ChunkState {
+        events: Box::pin(sse_stream(response)),
+        parse,
+        end: StreamEnd::default(),
+        pending: VecDeque::new(),
+        finished: false,
+    };
+
+    Box::pin(futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(chunk) = state.pending.pop_front() {
+                return Some((Ok(chunk), state));
+            }
+            if state.finished {
+                return None;
+            }
+
+            match state.events.next().await {
+                Some(Ok(event)) => match (state.parse)(&event, &mut state.end) {
+                    Ok(chunks) => state.pending.extend(chunks),
+                    Err(e) => {
+                        state.finished = true;
+                        return Some((Err(e), state));
+                    }
+                },
+                Some(Err(e)) => {
+                    state.finished = true;
+                    return Some((Err(e), state));
+                }
+                None => {
+                    state.finished = true;
+                    state.pending.push_back(StreamChunk::Done {
+                        finish_reason: state
+                            .end
+                            .finish_reason
+                            .take()
+                            .map(|reason| FinishReason::from_provider(&reason)),
+                        usage: state.end.usage.take(),
+                    });
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: Option<&str>, data: &str) -> SseEvent {
+        SseEvent {
+            event: event.map(str::to_string),
+            data: data.to_string(),
+        }
+    }
+
+    /// Feeds the chunks one by one and returns every event, including the flushed one.
+    fn parse(chunks: &[&[u8]]) -> Vec<SseEvent> {
+        let mut parser = SseParser::new();
+        let mut events: Vec<SseEvent> = chunks.iter().flat_map(|c| parser.feed(c)).collect();
+        events.extend(parser.finish());
+        events
+    }
+
+    #[test]
+    fn parses_events() {
+        assert_eq!(
+            parse(&[b"data: one\n\nevent: delta\ndata: two\n\n"]),
+            [event(None, "one"), event(Some("delta"), "two")]
+        );
+    }
+
+    #[test]
+    fn joins_events_split_across_chunks() {
+        let body = b"event: delta\ndata: {\"text\": \"h\xc3\xa9llo\"}\n\ndata: second\n\n";
+        let expected = [
+            event(Some("delta"), "{\"text\": \"h\u{e9}llo\"}"),
+            event(None, "second"),
+        ];
+        for split in 1..body.len() {
+            let (head, tail) = body.split_at(split);
+            assert_eq!(parse(&[head, tail]), expected, "split at byte {}", split);
+        }
+        let bytes: Vec<&[u8]> = body.chunks(1).collect();
+        assert_eq!(parse(&bytes), expected);
+    }
+
+    #[test]
+    fn joins_multi_line_data_with_newlines() {
+        assert_eq!(
+            parse(&[b"data: first\ndata: second\ndata:third\n\n"]),
+            [event(None, "first\nsecond\nthird")]
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_unknown_fields() {
+        assert_eq!(
+            parse(&[b": keep-alive\n\nid: 1\nretry: 1000\ndata: one\n: ping\n\n"]),
+            [event(None, "one")]
+        );
+    }
+
+    #[test]
+    fn handles_crlf_line_endings() {
+        assert_eq!(
+            parse(&[b"event: delta\r\ndata: one\r", b"\n\r\ndata: two\r\n\r\n"]),
+            [event(Some("delta"), "one"), event(None, "two")]
+        );
+    }
+
+    #[test]
+    fn events_without_data_are_dropped() {
+        assert_eq!(
+            parse(&[b"event: ping\n\ndata: one\n\n"]),
+            [event(None, "one")]
+        );
+    }
+
+    #[test]
+    fn flushes_unterminated_event_at_end() {
+        assert_eq!(
+            parse(&[b"data: one\n\ndata: two"]),
+            [event(None, "one"), event(None, "two")]
+        );
+        assert_eq!(parse(&[b"data: last\r\n"]), [event(None, "last")]);
+    }
+
+    #[test]
+    fn recognizes_done_marker() {
+        assert!(event(None, "[DONE]").is_done());
+        assert!(event(None, " [DONE]\n").is_done());
+        assert!(!event(None, "{\"done\": true}").is_done());
+    }
+}
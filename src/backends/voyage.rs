@@ -0,0 +1,208 @@
+//! Voyage AI client implementation for embedding functionality.
+//!
+//! Voyage serves embedding models only, which makes it the usual pairing for providers
+//! without embeddings such as Anthropic. Its models embed queries and documents
+//! differently, chosen with the input type, and several of them can shorten their vectors
+//! to a requested output dimension. The chat and completion methods return an error.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    backends::{http_client, probe, request_body, send_request, HttpOptions},
+    chat::{ChatMessage, ChatProvider, ChatResponse, ChatStream, Tool},
+    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    embedding::EmbeddingProvider,
+    error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
+    LLMProvider,
+};
+
+/// Base URL of Voyage's API.
+pub const BASE_URL: &str = "https://api.voyageai.com/v1";
+
+/// Model used when none is configured.
+pub const DEFAULT_MODEL: &str = "voyage-3.5";
+
+/// Client for interacting with Voyage AI's API.
+pub struct Voyage {
+    pub api_key: String,
+    pub model: String,
+    pub timeout_seconds: Option<u64>,
+    /// Kind of text embedded ("query" or "document"), or none to embed it as is
+    pub input_type: Option<String>,
+    /// Length of the returned vectors, for the models supporting several
+    pub output_dimension: Option<u32>,
+    /// Base URL of the API, without a trailing slash
+    pub base_url: String,
+    /// Extra parameters merged into the embedding request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
+    client: Client,
+}
+
+/// Request payload for Voyage's embeddings endpoint.
+#[derive(Serialize, Debug)]
+struct VoyageEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_type: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_dimension: Option<u32>,
+}
+
+/// Response of Voyage's embeddings endpoint.
+#[derive(Deserialize, Debug)]
+struct VoyageEmbedResponse {
+    data: Vec<VoyageEmbedding>,
+}
+
+/// Embedding of one of the input texts.
+#[derive(Deserialize, Debug)]
+struct VoyageEmbedding {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// Error returned by the chat and completion methods.
+fn chat_unsupported() -> LLMError {
+    LLMError::ProviderError(
+        "Voyage AI only serves embeddings; use another backend for chat and completion".to_string(),
+    )
+}
+
+impl Voyage {
+    /// Creates a new Voyage client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Voyage API key
+    /// * `model` - Embedding model to use (defaults to [`DEFAULT_MODEL`])
+    /// * `timeout_seconds` - Request timeout in seconds
+    /// * `input_type` - Kind of text embedded: "query" or "document"
+    /// * `output_dimension` - Length of the returned vectors (e.g. 256, 512, 1024 or 2048)
+    /// * `http` - HTTP settings, such as the base URL (defaults to [`BASE_URL`]), headers,
+    ///   proxy and retry policy
+    pub fn new(
+        api_key: impl Into<String>,
+        model: Option<String>,
+        timeout_seconds: Option<u64>,
+        input_type: Option<String>,
+        output_dimension: Option<u32>,
+        http: HttpOptions,
+    ) -> Self {
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
+        Self {
+            api_key: api_key.into(),
+            model: model.unwrap_or(DEFAULT_MODEL.to_string()),
+            timeout_seconds,
+            input_type,
+            output_dimension,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| BASE_URL.to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Voyage {
+    async fn chat_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        Err(chat_unsupported())
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        Err(chat_unsupported())
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Voyage {
+    async fn complete(&self, _req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        Err(chat_unsupported())
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Voyage {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        if self.api_key.is_empty() {
+            return Err(LLMError::AuthError("Missing Voyage API key".to_string()));
+        }
+
+        let body = VoyageEmbedRequest {
+            model: &self.model,
+            input: &input,
+            input_type: self.input_type.as_deref(),
+            output_dimension: self.output_dimension,
+        };
+        let request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&request_body(&body, &self.extra_params)?);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
+            .await?
+            .error_for_status()?;
+
+        let mut json_resp: VoyageEmbedResponse = resp.json().await?;
+        json_resp.data.sort_by_key(|embedding| embedding.index);
+        Ok(json_resp
+            .data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl LLMProvider for Voyage {
+    fn tools(&self) -> Option<&[Tool]> {
+        None
+    }
+
+    /// Embeds a one-word text, since Voyage has no endpoint listing its models.
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.client
+                .post(format!("{}/embeddings", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&VoyageEmbedRequest {
+                    model: &self.model,
+                    input: &["ping".to_string()],
+                    input_type: None,
+                    output_dimension: None,
+                }),
+        )
+        .await
+    }
+}
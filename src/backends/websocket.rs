@@ -0,0 +1,430 @@
+//! A minimal WebSocket client (RFC 6455), for the realtime endpoints.
+//!
+//! The opening handshake is an HTTP/1.1 upgrade sent through reqwest, so the connection
+//! reuses its TLS and DNS setup; the upgraded connection then carries the frames. Only
+//! text messages are surfaced: pings are answered, binary messages are skipped and
+//! fragmented messages are reassembled.
+
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use reqwest::header::{CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader, ReadHalf, WriteHalf};
+use tokio::sync::Mutex;
+
+use crate::{backends::client_builder, error::LLMError};
+
+/// Suffix appended to the key of the handshake before hashing it, fixed by the RFC.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest message accepted from the server.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// Sending half of a WebSocket connection.
+pub(crate) struct WebSocketWriter {
+    io: WriteHalf<reqwest::Upgraded>,
+    closed: bool,
+}
+
+/// Receiving half of a WebSocket connection.
+pub(crate) struct WebSocketReader {
+    io: BufReader<ReadHalf<reqwest::Upgraded>>,
+    /// The sending half, to answer pings and close requests
+    writer: Arc<Mutex<WebSocketWriter>>,
+}
+
+/// Returns the HTTP client opening WebSocket connections: the client supplied by the user,
+/// which must be able to speak HTTP/1.1, or a new one configured with the timeout, default
+/// headers and proxy and restricted to HTTP/1.1.
+pub(crate) fn http_client(
+    client: Option<reqwest::Client>,
+    timeout_seconds: Option<u64>,
+    headers: Option<reqwest::header::HeaderMap>,
+    proxy: Option<reqwest::Proxy>,
+) -> reqwest::Client {
+    if let Some(client) = client {
+        return client;
+    }
+    client_builder(timeout_seconds, headers, proxy)
+        .http1_only()
+        .build()
+        .expect("Failed to build reqwest Client")
+}
+
+/// Opens a WebSocket connection with an HTTP/1.1 upgrade request.
+///
+/// The request must be a GET to an `http` or `https` URL, and the client sending it must
+/// be restricted to HTTP/1.1, since HTTP/2 connections cannot be upgraded.
+pub(crate) async fn connect(
+    request: reqwest::RequestBuilder,
+) -> Result<(WebSocketReader, Arc<Mutex<WebSocketWriter>>), LLMError> {
+    let key = BASE64.encode(random_bytes::<16>()?);
+    let resp = request
+        .header(CONNECTION, "Upgrade")
+        .header(UPGRADE, "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header(SEC_WEBSOCKET_KEY, &key)
+        .send()
+        .await?;
+    if resp.status() != reqwest::StatusCode::SWITCHING_PROTOCOLS {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(LLMError::HttpError(format!(
+            "WebSocket handshake failed with status {}: {}",
+            status, body
+        )));
+    }
+    let expected = BASE64.encode(sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes()));
+    if resp
+        .headers()
+        .get(SEC_WEBSOCKET_ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        != Some(expected.as_str())
+    {
+        return Err(LLMError::HttpError(
+            "WebSocket handshake returned an invalid Sec-WebSocket-Accept header".to_string(),
+        ));
+    }
+
+    let (read, write) = tokio::io::split(resp.upgrade().await?);
+    let writer = Arc::new(Mutex::new(WebSocketWriter {
+        io: write,
+        closed: false,
+    }));
+    let reader = WebSocketReader {
+        io: BufReader::new(read),
+        writer: writer.clone(),
+    };
+    Ok((reader, writer))
+}
+
+impl WebSocketWriter {
+    /// Sends a text message.
+    pub async fn send_text(&mut self, text: &str) -> Result<(), LLMError> {
+        if self.closed {
+            return Err(LLMError::InvalidRequest(
+                "The WebSocket connection is closed".to_string(),
+            ));
+        }
+        self.send_frame(OPCODE_TEXT, text.as_bytes()).await
+    }
+
+    /// Starts closing the connection, unless it is already closing.
+    pub async fn close(&mut self) -> Result<(), LLMError> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        // 1000: normal closure.
+        self.send_frame(OPCODE_CLOSE, &1000u16.to_be_bytes()).await
+    }
+
+    /// Sends a single unfragmented frame, masked as required of clients.
+    async fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<(), LLMError> {
+        let frame = encode_frame(opcode, payload, random_bytes()?);
+        self.io.write_all(&frame).await.map_err(io_error)?;
+        self.io.flush().await.map_err(io_error)
+    }
+}
+
+impl WebSocketReader {
+    /// Receives the next text message, or `None` once the connection is closed.
+    pub async fn receive(&mut self) -> Result<Option<String>, LLMError> {
+        let mut message: Option<(u8, Vec<u8>)> = None;
+        loop {
+            let (fin, opcode, payload) = match read_frame(&mut self.io).await? {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+            match opcode {
+                OPCODE_PING => {
+                    self.writer
+                        .lock()
+                        .await
+                        .send_frame(OPCODE_PONG, &payload)
+                        .await?;
+                    continue;
+                }
+                OPCODE_PONG => continue,
+                OPCODE_CLOSE => {
+                    // Echo the close request, as the RFC asks, before reporting the end.
+                    let _ = self.writer.lock().await.close().await;
+                    return Ok(None);
+                }
+                OPCODE_TEXT | OPCODE_BINARY => message = Some((opcode, payload)),
+                OPCODE_CONTINUATION => match message.as_mut() {
+                    Some((_, data)) if data.len() + payload.len() <= MAX_MESSAGE_BYTES => {
+                        data.extend_from_slice(&payload)
+                    }
+                    Some(_) => {
+                        return Err(LLMError::ProviderError(
+                            "WebSocket message exceeds the size limit".to_string(),
+                        ))
+                    }
+                    None => {
+                        return Err(LLMError::ProviderError(
+                            "WebSocket continuation frame without a message".to_string(),
+                        ))
+                    }
+                },
+                opcode => {
+                    return Err(LLMError::ProviderError(format!(
+                        "Unknown WebSocket opcode {:#x}",
+                        opcode
+                    )))
+                }
+            }
+            if fin {
+                match message.take() {
+                    Some((OPCODE_TEXT, data)) => {
+                        return String::from_utf8(data).map(Some).map_err(|_| {
+                            LLMError::ProviderError(
+                                "WebSocket text message is not valid UTF-8".to_string(),
+                            )
+                        })
+                    }
+                    _ => continue,
+                }
+            }
+        }
+    }
+}
+
+/// Encodes a single unfragmented frame, with its payload masked by `mask`.
+fn encode_frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => frame.push(0x80 | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(&mask);
+    frame.extend(
+        payload
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| byte ^ mask[i % 4]),
+    );
+    frame
+}
+
+/// Reads a frame, returning whether it ends its message, its opcode and its payload,
+/// or `None` if the connection ended.
+async fn read_frame<R: AsyncRead + Unpin>(
+    io: &mut R,
+) -> Result<Option<(bool, u8, Vec<u8>)>, LLMError> {
+    let mut header = [0u8; 2];
+    match io.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(io_error(e)),
+    }
+    let fin = header[0] & 0x80 != 0;
+    let opcode = header[0] & 0x0F;
+    let masked = header[1] & 0x80 != 0;
+    let len = match header[1] & 0x7F {
+        126 => io.read_u16().await.map_err(io_error)? as usize,
+        127 => io.read_u64().await.map_err(io_error)? as usize,
+        len => len as usize,
+    };
+    if len > MAX_MESSAGE_BYTES {
+        return Err(LLMError::ProviderError(
+            "WebSocket message exceeds the size limit".to_string(),
+        ));
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        io.read_exact(&mut mask).await.map_err(io_error)?;
+    }
+    let mut payload = vec![0u8; len];
+    io.read_exact(&mut payload).await.map_err(io_error)?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok(Some((fin, opcode, payload)))
+}
+
+fn io_error(e: std::io::Error) -> LLMError {
+    LLMError::HttpError(format!("WebSocket connection failed: {}", e))
+}
+
+/// Returns random bytes for the handshake key and the frame masks.
+fn random_bytes<const N: usize>() -> Result<[u8; N], LLMError> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| LLMError::ProviderError(format!("Failed to generate random bytes: {}", e)))?;
+    Ok(bytes)
+}
+
+/// SHA-1 digest, which the handshake uses to prove the server speaks WebSocket.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (chunk, word) in digest.chunks_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn sha1_matches_known_digests() {
+        assert_eq!(hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(
+            hex(&sha1(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        // Two blocks once padded.
+        assert_eq!(
+            hex(&sha1(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+        assert_eq!(
+            hex(&sha1(&[b'a'; 1_000_000])),
+            "34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+        );
+    }
+
+    #[test]
+    fn accept_key_matches_rfc_example() {
+        let key = "dGhlIHNhbXBsZSBub25jZQ==";
+        assert_eq!(
+            BASE64.encode(sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes())),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn encodes_masked_frame_as_in_rfc_example() {
+        assert_eq!(
+            encode_frame(OPCODE_TEXT, b"Hello", [0x37, 0xfa, 0x21, 0x3d]),
+            [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58]
+        );
+    }
+
+    #[test]
+    fn encodes_extended_lengths() {
+        let frame = encode_frame(OPCODE_BINARY, &[0; 300], [0; 4]);
+        assert_eq!(frame[..4], [0x82, 0x80 | 126, 0x01, 0x2c]);
+        assert_eq!(frame.len(), 4 + 4 + 300);
+
+        let frame = encode_frame(OPCODE_BINARY, &[0; 70_000], [0; 4]);
+        assert_eq!(frame[1], 0x80 | 127);
+        assert_eq!(frame[2..10], 70_000u64.to_be_bytes());
+        assert_eq!(frame.len(), 10 + 4 + 70_000);
+    }
+
+    #[tokio::test]
+    async fn reads_unmasked_frame() {
+        let mut io: &[u8] = &[0x81, 0x05, b'H', b'e', b'l', b'l', b'o'];
+        assert_eq!(
+            read_frame(&mut io).await.unwrap(),
+            Some((true, OPCODE_TEXT, b"Hello".to_vec()))
+        );
+        assert_eq!(read_frame(&mut io).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reads_fragments() {
+        let mut io: &[u8] = &[0x01, 0x03, b'H', b'e', b'l', 0x80, 0x02, b'l', b'o'];
+        assert_eq!(
+            read_frame(&mut io).await.unwrap(),
+            Some((false, OPCODE_TEXT, b"Hel".to_vec()))
+        );
+        assert_eq!(
+            read_frame(&mut io).await.unwrap(),
+            Some((true, OPCODE_CONTINUATION, b"lo".to_vec()))
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_back_encoded_frames() {
+        for len in [0, 125, 126, 0xFFFF, 0x10000] {
+            let payload: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let frame = encode_frame(OPCODE_BINARY, &payload, [1, 2, 3, 4]);
+            assert_eq!(
+                read_frame(&mut frame.as_slice()).await.unwrap(),
+                Some((true, OPCODE_BINARY, payload)),
+                "payload of {} bytes",
+                len
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_frame() {
+        let mut header = vec![0x82, 127];
+        header.extend_from_slice(&u64::MAX.to_be_bytes());
+        assert!(read_frame(&mut header.as_slice()).await.is_err());
+    }
+
+    #[test]
+    fn random_bytes_differ() {
+        assert_ne!(random_bytes::<16>().unwrap(), random_bytes::<16>().unwrap());
+    }
+}
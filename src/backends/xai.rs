@@ -5,19 +5,39 @@
 
 #[cfg(feature = "xai")]
 use crate::{
-    chat::{ChatMessage, ChatProvider, ChatRole, StructuredOutputFormat},
-    completion::{CompletionProvider, CompletionRequest, CompletionResponse},
+    backends::{
+        check_no_audio, check_tool_messages, http_client, json_with_raw, probe, request_body,
+        request_id_header, send_request,
+        sse::{chat_chunk_stream, SseEvent, StreamEnd},
+        HttpOptions,
+    },
+    chat::{
+        ChatMessage, ChatProvider, ChatRole, ChatStream, MessageType, StreamChunk,
+        StreamedResponse, StructuredOutputFormat, Usage,
+    },
+    completion::{
+        text_stream, CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+    },
     embedding::EmbeddingProvider,
     error::LLMError,
+    health::HealthStatus,
+    logging::RequestLogging,
+    retry::RetryPolicy,
     LLMProvider,
 };
 use crate::{
-    chat::{ChatResponse, Tool},
+    chat::{
+        timed_stream, with_cancellation, with_stream_cancellation, ChatChoice, ChatOptions,
+        ChatResponse, FinishReason, RequestTimer, ResponseMetadata, ResponseTiming, TokenLogprob,
+        Tool, ToolChoice,
+    },
     ToolCall,
 };
 use async_trait::async_trait;
+use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 /// Client for interacting with X.AI's API.
 ///
@@ -48,7 +68,22 @@ pub struct XAI {
     pub embedding_dimensions: Option<u32>,
     /// JSON schema for structured output
     pub json_schema: Option<StructuredOutputFormat>,
+    /// How the model should choose between the provided tools
+    pub tool_choice: Option<ToolChoice>,
+    /// Number of candidate replies to generate per request
+    pub n: Option<u32>,
+    /// Whether to return log probabilities of the reply tokens
+    pub logprobs: Option<bool>,
+    /// Number of most likely alternatives to return for each token
+    pub top_logprobs: Option<u8>,
     /// HTTP client for making API requests
+    pub base_url: String,
+    /// Extra parameters merged into the chat request bodies
+    pub extra_params: Map<String, Value>,
+    /// Policy retrying transient request failures
+    pub retry: Option<RetryPolicy>,
+    /// Debug logging of the HTTP exchanges
+    pub logging: Option<RequestLogging>,
     client: Client,
 }
 
@@ -59,6 +94,12 @@ struct XAIChatMessage<'a> {
     role: &'a str,
     /// Content of the message
     content: &'a str,
+    /// Tool calls requested by the assistant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<&'a [ToolCall]>,
+    /// ID of the tool call a tool message answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<&'a str>,
 }
 
 /// Request payload for X.AI's chat API endpoint.
@@ -76,14 +117,48 @@ struct XAIChatRequest<'a> {
     temperature: Option<f32>,
     /// Whether to stream the response
     stream: bool,
+    /// Streaming options, only sent when streaming
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<XAIStreamOptions>,
     /// Top-p sampling parameter
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f32>,
     /// Top-k sampling parameter
     #[serde(skip_serializing_if = "Option::is_none")]
     top_k: Option<u32>,
+    /// Sequences ending the generation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    /// Seed for deterministic sampling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    /// Reasoning effort of reasoning models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<String>,
+    /// Number of candidate replies to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+    /// Whether to return log probabilities of the reply tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logprobs: Option<bool>,
+    /// Number of most likely alternatives to return for each token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_logprobs: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
     response_format: Option<XAIResponseFormat>,
+    /// Tools the model may call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<Tool>>,
+    /// Tool choice strategy, only sent with tools
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<ToolChoice>,
+}
+
+/// Options controlling a streamed response.
+#[derive(Serialize)]
+struct XAIStreamOptions {
+    /// Whether to send token usage in the last chunk
+    include_usage: bool,
 }
 
 /// Response from X.AI's chat API endpoint.
@@ -91,21 +166,93 @@ struct XAIChatRequest<'a> {
 struct XAIChatResponse {
     /// Array of generated responses
     choices: Vec<XAIChatChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    system_fingerprint: Option<String>,
+    /// The original response document
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// The provider's ID of the HTTP request
+    #[serde(skip)]
+    request_id: Option<String>,
+    /// How long the request took
+    #[serde(skip)]
+    timing: Option<ResponseTiming>,
 }
 
 impl std::fmt::Display for XAIChatResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for tool_call in self.tool_calls().unwrap_or_default() {
+            write!(f, "{}", tool_call)?;
+        }
         write!(f, "{}", self.text().unwrap_or_default())
     }
 }
 
 impl ChatResponse for XAIChatResponse {
+    fn usage(&self) -> Option<Usage> {
+        self.usage.clone()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some("xai".to_string()),
+            model: self.model.clone(),
+            response_id: self.id.clone(),
+            request_id: self.request_id.clone(),
+            system_fingerprint: self.system_fingerprint.clone(),
+            timing: self.timing,
+        }
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw)
+    }
+
     fn text(&self) -> Option<String> {
-        self.choices.first().map(|c| c.message.content.clone())
+        self.choices.first().and_then(|c| c.message.content.clone())
     }
 
     fn tool_calls(&self) -> Option<Vec<ToolCall>> {
-        None
+        self.choices
+            .first()
+            .and_then(|c| c.message.tool_calls.clone())
+    }
+
+    fn logprobs(&self) -> Option<Vec<TokenLogprob>> {
+        self.choices
+            .first()
+            .and_then(|c| c.logprobs.as_ref())
+            .and_then(|l| l.content.clone())
+    }
+
+    fn refusal(&self) -> Option<String> {
+        self.choices.first().and_then(|c| c.message.refusal.clone())
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.choices
+            .first()
+            .and_then(|c| c.finish_reason.as_deref())
+            .map(FinishReason::from_provider)
+    }
+
+    fn choices(&self) -> Vec<ChatChoice> {
+        self.choices
+            .iter()
+            .map(|c| ChatChoice {
+                text: c.message.content.clone(),
+                tool_calls: c.message.tool_calls.clone(),
+                finish_reason: c.finish_reason.as_deref().map(FinishReason::from_provider),
+                refusal: c.message.refusal.clone(),
+                logprobs: c.logprobs.as_ref().and_then(|l| l.content.clone()),
+            })
+            .collect()
     }
 }
 
@@ -114,13 +261,82 @@ impl ChatResponse for XAIChatResponse {
 struct XAIChatChoice {
     /// Message content and metadata
     message: XAIChatMsg,
+    /// Why generation stopped
+    #[serde(default)]
+    finish_reason: Option<String>,
+    /// Log probabilities of the reply tokens, if requested
+    #[serde(default)]
+    logprobs: Option<XAIChoiceLogprobs>,
+}
+
+/// Log probabilities attached to a choice.
+#[derive(Deserialize, Debug)]
+struct XAIChoiceLogprobs {
+    /// Log probabilities of the reply text tokens
+    #[serde(default)]
+    content: Option<Vec<TokenLogprob>>,
 }
 
 /// Message content from a chat response.
 #[derive(Deserialize, Debug)]
 struct XAIChatMsg {
-    /// Generated text content
-    content: String,
+    /// Generated text content, absent when the model only calls tools
+    content: Option<String>,
+    /// Tool calls requested by the model
+    tool_calls: Option<Vec<ToolCall>>,
+    /// Explanation given when the model declines to answer
+    #[serde(default)]
+    refusal: Option<String>,
+}
+
+/// A single chunk of a streamed chat response.
+#[derive(Deserialize, Debug)]
+struct XAIStreamChunk {
+    /// Deltas for each choice
+    #[serde(default)]
+    choices: Vec<XAIStreamChoice>,
+    /// Token usage, sent with the last chunk
+    usage: Option<Usage>,
+}
+
+/// Individual choice within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct XAIStreamChoice {
+    /// Incremental message content
+    delta: XAIStreamDelta,
+    /// Reason for ending generation, sent with the last delta
+    finish_reason: Option<String>,
+}
+
+/// Incremental message content within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct XAIStreamDelta {
+    /// Generated text fragment
+    content: Option<String>,
+    /// Reasoning fragment for reasoning models
+    reasoning_content: Option<String>,
+    /// Tool call fragments
+    tool_calls: Option<Vec<XAIToolCallDelta>>,
+}
+
+/// Fragment of a tool call within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct XAIToolCallDelta {
+    /// Position of the tool call within the response
+    index: usize,
+    /// ID of the tool call, sent with its first fragment
+    id: Option<String>,
+    /// Function name and arguments fragment
+    function: Option<XAIFunctionDelta>,
+}
+
+/// Fragment of a function call within a streamed chunk.
+#[derive(Deserialize, Debug)]
+struct XAIFunctionDelta {
+    /// Function name, sent with the first fragment
+    name: Option<String>,
+    /// Fragment of the JSON-encoded arguments
+    arguments: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -179,6 +395,12 @@ impl XAI {
     /// * `top_p` - Top-p sampling parameter
     /// * `top_k` - Top-k sampling parameter
     /// * `json_schema` - JSON schema for structured output
+    /// * `tool_choice` - How the model should choose between the provided tools
+    /// * `n` - Number of candidate replies to generate per request
+    /// * `logprobs` - Whether to return log probabilities of the reply tokens
+    /// * `top_logprobs` - Number of most likely alternatives to return for each token
+    /// * `http` - HTTP settings, such as the base URL (defaults to `https://api.x.ai/v1`), headers,
+    ///   proxy and retry policy
     ///
     /// # Returns
     ///
@@ -197,11 +419,21 @@ impl XAI {
         embedding_encoding_format: Option<String>,
         embedding_dimensions: Option<u32>,
         json_schema: Option<StructuredOutputFormat>,
+        tool_choice: Option<ToolChoice>,
+        n: Option<u32>,
+        logprobs: Option<bool>,
+        top_logprobs: Option<u8>,
+        http: HttpOptions,
     ) -> Self {
-        let mut builder = Client::builder();
-        if let Some(sec) = timeout_seconds {
-            builder = builder.timeout(std::time::Duration::from_secs(sec));
-        }
+        let HttpOptions {
+            base_url,
+            headers,
+            proxy,
+            client,
+            extra_params,
+            retry,
+            logging,
+        } = http;
         Self {
             api_key: api_key.into(),
             model: model.unwrap_or("grok-2-latest".to_string()),
@@ -215,35 +447,64 @@ impl XAI {
             embedding_encoding_format,
             embedding_dimensions,
             json_schema,
-            client: builder.build().expect("Failed to build reqwest Client"),
+            tool_choice,
+            n,
+            logprobs,
+            top_logprobs,
+            base_url: base_url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "https://api.x.ai/v1".to_string()),
+            extra_params: extra_params.unwrap_or_default(),
+            retry,
+            logging,
+            client: http_client(client, timeout_seconds, headers, proxy),
         }
     }
-}
 
-#[async_trait]
-impl ChatProvider for XAI {
-    /// Sends a chat request to the X.AI API and returns the response.
-    ///
-    /// # Arguments
-    ///
-    /// * `messages` - Array of chat messages representing the conversation
-    ///
-    /// # Returns
-    ///
-    /// The generated response text, or an error if the request fails.
-    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+    /// Builds and sends a chat request, returning the raw HTTP response.
+    async fn send_chat_request(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        stream: bool,
+        options: &ChatOptions,
+    ) -> Result<reqwest::Response, LLMError> {
         if self.api_key.is_empty() {
             return Err(LLMError::AuthError("Missing X.AI API key".to_string()));
         }
+        check_tool_messages(messages)?;
+        check_no_audio(messages, "xAI")?;
 
         let mut xai_msgs: Vec<XAIChatMessage> = messages
             .iter()
-            .map(|m| XAIChatMessage {
-                role: match m.role {
-                    ChatRole::User => "user",
-                    ChatRole::Assistant => "assistant",
+            .map(|m| match &m.message_type {
+                MessageType::ToolUse(calls) => XAIChatMessage {
+                    role: "assistant",
+                    content: &m.content,
+                    tool_calls: Some(calls),
+                    tool_call_id: None,
+                },
+                MessageType::ToolResult {
+                    tool_call_id,
+                    content,
+                    ..
+                } => XAIChatMessage {
+                    role: "tool",
+                    content,
+                    tool_calls: None,
+                    tool_call_id: Some(tool_call_id),
+                },
+                _ => XAIChatMessage {
+                    role: match m.role {
+                        ChatRole::System => "system",
+                        ChatRole::User => "user",
+                        ChatRole::Assistant => "assistant",
+                        ChatRole::Tool => "tool",
+                    },
+                    content: &m.content,
+                    tool_calls: None,
+                    tool_call_id: None,
                 },
-                content: &m.content,
             })
             .collect();
 
@@ -253,6 +514,8 @@ impl ChatProvider for XAI {
                 XAIChatMessage {
                     role: "system",
                     content: system,
+                    tool_calls: None,
+                    tool_call_id: None,
                 },
             );
         }
@@ -269,32 +532,144 @@ impl ChatProvider for XAI {
         let body = XAIChatRequest {
             model: &self.model,
             messages: xai_msgs,
-            max_tokens: self.max_tokens,
-            temperature: self.temperature,
-            stream: self.stream.unwrap_or(false),
-            top_p: self.top_p,
+            max_tokens: options.max_tokens.or(self.max_tokens),
+            temperature: options.temperature.or(self.temperature),
+            stream,
+            stream_options: stream.then_some(XAIStreamOptions {
+                include_usage: true,
+            }),
+            top_p: options.top_p.or(self.top_p),
             top_k: self.top_k,
+            stop: options.stop.clone(),
+            seed: options.seed,
+            reasoning_effort: options.reasoning_effort.map(|effort| effort.to_string()),
+            // Streams carry a single reply.
+            n: self.n.filter(|_| !stream),
+            logprobs: self.logprobs.filter(|_| !stream),
+            top_logprobs: self.top_logprobs.filter(|_| !stream),
             response_format,
+            tools: tools.map(|t| t.to_vec()),
+            tool_choice: tools.and(self.tool_choice.clone()),
         };
 
         let mut request = self
             .client
-            .post("https://api.x.ai/v1/chat/completions")
+            .post(format!("{}/chat/completions", self.base_url))
             .bearer_auth(&self.api_key)
-            .json(&body);
+            .json(&request_body(&body, &self.extra_params)?);
 
-        if let Some(timeout) = self.timeout_seconds {
-            request = request.timeout(std::time::Duration::from_secs(timeout));
+        if let Some(timeout) = options
+            .timeout
+            .or(self.timeout_seconds.map(std::time::Duration::from_secs))
+        {
+            request = request.timeout(timeout);
         }
 
-        let resp = request.send().await?.error_for_status()?;
+        Ok(
+            send_request(request, self.retry.as_ref(), self.logging.as_ref())
+                .await?
+                .error_for_status()?,
+        )
+    }
 
-        let json_resp: XAIChatResponse = resp.json().await?;
+    /// Sends a chat request and parses the reply, assembling it from a stream when
+    /// streaming is enabled.
+    async fn send_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if self.stream.unwrap_or(false) {
+            let mut timer = RequestTimer::start();
+            let mut stream = self.send_chat_stream(messages, tools, options).await?;
+            let mut response = StreamedResponse::default();
+            while let Some(chunk) = stream.next().await {
+                timer.first_byte();
+                response.push(chunk?);
+            }
+            response.timing = Some(timer.timing());
+            return Ok(Box::new(response));
+        }
+
+        let mut timer = RequestTimer::start();
+        let resp = self
+            .send_chat_request(messages, tools, false, options)
+            .await?;
+        timer.first_byte();
+        let request_id = request_id_header(&resp);
+        let (mut json_resp, raw): (XAIChatResponse, _) = json_with_raw(resp).await?;
+        json_resp.raw = raw;
+        json_resp.timing = Some(timer.finish("xai", &self.model));
+        json_resp.request_id = request_id;
         Ok(Box::new(json_resp))
     }
 
+    /// Sends a streaming chat request and returns its chunks.
+    async fn send_chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        let timer = RequestTimer::start();
+        let resp = self
+            .send_chat_request(messages, tools, true, options)
+            .await?;
+        Ok(timed_stream(
+            chat_chunk_stream(resp, Self::parse_stream_event),
+            timer,
+            "xai",
+            self.model.clone(),
+        ))
+    }
+
+    /// Converts a single streamed event into normalized chunks.
+    fn parse_stream_event(
+        event: &SseEvent,
+        end: &mut StreamEnd,
+    ) -> Result<Vec<StreamChunk>, LLMError> {
+        let chunk: XAIStreamChunk =
+            serde_json::from_str(&event.data).map_err(|e| LLMError::JsonError(e.to_string()))?;
+
+        if chunk.usage.is_some() {
+            end.usage = chunk.usage;
+        }
+
+        let mut chunks = Vec::new();
+        for choice in chunk.choices {
+            if let Some(thinking) = choice.delta.reasoning_content.filter(|t| !t.is_empty()) {
+                chunks.push(StreamChunk::Thinking(thinking));
+            }
+            if let Some(text) = choice.delta.content.filter(|t| !t.is_empty()) {
+                chunks.push(StreamChunk::Text(text));
+            }
+            for tool_call in choice.delta.tool_calls.unwrap_or_default() {
+                let (name, arguments) = match tool_call.function {
+                    Some(function) => (function.name, function.arguments.unwrap_or_default()),
+                    None => (None, String::new()),
+                };
+                chunks.push(StreamChunk::ToolCallDelta {
+                    index: tool_call.index,
+                    id: tool_call.id,
+                    name,
+                    arguments,
+                });
+            }
+            if choice.finish_reason.is_some() {
+                end.finish_reason = choice.finish_reason;
+            }
+        }
+        Ok(chunks)
+    }
+}
+
+#[async_trait]
+impl ChatProvider for XAI {
     /// Sends a chat request to X.AI's API with tools.
     ///
+    /// When streaming is enabled, the response is streamed and assembled before returning.
+    ///
     /// # Arguments
     ///
     /// * `messages` - The conversation history as a slice of chat messages
@@ -302,13 +677,51 @@ impl ChatProvider for XAI {
     ///
     /// # Returns
     ///
-    /// The provider's response text or an error
+    /// The provider's response text and tool calls, or an error
     async fn chat_with_tools(
         &self,
-        _messages: &[ChatMessage],
-        _tools: Option<&[Tool]>,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
     ) -> Result<Box<dyn ChatResponse>, LLMError> {
-        todo!()
+        with_cancellation(options, self.send_chat(messages, tools, options)).await
+    }
+
+    /// Sends a streaming chat request to the X.AI API.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Array of chat messages representing the conversation
+    /// * `tools` - Optional slice of tools to use in the chat
+    ///
+    /// # Returns
+    ///
+    /// A stream of response chunks, or an error if the request fails.
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.send_chat_stream(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        with_stream_cancellation(options, self.send_chat_stream(messages, tools, options)).await
     }
 }
 
@@ -330,6 +743,20 @@ impl CompletionProvider for XAI {
             text: "X.AI completion not implemented.".into(),
         })
     }
+
+    /// Streams a completion by sending the prompt as a single user message to the chat endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The completion request parameters
+    ///
+    /// # Returns
+    ///
+    /// A stream of generated text fragments, or an error if the request fails.
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        let message = ChatMessage::user().content(req.prompt.clone()).build();
+        Ok(text_stream(self.chat_stream(&[message]).await?))
+    }
 }
 
 #[async_trait]
@@ -351,12 +778,12 @@ impl EmbeddingProvider for XAI {
             dimensions: self.embedding_dimensions,
         };
 
-        let resp = self
+        let request = self
             .client
-            .post("https://api.x.ai/v1/embeddings")
+            .post(format!("{}/embeddings", self.base_url))
             .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
+            .json(&body);
+        let resp = send_request(request, self.retry.as_ref(), self.logging.as_ref())
             .await?
             .error_for_status()?;
 
@@ -367,4 +794,14 @@ impl EmbeddingProvider for XAI {
     }
 }
 
-impl LLMProvider for XAI {}
+#[async_trait]
+impl LLMProvider for XAI {
+    async fn validate(&self) -> HealthStatus {
+        probe(
+            self.client
+                .get(format!("{}/models", self.base_url))
+                .bearer_auth(&self.api_key),
+        )
+        .await
+    }
+}
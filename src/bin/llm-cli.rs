@@ -134,8 +134,43 @@ fn get_api_key(backend: &LLMBackend, args: &CliArgs) -> Option<String> {
             LLMBackend::Groq => store.get("GROQ_API_KEY")
                 .cloned()
                 .or_else(|| std::env::var("GROQ_API_KEY").ok()),
+            LLMBackend::AzureOpenAI => store.get("AZURE_OPENAI_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("AZURE_OPENAI_API_KEY").ok()),
+            LLMBackend::Mistral => store.get("MISTRAL_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("MISTRAL_API_KEY").ok()),
+            LLMBackend::Cohere => store.get("COHERE_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("COHERE_API_KEY").ok()),
+            LLMBackend::Fireworks => store.get("FIREWORKS_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("FIREWORKS_API_KEY").ok()),
+            LLMBackend::Perplexity => store.get("PERPLEXITY_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("PERPLEXITY_API_KEY").ok()),
+            LLMBackend::Nvidia => store.get("NVIDIA_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("NVIDIA_API_KEY").ok()),
+            LLMBackend::OpenAICompatible => store.get("OPENAI_COMPATIBLE_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("OPENAI_COMPATIBLE_API_KEY").ok()),
+            LLMBackend::Qwen => store.get("DASHSCOPE_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("DASHSCOPE_API_KEY").ok()),
+            LLMBackend::Moonshot => store.get("MOONSHOT_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("MOONSHOT_API_KEY").ok()),
+            LLMBackend::Voyage => store.get("VOYAGE_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("VOYAGE_API_KEY").ok()),
+            LLMBackend::Jina => store.get("JINA_API_KEY")
+                .cloned()
+                .or_else(|| std::env::var("JINA_API_KEY").ok()),
             LLMBackend::Ollama => None,
             LLMBackend::Phind => None,
+            LLMBackend::Mock => None,
+            LLMBackend::LMStudio => None,
         }
     })
 }
@@ -233,7 +268,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let (provider_name, model_name) = get_provider_info(&args)
-        .ok_or_else(|| "No provider specified. Use --provider, provider:model argument, or set a default provider with 'llm default <provider:model>'")?;
+        .ok_or("No provider specified. Use --provider, provider:model argument, or set a default provider with 'llm default <provider:model>'")?;
 
     let backend = LLMBackend::from_str(&provider_name)
         .map_err(|e| format!("Invalid provider: {}", e))?;
@@ -0,0 +1,406 @@
+//! Spending limits for providers.
+//!
+//! A [`Budget`] caps the tokens or dollars spent per period by the providers it is
+//! attached to. Once a limit is reached, a [`BudgetLLM`] refuses further requests with
+//! [`LLMError::BudgetExceeded`] until the period rolls over, acting as a kill switch for
+//! runaway agents.
+//!
+//! [`Budget`]: crate::budget::Budget
+//! [`BudgetLLM`]: crate::budget::BudgetLLM
+//! [`LLMError::BudgetExceeded`]: crate::error::LLMError::BudgetExceeded
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use llm::budget::{Budget, BudgetLLM};
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! // At most $5 a day.
+//! let budget = Budget::new()
+//!     .max_cost(5.0)
+//!     .period(Duration::from_secs(24 * 3600));
+//! let llm = BudgetLLM::new(
+//!     LLMBuilder::new()
+//!         .backend(LLMBackend::OpenAI)
+//!         .api_key("sk-...")
+//!         .model("gpt-4o-mini")
+//!         .build()?,
+//!     budget.clone(),
+//! )
+//! .model("gpt-4o-mini");
+//!
+//! llm.chat(&[ChatMessage::user().content("Hello!").build()]).await?;
+//! println!("Spent ${:.4} so far today", budget.spent().cost);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, StreamChunk, Tool, Usage,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::pricing;
+use crate::LLMProvider;
+
+/// Tokens and dollars spent in the current period of a [`Budget`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Spending {
+    /// Tokens used, prompts and replies included
+    pub tokens: u64,
+    /// Estimated cost in US dollars
+    pub cost: f64,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    spent: Spending,
+    /// Start of the current period
+    period_start: Instant,
+}
+
+/// Shared spending limits, with the spending of the current period.
+///
+/// Clones share the same spending, so one budget can cap several providers. Limits are
+/// checked before each request, so the request that crosses a limit still completes.
+#[derive(Debug, Clone)]
+pub struct Budget {
+    /// Most tokens spendable per period
+    max_tokens: Option<u64>,
+    /// Most dollars spendable per period
+    max_cost: Option<f64>,
+    /// Length of a period, after which spending resets
+    period: Option<Duration>,
+    state: Arc<Mutex<BudgetState>>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Budget {
+    /// Creates a budget with no limits and a single period that never resets.
+    pub fn new() -> Self {
+        Self {
+            max_tokens: None,
+            max_cost: None,
+            period: None,
+            state: Arc::new(Mutex::new(BudgetState {
+                spent: Spending::default(),
+                period_start: Instant::now(),
+            })),
+        }
+    }
+
+    /// Limits the tokens spent per period.
+    pub fn max_tokens(mut self, tokens: u64) -> Self {
+        self.max_tokens = Some(tokens);
+        self
+    }
+
+    /// Limits the estimated cost per period, in US dollars.
+    ///
+    /// Costs are estimated with [`crate::pricing`]; requests to models without a price
+    /// only count towards the token limit.
+    pub fn max_cost(mut self, dollars: f64) -> Self {
+        self.max_cost = Some(dollars);
+        self
+    }
+
+    /// Resets the spending every `period`, counted from the creation of the budget.
+    pub fn period(mut self, period: Duration) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Returns the spending of the current period.
+    pub fn spent(&self) -> Spending {
+        self.current().spent
+    }
+
+    /// Clears the spending and starts a new period.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.spent = Spending::default();
+        state.period_start = Instant::now();
+    }
+
+    /// Returns an error if a limit has been reached in the current period.
+    pub fn check(&self) -> Result<(), LLMError> {
+        let spent = self.current().spent;
+        if let Some(max_tokens) = self.max_tokens.filter(|max| spent.tokens >= *max) {
+            return Err(LLMError::BudgetExceeded(format!(
+                "{} tokens spent of a {} token budget",
+                spent.tokens, max_tokens
+            )));
+        }
+        if let Some(max_cost) = self.max_cost.filter(|max| spent.cost >= *max) {
+            return Err(LLMError::BudgetExceeded(format!(
+                "${:.4} spent of a ${:.2} budget",
+                spent.cost, max_cost
+            )));
+        }
+        Ok(())
+    }
+
+    /// Adds the usage of a request to a model.
+    pub fn record(&self, model: Option<&str>, usage: &Usage) {
+        let cost = model
+            .and_then(|model| pricing::estimate(model, usage))
+            .map_or(0.0, |estimate| estimate.total_cost);
+        let mut state = self.current();
+        state.spent.tokens += u64::from(usage.total_tokens);
+        state.spent.cost += cost;
+    }
+
+    /// Locks the state, starting a new period if the current one is over.
+    fn current(&self) -> std::sync::MutexGuard<'_, BudgetState> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(period) = self.period {
+            let elapsed = state.period_start.elapsed();
+            if elapsed >= period {
+                let periods = (elapsed.as_secs_f64() / period.as_secs_f64()).floor();
+                state.period_start += period.mul_f64(periods);
+                state.spent = Spending::default();
+            }
+        }
+        state
+    }
+}
+
+/// A wrapper around an LLM provider refusing requests once a [`Budget`] is spent.
+///
+/// Chat requests, streams included, count their reported usage. Completions and
+/// embeddings are refused once the budget is spent, but report no usage to count.
+pub struct BudgetLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Budget the requests are charged to
+    budget: Budget,
+    /// Model pricing responses that don't report one, and streams
+    model: Option<String>,
+}
+
+impl BudgetLLM {
+    /// Wraps a provider, charging its requests to a budget.
+    pub fn new(inner: Box<dyn LLMProvider>, budget: Budget) -> Self {
+        Self {
+            inner,
+            budget,
+            model: None,
+        }
+    }
+
+    /// Sets the model the provider was configured with, which prices streams and
+    /// responses that don't report their model.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Returns the budget the requests are charged to.
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    fn record(&self, response: &dyn ChatResponse) {
+        if let Some(usage) = response.usage() {
+            let model = response.metadata().model.or_else(|| self.model.clone());
+            self.budget.record(model.as_deref(), &usage);
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for BudgetLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for BudgetLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.budget.check()?;
+        let response = self.inner.chat(messages).await?;
+        self.record(response.as_ref());
+        Ok(response)
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.budget.check()?;
+        let response = self.inner.chat_with_tools(messages, tools).await?;
+        self.record(response.as_ref());
+        Ok(response)
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.budget.check()?;
+        let response = self
+            .inner
+            .chat_with_options(messages, tools, options)
+            .await?;
+        self.record(response.as_ref());
+        Ok(response)
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.chat_stream_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.budget.check()?;
+        let stream = self
+            .inner
+            .chat_stream_with_options(messages, tools, options)
+            .await?;
+        let budget = self.budget.clone();
+        let model = self.model.clone();
+        Ok(Box::pin(stream.inspect(move |chunk| {
+            if let Ok(StreamChunk::Done {
+                usage: Some(usage), ..
+            }) = chunk
+            {
+                budget.record(model.as_deref(), usage);
+            }
+        })))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for BudgetLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.budget.check()?;
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.budget.check()?;
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.budget.check()?;
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for BudgetLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.budget.check()?;
+        self.inner.embed(input).await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockLLM;
+
+    fn usage(prompt_tokens: u32, completion_tokens: u32) -> Usage {
+        Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cached_prompt_tokens: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_requests_once_the_token_limit_is_spent() {
+        let mock = MockLLM::new().fallback_reply("A reply long enough to spend a few tokens");
+        let budget = Budget::new().max_tokens(1);
+        let llm = BudgetLLM::new(Box::new(mock.clone()), budget.clone());
+        let messages = vec![ChatMessage::user().content("Hello").build()];
+
+        llm.chat(&messages).await.unwrap();
+        assert!(budget.spent().tokens >= 1);
+        assert!(matches!(
+            llm.chat(&messages).await.err(),
+            Some(LLMError::BudgetExceeded(_))
+        ));
+        assert_eq!(mock.calls().len(), 1);
+    }
+
+    #[test]
+    fn checks_the_cost_limit_with_model_prices() {
+        let budget = Budget::new().max_cost(0.1);
+        budget.record(Some("unpriced-model"), &usage(1_000_000, 0));
+        assert!(budget.check().is_ok());
+        assert_eq!(budget.spent().cost, 0.0);
+
+        budget.record(Some("gpt-4o-mini"), &usage(1_000_000, 0));
+        assert!(budget.spent().cost > 0.1);
+        assert!(matches!(budget.check(), Err(LLMError::BudgetExceeded(_))));
+    }
+
+    #[test]
+    fn resets_the_spending_when_a_period_ends() {
+        let budget = Budget::new()
+            .max_tokens(10)
+            .period(Duration::from_millis(50));
+        budget.record(None, &usage(6, 4));
+        assert!(budget.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(70));
+        assert_eq!(budget.spent(), Spending::default());
+        assert!(budget.check().is_ok());
+
+        budget.record(None, &usage(3, 0));
+        assert_eq!(budget.spent().tokens, 3);
+    }
+
+    #[test]
+    fn clones_share_their_spending() {
+        let budget = Budget::new().max_tokens(10);
+        budget.clone().record(None, &usage(10, 0));
+        assert!(budget.check().is_err());
+
+        budget.reset();
+        assert!(budget.check().is_ok());
+    }
+}
@@ -0,0 +1,208 @@
+//! Cache store keeping responses in files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chat::ResponseSnapshot;
+use crate::error::LLMError;
+
+use super::CacheStore;
+
+/// Layout of a cached response file.
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    /// The full request key, as file names only hold its hash
+    key: String,
+    expires_at: SystemTime,
+    response: ResponseSnapshot,
+}
+
+/// Store keeping each response in a JSON file of a directory, named after the hash of its
+/// request, so cached responses survive restarts.
+///
+/// Once the files take more than `max_bytes`, the least recently used are deleted. Use a
+/// separate directory for each provider and model.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use llm::cache::{CacheStore, DiskStore};
+/// use llm::chat::{ChatResponse, ResponseSnapshot};
+///
+/// let dir = std::env::temp_dir().join("llm-doc-cache");
+/// let store = DiskStore::new(&dir, 1 << 20, Duration::from_secs(3600)).unwrap();
+/// let response = ResponseSnapshot {
+///     text: Some("Paris".to_string()),
+///     tool_calls: None,
+///     thinking: None,
+///     finish_reason: None,
+///     raw: None,
+///     refusal: None,
+///     usage: None,
+///     logprobs: None,
+///     citations: None,
+///     audio: None,
+///     safety_ratings: None,
+///     grounding: None,
+///     metadata: Default::default(),
+///     choices: Vec::new(),
+///     display: "Paris".to_string(),
+/// };
+///
+/// store.put("capital of France".to_string(), response);
+/// let reopened = DiskStore::new(&dir, 1 << 20, Duration::from_secs(3600)).unwrap();
+/// let cached = reopened.get("capital of France").unwrap();
+/// assert_eq!(cached.text().as_deref(), Some("Paris"));
+/// # reopened.clear();
+/// ```
+#[derive(Debug)]
+pub struct DiskStore {
+    dir: PathBuf,
+    /// Largest total size of the cached files
+    max_bytes: u64,
+    /// Time a response stays cached
+    ttl: Duration,
+    /// Serializes writes and evictions within the process
+    write_lock: Mutex<()>,
+}
+
+impl DiskStore {
+    /// Opens a store in a directory, creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory holding the cached responses
+    /// * `max_bytes` - Largest total size of the cached files
+    /// * `ttl` - Time a response stays cached
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64, ttl: Duration) -> Result<Self, LLMError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            LLMError::InvalidRequest(format!(
+                "Failed to create cache directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+        Ok(Self {
+            dir,
+            max_bytes,
+            ttl,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+
+    /// Lists the cached files with their size and last use, from least to most recently
+    /// used.
+    fn files(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+            })
+            .collect();
+        files.sort_by_key(|(_, _, modified)| *modified);
+        files
+    }
+
+    /// Deletes the least recently used files until the store fits in `max_bytes`.
+    fn evict(&self) {
+        let files = self.files();
+        let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+        for (path, size, _) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total -= size;
+            }
+        }
+    }
+}
+
+/// Hashes bytes with 64-bit FNV-1a, which is stable across runs and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Marks a file as just used, so eviction keeps it longer.
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get(&self, key: &str) -> Option<ResponseSnapshot> {
+        let path = self.path(key);
+        let json = fs::read(&path).ok()?;
+        let cached: CachedFile = match serde_json::from_slice(&json) {
+            Ok(cached) => cached,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "ignoring corrupt cache file");
+                return None;
+            }
+        };
+        if cached.key != key {
+            return None;
+        }
+        if SystemTime::now() >= cached.expires_at {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        touch(&path);
+        Some(cached.response)
+    }
+
+    fn put(&self, key: String, response: ResponseSnapshot) {
+        let path = self.path(&key);
+        let cached = CachedFile {
+            key,
+            expires_at: SystemTime::now() + self.ttl,
+            response,
+        };
+        let json = match serde_json::to_vec(&cached) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to serialize cached response");
+                return;
+            }
+        };
+
+        let _guard = self.write_lock.lock().unwrap();
+        // Write to a temporary file first so readers never see a partial file.
+        let tmp = path.with_extension("tmp");
+        if let Err(e) = fs::write(&tmp, json).and_then(|_| fs::rename(&tmp, &path)) {
+            tracing::warn!(path = %path.display(), error = %e, "failed to write cache file");
+            let _ = fs::remove_file(&tmp);
+            return;
+        }
+        self.evict();
+    }
+
+    fn len(&self) -> usize {
+        self.files().len()
+    }
+
+    fn clear(&self) {
+        let _guard = self.write_lock.lock().unwrap();
+        for (path, _, _) in self.files() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
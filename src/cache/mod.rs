@@ -0,0 +1,422 @@
+//! Caching of chat responses.
+//!
+//! A [`CachedLLM`] answers a chat request it has already seen from memory rather than
+//! calling the provider again. Requests match when their messages, tools and sampling
+//! options are identical; entries expire after a time to live, and the least recently
+//! used ones are evicted once the cache is full. This suits workloads that tolerate
+//! repeated answers, such as tests, retried jobs or idempotent pipelines.
+//!
+//! A [`SemanticCachedLLM`] also answers prompts that are worded differently but mean the
+//! same, comparing their embeddings.
+//!
+//! Responses are kept in a [`MemoryStore`] by default. A [`DiskStore`] keeps them in a
+//! directory instead, so they survive restarts.
+//!
+//! [`CachedLLM`]: crate::cache::CachedLLM
+//! [`SemanticCachedLLM`]: crate::cache::SemanticCachedLLM
+//! [`MemoryStore`]: crate::cache::MemoryStore
+//! [`DiskStore`]: crate::cache::DiskStore
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::cache::CachedLLM;
+//! use llm::chat::{ChatMessage, ChatProvider};
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = CachedLLM::new(
+//!     LLMBuilder::new()
+//!         .backend(LLMBackend::OpenAI)
+//!         .api_key("sk-...")
+//!         .build()?,
+//!     1000,
+//!     Duration::from_secs(3600),
+//! );
+//!
+//! let messages = vec![ChatMessage::user().content("What is the capital of France?").build()];
+//! llm.chat(&messages).await?;
+//! // Served from the cache.
+//! llm.chat(&messages).await?;
+//! assert_eq!(llm.stats().hits, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, ResponseSnapshot, Tool,
+};
+use crate::coalesce::request_key;
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+mod disk;
+mod semantic;
+
+pub use disk::DiskStore;
+pub use semantic::SemanticCachedLLM;
+
+/// Hit and miss counts of a cache, as returned by [`CachedLLM::stats`] and
+/// [`SemanticCachedLLM::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Requests answered from the cache
+    pub hits: u64,
+    /// Requests sent to the provider
+    pub misses: u64,
+    /// Responses currently cached
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// Returns the share of requests answered from the cache, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let requests = self.hits + self.misses;
+        if requests == 0 {
+            0.0
+        } else {
+            self.hits as f64 / requests as f64
+        }
+    }
+}
+
+/// Storage for cached responses, keyed by request.
+///
+/// Stores decide how long responses are kept and what is evicted. Implement it to keep
+/// the cache in another place, such as a shared database.
+pub trait CacheStore: Send + Sync {
+    /// Returns the response cached for a key, if it has not expired.
+    fn get(&self, key: &str) -> Option<ResponseSnapshot>;
+
+    /// Caches a response, replacing the one cached for the same key.
+    fn put(&self, key: String, response: ResponseSnapshot);
+
+    /// Returns the number of cached responses.
+    fn len(&self) -> usize;
+
+    /// Returns whether no response is cached.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every cached response.
+    fn clear(&self);
+}
+
+/// A response cached in memory.
+struct Entry {
+    response: ResponseSnapshot,
+    expires_at: Instant,
+    /// Position of the entry in the recency order
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct MemoryEntries {
+    entries: HashMap<String, Entry>,
+    /// Keys ordered from least to most recently used
+    recency: BTreeMap<u64, String>,
+    /// Next recency position
+    tick: u64,
+}
+
+impl MemoryEntries {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+}
+
+/// In-memory LRU store of responses with a time to live.
+pub struct MemoryStore {
+    /// Cached responses in recency order
+    entries: Mutex<MemoryEntries>,
+    /// Maximum number of cached responses
+    capacity: usize,
+    /// Time a response stays cached
+    ttl: Duration,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of cached responses, beyond which the least recently
+    ///   used are evicted
+    /// * `ttl` - Time a response stays cached
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(MemoryEntries::default()),
+            capacity: capacity.max(1),
+            ttl,
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<ResponseSnapshot> {
+        let mut cache = self.entries.lock().unwrap();
+        let cache = &mut *cache;
+        match cache.entries.get_mut(key) {
+            Some(entry) if Instant::now() < entry.expires_at => {
+                cache.recency.remove(&entry.last_used);
+                cache.recency.insert(cache.tick, key.to_string());
+                entry.last_used = cache.tick;
+                cache.tick += 1;
+                Some(entry.response.clone())
+            }
+            Some(_) => {
+                cache.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put(&self, key: String, response: ResponseSnapshot) {
+        let mut cache = self.entries.lock().unwrap();
+        cache.remove(&key);
+        while cache.entries.len() >= self.capacity {
+            let Some((_, oldest)) = cache.recency.pop_first() else {
+                break;
+            };
+            cache.entries.remove(&oldest);
+        }
+        let tick = cache.tick;
+        cache.recency.insert(tick, key.clone());
+        cache.entries.insert(
+            key,
+            Entry {
+                response,
+                expires_at: Instant::now() + self.ttl,
+                last_used: tick,
+            },
+        );
+        cache.tick += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().entries.len()
+    }
+
+    fn clear(&self) {
+        let mut cache = self.entries.lock().unwrap();
+        cache.entries.clear();
+        cache.recency.clear();
+    }
+}
+
+/// A wrapper around an LLM provider serving repeated chat requests from a cache.
+///
+/// The cache belongs to the wrapped provider, so its model is implied by every key; give
+/// each provider its own store, or its own directory for a [`DiskStore`]. Only successful
+/// responses are cached. Streams, completions and embeddings are passed through.
+pub struct CachedLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Where responses are cached
+    store: Box<dyn CacheStore>,
+    /// Requests answered from the cache
+    hits: AtomicU64,
+    /// Requests sent to the provider
+    misses: AtomicU64,
+}
+
+impl CachedLLM {
+    /// Wraps a provider in an empty in-memory cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to cache
+    /// * `capacity` - Maximum number of cached responses
+    /// * `ttl` - Time a response stays cached
+    pub fn new(inner: Box<dyn LLMProvider>, capacity: usize, ttl: Duration) -> Self {
+        Self::with_store(inner, Box::new(MemoryStore::new(capacity, ttl)))
+    }
+
+    /// Wraps a provider in a cache kept in a store.
+    pub fn with_store(inner: Box<dyn LLMProvider>, store: Box<dyn CacheStore>) -> Self {
+        Self {
+            inner,
+            store,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the hit and miss counts since the cache was created.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.store.len(),
+        }
+    }
+
+    /// Removes every cached response, keeping the hit and miss counts.
+    pub fn clear(&self) {
+        self.store.clear();
+    }
+
+    /// Answers a chat request from the cache, or sends it and caches the response.
+    async fn cached_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let key = request_key(messages, tools, options)?;
+        if let Some(response) = self.store.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Box::new(response));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let response = self
+            .inner
+            .chat_with_options(messages, tools, options)
+            .await?;
+        self.store
+            .put(key, ResponseSnapshot::from_response(response.as_ref()));
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for CachedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for CachedLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.cached_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.cached_chat(messages, tools, options).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner.chat_stream_with_tools(messages, tools).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for CachedLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CachedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockLLM;
+
+    fn prompt(text: &str) -> Vec<ChatMessage> {
+        vec![ChatMessage::user().content(text).build()]
+    }
+
+    #[tokio::test]
+    async fn evicts_the_least_recently_used_response() {
+        let mock = MockLLM::new().fallback_reply("ok");
+        let llm = CachedLLM::new(Box::new(mock.clone()), 2, Duration::from_secs(3600));
+
+        llm.chat(&prompt("a")).await.unwrap();
+        llm.chat(&prompt("b")).await.unwrap();
+        // Using "a" again makes "b" the least recently used.
+        llm.chat(&prompt("a")).await.unwrap();
+        llm.chat(&prompt("c")).await.unwrap();
+        assert_eq!(llm.stats().entries, 2);
+
+        llm.chat(&prompt("a")).await.unwrap();
+        assert_eq!(llm.stats().hits, 2);
+        llm.chat(&prompt("b")).await.unwrap();
+        assert_eq!(llm.stats().hits, 2);
+        assert_eq!(llm.stats().misses, 4);
+        assert_eq!(mock.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn expires_responses_after_their_time_to_live() {
+        let mock = MockLLM::new().reply("first").reply("second");
+        let llm = CachedLLM::new(Box::new(mock.clone()), 10, Duration::from_millis(50));
+
+        let first = llm.chat(&prompt("a")).await.unwrap();
+        let cached = llm.chat(&prompt("a")).await.unwrap();
+        assert_eq!(first.text(), cached.text());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let refreshed = llm.chat(&prompt("a")).await.unwrap();
+        assert_eq!(refreshed.text().as_deref(), Some("second"));
+        assert_eq!(llm.stats().hits, 1);
+        assert_eq!(mock.calls().len(), 2);
+    }
+}
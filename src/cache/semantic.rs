@@ -0,0 +1,322 @@
+//! Caching of chat responses by prompt similarity.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatRole, ChatStream, MessageType,
+    ResponseSnapshot, Tool,
+};
+use crate::coalesce::request_key;
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+use super::CacheStats;
+
+/// A cached response with the embedding of the prompt it answered.
+struct SemanticEntry {
+    /// Key of the conversation before the prompt, with the tools and options
+    context: String,
+    embedding: Vec<f32>,
+    response: ResponseSnapshot,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct SemanticStore {
+    /// Entries from oldest to newest
+    entries: VecDeque<SemanticEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+/// A wrapper around an LLM provider answering chat requests whose prompt is close enough
+/// to one it has already answered.
+///
+/// The last message of a request is its prompt: it must be a user text message, which is
+/// embedded and compared by cosine similarity to the prompts of cached responses. The
+/// earlier messages, tools and sampling options must match exactly. Requests that cannot
+/// be embedded, or whose embedding fails, go to the provider uncached. Once the cache is
+/// full, the oldest responses are evicted first.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use llm::builder::{LLMBackend, LLMBuilder};
+/// use llm::cache::SemanticCachedLLM;
+/// use llm::chat::{ChatMessage, ChatProvider};
+///
+/// # async fn run() -> Result<(), llm::error::LLMError> {
+/// let llm = SemanticCachedLLM::new(
+///     LLMBuilder::new()
+///         .backend(LLMBackend::OpenAI)
+///         .api_key("sk-...")
+///         .build()?,
+///     0.95,
+/// )
+/// .ttl(Duration::from_secs(3600));
+///
+/// llm.chat(&[ChatMessage::user().content("What is the capital of France?").build()])
+///     .await?;
+/// // Likely served from the cache.
+/// llm.chat(&[ChatMessage::user().content("What's France's capital?").build()])
+///     .await?;
+/// println!("hit rate: {:.0}%", llm.stats().hit_rate() * 100.0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SemanticCachedLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Provider embedding the prompts, the wrapped provider if not set
+    embedder: Option<Box<dyn LLMProvider>>,
+    /// Cached responses and hit counts
+    store: Mutex<SemanticStore>,
+    /// Lowest cosine similarity between two prompts sharing a response
+    threshold: f32,
+    /// Maximum number of cached responses
+    capacity: usize,
+    /// Time a response stays cached
+    ttl: Duration,
+}
+
+impl SemanticCachedLLM {
+    /// Wraps a provider in an empty semantic cache, holding up to 1000 responses for an
+    /// hour.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to cache, which also embeds the prompts unless
+    ///   [`SemanticCachedLLM::embedder`] is set
+    /// * `threshold` - Lowest cosine similarity, up to 1, for two prompts to share a
+    ///   response
+    pub fn new(inner: Box<dyn LLMProvider>, threshold: f32) -> Self {
+        Self {
+            inner,
+            embedder: None,
+            store: Mutex::new(SemanticStore::default()),
+            threshold,
+            capacity: 1000,
+            ttl: Duration::from_secs(3600),
+        }
+    }
+
+    /// Embeds the prompts with another provider.
+    pub fn embedder(mut self, embedder: Box<dyn LLMProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Sets the maximum number of cached responses.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets the time a response stays cached.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the hit and miss counts since the cache was created.
+    pub fn stats(&self) -> CacheStats {
+        let store = self.store.lock().unwrap();
+        CacheStats {
+            hits: store.hits,
+            misses: store.misses,
+            entries: store.entries.len(),
+        }
+    }
+
+    /// Removes every cached response, keeping the hit and miss counts.
+    pub fn clear(&self) {
+        self.store.lock().unwrap().entries.clear();
+    }
+
+    /// Embeds the prompt of a request, returning the key of its context with the
+    /// embedding, or `None` if it cannot be cached.
+    async fn embed_prompt(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Option<(String, Vec<f32>)> {
+        let (prompt, context) = messages.split_last()?;
+        if prompt.role != ChatRole::User
+            || !matches!(prompt.message_type, MessageType::Text)
+            || prompt.content.trim().is_empty()
+        {
+            return None;
+        }
+        let context = request_key(context, tools, options).ok()?;
+
+        let embedder = self.embedder.as_ref().unwrap_or(&self.inner);
+        match embedder.embed(vec![prompt.content.clone()]).await {
+            Ok(embeddings) => Some((context, embeddings.into_iter().next()?)),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to embed prompt, skipping semantic cache");
+                None
+            }
+        }
+    }
+
+    /// Returns the cached response for the most similar prompt above the threshold.
+    fn lookup(&self, context: &str, embedding: &[f32]) -> Option<ResponseSnapshot> {
+        let mut store = self.store.lock().unwrap();
+        let now = Instant::now();
+        store.entries.retain(|entry| now < entry.expires_at);
+
+        let response = store
+            .entries
+            .iter()
+            .filter(|entry| entry.context == context)
+            .map(|entry| (cosine_similarity(&entry.embedding, embedding), entry))
+            .filter(|(similarity, _)| *similarity >= self.threshold)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, entry)| entry.response.clone());
+        match response {
+            Some(_) => store.hits += 1,
+            None => store.misses += 1,
+        }
+        response
+    }
+
+    /// Caches a response, evicting the oldest entries beyond the capacity.
+    fn insert(&self, context: String, embedding: Vec<f32>, response: ResponseSnapshot) {
+        let mut store = self.store.lock().unwrap();
+        while store.entries.len() >= self.capacity {
+            store.entries.pop_front();
+        }
+        store.entries.push_back(SemanticEntry {
+            context,
+            embedding,
+            response,
+            expires_at: Instant::now() + self.ttl,
+        });
+    }
+
+    /// Answers a chat request from the cache, or sends it and caches the response.
+    async fn cached_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let Some((context, embedding)) = self.embed_prompt(messages, tools, options).await else {
+            self.store.lock().unwrap().misses += 1;
+            return self.inner.chat_with_options(messages, tools, options).await;
+        };
+        if let Some(response) = self.lookup(&context, &embedding) {
+            return Ok(Box::new(response));
+        }
+
+        let response = self
+            .inner
+            .chat_with_options(messages, tools, options)
+            .await?;
+        self.insert(
+            context,
+            embedding,
+            ResponseSnapshot::from_response(response.as_ref()),
+        );
+        Ok(response)
+    }
+}
+
+/// Returns the cosine similarity of two vectors, or 0 if either is null.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for SemanticCachedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for SemanticCachedLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.cached_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.cached_chat(messages, tools, options).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner.chat_stream_with_tools(messages, tools).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for SemanticCachedLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for SemanticCachedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
@@ -1,6 +1,7 @@
 mod multi;
 
 use crate::{error::LLMError, LLMProvider};
+use futures::StreamExt;
 use std::collections::HashMap;
 
 pub use multi::{
@@ -124,30 +125,85 @@ impl<'a> PromptChain<'a> {
     }
 
     /// Executes all steps in the chain and returns the results
-    pub async fn run(mut self) -> Result<HashMap<String, String>, LLMError> {
-        for step in &self.steps {
+    pub async fn run(self) -> Result<HashMap<String, String>, LLMError> {
+        self.execute(None).await
+    }
+
+    /// Executes all steps in the chain, streaming the output of the last step to `on_chunk`
+    ///
+    /// Earlier steps still run buffered since their full output is needed to fill later
+    /// templates. The returned results contain the complete text of every step.
+    pub async fn run_stream_with<F>(
+        self,
+        mut on_chunk: F,
+    ) -> Result<HashMap<String, String>, LLMError>
+    where
+        F: FnMut(&str) + Send,
+    {
+        self.execute(Some(&mut on_chunk)).await
+    }
+
+    async fn execute(
+        mut self,
+        mut on_chunk: Option<&mut (dyn FnMut(&str) + Send)>,
+    ) -> Result<HashMap<String, String>, LLMError> {
+        let last_index = self.steps.len().saturating_sub(1);
+        for (index, step) in self.steps.iter().enumerate() {
             let prompt = self.apply_template(&step.template);
+            let stream_to = if index == last_index {
+                on_chunk.take()
+            } else {
+                None
+            };
 
-            let response_text = match step.mode {
-                ChainStepMode::Chat => {
+            let response_text = match (&step.mode, stream_to) {
+                (ChainStepMode::Chat, None) => {
+                    let messages = vec![crate::chat::ChatMessage {
+                        role: crate::chat::ChatRole::User,
+                        message_type: crate::chat::MessageType::Text,
+                        content: prompt,
+                        metadata: Default::default(),
+                    }];
+                    self.llm.chat(&messages).await?.text().unwrap_or_default()
+                }
+                (ChainStepMode::Chat, Some(on_chunk)) => {
                     let messages = vec![crate::chat::ChatMessage {
                         role: crate::chat::ChatRole::User,
                         message_type: crate::chat::MessageType::Text,
                         content: prompt,
+                        metadata: Default::default(),
                     }];
-                    self.llm.chat(&messages).await?
+                    self.llm
+                        .chat_stream_with(&messages, &mut |chunk| {
+                            if let crate::chat::StreamChunk::Text(text) = chunk {
+                                on_chunk(text);
+                            }
+                        })
+                        .await?
+                        .text()
+                        .unwrap_or_default()
                 }
-                ChainStepMode::Completion => {
+                (ChainStepMode::Completion, stream_to) => {
                     let mut req = crate::completion::CompletionRequest::new(prompt);
                     req.max_tokens = step.max_tokens;
                     req.temperature = step.temperature;
-                    let resp = self.llm.complete(&req).await?;
-                    Box::new(resp)
+                    match stream_to {
+                        None => self.llm.complete(&req).await?.text,
+                        Some(on_chunk) => {
+                            let mut stream = self.llm.complete_stream(&req).await?;
+                            let mut text = String::new();
+                            while let Some(fragment) = stream.next().await {
+                                let fragment = fragment?;
+                                on_chunk(&fragment);
+                                text.push_str(&fragment);
+                            }
+                            text
+                        }
+                    }
                 }
             };
 
-            self.memory
-                .insert(step.id.clone(), response_text.text().unwrap_or_default());
+            self.memory.insert(step.id.clone(), response_text);
         }
 
         Ok(self.memory)
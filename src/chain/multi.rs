@@ -3,8 +3,10 @@
 
 use std::collections::HashMap;
 
+use futures::StreamExt;
+
 use crate::{
-    chat::{ChatMessage, ChatRole, MessageType},
+    chat::{ChatMessage, ChatRole, MessageType, StreamChunk},
     completion::CompletionRequest,
     error::LLMError,
     LLMProvider,
@@ -210,8 +212,30 @@ impl<'a> MultiPromptChain<'a> {
     }
 
     /// Executes all steps
-    pub async fn run(mut self) -> Result<HashMap<String, String>, LLMError> {
-        for step in &self.steps {
+    pub async fn run(self) -> Result<HashMap<String, String>, LLMError> {
+        self.execute(None).await
+    }
+
+    /// Executes all steps, streaming the output of the last step to `on_chunk`
+    ///
+    /// Earlier steps still run buffered. The last step's `response_transform`, if any,
+    /// is applied to its full output once streaming ends; the streamed chunks are untransformed.
+    pub async fn run_stream_with<F>(
+        self,
+        mut on_chunk: F,
+    ) -> Result<HashMap<String, String>, LLMError>
+    where
+        F: FnMut(&str) + Send,
+    {
+        self.execute(Some(&mut on_chunk)).await
+    }
+
+    async fn execute(
+        mut self,
+        mut on_chunk: Option<&mut (dyn FnMut(&str) + Send)>,
+    ) -> Result<HashMap<String, String>, LLMError> {
+        let last_index = self.steps.len().saturating_sub(1);
+        for (index, step) in self.steps.iter().enumerate() {
             // 1) Replace {{xyz}} in template with existing memory
             let prompt_text = self.replace_template(&step.template);
 
@@ -223,22 +247,55 @@ impl<'a> MultiPromptChain<'a> {
                 ))
             })?;
 
-            // 3) Execute
-            let mut response = match step.mode {
-                MultiChainStepMode::Chat => {
+            // 3) Execute, streaming the last step if requested
+            let stream_to = if index == last_index {
+                on_chunk.take()
+            } else {
+                None
+            };
+            let mut response = match (&step.mode, stream_to) {
+                (MultiChainStepMode::Chat, None) => {
                     let messages = vec![ChatMessage {
                         role: ChatRole::User,
                         message_type: MessageType::Text,
                         content: prompt_text,
+                        metadata: Default::default(),
                     }];
                     llm.chat(&messages).await?.text().unwrap_or_default()
                 }
-                MultiChainStepMode::Completion => {
+                (MultiChainStepMode::Chat, Some(on_chunk)) => {
+                    let messages = vec![ChatMessage {
+                        role: ChatRole::User,
+                        message_type: MessageType::Text,
+                        content: prompt_text,
+                        metadata: Default::default(),
+                    }];
+                    llm.chat_stream_with(&messages, &mut |chunk| {
+                        if let StreamChunk::Text(text) = chunk {
+                            on_chunk(text);
+                        }
+                    })
+                    .await?
+                    .text()
+                    .unwrap_or_default()
+                }
+                (MultiChainStepMode::Completion, stream_to) => {
                     let mut req = CompletionRequest::new(prompt_text);
                     req.temperature = step.temperature;
                     req.max_tokens = step.max_tokens;
-                    let c = llm.complete(&req).await?;
-                    c.text.to_string()
+                    match stream_to {
+                        None => llm.complete(&req).await?.text,
+                        Some(on_chunk) => {
+                            let mut stream = llm.complete_stream(&req).await?;
+                            let mut text = String::new();
+                            while let Some(fragment) = stream.next().await {
+                                let fragment = fragment?;
+                                on_chunk(&fragment);
+                                text.push_str(&fragment);
+                            }
+                            text
+                        }
+                    }
                 }
             };
 
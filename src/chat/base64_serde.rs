@@ -0,0 +1,57 @@
+//! Serde helpers encoding binary message payloads as base64 strings.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes a byte buffer as a base64 string.
+pub(crate) mod bytes {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        BASE64.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes a `(mime, bytes)` pair as `{"mime": ..., "data": "<base64>"}`.
+pub(crate) mod mime_bytes {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct MimeBytesRef<'a, M> {
+        mime: &'a M,
+        #[serde(with = "super::bytes")]
+        data: &'a [u8],
+    }
+
+    #[derive(Deserialize)]
+    struct MimeBytes<M> {
+        mime: M,
+        #[serde(with = "super::bytes")]
+        data: Vec<u8>,
+    }
+
+    pub(crate) fn serialize<S: Serializer, M: Serialize>(
+        value: &(M, Vec<u8>),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        MimeBytesRef {
+            mime: &value.0,
+            data: &value.1,
+        }
+        .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, M: DeserializeOwned>(
+        deserializer: D,
+    ) -> Result<(M, Vec<u8>), D::Error> {
+        let value = MimeBytes::<M>::deserialize(deserializer)?;
+        Ok((value.mime, value.data))
+    }
+}
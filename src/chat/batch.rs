@@ -0,0 +1,112 @@
+//! Concurrent sending of independent chat requests, and the requests of the providers'
+//! batch APIs, which process them offline at a discount.
+
+use futures::StreamExt;
+
+use crate::error::LLMError;
+
+use super::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, Tool};
+
+/// One chat request of a batch submitted to a provider's batch API.
+#[derive(Debug, Clone)]
+pub struct BatchRequest {
+    /// ID matching the reply to the request, unique within the batch
+    pub custom_id: String,
+    /// The conversation to reply to
+    pub messages: Vec<ChatMessage>,
+    /// Function tools the model may call
+    pub tools: Option<Vec<Tool>>,
+    /// Settings overriding the client's for this request
+    pub options: ChatOptions,
+}
+
+impl BatchRequest {
+    /// Creates a request replying to a conversation.
+    pub fn new(custom_id: impl Into<String>, messages: Vec<ChatMessage>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            messages,
+            tools: None,
+            options: ChatOptions::default(),
+        }
+    }
+
+    /// Sets the function tools the model may call.
+    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Sets the settings overriding the client's for this request.
+    pub fn options(mut self, options: ChatOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// Reply to one of the requests of a batch.
+#[derive(Debug)]
+pub struct BatchResult {
+    /// ID of the request the reply is to
+    pub custom_id: String,
+    /// The model's response, or why the request failed
+    pub response: Result<Box<dyn ChatResponse>, LLMError>,
+}
+
+/// Sends independent chat requests concurrently and returns their results in order.
+///
+/// At most `concurrency` requests are in flight at once. A failed request yields its error
+/// at its position without affecting the others.
+///
+/// # Arguments
+///
+/// * `provider` - The provider answering every request
+/// * `batches` - One conversation per request
+/// * `concurrency` - Maximum number of requests in flight; 0 is treated as 1
+///
+/// # Returns
+///
+/// One result per conversation, in the order of `batches`
+///
+/// # Example
+///
+/// ```no_run
+/// use llm::builder::{LLMBackend, LLMBuilder};
+/// use llm::chat::{chat_batch, ChatMessage};
+///
+/// # async fn run() -> Result<(), llm::error::LLMError> {
+/// let llm = LLMBuilder::new()
+///     .backend(LLMBackend::OpenAI)
+///     .api_key("sk-...")
+///     .build()?;
+///
+/// let reviews = ["Great product!", "Broke after a day.", "It's fine."];
+/// let batches: Vec<Vec<ChatMessage>> = reviews
+///     .iter()
+///     .map(|review| {
+///         vec![ChatMessage::user()
+///             .content(format!("Label the sentiment of this review: {}", review))
+///             .build()]
+///     })
+///     .collect();
+///
+/// for (review, result) in reviews.iter().zip(chat_batch(llm.as_ref(), &batches, 4).await) {
+///     match result {
+///         Ok(label) => println!("{}: {}", review, label),
+///         Err(e) => eprintln!("{}: failed: {}", review, e),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn chat_batch<P: ChatProvider + ?Sized>(
+    provider: &P,
+    batches: &[Vec<ChatMessage>],
+    concurrency: usize,
+) -> Vec<Result<Box<dyn ChatResponse>, LLMError>> {
+    futures::stream::iter(batches)
+        .map(|messages| provider.chat(messages))
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
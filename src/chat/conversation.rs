@@ -0,0 +1,217 @@
+//! Conversations owning their message history.
+
+use std::path::Path;
+
+use crate::{error::LLMError, LLMProvider};
+
+use super::{store, ChatMessage, ChatResponse, ConversationStore, TrimStrategy};
+
+/// A chat session that owns its message history.
+///
+/// Each call to [`Conversation::send`] appends the message, sends the whole history to the
+/// provider and appends the reply, including any tool calls it requests. Tool outputs are
+/// added with [`Conversation::push_tool_result`] before sending the next turn.
+///
+/// A [`TrimStrategy`] can shorten the stored history before each request, so long chats
+/// stay within the model's context window.
+///
+/// # Example
+///
+/// ```no_run
+/// use llm::builder::{LLMBackend, LLMBuilder};
+/// use llm::chat::{model_context_length, Conversation, TokenBudget};
+///
+/// # async fn run() -> Result<(), llm::error::LLMError> {
+/// let llm = LLMBuilder::new()
+///     .backend(LLMBackend::OpenAI)
+///     .api_key("sk-...")
+///     .build()?;
+///
+/// let mut conversation = Conversation::new(llm)
+///     .trim_strategy(TokenBudget::from_context_length())
+///     .context_length(model_context_length("gpt-4o-mini").unwrap_or(8_192));
+/// conversation.say("My name is Ada.").await?;
+/// let reply = conversation.say("What is my name?").await?;
+/// println!("{}", reply);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Conversation {
+    /// The provider answering the conversation
+    provider: Box<dyn LLMProvider>,
+    /// Messages exchanged so far, oldest first
+    messages: Vec<ChatMessage>,
+    /// Strategy shortening the history before each request
+    trim: Option<Box<dyn TrimStrategy>>,
+    /// Context window of the model, in tokens
+    context_length: Option<usize>,
+}
+
+impl Conversation {
+    /// Starts an empty conversation with a provider.
+    pub fn new(provider: Box<dyn LLMProvider>) -> Self {
+        Self::with_history(provider, Vec::new())
+    }
+
+    /// Resumes a conversation from an existing message history.
+    pub fn with_history(provider: Box<dyn LLMProvider>, messages: Vec<ChatMessage>) -> Self {
+        Self {
+            provider,
+            messages,
+            trim: None,
+            context_length: None,
+        }
+    }
+
+    /// Sets the strategy shortening the history before each request.
+    ///
+    /// Trimmed messages are removed from the stored history.
+    pub fn trim_strategy(mut self, strategy: impl TrimStrategy + 'static) -> Self {
+        self.trim = Some(Box::new(strategy));
+        self
+    }
+
+    /// Sets the model's context window in tokens, used by trim strategies without an
+    /// explicit budget. See [`crate::chat::model_context_length`].
+    pub fn context_length(mut self, context_length: usize) -> Self {
+        self.context_length = Some(context_length);
+        self
+    }
+
+    /// Resumes a conversation saved with [`Conversation::save_json`].
+    pub fn load_json(
+        provider: Box<dyn LLMProvider>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, LLMError> {
+        Ok(Self::with_history(
+            provider,
+            store::load_json(path.as_ref())?,
+        ))
+    }
+
+    /// Resumes a conversation from a store, starting empty if the session is unknown.
+    pub fn load(
+        provider: Box<dyn LLMProvider>,
+        store: &dyn ConversationStore,
+        id: &str,
+    ) -> Result<Self, LLMError> {
+        Ok(Self::with_history(
+            provider,
+            store.load(id)?.unwrap_or_default(),
+        ))
+    }
+
+    /// Saves the history to a JSON file.
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), LLMError> {
+        store::save_json(path.as_ref(), &self.messages)
+    }
+
+    /// Saves the history to a store under a session ID.
+    pub fn save(&self, store: &dyn ConversationStore, id: &str) -> Result<(), LLMError> {
+        store.save(id, &self.messages)
+    }
+
+    /// Returns the provider answering the conversation.
+    pub fn provider(&self) -> &dyn LLMProvider {
+        self.provider.as_ref()
+    }
+
+    /// Replaces the provider, keeping the history.
+    pub fn set_provider(&mut self, provider: Box<dyn LLMProvider>) {
+        self.provider = provider;
+    }
+
+    /// Returns the messages exchanged so far, oldest first.
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Consumes the conversation and returns its history.
+    pub fn into_messages(self) -> Vec<ChatMessage> {
+        self.messages
+    }
+
+    /// Appends a message without sending it.
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+    }
+
+    /// Appends the output of a tool call requested by the last reply.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call_id` - ID of the tool call being answered
+    /// * `name` - Name of the called function
+    /// * `content` - Output of the tool
+    pub fn push_tool_result(
+        &mut self,
+        tool_call_id: impl Into<String>,
+        name: impl Into<String>,
+        content: impl Into<String>,
+    ) {
+        self.push(
+            ChatMessage::tool()
+                .tool_result(tool_call_id, name, content)
+                .build(),
+        );
+    }
+
+    /// Removes every message from the history.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// Appends a message, sends the history to the provider and records the reply.
+    ///
+    /// If the request fails, the message stays in the history so it can be retried with
+    /// [`Conversation::resend`].
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The next message of the conversation
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Box<dyn ChatResponse>)` - The provider's reply
+    /// * `Err(LLMError)` - If the request fails
+    pub async fn send(&mut self, message: ChatMessage) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.push(message);
+        self.resend().await
+    }
+
+    /// Sends a user text message. See [`Conversation::send`].
+    pub async fn say(
+        &mut self,
+        text: impl Into<String>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.send(ChatMessage::user().content(text).build()).await
+    }
+
+    /// Sends the current history without appending a message, e.g. after adding tool
+    /// results, and records the reply.
+    pub async fn resend(&mut self) -> Result<Box<dyn ChatResponse>, LLMError> {
+        if let Some(trim) = &self.trim {
+            // Trim a copy, so a failing strategy leaves the history untouched.
+            self.messages = trim
+                .trim(
+                    self.messages.clone(),
+                    self.provider.as_ref(),
+                    self.context_length,
+                )
+                .await?;
+        }
+
+        let response = self.provider.chat(&self.messages).await?;
+
+        let text = response.text().unwrap_or_default();
+        let reply = match response.tool_calls() {
+            Some(calls) if !calls.is_empty() => {
+                ChatMessage::assistant().content(text).tool_use(calls)
+            }
+            _ => ChatMessage::assistant().content(text),
+        };
+        self.push(reply.build());
+
+        Ok(response)
+    }
+}
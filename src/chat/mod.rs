@@ -1,23 +1,53 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::pin::Pin;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+pub use tokio_util::sync::CancellationToken;
 
-use crate::{error::LLMError, ToolCall};
+use crate::{error::LLMError, pricing::CostEstimate, FunctionCall, ToolCall};
+
+mod base64_serde;
+mod batch;
+mod conversation;
+mod snapshot;
+mod store;
+mod structured;
+mod timing;
+mod trim;
+
+pub use batch::{chat_batch, BatchRequest, BatchResult};
+pub use conversation::Conversation;
+pub use snapshot::ResponseSnapshot;
+pub use store::{ConversationStore, JsonFileStore};
+pub use structured::StructuredChat;
+#[cfg(any(feature = "openai", feature = "xai"))]
+pub(crate) use timing::timed_stream;
+pub(crate) use timing::RequestTimer;
+pub use timing::ResponseTiming;
+pub use trim::{model_context_length, KeepLastN, SummarizeOlder, TokenBudget, TrimStrategy};
 
 /// Role of a participant in a chat conversation.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ChatRole {
+    /// Instructions guiding the assistant, which may appear mid-conversation
+    System,
     /// The user/human participant in the conversation
     User,
     /// The AI assistant participant in the conversation
     Assistant,
+    /// The output of a tool called by the assistant
+    Tool,
 }
 
 /// The supported MIME type of an image.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum ImageMime {
     /// JPEG image
@@ -39,23 +69,148 @@ impl ImageMime {
             ImageMime::WEBP => "image/webp",
         }
     }
+
+    /// Detects the image format from the file signature at the start of the bytes.
+    ///
+    /// ```
+    /// use llm::chat::ImageMime;
+    ///
+    /// let png = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', 0, 0];
+    /// assert_eq!(ImageMime::from_bytes(&png), Some(ImageMime::PNG));
+    /// assert_eq!(ImageMime::from_bytes(b"plain text"), None);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageMime::JPEG)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']) {
+            Some(ImageMime::PNG)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageMime::GIF)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(ImageMime::WEBP)
+        } else {
+            None
+        }
+    }
+}
+
+/// How closely a vision model looks at an image, trading accuracy for tokens.
+///
+/// Sent as the `detail` of images by OpenAI and the backends built on its API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageDetail {
+    /// A low-resolution view of the image, at a fixed small token cost
+    Low,
+    /// A high-resolution view of the image, costing tokens with its size
+    High,
+    /// Lets the provider choose from the size of the image
+    Auto,
+}
+
+/// The supported MIME type of an audio clip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum AudioMime {
+    /// WAV audio
+    WAV,
+    /// MP3 audio
+    MP3,
+    /// AAC audio
+    AAC,
+    /// FLAC audio
+    FLAC,
+    /// Ogg Vorbis audio
+    OGG,
+}
+
+impl AudioMime {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            AudioMime::WAV => "audio/wav",
+            AudioMime::MP3 => "audio/mp3",
+            AudioMime::AAC => "audio/aac",
+            AudioMime::FLAC => "audio/flac",
+            AudioMime::OGG => "audio/ogg",
+        }
+    }
+
+    /// Returns the short format name used by OpenAI-style APIs (e.g. "wav").
+    pub fn format(&self) -> &'static str {
+        match self {
+            AudioMime::WAV => "wav",
+            AudioMime::MP3 => "mp3",
+            AudioMime::AAC => "aac",
+            AudioMime::FLAC => "flac",
+            AudioMime::OGG => "ogg",
+        }
+    }
+}
+
+/// A single part of a multi-part message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentPart {
+    /// A text part
+    Text(String),
+    /// An image part
+    Image(#[serde(with = "base64_serde::mime_bytes")] (ImageMime, Vec<u8>)),
+    /// An image URL part
+    #[serde(rename = "image_url")]
+    ImageURL(String),
+    /// A PDF part
+    Pdf(#[serde(with = "base64_serde::bytes")] Vec<u8>),
+    /// An audio part
+    Audio(#[serde(with = "base64_serde::mime_bytes")] (AudioMime, Vec<u8>)),
+    /// A file uploaded to the provider beforehand, referenced by its ID (OpenAI) or its URI
+    /// (Google)
+    File(String),
 }
 
 /// The type of a message in a chat conversation.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+///
+/// Binary payloads are serialized as base64 strings.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MessageType {
     /// A text message
     #[default]
     Text,
     /// An image message
-    Image((ImageMime, Vec<u8>)),
+    Image(#[serde(with = "base64_serde::mime_bytes")] (ImageMime, Vec<u8>)),
     /// PDF message
-    Pdf(Vec<u8>),
+    Pdf(#[serde(with = "base64_serde::bytes")] Vec<u8>),
     /// An image URL message
+    #[serde(rename = "image_url")]
     ImageURL(String),
+    /// An audio message
+    Audio(#[serde(with = "base64_serde::mime_bytes")] (AudioMime, Vec<u8>)),
+    /// A message made of several parts, such as text and multiple images
+    Parts(Vec<ContentPart>),
+    /// An assistant message requesting tool calls
+    ToolUse(Vec<ToolCall>),
+    /// The output of a tool call, sent back to the model
+    ToolResult {
+        /// The ID of the tool call this result answers
+        tool_call_id: String,
+        /// The name of the function that was called
+        name: String,
+        /// The output of the tool
+        content: String,
+        /// The output as several parts, such as a screenshot and its caption, when it is
+        /// not only text. Backends other than Anthropic send its text parts only.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        parts: Vec<ContentPart>,
+        /// Whether the tool failed, the output then describing the error
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
 }
 
 /// The type of reasoning effort for a message in a chat conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ReasoningEffort {
     /// Low reasoning effort
     Low,
@@ -66,23 +221,84 @@ pub enum ReasoningEffort {
 }
 
 /// A single message in a chat conversation.
-#[derive(Debug, Clone)]
+///
+/// Messages implement `Serialize` and `Deserialize`, so conversations can be stored or sent
+/// over the wire:
+///
+/// ```
+/// use llm::chat::{AudioMime, ChatMessage, ContentPart, ImageDetail, ImageMime};
+///
+/// let history = vec![
+///     ChatMessage::system().content("Answer briefly.").build(),
+///     ChatMessage::user()
+///         .name("ada")
+///         .timestamp_now()
+///         .tag("channel", "support")
+///         .image_detail(ImageDetail::Low)
+///         .parts(vec![
+///             ContentPart::Text("What is in this image?".to_string()),
+///             ContentPart::Image((ImageMime::PNG, vec![137, 80, 78, 71])),
+///         ])
+///         .build(),
+///     ChatMessage::user().audio(AudioMime::WAV, vec![82, 73, 70, 70]).build(),
+///     ChatMessage::tool().tool_result("call_1", "describe", "A cat.").build(),
+///     ChatMessage::assistant().content("A cat.").build(),
+/// ];
+///
+/// let json = serde_json::to_string(&history).unwrap();
+/// let restored: Vec<ChatMessage> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, history);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ChatMessage {
     /// The role of who sent this message (user or assistant)
     pub role: ChatRole,
     /// The type of the message (text, image, audio, video, etc)
+    #[serde(default)]
     pub message_type: MessageType,
     /// The text content of the message
     pub content: String,
+    /// Optional information about the message, ignored by providers that do not support it
+    #[serde(default, skip_serializing_if = "MessageMetadata::is_empty")]
+    pub metadata: MessageMetadata,
+}
+
+/// Optional information attached to a chat message.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MessageMetadata {
+    /// Name of the participant, sent as the `name` field by OpenAI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// When the message was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<SystemTime>,
+    /// Arbitrary key/value tags, never sent to providers
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    /// Detail level the images of the message are read at, overriding
+    /// [`ChatOptions::image_detail`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_detail: Option<ImageDetail>,
+}
+
+impl MessageMetadata {
+    /// Returns true if no metadata is set.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.timestamp.is_none()
+            && self.tags.is_empty()
+            && self.image_detail.is_none()
+    }
 }
 
 /// Represents a parameter in a function tool
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParameterProperty {
     /// The type of the parameter (e.g. "string", "number", "array", etc)
-    #[serde(rename = "type")]
+    #[serde(rename = "type", deserialize_with = "property_type")]
     pub property_type: String,
     /// Description of what the parameter does
+    #[serde(default)]
     pub description: String,
     /// When type is "array", this defines the type of the array items
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -93,36 +309,289 @@ pub struct ParameterProperty {
 }
 
 /// Represents the parameters schema for a function tool
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParametersSchema {
     /// The type of the parameters object (usually "object")
     #[serde(rename = "type")]
     pub schema_type: String,
     /// Map of parameter names to their properties
+    #[serde(default)]
     pub properties: HashMap<String, ParameterProperty>,
     /// List of required parameter names
+    #[serde(default)]
     pub required: Vec<String>,
 }
 
+impl Default for ParametersSchema {
+    fn default() -> Self {
+        Self {
+            schema_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: Vec::new(),
+        }
+    }
+}
+
 /// Represents a function definition for a tool
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionTool {
     /// The name of the function
     pub name: String,
     /// Description of what the function does
+    #[serde(default)]
     pub description: String,
     /// The parameters schema for the function
+    #[serde(default)]
     pub parameters: ParametersSchema,
 }
 
 /// Represents a tool that can be used in chat
-#[derive(Debug, Clone, Serialize)]
+///
+/// Tools use OpenAI's tool format and can be loaded from JSON or YAML configuration:
+///
+/// ```
+/// use llm::chat::Tool;
+/// use serde_json::json;
+///
+/// let tool = Tool::from_value(json!({
+///     "type": "function",
+///     "function": {
+///         "name": "get_weather",
+///         "description": "Get the current weather in a city",
+///         "parameters": {
+///             "type": "object",
+///             "properties": {
+///                 "city": { "type": "string", "description": "Name of the city" }
+///             },
+///             "required": ["city"]
+///         }
+///     }
+/// }))
+/// .unwrap();
+/// assert_eq!(tool.function.name, "get_weather");
+/// ```
+///
+/// Providers also define tools of their own, whose schema the model already knows, and
+/// only the provider defining a tool accepts it. The calls of Anthropic's
+/// [`Tool::computer`], [`Tool::text_editor`] and [`Tool::bash`] are executed by the
+/// application like those of function tools, while Google runs [`Tool::google_search`]
+/// itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
-    /// The type of tool (e.g. "function")
-    #[serde(rename = "type")]
+    /// The type of tool (e.g. "function", or a versioned type such as
+    /// "computer_20250124" for the tools defined by the provider)
+    #[serde(rename = "type", default = "default_tool_type")]
     pub tool_type: String,
-    /// The function definition if this is a function tool
+    /// The function definition if this is a function tool, or the name of a tool defined by
+    /// the provider
     pub function: FunctionTool,
+    /// Settings of a tool defined by the provider, such as the display size of the
+    /// computer use tool
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub settings: Map<String, Value>,
+}
+
+impl FunctionTool {
+    /// Builds a function definition from a JSON schema describing its parameters.
+    ///
+    /// The schema must describe an object whose properties each have a `type`, which is
+    /// the shape produced by most schema generators for plain argument structs. A list of
+    /// types, as generated for optional fields, is reduced to its first type other than
+    /// "null".
+    ///
+    /// The crate has no schemars integration: to build the schema from a Rust type, derive
+    /// `schemars::JsonSchema` on it and pass `serde_json::to_value(schema_for!(T))`.
+    pub fn from_json_schema(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        schema: Value,
+    ) -> Result<Self, LLMError> {
+        let parameters =
+            serde_json::from_value(schema).map_err(|e| LLMError::JsonError(e.to_string()))?;
+        Ok(Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
+        })
+    }
+}
+
+/// Deserializes the type of a parameter, which schema generators write as a list such as
+/// `["string", "null"]` for optional parameters. The first type other than "null" is kept;
+/// whether the parameter may be left out is told by the required list.
+fn property_type<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PropertyType {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    match PropertyType::deserialize(deserializer)? {
+        PropertyType::One(property_type) => Ok(property_type),
+        PropertyType::Many(types) => types
+            .iter()
+            .find(|property_type| *property_type != "null")
+            .or_else(|| types.first())
+            .cloned()
+            .ok_or_else(|| serde::de::Error::custom("empty list of parameter types")),
+    }
+}
+
+fn default_tool_type() -> String {
+    "function".to_string()
+}
+
+/// A tool file holds either a single tool or a list of tools.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ToolFile {
+    Many(Vec<Tool>),
+    One(Tool),
+}
+
+impl From<ToolFile> for Vec<Tool> {
+    fn from(file: ToolFile) -> Self {
+        match file {
+            ToolFile::Many(tools) => tools,
+            ToolFile::One(tool) => vec![tool],
+        }
+    }
+}
+
+impl Tool {
+    /// Anthropic's computer use tool, letting the model control a desktop through
+    /// screenshots, mouse and keyboard actions.
+    ///
+    /// The calls hold an `action` (such as "screenshot", "left_click" or "type") and its
+    /// arguments; the results of the screenshots are sent back with
+    /// [`ChatMessageBuilder::tool_result_parts`].
+    ///
+    /// # Arguments
+    ///
+    /// * `display_width_px` - Width of the screenshots, in pixels
+    /// * `display_height_px` - Height of the screenshots, in pixels
+    /// * `display_number` - X11 display number, for environments with several displays
+    pub fn computer(
+        display_width_px: u32,
+        display_height_px: u32,
+        display_number: Option<u32>,
+    ) -> Self {
+        let mut tool = Self::provider_defined("computer_20250124", "computer");
+        tool.settings
+            .insert("display_width_px".to_string(), display_width_px.into());
+        tool.settings
+            .insert("display_height_px".to_string(), display_height_px.into());
+        if let Some(display_number) = display_number {
+            tool.settings
+                .insert("display_number".to_string(), display_number.into());
+        }
+        tool
+    }
+
+    /// Anthropic's text editor tool, letting the model view, create and edit files.
+    ///
+    /// The calls hold a `command` ("view", "create", "str_replace" or "insert") and a
+    /// `path`.
+    pub fn text_editor() -> Self {
+        Self::provider_defined("text_editor_20250728", "str_replace_based_edit_tool")
+    }
+
+    /// Anthropic's bash tool, letting the model run shell commands in a persistent session.
+    ///
+    /// The calls hold the `command` to run, or `restart` to start a new session.
+    pub fn bash() -> Self {
+        Self::provider_defined("bash_20250124", "bash")
+    }
+
+    /// Google's search tool, letting Gemini ground its reply in Google Search results,
+    /// described by [`ChatResponse::grounding`].
+    pub fn google_search() -> Self {
+        Self::provider_defined("google_search", "google_search")
+    }
+
+    /// Returns whether the tool is a function tool, rather than a tool defined by the
+    /// provider.
+    pub fn is_function(&self) -> bool {
+        self.tool_type == "function"
+    }
+
+    /// A tool defined by the provider, of a versioned type and with a fixed name.
+    fn provider_defined(tool_type: &str, name: &str) -> Self {
+        Self {
+            tool_type: tool_type.to_string(),
+            function: FunctionTool {
+                name: name.to_string(),
+                description: String::new(),
+                parameters: ParametersSchema::default(),
+            },
+            settings: Map::new(),
+        }
+    }
+
+    /// Builds a tool from a JSON value in OpenAI's tool format.
+    pub fn from_value(value: Value) -> Result<Self, LLMError> {
+        serde_json::from_value(value).map_err(|e| LLMError::JsonError(e.to_string()))
+    }
+
+    /// Loads tools from a JSON file holding a single tool or an array of tools.
+    pub fn from_json_file(path: impl AsRef<std::path::Path>) -> Result<Vec<Self>, LLMError> {
+        let content = read_tool_file(path.as_ref())?;
+        let file: ToolFile =
+            serde_json::from_str(&content).map_err(|e| LLMError::JsonError(e.to_string()))?;
+        Ok(file.into())
+    }
+
+    /// Loads tools from a YAML file holding a single tool or a list of tools.
+    pub fn from_yaml_file(path: impl AsRef<std::path::Path>) -> Result<Vec<Self>, LLMError> {
+        let content = read_tool_file(path.as_ref())?;
+        let file: ToolFile = serde_yaml::from_str(&content).map_err(|e| {
+            LLMError::InvalidRequest(format!("Invalid YAML tool definition: {}", e))
+        })?;
+        Ok(file.into())
+    }
+}
+
+fn read_tool_file(path: &std::path::Path) -> Result<String, LLMError> {
+    std::fs::read_to_string(path).map_err(|e| {
+        LLMError::InvalidRequest(format!(
+            "Failed to read tool file {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Controls whether and how the model calls the provided tools.
+///
+/// Serializes to OpenAI's `tool_choice` format; backends with other formats convert it
+/// when building their requests. It is only sent when tools are included in the request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ToolChoice {
+    /// The model decides whether to call tools
+    #[default]
+    Auto,
+    /// The model must not call any tool
+    None,
+    /// The model must call at least one tool
+    Required,
+    /// The model must call the function with this name
+    Function(String),
+}
+
+impl Serialize for ToolChoice {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ToolChoice::Auto => serializer.serialize_str("auto"),
+            ToolChoice::None => serializer.serialize_str("none"),
+            ToolChoice::Required => serializer.serialize_str("required"),
+            ToolChoice::Function(name) => serde_json::json!({
+                "type": "function",
+                "function": { "name": name },
+            })
+            .serialize(serializer),
+        }
+    }
 }
 
 /// Defines rules for structured output responses based on [OpenAI's structured output requirements](https://platform.openai.com/docs/api-reference/chat/create#chat-create-response_format).
@@ -173,12 +642,769 @@ pub struct StructuredOutputFormat {
     pub strict: Option<bool>,
 }
 
+/// Token usage reported by a provider for a single request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Number of tokens in the prompt
+    pub prompt_tokens: u32,
+    /// Number of tokens generated by the model
+    pub completion_tokens: u32,
+    /// Total number of tokens used by the request
+    pub total_tokens: u32,
+    /// Number of prompt tokens read from the provider's prompt cache, which are usually
+    /// billed at a discount, if the provider reports them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_prompt_tokens: Option<u32>,
+}
+
+/// A single event emitted by a streaming chat request.
+///
+/// Streaming backends normalize their provider-specific events into this type, so
+/// consumers can handle every provider's stream the same way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamChunk {
+    /// A fragment of the assistant's text output
+    Text(String),
+    /// A fragment of the model's reasoning output
+    Thinking(String),
+    /// A fragment of a tool call.
+    ///
+    /// Providers usually send the `id` and `name` only with the first fragment of a
+    /// call; later fragments for the same `index` only carry more `arguments` text.
+    ToolCallDelta {
+        /// Position of the tool call within the response
+        index: usize,
+        /// The ID of the tool call, if sent with this fragment
+        id: Option<String>,
+        /// The name of the function, if sent with this fragment
+        name: Option<String>,
+        /// A fragment of the JSON-encoded arguments
+        arguments: String,
+    },
+    /// The last chunk of a stream, carrying why generation stopped and token usage if the provider reports them
+    Done {
+        /// The provider's reason for ending generation
+        finish_reason: Option<FinishReason>,
+        /// Token usage for the whole request
+        usage: Option<Usage>,
+    },
+}
+
+/// Why a model stopped generating, normalized across providers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// The model finished its answer or hit a stop sequence
+    Stop,
+    /// The output was truncated by the token limit
+    Length,
+    /// The model stopped to call tools
+    ToolCalls,
+    /// The output was blocked or cut by a safety filter
+    ContentFilter,
+    /// A provider-specific reason, kept verbatim
+    Other(String),
+}
+
+impl FinishReason {
+    /// Maps a provider's finish reason (e.g. OpenAI's "length", Anthropic's "max_tokens" or
+    /// Gemini's "MAX_TOKENS") to its normalized value.
+    ///
+    /// ```
+    /// use llm::chat::FinishReason;
+    ///
+    /// assert_eq!(FinishReason::from_provider("max_tokens"), FinishReason::Length);
+    /// assert_eq!(FinishReason::from_provider("tool_use"), FinishReason::ToolCalls);
+    /// assert_eq!(FinishReason::from_provider("SAFETY"), FinishReason::ContentFilter);
+    /// ```
+    pub fn from_provider(reason: &str) -> Self {
+        match reason.to_ascii_lowercase().as_str() {
+            "stop" | "end_turn" | "stop_sequence" | "eos" | "complete" => FinishReason::Stop,
+            "length" | "max_tokens" | "model_length" => FinishReason::Length,
+            "tool_calls" | "tool_use" | "function_call" => FinishReason::ToolCalls,
+            "content_filter" | "safety" | "recitation" | "blocklist" | "prohibited_content"
+            | "spii" | "refusal" | "error_toxic" => FinishReason::ContentFilter,
+            _ => FinishReason::Other(reason.to_string()),
+        }
+    }
+
+    /// Returns the reason in OpenAI's format (e.g. "length").
+    pub fn as_str(&self) -> &str {
+        match self {
+            FinishReason::Stop => "stop",
+            FinishReason::Length => "length",
+            FinishReason::ToolCalls => "tool_calls",
+            FinishReason::ContentFilter => "content_filter",
+            FinishReason::Other(reason) => reason,
+        }
+    }
+}
+
+impl fmt::Display for FinishReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A stream of chunks returned by a streaming chat request.
+pub type ChatStream = Pin<Box<dyn Stream<Item = Result<StreamChunk, LLMError>> + Send>>;
+
+/// A chat response assembled from the chunks of a streaming request.
+#[derive(Debug, Clone, Default)]
+pub struct StreamedResponse {
+    /// The concatenated text output
+    pub text: String,
+    /// The concatenated reasoning output
+    pub thinking: String,
+    /// Tool calls, ordered by their index in the stream
+    pub tool_calls: Vec<ToolCall>,
+    /// The provider's reason for ending generation
+    pub finish_reason: Option<FinishReason>,
+    /// Token usage for the whole request, if reported
+    pub usage: Option<Usage>,
+    /// How long the stream took, if it was timed
+    pub timing: Option<ResponseTiming>,
+}
+
+impl StreamedResponse {
+    /// Merges a chunk into the response.
+    pub fn push(&mut self, chunk: StreamChunk) {
+        match chunk {
+            StreamChunk::Text(text) => self.text.push_str(&text),
+            StreamChunk::Thinking(thinking) => self.thinking.push_str(&thinking),
+            StreamChunk::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => merge_tool_call_delta(&mut self.tool_calls, index, id, name, &arguments),
+            StreamChunk::Done {
+                finish_reason,
+                usage,
+            } => {
+                self.finish_reason = finish_reason;
+                self.usage = usage;
+            }
+        }
+    }
+}
+
+/// Merges a tool-call fragment into the call at `index`, creating missing entries.
+fn merge_tool_call_delta(
+    calls: &mut Vec<ToolCall>,
+    index: usize,
+    id: Option<String>,
+    name: Option<String>,
+    arguments: &str,
+) {
+    while calls.len() <= index {
+        calls.push(ToolCall {
+            id: String::new(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: String::new(),
+                arguments: String::new(),
+            },
+        });
+    }
+    let call = &mut calls[index];
+    if let Some(id) = id {
+        call.id = id;
+    }
+    if let Some(name) = name {
+        call.function.name = name;
+    }
+    call.function.arguments.push_str(arguments);
+}
+
+/// Collects streamed tool-call fragments into complete tool calls.
+///
+/// Tool call arguments arrive as JSON fragments spread over several
+/// [`StreamChunk::ToolCallDelta`] chunks. The accumulator merges them by index and
+/// hands out each call once its arguments form valid JSON, so it can be executed
+/// while the rest of the response is still streaming.
+///
+/// ```
+/// use llm::chat::{StreamChunk, ToolCallAccumulator};
+///
+/// let mut acc = ToolCallAccumulator::new();
+/// let first = StreamChunk::ToolCallDelta {
+///     index: 0,
+///     id: Some("call_1".into()),
+///     name: Some("get_weather".into()),
+///     arguments: "{\"city\":".into(),
+/// };
+/// assert!(acc.push(&first).is_none());
+///
+/// let second = StreamChunk::ToolCallDelta {
+///     index: 0,
+///     id: None,
+///     name: None,
+///     arguments: "\"Paris\"}".into(),
+/// };
+/// let call = acc.push(&second).unwrap();
+/// assert_eq!(call.function.name, "get_weather");
+/// assert_eq!(call.function.arguments, "{\"city\":\"Paris\"}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallAccumulator {
+    calls: Vec<ToolCall>,
+    yielded: Vec<bool>,
+}
+
+impl ToolCallAccumulator {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges a chunk and returns the tool call it completed, if any.
+    ///
+    /// Chunks other than [`StreamChunk::ToolCallDelta`] are ignored. Each call is
+    /// returned at most once, the first time its arguments parse as JSON.
+    pub fn push(&mut self, chunk: &StreamChunk) -> Option<ToolCall> {
+        let StreamChunk::ToolCallDelta {
+            index,
+            id,
+            name,
+            arguments,
+        } = chunk
+        else {
+            return None;
+        };
+
+        merge_tool_call_delta(&mut self.calls, *index, id.clone(), name.clone(), arguments);
+        self.yielded.resize(self.calls.len(), false);
+
+        let call = &self.calls[*index];
+        if self.yielded[*index] || serde_json::from_str::<Value>(&call.function.arguments).is_err()
+        {
+            return None;
+        }
+        self.yielded[*index] = true;
+        Some(call.clone())
+    }
+
+    /// Returns the tool calls merged so far, complete or not, ordered by index.
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        &self.calls
+    }
+
+    /// Consumes the accumulator and returns every tool call, ordered by index.
+    pub fn finish(self) -> Vec<ToolCall> {
+        self.calls
+    }
+}
+
+impl fmt::Display for StreamedResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for tool_call in &self.tool_calls {
+            write!(f, "{}", tool_call)?;
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+impl ChatResponse for StreamedResponse {
+    fn text(&self) -> Option<String> {
+        if self.text.is_empty() {
+            None
+        } else {
+            Some(self.text.clone())
+        }
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        if self.tool_calls.is_empty() {
+            None
+        } else {
+            Some(self.tool_calls.clone())
+        }
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.finish_reason.clone()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            timing: self.timing,
+            ..Default::default()
+        }
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.usage.clone()
+    }
+
+    fn thinking(&self) -> Option<String> {
+        if self.thinking.is_empty() {
+            None
+        } else {
+            Some(self.thinking.clone())
+        }
+    }
+}
+
+/// Per-request options for chat calls.
+///
+/// Sampling parameters and the timeout set here override the ones the provider was built
+/// with, for a single request. Parameters a backend does not support are ignored.
+///
+/// Dropping a request future aborts the underlying HTTP request, so cancelling a call
+/// through its token stops the generation without leaking the connection.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use llm::chat::{ChatOptions, ReasoningEffort};
+///
+/// let options = ChatOptions::new()
+///     .temperature(0.0)
+///     .max_tokens(256)
+///     .stop(["\n\n"])
+///     .seed(42)
+///     .reasoning_effort(ReasoningEffort::Low)
+///     .timeout(Duration::from_secs(300));
+/// assert_eq!(options.stop, Some(vec!["\n\n".to_string()]));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChatOptions {
+    /// Token that cancels the request when triggered
+    pub cancel_token: Option<CancellationToken>,
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Maximum tokens to generate
+    pub max_tokens: Option<u32>,
+    /// Top-p (nucleus) sampling parameter
+    pub top_p: Option<f32>,
+    /// Sequences that end the generation when produced
+    pub stop: Option<Vec<String>>,
+    /// Seed for deterministic sampling
+    pub seed: Option<u64>,
+    /// Reasoning effort of reasoning models
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Timeout of the request
+    pub timeout: Option<Duration>,
+    /// Model serving the request instead of the configured one
+    pub model: Option<String>,
+    /// Detail level of the images in messages that don't set their own
+    pub image_detail: Option<ImageDetail>,
+    /// Thresholds of the provider's content filter, replacing the configured ones
+    pub safety_settings: Option<Vec<SafetySetting>>,
+}
+
+impl ChatOptions {
+    /// Creates empty options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the token used to cancel the request.
+    pub fn cancel_token(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Overrides the sampling temperature.
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Overrides the maximum number of tokens to generate.
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Overrides the top-p (nucleus) sampling parameter.
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Sets sequences that end the generation when produced.
+    pub fn stop<I, S>(mut self, stop: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.stop = Some(stop.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the seed used for sampling, for reproducible replies where supported.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Overrides the reasoning effort of reasoning models.
+    pub fn reasoning_effort(mut self, effort: ReasoningEffort) -> Self {
+        self.reasoning_effort = Some(effort);
+        self
+    }
+
+    /// Overrides the request timeout, e.g. to give a long reasoning request more time.
+    ///
+    /// The built-in backends apply it to the whole HTTP exchange, including reading a
+    /// streamed reply.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the model serving the request, e.g. to switch the model LM Studio has
+    /// loaded.
+    ///
+    /// Supported by OpenAI and the backends built on its API; other backends use their
+    /// configured model.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Sets the detail level of the images in messages that don't set their own with
+    /// [`ChatMessageBuilder::image_detail`].
+    ///
+    /// Supported by OpenAI and the backends built on its API.
+    pub fn image_detail(mut self, detail: ImageDetail) -> Self {
+        self.image_detail = Some(detail);
+        self
+    }
+
+    /// Adds a threshold of the provider's content filter, replacing the configured
+    /// thresholds for this request. See [`crate::builder::LLMBuilder::safety_setting`].
+    ///
+    /// Supported by Google.
+    pub fn safety_setting(
+        mut self,
+        category: impl Into<String>,
+        threshold: impl Into<String>,
+    ) -> Self {
+        self.safety_settings
+            .get_or_insert_with(Vec::new)
+            .push(SafetySetting {
+                category: category.into(),
+                threshold: threshold.into(),
+            });
+        self
+    }
+}
+
+/// Runs a request future, aborting it with [`LLMError::Cancelled`] if the token in
+/// `options` is triggered first.
+pub(crate) async fn with_cancellation<T, F>(
+    options: &ChatOptions,
+    request: F,
+) -> Result<T, LLMError>
+where
+    F: std::future::Future<Output = Result<T, LLMError>>,
+{
+    match &options.cancel_token {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(LLMError::Cancelled),
+            result = request => result,
+        },
+        None => request.await,
+    }
+}
+
+/// Opens a stream with [`with_cancellation`], then makes it cancellable with
+/// [`cancellable_stream`] if `options` carry a token.
+pub(crate) async fn with_stream_cancellation<F>(
+    options: &ChatOptions,
+    request: F,
+) -> Result<ChatStream, LLMError>
+where
+    F: std::future::Future<Output = Result<ChatStream, LLMError>>,
+{
+    let stream = with_cancellation(options, request).await?;
+    Ok(match &options.cancel_token {
+        Some(token) => cancellable_stream(stream, token.clone()),
+        None => stream,
+    })
+}
+
+/// Wraps a chunk stream so it yields [`LLMError::Cancelled`] and ends once the token
+/// is triggered. The inner stream, and with it the HTTP response, is dropped on
+/// cancellation.
+pub(crate) fn cancellable_stream(stream: ChatStream, token: CancellationToken) -> ChatStream {
+    Box::pin(futures::stream::unfold(
+        Some((stream, token)),
+        |state| async move {
+            let (mut stream, token) = state?;
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => Some((Err(LLMError::Cancelled), None)),
+                chunk = stream.next() => chunk.map(|chunk| (chunk, Some((stream, token)))),
+            }
+        },
+    ))
+}
+
+/// Log probability of a generated token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    /// The token
+    pub token: String,
+    /// Natural log of the probability of the token
+    pub logprob: f32,
+    /// UTF-8 bytes of the token, useful when a character spans several tokens
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+    /// The most likely alternatives at this position, including the chosen token
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// A likely alternative token at a position of the reply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    /// The token
+    pub token: String,
+    /// Natural log of the probability of the token
+    pub logprob: f32,
+    /// UTF-8 bytes of the token
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+}
+
+/// A source the provider searched to ground a reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    /// URL of the source
+    pub url: String,
+    /// Title of the source, if the provider reports it
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Publication or last update date of the source, if the provider reports it
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// How a reply is grounded in the results of the searches the model ran.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Grounding {
+    /// Search queries the model ran
+    #[serde(default)]
+    pub queries: Vec<String>,
+    /// Sources found by the searches
+    #[serde(default)]
+    pub sources: Vec<Citation>,
+    /// Parts of the reply, with the sources supporting them
+    #[serde(default)]
+    pub supports: Vec<GroundingSupport>,
+    /// HTML and CSS of the search suggestions, which Google requires applications to
+    /// display along with grounded replies
+    #[serde(default)]
+    pub search_entry_point: Option<String>,
+}
+
+/// A part of a grounded reply, with the sources supporting it.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GroundingSupport {
+    /// The supported text
+    pub text: String,
+    /// Byte offset of the start of the text in the reply
+    pub start_index: usize,
+    /// Byte offset of the end of the text in the reply
+    pub end_index: usize,
+    /// Positions of the supporting sources in [`Grounding::sources`]
+    pub source_indices: Vec<usize>,
+    /// Confidence in each source, in the order of `source_indices`
+    #[serde(default)]
+    pub confidence_scores: Vec<f32>,
+}
+
+/// A threshold of the provider's content filter, above which it blocks the prompt or the
+/// reply for a harm category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafetySetting {
+    /// Harm category (e.g. "HARM_CATEGORY_HARASSMENT" or "HARM_CATEGORY_DANGEROUS_CONTENT")
+    pub category: String,
+    /// Probability of harm from which to block (e.g. "BLOCK_LOW_AND_ABOVE",
+    /// "BLOCK_ONLY_HIGH" or "BLOCK_NONE")
+    pub threshold: String,
+}
+
+/// The probability, rated by the provider's content filter, that a prompt or reply is
+/// harmful in a category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafetyRating {
+    /// Harm category (e.g. "HARM_CATEGORY_HARASSMENT")
+    pub category: String,
+    /// Probability of harm: "NEGLIGIBLE", "LOW", "MEDIUM" or "HIGH"
+    pub probability: String,
+    /// Whether the content was blocked because of this rating
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+/// Voice and format of the spoken replies requested from audio models.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioOutput {
+    /// Voice the reply is spoken with (e.g. "alloy")
+    pub voice: String,
+    /// Encoding of the audio (e.g. "wav", "mp3", "flac", "opus" or "pcm16")
+    pub format: String,
+}
+
+/// A spoken reply, returned by audio models asked to answer with audio.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioReply {
+    /// ID of the audio, to refer to it in later turns, if the provider reports it
+    #[serde(default)]
+    pub id: Option<String>,
+    /// The audio, in the format requested
+    #[serde(with = "base64_serde::bytes")]
+    pub data: Vec<u8>,
+    /// Transcript of the audio
+    #[serde(default)]
+    pub transcript: Option<String>,
+    /// When the provider forgets the audio, as seconds since the Unix epoch
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+/// One candidate reply of a chat response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatChoice {
+    /// The reply text
+    pub text: Option<String>,
+    /// Tool calls requested in this reply
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Why generation of this reply stopped
+    pub finish_reason: Option<FinishReason>,
+    /// The model's explanation for declining to answer, if it refused
+    pub refusal: Option<String>,
+    /// Log probabilities of the reply tokens, if requested
+    pub logprobs: Option<Vec<TokenLogprob>>,
+}
+
+/// Identifies what served a chat request, for debugging with provider support.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    /// Name of the provider that served the request (e.g. "openai")
+    pub provider: Option<String>,
+    /// The model that actually served the request, which may be a dated snapshot of the
+    /// requested one
+    pub model: Option<String>,
+    /// The ID the provider gave this response (e.g. "chatcmpl-..." or "msg_...")
+    pub response_id: Option<String>,
+    /// The provider's ID of the HTTP request, from its request-ID header
+    pub request_id: Option<String>,
+    /// Fingerprint of the backend configuration that served the request
+    pub system_fingerprint: Option<String>,
+    /// How long the request took
+    pub timing: Option<ResponseTiming>,
+}
+
 pub trait ChatResponse: std::fmt::Debug + std::fmt::Display {
     fn text(&self) -> Option<String>;
+    /// Returns the tool calls requested by the model, in the order the model sent them.
+    ///
+    /// A single response may hold several calls when the provider supports parallel tool
+    /// calls. Each result sent back with [`ChatMessageBuilder::tool_result`] is matched to
+    /// its call by ID.
     fn tool_calls(&self) -> Option<Vec<ToolCall>>;
     fn thinking(&self) -> Option<String> {
         None
     }
+    /// Returns why the model stopped generating, if the provider reports it.
+    ///
+    /// [`FinishReason::Length`] means the output was truncated by the token limit.
+    fn finish_reason(&self) -> Option<FinishReason> {
+        None
+    }
+    /// Returns the provider's response document as received, for provider-specific fields
+    /// the normalized accessors don't cover (e.g. the citations of Anthropic replies).
+    ///
+    /// Returns `None` for streamed responses and backends that don't return JSON.
+    fn raw(&self) -> Option<&serde_json::Value> {
+        None
+    }
+    /// Returns the model's explanation when it declined to answer, or the reason the
+    /// provider blocked the request.
+    ///
+    /// A refused reply usually has no text, so check this before treating an empty
+    /// reply as an answer. Output cut short by a safety filter ends with
+    /// [`FinishReason::ContentFilter`].
+    fn refusal(&self) -> Option<String> {
+        None
+    }
+    /// Returns the token usage reported for the request.
+    fn usage(&self) -> Option<Usage> {
+        None
+    }
+    /// Returns the estimated cost of the request, from its usage and the price of the model
+    /// that served it.
+    ///
+    /// Returns `None` if the provider reports no usage or model, or the model has no
+    /// price in [`crate::pricing`].
+    fn cost(&self) -> Option<CostEstimate> {
+        crate::pricing::estimate(self.metadata().model.as_deref()?, &self.usage()?)
+    }
+    /// Returns the log probabilities of the reply tokens, in order.
+    ///
+    /// Only returned when requested with [`crate::builder::LLMBuilder::logprobs`].
+    fn logprobs(&self) -> Option<Vec<TokenLogprob>> {
+        None
+    }
+    /// Returns the sources the provider searched to ground the reply (e.g. Perplexity's
+    /// search results), in the order it cites them.
+    ///
+    /// Returns `None` for providers that don't search and for streamed responses.
+    fn citations(&self) -> Option<Vec<Citation>> {
+        None
+    }
+    /// Returns the spoken reply of an audio model, with its transcript.
+    ///
+    /// Only returned when audio output was requested with
+    /// [`crate::builder::LLMBuilder::audio_output`], and not for streamed responses.
+    fn audio(&self) -> Option<AudioReply> {
+        None
+    }
+    /// Returns the ratings of the provider's content filter for the reply, or for the
+    /// prompt when the filter blocked it.
+    ///
+    /// A blocked reply has no text; [`ChatResponse::refusal`] then returns why it was
+    /// blocked. Returns `None` for providers that don't rate their content.
+    fn safety_ratings(&self) -> Option<Vec<SafetyRating>> {
+        None
+    }
+    /// Returns the searches the model ran to ground the reply, their sources and the parts
+    /// of the reply each source supports.
+    ///
+    /// Only returned when a search tool such as [`Tool::google_search`] was given and the
+    /// model used it. The sources are also returned by [`ChatResponse::citations`].
+    fn grounding(&self) -> Option<Grounding> {
+        None
+    }
+    /// Returns the provider, model, IDs and fingerprint reported with the response.
+    ///
+    /// Fields the provider doesn't report are `None`; streamed responses only report
+    /// their timing.
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata::default()
+    }
+    /// Returns every candidate reply, in the order the provider returned them.
+    ///
+    /// Several candidates are returned when the request asked for more than one (see
+    /// [`crate::builder::LLMBuilder::n`]); [`ChatResponse::text`] and the other accessors
+    /// describe the first one.
+    fn choices(&self) -> Vec<ChatChoice> {
+        vec![ChatChoice {
+            text: self.text(),
+            tool_calls: self.tool_calls(),
+            finish_reason: self.finish_reason(),
+            refusal: self.refusal(),
+            logprobs: self.logprobs(),
+        }]
+    }
 }
 
 /// Trait for providers that support chat-style interactions.
@@ -212,6 +1438,127 @@ pub trait ChatProvider: Sync + Send {
         messages: &[ChatMessage],
         tools: Option<&[Tool]>,
     ) -> Result<Box<dyn ChatResponse>, LLMError>;
+
+    /// Sends a streaming chat request to the provider with a sequence of messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    ///
+    /// # Returns
+    ///
+    /// A stream of response chunks or an error
+    async fn chat_stream(&self, messages: &[ChatMessage]) -> Result<ChatStream, LLMError> {
+        self.chat_stream_with_tools(messages, None).await
+    }
+
+    /// Sends a streaming chat request to the provider with a sequence of messages and tools.
+    ///
+    /// Providers that do not support streaming return an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    /// * `tools` - Optional slice of tools to use in the chat
+    ///
+    /// # Returns
+    ///
+    /// A stream of response chunks or an error
+    async fn chat_stream_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        Err(LLMError::ProviderError(
+            "Streaming is not supported by this provider".to_string(),
+        ))
+    }
+
+    /// Sends a streaming chat request and invokes a callback for every chunk.
+    ///
+    /// This is a convenience over [`ChatProvider::chat_stream`] for callers that just
+    /// want to print tokens as they arrive: the chunks are also merged, and the full
+    /// response is returned once the stream ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    /// * `on_chunk` - Callback invoked with each chunk as it arrives
+    ///
+    /// # Returns
+    ///
+    /// The assembled response or an error
+    async fn chat_stream_with(
+        &self,
+        messages: &[ChatMessage],
+        on_chunk: &mut (dyn for<'c> FnMut(&'c StreamChunk) + Send),
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let mut timer = RequestTimer::start();
+        let mut stream = self.chat_stream(messages).await?;
+        let mut response = StreamedResponse::default();
+        while let Some(chunk) = stream.next().await {
+            timer.first_byte();
+            let chunk = chunk?;
+            on_chunk(&chunk);
+            response.push(chunk);
+        }
+        response.timing = Some(timer.timing());
+        Ok(Box::new(response))
+    }
+
+    /// Sends a chat request with per-request options.
+    ///
+    /// Sampling parameters in the options override the provider's configuration for this
+    /// request. The default implementation ignores them; the built-in backends apply them.
+    ///
+    /// If the options carry a cancellation token and it is triggered before the
+    /// response arrives, the HTTP request is aborted and [`LLMError::Cancelled`] is
+    /// returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    /// * `tools` - Optional slice of tools to use in the chat
+    /// * `options` - Per-request sampling overrides and cancellation token
+    ///
+    /// # Returns
+    ///
+    /// The provider's response or an error
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        with_cancellation(options, self.chat_with_tools(messages, tools)).await
+    }
+
+    /// Sends a streaming chat request with per-request options.
+    ///
+    /// Sampling parameters in the options override the provider's configuration for this
+    /// request, as in [`ChatProvider::chat_with_options`].
+    ///
+    /// If the options carry a cancellation token, triggering it aborts the request
+    /// while it is being sent, or makes the stream yield [`LLMError::Cancelled`] and
+    /// end once chunks are flowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    /// * `tools` - Optional slice of tools to use in the chat
+    /// * `options` - Per-request sampling overrides and cancellation token
+    ///
+    /// # Returns
+    ///
+    /// A stream of response chunks or an error
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        with_stream_cancellation(options, self.chat_stream_with_tools(messages, tools)).await
+    }
 }
 
 impl fmt::Display for ReasoningEffort {
@@ -234,6 +1581,17 @@ impl ChatMessage {
     pub fn assistant() -> ChatMessageBuilder {
         ChatMessageBuilder::new(ChatRole::Assistant)
     }
+
+    /// Create a new builder for a system message
+    pub fn system() -> ChatMessageBuilder {
+        ChatMessageBuilder::new(ChatRole::System)
+    }
+
+    /// Create a new builder for a tool message, typically completed with
+    /// [`ChatMessageBuilder::tool_result`]
+    pub fn tool() -> ChatMessageBuilder {
+        ChatMessageBuilder::new(ChatRole::Tool)
+    }
 }
 
 /// Builder for ChatMessage
@@ -242,6 +1600,7 @@ pub struct ChatMessageBuilder {
     role: ChatRole,
     message_type: MessageType,
     content: String,
+    metadata: MessageMetadata,
 }
 
 impl ChatMessageBuilder {
@@ -251,9 +1610,40 @@ impl ChatMessageBuilder {
             role,
             message_type: MessageType::default(),
             content: String::new(),
+            metadata: MessageMetadata::default(),
         }
     }
 
+    /// Set the name of the participant sending the message
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.metadata.name = Some(name.into());
+        self
+    }
+
+    /// Set when the message was created
+    pub fn timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.metadata.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Set the creation time of the message to now
+    pub fn timestamp_now(self) -> Self {
+        self.timestamp(SystemTime::now())
+    }
+
+    /// Add a key/value tag to the message
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the detail level the images of the message are read at, e.g.
+    /// [`ImageDetail::Low`] to spend fewer tokens on them
+    pub fn image_detail(mut self, detail: ImageDetail) -> Self {
+        self.metadata.image_detail = Some(detail);
+        self
+    }
+
     /// Set the message content
     pub fn content<S: Into<String>>(mut self, content: S) -> Self {
         self.content = content.into();
@@ -266,24 +1656,217 @@ impl ChatMessageBuilder {
         self
     }
 
+    /// Set the message type as Image, detecting the format from the image bytes
+    ///
+    /// Returns an error if the bytes are not a JPEG, PNG, GIF or WebP image.
+    pub fn image_from_bytes(self, raw_bytes: Vec<u8>) -> Result<Self, LLMError> {
+        let image_mime = ImageMime::from_bytes(&raw_bytes).ok_or_else(|| {
+            LLMError::InvalidRequest(
+                "Unsupported image format: expected JPEG, PNG, GIF or WebP".to_string(),
+            )
+        })?;
+        Ok(self.image(image_mime, raw_bytes))
+    }
+
+    /// Set the message type as Image, reading the image from a file and detecting its format
+    ///
+    /// Returns an error if the file cannot be read or is not a JPEG, PNG, GIF or WebP image.
+    pub fn image_from_path(self, path: impl AsRef<std::path::Path>) -> Result<Self, LLMError> {
+        let path = path.as_ref();
+        let raw_bytes = std::fs::read(path).map_err(|e| {
+            LLMError::InvalidRequest(format!("Failed to read image {}: {}", path.display(), e))
+        })?;
+        self.image_from_bytes(raw_bytes)
+    }
+
     /// Set the message type as Image
     pub fn pdf(mut self, raw_bytes: Vec<u8>) -> Self {
         self.message_type = MessageType::Pdf(raw_bytes);
         self
     }
 
+    /// Set the message type as Audio
+    pub fn audio(mut self, audio_mime: AudioMime, raw_bytes: Vec<u8>) -> Self {
+        self.message_type = MessageType::Audio((audio_mime, raw_bytes));
+        self
+    }
+
     /// Set the message type as ImageURL
     pub fn image_url(mut self, url: impl Into<String>) -> Self {
         self.message_type = MessageType::ImageURL(url.into());
         self
     }
 
+    /// Set the message type as Parts, sending several pieces of content in one message.
+    ///
+    /// The text parts are also joined into the message content, for backends that only
+    /// accept text.
+    pub fn parts(mut self, parts: Vec<ContentPart>) -> Self {
+        self.content = parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.message_type = MessageType::Parts(parts);
+        self
+    }
+
+    /// Set the message type as ToolUse, replaying the tool calls the model requested
+    pub fn tool_use(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        self.message_type = MessageType::ToolUse(tool_calls);
+        self
+    }
+
+    /// Set the message type as ToolResult, with the output of a tool call
+    pub fn tool_result(
+        mut self,
+        tool_call_id: impl Into<String>,
+        name: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        let content = content.into();
+        self.content = content.clone();
+        self.message_type = MessageType::ToolResult {
+            tool_call_id: tool_call_id.into(),
+            name: name.into(),
+            content,
+            parts: Vec::new(),
+            is_error: false,
+        };
+        self
+    }
+
+    /// Set the message type as ToolResult, with the output of a tool call made of several
+    /// parts, such as the screenshot taken by the computer use tool
+    pub fn tool_result_parts(
+        mut self,
+        tool_call_id: impl Into<String>,
+        name: impl Into<String>,
+        parts: Vec<ContentPart>,
+    ) -> Self {
+        let content = parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.content = content.clone();
+        self.message_type = MessageType::ToolResult {
+            tool_call_id: tool_call_id.into(),
+            name: name.into(),
+            content,
+            parts,
+            is_error: false,
+        };
+        self
+    }
+
+    /// Set the message type as ToolResult, with the error of a failed tool call
+    pub fn tool_error(
+        mut self,
+        tool_call_id: impl Into<String>,
+        name: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        let content = error.into();
+        self.content = content.clone();
+        self.message_type = MessageType::ToolResult {
+            tool_call_id: tool_call_id.into(),
+            name: name.into(),
+            content,
+            parts: Vec::new(),
+            is_error: true,
+        };
+        self
+    }
+
     /// Build the ChatMessage
     pub fn build(self) -> ChatMessage {
         ChatMessage {
             role: self.role,
             message_type: self.message_type,
             content: self.content,
+            metadata: self.metadata,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn function_from_json_schema() {
+        let function = FunctionTool::from_json_schema(
+            "get_weather",
+            "Get the current weather in a city",
+            json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string", "description": "Name of the city" },
+                    "days": { "type": "array", "items": { "type": "integer" } }
+                },
+                "required": ["city"]
+            }),
+        )
+        .unwrap();
+        assert_eq!(function.parameters.schema_type, "object");
+        assert_eq!(
+            function.parameters.properties["city"].property_type,
+            "string"
+        );
+        assert_eq!(
+            function.parameters.properties["days"]
+                .items
+                .as_ref()
+                .unwrap()
+                .property_type,
+            "integer"
+        );
+        assert_eq!(function.parameters.required, ["city"]);
+    }
+
+    #[test]
+    fn function_from_json_schema_with_nullable_types() {
+        let function = FunctionTool::from_json_schema(
+            "search",
+            "Search the documents",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": ["integer", "null"] },
+                    "tags": { "type": ["null", "array"], "items": { "type": ["string", "null"] } }
+                },
+                "required": ["query"]
+            }),
+        )
+        .unwrap();
+        let properties = &function.parameters.properties;
+        assert_eq!(properties["limit"].property_type, "integer");
+        assert_eq!(properties["tags"].property_type, "array");
+        assert_eq!(
+            properties["tags"].items.as_ref().unwrap().property_type,
+            "string"
+        );
+    }
+
+    #[test]
+    fn function_from_json_schema_rejects_empty_type_list() {
+        let result = FunctionTool::from_json_schema(
+            "search",
+            "Search the documents",
+            json!({
+                "type": "object",
+                "properties": { "query": { "type": [] } }
+            }),
+        );
+        assert!(matches!(result, Err(LLMError::JsonError(_))));
+    }
+}
@@ -0,0 +1,141 @@
+//! Owned copies of chat responses.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ToolCall;
+
+use super::{
+    AudioReply, ChatChoice, ChatResponse, Citation, FinishReason, Grounding, ResponseMetadata,
+    SafetyRating, TokenLogprob, Usage,
+};
+
+/// An owned copy of a chat response, which can be cloned and shared between callers.
+///
+/// Every accessor of the original response is captured when the snapshot is taken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseSnapshot {
+    /// The reply text
+    pub text: Option<String>,
+    /// Tool calls requested by the model
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// The model's reasoning, if it reported any
+    pub thinking: Option<String>,
+    /// Why the model stopped generating
+    pub finish_reason: Option<FinishReason>,
+    /// The provider's response document as received
+    pub raw: Option<Value>,
+    /// The model's explanation when it declined to answer
+    pub refusal: Option<String>,
+    /// Token usage reported for the request
+    pub usage: Option<Usage>,
+    /// Log probabilities of the reply tokens
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    /// Sources the provider searched to ground the reply
+    #[serde(default)]
+    pub citations: Option<Vec<Citation>>,
+    /// The spoken reply of an audio model
+    #[serde(default)]
+    pub audio: Option<AudioReply>,
+    /// Ratings of the provider's content filter
+    #[serde(default)]
+    pub safety_ratings: Option<Vec<SafetyRating>>,
+    /// How the reply is grounded in search results
+    #[serde(default)]
+    pub grounding: Option<Grounding>,
+    /// What served the request
+    pub metadata: ResponseMetadata,
+    /// Every candidate reply
+    pub choices: Vec<ChatChoice>,
+    /// The response as displayed
+    pub display: String,
+}
+
+impl ResponseSnapshot {
+    /// Captures a copy of a response.
+    pub fn from_response(response: &dyn ChatResponse) -> Self {
+        Self {
+            text: response.text(),
+            tool_calls: response.tool_calls(),
+            thinking: response.thinking(),
+            finish_reason: response.finish_reason(),
+            raw: response.raw().cloned(),
+            refusal: response.refusal(),
+            usage: response.usage(),
+            logprobs: response.logprobs(),
+            citations: response.citations(),
+            audio: response.audio(),
+            safety_ratings: response.safety_ratings(),
+            grounding: response.grounding(),
+            metadata: response.metadata(),
+            choices: response.choices(),
+            display: response.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ResponseSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.display)
+    }
+}
+
+impl ChatResponse for ResponseSnapshot {
+    fn text(&self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        self.tool_calls.clone()
+    }
+
+    fn thinking(&self) -> Option<String> {
+        self.thinking.clone()
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.finish_reason.clone()
+    }
+
+    fn raw(&self) -> Option<&Value> {
+        self.raw.as_ref()
+    }
+
+    fn refusal(&self) -> Option<String> {
+        self.refusal.clone()
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.usage.clone()
+    }
+
+    fn logprobs(&self) -> Option<Vec<TokenLogprob>> {
+        self.logprobs.clone()
+    }
+
+    fn citations(&self) -> Option<Vec<Citation>> {
+        self.citations.clone()
+    }
+
+    fn audio(&self) -> Option<AudioReply> {
+        self.audio.clone()
+    }
+
+    fn safety_ratings(&self) -> Option<Vec<SafetyRating>> {
+        self.safety_ratings.clone()
+    }
+
+    fn grounding(&self) -> Option<Grounding> {
+        self.grounding.clone()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        self.metadata.clone()
+    }
+
+    fn choices(&self) -> Vec<ChatChoice> {
+        self.choices.clone()
+    }
+}
@@ -0,0 +1,237 @@
+//! Persistence of conversation histories.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::LLMError;
+
+use super::ChatMessage;
+
+/// Storage for conversation histories, keyed by session ID.
+///
+/// Stored histories keep tool calls, tool results and attachments, so a session can be
+/// resumed with [`super::Conversation::with_history`] after a restart.
+///
+/// The crate provides [`JsonFileStore`]; implement this trait to keep histories in a
+/// database such as SQLite.
+pub trait ConversationStore: Send + Sync {
+    /// Saves a history, replacing any history stored under the same ID.
+    fn save(&self, id: &str, messages: &[ChatMessage]) -> Result<(), LLMError>;
+
+    /// Loads a history, or returns `None` if no history is stored under the ID.
+    fn load(&self, id: &str) -> Result<Option<Vec<ChatMessage>>, LLMError>;
+
+    /// Deletes a history. Deleting a missing history is not an error.
+    fn delete(&self, id: &str) -> Result<(), LLMError>;
+
+    /// Lists the IDs of the stored histories.
+    fn list(&self) -> Result<Vec<String>, LLMError>;
+}
+
+/// Layout of a saved conversation file.
+#[derive(Serialize)]
+struct SavedConversationRef<'a> {
+    messages: &'a [ChatMessage],
+}
+
+#[derive(Deserialize)]
+struct SavedConversation {
+    messages: Vec<ChatMessage>,
+}
+
+/// Writes a history to a JSON file.
+pub(crate) fn save_json(path: &Path, messages: &[ChatMessage]) -> Result<(), LLMError> {
+    let json = serde_json::to_vec_pretty(&SavedConversationRef { messages })
+        .map_err(|e| LLMError::JsonError(e.to_string()))?;
+    fs::write(path, json).map_err(|e| {
+        LLMError::InvalidRequest(format!(
+            "Failed to write conversation {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Reads a history from a JSON file written by [`save_json`].
+pub(crate) fn load_json(path: &Path) -> Result<Vec<ChatMessage>, LLMError> {
+    let json = fs::read(path).map_err(|e| {
+        LLMError::InvalidRequest(format!(
+            "Failed to read conversation {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let saved: SavedConversation =
+        serde_json::from_slice(&json).map_err(|e| LLMError::JsonError(e.to_string()))?;
+    Ok(saved.messages)
+}
+
+/// Store keeping each history in a `<id>.json` file of a directory.
+///
+/// ```
+/// use llm::chat::{ChatMessage, ConversationStore, JsonFileStore};
+///
+/// let store = JsonFileStore::new(std::env::temp_dir().join("llm-doc-sessions")).unwrap();
+/// let history = vec![ChatMessage::user().content("Hello").build()];
+///
+/// store.save("session-1", &history).unwrap();
+/// assert_eq!(store.load("session-1").unwrap(), Some(history));
+/// assert!(store.list().unwrap().contains(&"session-1".to_string()));
+///
+/// store.delete("session-1").unwrap();
+/// assert_eq!(store.load("session-1").unwrap(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonFileStore {
+    dir: PathBuf,
+}
+
+impl JsonFileStore {
+    /// Opens a store in a directory, creating the directory if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, LLMError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            LLMError::InvalidRequest(format!(
+                "Failed to create conversation directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf, LLMError> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !id.starts_with('.');
+        if !valid {
+            return Err(LLMError::InvalidRequest(format!(
+                "Invalid conversation ID '{}': use letters, digits, '-', '_' and '.'",
+                id
+            )));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+impl ConversationStore for JsonFileStore {
+    fn save(&self, id: &str, messages: &[ChatMessage]) -> Result<(), LLMError> {
+        save_json(&self.path(id)?, messages)
+    }
+
+    fn load(&self, id: &str) -> Result<Option<Vec<ChatMessage>>, LLMError> {
+        let path = self.path(id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        load_json(&path).map(Some)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), LLMError> {
+        match fs::remove_file(self.path(id)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(LLMError::InvalidRequest(
+                format!("Failed to delete conversation '{}': {}", id, e),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<String>, LLMError> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| {
+            LLMError::InvalidRequest(format!(
+                "Failed to list conversations in {}: {}",
+                self.dir.display(),
+                e
+            ))
+        })?;
+
+        let mut ids: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                (path.extension()? == "json")
+                    .then(|| path.file_stem()?.to_str().map(str::to_string))
+                    .flatten()
+            })
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(name: &str) -> JsonFileStore {
+        JsonFileStore::new(std::env::temp_dir().join(format!(
+            "llm-store-{}-{}",
+            name,
+            std::process::id()
+        )))
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_plain_ids() {
+        let store = store("valid");
+        for id in ["session-1", "user_42", "2025.01.01", "A"] {
+            assert_eq!(
+                store.path(id).unwrap(),
+                store.dir.join(format!("{}.json", id))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_ids_escaping_the_directory() {
+        let store = store("invalid");
+        for id in [
+            "",
+            ".",
+            "..",
+            ".hidden",
+            "../escape",
+            "a/b",
+            "a\\b",
+            "/etc/passwd",
+            "é",
+        ] {
+            assert!(
+                matches!(store.path(id), Err(LLMError::InvalidRequest(_))),
+                "{:?} was accepted",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_ids_fail_every_operation() {
+        let store = store("operations");
+        let messages = vec![ChatMessage::user().content("Hello").build()];
+        assert!(store.save("../escape", &messages).is_err());
+        assert!(store.load("../escape").is_err());
+        assert!(store.delete("../escape").is_err());
+        assert!(!std::env::temp_dir().join("escape.json").exists());
+    }
+
+    #[test]
+    fn saves_loads_lists_and_deletes() {
+        let store = store("roundtrip");
+        let messages = vec![ChatMessage::user().content("Hello").build()];
+        store.save("b", &messages).unwrap();
+        store.save("a", &messages).unwrap();
+        assert_eq!(store.load("a").unwrap(), Some(messages));
+        assert_eq!(store.list().unwrap(), ["a", "b"]);
+
+        store.delete("a").unwrap();
+        store.delete("a").unwrap();
+        assert_eq!(store.load("a").unwrap(), None);
+        assert_eq!(store.list().unwrap(), ["b"]);
+        let _ = fs::remove_dir_all(&store.dir);
+    }
+}
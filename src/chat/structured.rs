@@ -0,0 +1,98 @@
+//! Typed structured output for chat requests.
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use crate::error::LLMError;
+
+use super::{ChatMessage, ChatProvider};
+
+/// Chat requests whose reply is deserialized into a typed value.
+///
+/// The JSON schema describing `T` is the one configured on the provider, e.g. with
+/// [`crate::builder::LLMBuilder::schema`]. Replies wrapped in a Markdown code fence are
+/// accepted.
+///
+/// # Example
+///
+/// ```no_run
+/// use llm::builder::{LLMBackend, LLMBuilder};
+/// use llm::chat::{ChatMessage, StructuredChat, StructuredOutputFormat};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Student {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let schema: StructuredOutputFormat = serde_json::from_value(serde_json::json!({
+///     "name": "Student",
+///     "schema": {
+///         "type": "object",
+///         "properties": {
+///             "name": { "type": "string" },
+///             "age": { "type": "integer" }
+///         },
+///         "required": ["name", "age"]
+///     }
+/// }))?;
+///
+/// let llm = LLMBuilder::new()
+///     .backend(LLMBackend::OpenAI)
+///     .api_key("sk-...")
+///     .schema(schema)
+///     .build()?;
+///
+/// let messages = vec![ChatMessage::user().content("Invent a student").build()];
+/// let student: Student = llm.chat_structured(&messages).await?;
+/// println!("{} is {}", student.name, student.age);
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait StructuredChat {
+    /// Sends a chat request and deserializes the reply text into `T`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(T)` - The parsed reply
+    /// * `Err(LLMError::Refused)` - If the model declined to answer
+    /// * `Err(LLMError::StructuredOutputError)` - If the reply is not valid JSON for `T`
+    /// * `Err(LLMError)` - If the request fails
+    async fn chat_structured<T: DeserializeOwned>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<T, LLMError>;
+}
+
+#[async_trait]
+impl<P: ChatProvider + ?Sized> StructuredChat for P {
+    async fn chat_structured<T: DeserializeOwned>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<T, LLMError> {
+        let response = self.chat(messages).await?;
+        if let Some(refusal) = response.refusal() {
+            return Err(LLMError::Refused(refusal));
+        }
+        let text = response.text().unwrap_or_default();
+        parse_structured(&text)
+    }
+}
+
+/// Parses a reply as JSON, ignoring a surrounding Markdown code fence.
+fn parse_structured<T: DeserializeOwned>(text: &str) -> Result<T, LLMError> {
+    let trimmed = text.trim();
+    let json = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(|fenced| fenced.trim_start_matches("json").trim())
+        .unwrap_or(trimmed);
+
+    serde_json::from_str(json).map_err(|e| LLMError::StructuredOutputError {
+        message: e.to_string(),
+        text: text.to_string(),
+    })
+}
@@ -0,0 +1,106 @@
+//! Timing of chat requests.
+
+#[cfg(any(feature = "openai", feature = "xai"))]
+use std::task::Poll;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "openai", feature = "xai"))]
+use futures::StreamExt;
+
+#[cfg(any(feature = "openai", feature = "xai"))]
+use super::ChatStream;
+
+/// How long a chat request took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseTiming {
+    /// When the request was sent
+    pub started_at: SystemTime,
+    /// Time until the response headers arrived, or for streams the first chunk
+    pub time_to_first_byte: Option<Duration>,
+    /// Time until the response was complete
+    pub total: Duration,
+}
+
+/// Measures a request from the moment it is sent.
+pub(crate) struct RequestTimer {
+    started_at: SystemTime,
+    start: Instant,
+    first_byte: Option<Duration>,
+}
+
+impl RequestTimer {
+    /// Starts timing a request about to be sent.
+    pub(crate) fn start() -> Self {
+        Self {
+            started_at: SystemTime::now(),
+            start: Instant::now(),
+            first_byte: None,
+        }
+    }
+
+    /// Records that the first byte arrived, unless it was already recorded.
+    pub(crate) fn first_byte(&mut self) {
+        self.first_byte.get_or_insert_with(|| self.start.elapsed());
+    }
+
+    /// Returns the timing so far, without reporting it.
+    pub(crate) fn timing(&self) -> ResponseTiming {
+        ResponseTiming {
+            started_at: self.started_at,
+            time_to_first_byte: self.first_byte,
+            total: self.start.elapsed(),
+        }
+    }
+
+    /// Returns the timing of a completed request and reports it as a `tracing` event.
+    #[cfg_attr(
+        not(any(
+            feature = "openai",
+            feature = "anthropic",
+            feature = "ollama",
+            feature = "deepseek",
+            feature = "xai",
+            feature = "google",
+            feature = "groq"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) fn finish(&self, provider: &str, model: &str) -> ResponseTiming {
+        let timing = self.timing();
+        tracing::debug!(
+            target: "llm::timing",
+            provider,
+            model,
+            time_to_first_byte_ms = timing.time_to_first_byte.map(|d| d.as_millis() as u64),
+            total_ms = timing.total.as_millis() as u64,
+            "chat request completed"
+        );
+        timing
+    }
+}
+
+/// Wraps a chunk stream so its time to first chunk and total duration are reported as a
+/// `tracing` event once it ends.
+#[cfg(any(feature = "openai", feature = "xai"))]
+pub(crate) fn timed_stream(
+    mut stream: ChatStream,
+    mut timer: RequestTimer,
+    provider: &'static str,
+    model: String,
+) -> ChatStream {
+    let mut finished = false;
+    Box::pin(futures::stream::poll_fn(move |cx| {
+        let poll = stream.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(_)) => timer.first_byte(),
+            Poll::Ready(None) if !finished => {
+                finished = true;
+                timer.finish(provider, &model);
+            }
+            _ => {}
+        }
+        poll
+    }))
+}
@@ -0,0 +1,399 @@
+//! Strategies keeping a conversation history within the model's context window.
+
+use async_trait::async_trait;
+
+use crate::{error::LLMError, LLMProvider};
+
+use super::{ChatMessage, ChatRole, MessageType};
+
+/// Share of the context window a history may fill when no explicit budget is set, leaving
+/// the rest for the reply.
+const CONTEXT_SHARE_PERCENT: usize = 75;
+
+/// Approximate token cost of an image, audio clip or document attachment.
+const ATTACHMENT_TOKENS: usize = 1000;
+
+/// Approximate token overhead of a message's role and formatting.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// A strategy shortening a conversation history before it is sent.
+///
+/// Strategies keep system messages in place and never separate tool results from the
+/// assistant message requesting them.
+#[async_trait]
+pub trait TrimStrategy: Send + Sync {
+    /// Returns the history to keep.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The full history, oldest first
+    /// * `provider` - The provider answering the conversation, for strategies that need it
+    /// * `context_length` - The model's context window in tokens, if known
+    async fn trim(
+        &self,
+        messages: Vec<ChatMessage>,
+        provider: &dyn LLMProvider,
+        context_length: Option<usize>,
+    ) -> Result<Vec<ChatMessage>, LLMError>;
+}
+
+/// Keeps the system messages and the last `n` other messages.
+#[derive(Debug, Clone)]
+pub struct KeepLastN(pub usize);
+
+#[async_trait]
+impl TrimStrategy for KeepLastN {
+    async fn trim(
+        &self,
+        messages: Vec<ChatMessage>,
+        _provider: &dyn LLMProvider,
+        _context_length: Option<usize>,
+    ) -> Result<Vec<ChatMessage>, LLMError> {
+        let cut = last_n_cut(&messages, self.0);
+        Ok(keep_from(messages, cut))
+    }
+}
+
+/// Drops the oldest messages until the history fits a token budget.
+///
+/// Without an explicit budget, the history may fill three quarters of the model's context
+/// window. Token counts are estimated from the message length.
+#[derive(Debug, Clone, Default)]
+pub struct TokenBudget {
+    max_tokens: Option<usize>,
+}
+
+impl TokenBudget {
+    /// Creates a strategy keeping the history under `max_tokens`.
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens: Some(max_tokens),
+        }
+    }
+
+    /// Creates a strategy sized from the model's context window.
+    pub fn from_context_length() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TrimStrategy for TokenBudget {
+    async fn trim(
+        &self,
+        messages: Vec<ChatMessage>,
+        _provider: &dyn LLMProvider,
+        context_length: Option<usize>,
+    ) -> Result<Vec<ChatMessage>, LLMError> {
+        let Some(budget) = budget(self.max_tokens, context_length) else {
+            return Ok(messages);
+        };
+        let cut = budget_cut(&messages, budget);
+        Ok(keep_from(messages, cut))
+    }
+}
+
+/// Replaces the oldest messages with a summary written by the model itself.
+///
+/// When the history exceeds the token budget, every message but the last `keep_last` is
+/// summarized into a single system message.
+#[derive(Debug, Clone)]
+pub struct SummarizeOlder {
+    keep_last: usize,
+    max_tokens: Option<usize>,
+}
+
+impl SummarizeOlder {
+    /// Creates a strategy keeping the last `keep_last` messages verbatim once the history
+    /// exceeds three quarters of the model's context window.
+    pub fn new(keep_last: usize) -> Self {
+        Self {
+            keep_last,
+            max_tokens: None,
+        }
+    }
+
+    /// Summarizes once the history exceeds `max_tokens` rather than the context window.
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+#[async_trait]
+impl TrimStrategy for SummarizeOlder {
+    async fn trim(
+        &self,
+        messages: Vec<ChatMessage>,
+        provider: &dyn LLMProvider,
+        context_length: Option<usize>,
+    ) -> Result<Vec<ChatMessage>, LLMError> {
+        let Some(budget) = budget(self.max_tokens, context_length) else {
+            return Ok(messages);
+        };
+        if estimate_tokens(&messages) <= budget {
+            return Ok(messages);
+        }
+
+        let cut = last_n_cut(&messages, self.keep_last);
+        let transcript = messages[..cut]
+            .iter()
+            .filter(|m| m.role != ChatRole::System)
+            .map(|m| format!("{}: {}", role_name(&m.role), m.content))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if transcript.is_empty() {
+            return Ok(messages);
+        }
+
+        let request = vec![ChatMessage::user()
+            .content(format!(
+                "Summarize the following conversation in a few sentences, keeping every fact \
+                 needed to continue it:\n\n{}",
+                transcript
+            ))
+            .build()];
+        let summary = provider.chat(&request).await?.text().unwrap_or_default();
+
+        let mut rest = messages;
+        let recent = rest.split_off(cut);
+        let mut kept: Vec<ChatMessage> = rest
+            .into_iter()
+            .filter(|m| m.role == ChatRole::System)
+            .collect();
+        kept.push(
+            ChatMessage::system()
+                .content(format!("Summary of the earlier conversation: {}", summary))
+                .build(),
+        );
+        kept.extend(recent);
+        Ok(kept)
+    }
+}
+
+/// Returns the context window of a known model, in tokens.
+///
+/// Dated snapshots such as `gpt-4o-2024-08-06` are recognized. See [`crate::models`] for
+/// the other capabilities of the model, and to register models that are not known.
+pub fn model_context_length(model: &str) -> Option<usize> {
+    crate::models::lookup(model).map(|info| info.context_window)
+}
+
+/// Estimates the number of tokens a history takes, at about four characters per token.
+pub(crate) fn estimate_tokens(messages: &[ChatMessage]) -> usize {
+    messages.iter().map(estimate_message_tokens).sum()
+}
+
+fn estimate_message_tokens(message: &ChatMessage) -> usize {
+    let payload = match &message.message_type {
+        MessageType::Image(_) | MessageType::ImageURL(_) => ATTACHMENT_TOKENS,
+        MessageType::Pdf(_) | MessageType::Audio(_) => ATTACHMENT_TOKENS,
+        MessageType::Parts(parts) | MessageType::ToolResult { parts, .. } => {
+            parts
+                .iter()
+                .filter(|part| !matches!(part, super::ContentPart::Text(_)))
+                .count()
+                * ATTACHMENT_TOKENS
+        }
+        MessageType::ToolUse(calls) => calls
+            .iter()
+            .map(|call| (call.function.name.len() + call.function.arguments.len()).div_ceil(4))
+            .sum(),
+        MessageType::Text => 0,
+    };
+    message.content.len().div_ceil(4) + payload + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Resolves an explicit budget, or a share of the context window.
+fn budget(max_tokens: Option<usize>, context_length: Option<usize>) -> Option<usize> {
+    max_tokens.or_else(|| context_length.map(|length| length * CONTEXT_SHARE_PERCENT / 100))
+}
+
+/// Returns the index from which the last `n` non-system messages are kept.
+fn last_n_cut(messages: &[ChatMessage], n: usize) -> usize {
+    let mut cut = messages.len();
+    let mut remaining = n;
+    for (index, message) in messages.iter().enumerate().rev() {
+        if message.role == ChatRole::System {
+            continue;
+        }
+        if remaining == 0 {
+            break;
+        }
+        remaining -= 1;
+        cut = index;
+    }
+    advance_cut(messages, cut)
+}
+
+/// Returns the index from which the non-system messages fit the budget, alongside every
+/// system message.
+fn budget_cut(messages: &[ChatMessage], budget: usize) -> usize {
+    let mut used = messages
+        .iter()
+        .filter(|m| m.role == ChatRole::System)
+        .map(estimate_message_tokens)
+        .sum::<usize>();
+    let mut cut = messages.len();
+    for (index, message) in messages.iter().enumerate().rev() {
+        if message.role == ChatRole::System {
+            continue;
+        }
+        used += estimate_message_tokens(message);
+        if used > budget {
+            break;
+        }
+        cut = index;
+    }
+    advance_cut(messages, cut)
+}
+
+/// Moves a cut point forward past tool results, which must follow the request for them.
+fn advance_cut(messages: &[ChatMessage], mut cut: usize) -> usize {
+    while cut < messages.len() && is_tool_result(&messages[cut]) {
+        cut += 1;
+    }
+    cut
+}
+
+/// Keeps the messages from `cut` onwards, and every system message before it.
+fn keep_from(messages: Vec<ChatMessage>, cut: usize) -> Vec<ChatMessage> {
+    messages
+        .into_iter()
+        .enumerate()
+        .filter(|(index, m)| *index >= cut || m.role == ChatRole::System)
+        .map(|(_, m)| m)
+        .collect()
+}
+
+fn is_tool_result(message: &ChatMessage) -> bool {
+    message.role == ChatRole::Tool || matches!(message.message_type, MessageType::ToolResult { .. })
+}
+
+fn role_name(role: &ChatRole) -> &'static str {
+    match role {
+        ChatRole::System => "System",
+        ChatRole::User => "User",
+        ChatRole::Assistant => "Assistant",
+        ChatRole::Tool => "Tool",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FunctionCall, ToolCall};
+
+    fn contents(messages: &[ChatMessage]) -> Vec<&str> {
+        messages.iter().map(|m| m.content.as_str()).collect()
+    }
+
+    fn history() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system().content("system").build(),
+            ChatMessage::user().content("first").build(),
+            ChatMessage::assistant().content("second").build(),
+            ChatMessage::user().content("third").build(),
+            ChatMessage::assistant()
+                .content("call")
+                .tool_use(vec![ToolCall {
+                    id: "call_1".to_string(),
+                    call_type: "function".to_string(),
+                    function: FunctionCall {
+                        name: "lookup".to_string(),
+                        arguments: "{}".to_string(),
+                    },
+                }])
+                .build(),
+            ChatMessage::tool()
+                .tool_result("call_1", "lookup", "result")
+                .build(),
+            ChatMessage::assistant().content("answer").build(),
+        ]
+    }
+
+    #[test]
+    fn keeps_system_messages_and_last_n() {
+        let messages = history();
+        let cut = last_n_cut(&messages, 3);
+        assert_eq!(
+            contents(&keep_from(messages, cut)),
+            ["system", "call", "result", "answer"]
+        );
+    }
+
+    #[test]
+    fn never_keeps_a_tool_result_without_its_request() {
+        let messages = history();
+        let cut = last_n_cut(&messages, 2);
+        assert_eq!(contents(&keep_from(messages, cut)), ["system", "answer"]);
+    }
+
+    #[test]
+    fn keeps_everything_when_n_exceeds_history() {
+        let messages = history();
+        assert_eq!(last_n_cut(&messages, 100), 1);
+        assert_eq!(last_n_cut(&messages, 0), messages.len());
+    }
+
+    #[test]
+    fn drops_oldest_messages_over_budget() {
+        let messages = history();
+        let total = estimate_tokens(&messages);
+        assert_eq!(budget_cut(&messages, total), 1);
+
+        let system = estimate_message_tokens(&messages[0]);
+        let last = estimate_message_tokens(&messages[6]);
+        let cut = budget_cut(&messages, system + last);
+        assert_eq!(contents(&keep_from(messages, cut)), ["system", "answer"]);
+    }
+
+    #[test]
+    fn estimates_about_four_characters_per_token() {
+        let message = ChatMessage::user().content("a".repeat(40)).build();
+        assert_eq!(estimate_tokens(&[message]), 10 + MESSAGE_OVERHEAD_TOKENS);
+    }
+
+    #[test]
+    fn budget_defaults_to_share_of_context_window() {
+        assert_eq!(budget(Some(100), Some(8000)), Some(100));
+        assert_eq!(budget(None, Some(8000)), Some(6000));
+        assert_eq!(budget(None, None), None);
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn summarizes_older_messages() {
+        use crate::backends::mock::MockLLM;
+
+        let mock = MockLLM::new().reply("they talked");
+        let kept = SummarizeOlder::new(1)
+            .max_tokens(1)
+            .trim(history(), &mock, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            contents(&kept),
+            [
+                "system",
+                "Summary of the earlier conversation: they talked",
+                "answer"
+            ]
+        );
+        assert!(mock.calls()[0].messages[0].content.contains("User: first"));
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn does_not_summarize_within_budget() {
+        use crate::backends::mock::MockLLM;
+
+        let mock = MockLLM::new();
+        let kept = SummarizeOlder::new(1)
+            .trim(history(), &mock, Some(1_000_000))
+            .await
+            .unwrap();
+        assert_eq!(kept.len(), history().len());
+        assert!(mock.calls().is_empty());
+    }
+}
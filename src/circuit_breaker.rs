@@ -0,0 +1,373 @@
+//! Circuit breaker protecting applications from a degraded provider.
+//!
+//! A [`CircuitBreakerLLM`] counts the consecutive failures of the provider it wraps. Once
+//! they reach a threshold the circuit opens: requests fail fast, or go to a fallback
+//! provider, until a cool-down period has passed. A single trial request is then let
+//! through, closing the circuit again if it succeeds.
+//!
+//! [`CircuitBreakerLLM`]: crate::circuit_breaker::CircuitBreakerLLM
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::circuit_breaker::CircuitBreakerLLM;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let primary = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .build()?;
+//! let backup = LLMBuilder::new()
+//!     .backend(LLMBackend::Ollama)
+//!     .model("llama3.2")
+//!     .build()?;
+//!
+//! let llm = CircuitBreakerLLM::new(primary, 5, Duration::from_secs(30)).fallback(backup);
+//! let reply = llm
+//!     .chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! println!("{}", reply);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// State of a circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go to the provider
+    Closed,
+    /// Requests fail fast, or go to the fallback provider
+    Open,
+    /// The cool-down has passed and a trial request decides whether the circuit closes
+    HalfOpen,
+}
+
+/// Internal state, with the instants it changes at.
+#[derive(Debug)]
+enum Circuit {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A trial request is in flight; another is allowed after `until` in case it never
+    /// completes.
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+/// A wrapper around an LLM provider that stops calling it after repeated failures.
+///
+/// HTTP errors, provider errors and rate limits count as failures. Other errors, such as
+/// invalid requests or refusals, show the provider is reachable and reset the count.
+/// Streams are judged by whether they open.
+pub struct CircuitBreakerLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Provider answering while the circuit is open
+    fallback: Option<Box<dyn LLMProvider>>,
+    /// Consecutive failures opening the circuit
+    failure_threshold: u32,
+    /// Time the circuit stays open before a trial request
+    cooldown: Duration,
+    /// Current state of the circuit
+    circuit: Mutex<Circuit>,
+}
+
+impl CircuitBreakerLLM {
+    /// Wraps a provider in a closed circuit breaker.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to protect
+    /// * `failure_threshold` - Number of consecutive failures opening the circuit
+    /// * `cooldown` - Time the circuit stays open before a trial request is let through
+    pub fn new(inner: Box<dyn LLMProvider>, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            inner,
+            fallback: None,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            circuit: Mutex::new(Circuit::Closed { failures: 0 }),
+        }
+    }
+
+    /// Sends requests to another provider while the circuit is open, instead of failing.
+    pub fn fallback(mut self, provider: Box<dyn LLMProvider>) -> Self {
+        self.fallback = Some(provider);
+        self
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        match *self.circuit.lock().unwrap() {
+            Circuit::Closed { .. } => CircuitState::Closed,
+            Circuit::Open { until } if Instant::now() >= until => CircuitState::HalfOpen,
+            Circuit::Open { .. } => CircuitState::Open,
+            Circuit::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Closes the circuit and clears the failure count.
+    pub fn reset(&self) {
+        *self.circuit.lock().unwrap() = Circuit::Closed { failures: 0 };
+    }
+
+    /// Returns whether a request may go to the wrapped provider, starting a trial request
+    /// if the cool-down has passed.
+    fn acquire(&self) -> bool {
+        let mut circuit = self.circuit.lock().unwrap();
+        let now = Instant::now();
+        match *circuit {
+            Circuit::Closed { .. } => true,
+            Circuit::Open { until } | Circuit::HalfOpen { until } if now >= until => {
+                *circuit = Circuit::HalfOpen {
+                    until: now + self.cooldown,
+                };
+                true
+            }
+            Circuit::Open { .. } | Circuit::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Records the outcome of a request sent to the wrapped provider.
+    fn record<T>(&self, result: &Result<T, LLMError>) {
+        let failed = result.as_ref().is_err_and(LLMError::is_provider_failure);
+        let mut circuit = self.circuit.lock().unwrap();
+        *circuit = match (&*circuit, failed) {
+            (_, false) => Circuit::Closed { failures: 0 },
+            (Circuit::Closed { failures }, true) if failures + 1 < self.failure_threshold => {
+                Circuit::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, true) => {
+                tracing::warn!(
+                    cooldown_seconds = self.cooldown.as_secs_f64(),
+                    "circuit breaker opened"
+                );
+                Circuit::Open {
+                    until: Instant::now() + self.cooldown,
+                }
+            }
+        };
+    }
+
+    /// Sends a request to the wrapped provider if the circuit allows it, or to the fallback.
+    async fn call<'a, T, F, Fut>(&'a self, request: F) -> Result<T, LLMError>
+    where
+        F: Fn(&'a dyn LLMProvider) -> Fut + Send,
+        Fut: Future<Output = Result<T, LLMError>> + Send + 'a,
+    {
+        if !self.acquire() {
+            return match &self.fallback {
+                Some(fallback) => request(fallback.as_ref()).await,
+                None => Err(LLMError::ProviderError(format!(
+                    "Circuit breaker is open after {} consecutive failures",
+                    self.failure_threshold
+                ))),
+            };
+        }
+        let result = request(self.inner.as_ref()).await;
+        self.record(&result);
+        result
+    }
+}
+
+#[async_trait]
+impl LLMProvider for CircuitBreakerLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for CircuitBreakerLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call(|llm| llm.chat(messages)).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call(|llm| llm.chat_with_tools(messages, tools)).await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call(|llm| llm.chat_with_options(messages, tools, options))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.call(|llm| llm.chat_stream_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.call(|llm| llm.chat_stream_with_options(messages, tools, options))
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for CircuitBreakerLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.call(|llm| llm.complete(req)).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.call(|llm| llm.complete_stream(req)).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.call(|llm| llm.complete_with_options(req, options))
+            .await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CircuitBreakerLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.call(|llm| llm.embed(input.clone())).await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockLLM;
+
+    fn overloaded() -> LLMError {
+        LLMError::ProviderError("overloaded".to_string())
+    }
+
+    fn messages() -> Vec<ChatMessage> {
+        vec![ChatMessage::user().content("Hello").build()]
+    }
+
+    #[tokio::test]
+    async fn opens_after_threshold_then_closes_after_successful_trial() {
+        let mock = MockLLM::new()
+            .fail(overloaded())
+            .fail(overloaded())
+            .reply("recovered");
+        let breaker = CircuitBreakerLLM::new(Box::new(mock.clone()), 2, Duration::from_millis(50));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        assert!(breaker.chat(&messages()).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.chat(&messages()).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // Requests fail fast without reaching the provider while the circuit is open.
+        assert!(breaker.chat(&messages()).await.is_err());
+        assert_eq!(mock.calls().len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        let reply = breaker.chat(&messages()).await.unwrap();
+        assert_eq!(reply.text().as_deref(), Some("recovered"));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(mock.calls().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_trial_reopens_the_circuit() {
+        let mock = MockLLM::new().fail(overloaded()).fail(overloaded());
+        let breaker = CircuitBreakerLLM::new(Box::new(mock), 1, Duration::from_millis(50));
+
+        assert!(breaker.chat(&messages()).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.chat(&messages()).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn only_one_trial_request_while_half_open() {
+        let breaker = CircuitBreakerLLM::new(
+            Box::new(MockLLM::new().fail(overloaded())),
+            1,
+            Duration::from_millis(50),
+        );
+        assert!(breaker.chat(&messages()).await.is_err());
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.acquire());
+        assert!(!breaker.acquire());
+    }
+
+    #[tokio::test]
+    async fn non_provider_errors_reset_the_count() {
+        let mock = MockLLM::new()
+            .fail(overloaded())
+            .fail(LLMError::InvalidRequest("bad request".to_string()))
+            .fail(overloaded());
+        let breaker = CircuitBreakerLLM::new(Box::new(mock), 2, Duration::from_secs(60));
+        for _ in 0..3 {
+            assert!(breaker.chat(&messages()).await.is_err());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_uses_fallback() {
+        let breaker = CircuitBreakerLLM::new(
+            Box::new(MockLLM::new().fail(overloaded())),
+            1,
+            Duration::from_secs(60),
+        )
+        .fallback(Box::new(MockLLM::new().reply("backup")));
+        assert!(breaker.chat(&messages()).await.is_err());
+        let reply = breaker.chat(&messages()).await.unwrap();
+        assert_eq!(reply.text().as_deref(), Some("backup"));
+
+        breaker.reset();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,390 @@
+//! Coalescing of identical concurrent chat requests.
+//!
+//! A [`CoalescingLLM`] notices when a chat request is identical to one still in flight —
+//! same messages, tools and sampling overrides — and makes the callers share the single
+//! provider call instead of paying for it twice.
+//!
+//! [`CoalescingLLM`]: crate::coalesce::CoalescingLLM
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::coalesce::CoalescingLLM;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = CoalescingLLM::new(
+//!     LLMBuilder::new()
+//!         .backend(LLMBackend::OpenAI)
+//!         .api_key("sk-...")
+//!         .build()?,
+//! );
+//!
+//! let messages = vec![ChatMessage::user().content("Summarize today's news").build()];
+//! // Both callers get the reply of a single request.
+//! let (first, second) = futures::join!(llm.chat(&messages), llm.chat(&messages));
+//! assert_eq!(first?.text(), second?.text());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use serde_json::json;
+
+use crate::chat::{
+    with_cancellation, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream,
+    ResponseSnapshot, Tool,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// A provider call shared by every caller sending the same request.
+type SharedCall = Shared<BoxFuture<'static, Result<ResponseSnapshot, LLMError>>>;
+
+/// Calls in flight, keyed by their request.
+type InFlightCalls = Arc<Mutex<HashMap<String, InFlightCall>>>;
+
+/// A call in flight and the callers sharing it.
+struct InFlightCall {
+    /// Distinguishes the call from later calls for the same request
+    id: u64,
+    /// The shared provider call
+    call: SharedCall,
+    /// Callers waiting for the reply
+    waiters: usize,
+}
+
+/// Registers a caller waiting for a call, cancelling the call when its last caller leaves.
+struct Waiter {
+    calls: InFlightCalls,
+    key: String,
+    id: u64,
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        let mut calls = self.calls.lock().unwrap();
+        if let Some(entry) = calls.get_mut(&self.key).filter(|entry| entry.id == self.id) {
+            entry.waiters -= 1;
+            if entry.waiters == 0 {
+                calls.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// A wrapper around an LLM provider sharing one call among identical concurrent chat
+/// requests.
+///
+/// Only requests still in flight are shared; once a reply arrives, the next identical
+/// request is sent again. Each caller keeps its own cancellation token: the call is only
+/// aborted once every caller sharing it has been cancelled or dropped. Streams,
+/// completions and embeddings are passed through.
+pub struct CoalescingLLM {
+    /// The wrapped LLM provider
+    inner: Arc<dyn LLMProvider>,
+    /// Calls in flight, keyed by their request
+    in_flight: InFlightCalls,
+    /// ID of the next call
+    next_id: AtomicU64,
+}
+
+impl CoalescingLLM {
+    /// Wraps a provider, coalescing its identical concurrent chat requests.
+    pub fn new(inner: Box<dyn LLMProvider>) -> Self {
+        Self {
+            inner: Arc::from(inner),
+            in_flight: InFlightCalls::default(),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of distinct chat requests in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+
+    /// Sends a chat request, joining an identical call in flight if there is one.
+    async fn coalesced_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let key = request_key(messages, tools, options)?;
+        let (call, _waiter) = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let entry = in_flight.entry(key.clone()).or_insert_with(|| {
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                InFlightCall {
+                    id,
+                    call: self.start_call(key.clone(), id, messages, tools, options),
+                    waiters: 0,
+                }
+            });
+            entry.waiters += 1;
+            let waiter = Waiter {
+                calls: Arc::clone(&self.in_flight),
+                key,
+                id: entry.id,
+            };
+            (entry.call.clone(), waiter)
+        };
+
+        let snapshot = with_cancellation(options, call).await?;
+        Ok(Box::new(snapshot))
+    }
+
+    /// Creates the shared call for a request, which removes itself once answered.
+    fn start_call(
+        &self,
+        key: String,
+        id: u64,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> SharedCall {
+        let inner = Arc::clone(&self.inner);
+        let calls = Arc::clone(&self.in_flight);
+        let messages = messages.to_vec();
+        let tools = tools.map(<[Tool]>::to_vec);
+        // The shared call outlives any single caller, so it ignores their tokens.
+        let options = ChatOptions {
+            cancel_token: None,
+            ..options.clone()
+        };
+        async move {
+            let result = inner
+                .chat_with_options(&messages, tools.as_deref(), &options)
+                .await
+                .map(|response| ResponseSnapshot::from_response(response.as_ref()));
+            let mut calls = calls.lock().unwrap();
+            if calls.get(&key).is_some_and(|entry| entry.id == id) {
+                calls.remove(&key);
+            }
+            result
+        }
+        .boxed()
+        .shared()
+    }
+}
+
+/// Identifies a chat request by everything that affects the reply.
+pub(crate) fn request_key(
+    messages: &[ChatMessage],
+    tools: Option<&[Tool]>,
+    options: &ChatOptions,
+) -> Result<String, LLMError> {
+    let mut key = json!({
+        "messages": messages,
+        "tools": tools,
+        "temperature": options.temperature,
+        "max_tokens": options.max_tokens,
+        "top_p": options.top_p,
+        "stop": options.stop,
+        "seed": options.seed,
+        "reasoning_effort": options.reasoning_effort.map(|effort| format!("{:?}", effort)),
+    });
+    // Only set when overridden, to keep the keys of existing caches and recordings.
+    if let Some(model) = &options.model {
+        key["model"] = json!(model);
+    }
+    if let Some(detail) = options.image_detail {
+        key["image_detail"] = json!(detail);
+    }
+    if let Some(settings) = &options.safety_settings {
+        key["safety_settings"] = json!(settings);
+    }
+    serde_json::to_string(&key).map_err(|e| LLMError::JsonError(e.to_string()))
+}
+
+#[async_trait]
+impl LLMProvider for CoalescingLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for CoalescingLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.coalesced_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.coalesced_chat(messages, tools, options).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner.chat_stream_with_tools(messages, tools).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for CoalescingLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CoalescingLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::backends::mock::MockLLM;
+    use crate::chat::CancellationToken;
+
+    fn messages() -> Vec<ChatMessage> {
+        vec![ChatMessage::user().content("Hello").build()]
+    }
+
+    async fn cancel_soon(token: &CancellationToken) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        token.cancel();
+    }
+
+    #[tokio::test]
+    async fn shares_a_call_among_identical_requests() {
+        let mock = MockLLM::new()
+            .reply("shared")
+            .after(Duration::from_millis(50));
+        let llm = CoalescingLLM::new(Box::new(mock.clone()));
+        let messages = messages();
+
+        let (first, second) = futures::join!(llm.chat(&messages), llm.chat(&messages));
+        assert_eq!(first.unwrap().text().as_deref(), Some("shared"));
+        assert_eq!(second.unwrap().text().as_deref(), Some("shared"));
+        assert_eq!(mock.calls().len(), 1);
+        assert_eq!(llm.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_call_while_a_caller_waits() {
+        let mock = MockLLM::new()
+            .reply("shared")
+            .after(Duration::from_millis(50));
+        let llm = CoalescingLLM::new(Box::new(mock.clone()));
+        let messages = messages();
+        let token = CancellationToken::new();
+        let options = ChatOptions::new().cancel_token(token.clone());
+
+        let (cancelled, answered, _) = futures::join!(
+            llm.chat_with_options(&messages, None, &options),
+            llm.chat(&messages),
+            cancel_soon(&token),
+        );
+        assert!(matches!(cancelled.err(), Some(LLMError::Cancelled)));
+        assert_eq!(answered.unwrap().text().as_deref(), Some("shared"));
+        assert_eq!(mock.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn abandons_the_call_once_every_caller_is_cancelled() {
+        let mock = MockLLM::new()
+            .reply("abandoned")
+            .after(Duration::from_millis(50))
+            .reply("fresh");
+        let llm = CoalescingLLM::new(Box::new(mock.clone()));
+        let messages = messages();
+        let token = CancellationToken::new();
+        let options = ChatOptions::new().cancel_token(token.clone());
+
+        let (first, second, _) = futures::join!(
+            llm.chat_with_options(&messages, None, &options),
+            llm.chat_with_options(&messages, None, &options),
+            cancel_soon(&token),
+        );
+        assert!(matches!(first.err(), Some(LLMError::Cancelled)));
+        assert!(matches!(second.err(), Some(LLMError::Cancelled)));
+        assert_eq!(llm.in_flight(), 0);
+
+        // The next identical request starts a call of its own.
+        let response = llm.chat(&messages).await.unwrap();
+        assert_eq!(response.text().as_deref(), Some("fresh"));
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn ignores_callers_of_an_earlier_call_for_the_same_request() {
+        let mock = MockLLM::new()
+            .reply("current")
+            .after(Duration::from_millis(50));
+        let llm = CoalescingLLM::new(Box::new(mock.clone()));
+        let messages = messages();
+        let key = request_key(&messages, None, &ChatOptions::default()).unwrap();
+
+        let stale_caller_leaves = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert_eq!(llm.in_flight(), 1);
+            drop(Waiter {
+                calls: Arc::clone(&llm.in_flight),
+                key,
+                id: u64::MAX,
+            });
+            assert_eq!(llm.in_flight(), 1);
+        };
+        let (response, _) = futures::join!(llm.chat(&messages), stale_caller_leaves);
+        assert_eq!(response.unwrap().text().as_deref(), Some("current"));
+        assert_eq!(llm.in_flight(), 0);
+    }
+}
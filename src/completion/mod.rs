@@ -1,6 +1,13 @@
+use std::pin::Pin;
+
 use async_trait::async_trait;
+use futures::Stream;
 
-use crate::{chat::ChatResponse, error::LLMError, ToolCall};
+use crate::{
+    chat::{ChatOptions, ChatResponse},
+    error::LLMError,
+    ToolCall,
+};
 
 /// A request for text completion from an LLM provider.
 #[derive(Debug, Clone)]
@@ -92,6 +99,24 @@ impl CompletionRequestBuilder {
     }
 }
 
+/// A stream of text fragments returned by a streaming completion request.
+pub type CompletionStream = Pin<Box<dyn Stream<Item = Result<String, LLMError>> + Send>>;
+
+/// Keeps only the text fragments of a chat stream, for providers that implement
+/// streaming completions on top of their chat endpoint.
+#[cfg(any(feature = "openai", feature = "xai"))]
+pub(crate) fn text_stream(stream: crate::chat::ChatStream) -> CompletionStream {
+    use futures::StreamExt;
+
+    Box::pin(stream.filter_map(|chunk| async move {
+        match chunk {
+            Ok(crate::chat::StreamChunk::Text(text)) => Some(Ok(text)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }))
+}
+
 /// Trait for providers that support text completion requests.
 #[async_trait]
 pub trait CompletionProvider {
@@ -105,6 +130,51 @@ pub trait CompletionProvider {
     ///
     /// The generated completion text or an error
     async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError>;
+
+    /// Sends a completion request and streams the generated text as it arrives.
+    ///
+    /// Providers that do not support streaming completions return an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The completion request parameters
+    ///
+    /// # Returns
+    ///
+    /// A stream of generated text fragments or an error
+    async fn complete_stream(&self, _req: &CompletionRequest) -> Result<CompletionStream, LLMError>
+    where
+        Self: Sync,
+    {
+        Err(LLMError::ProviderError(
+            "Streaming completion is not supported by this provider".to_string(),
+        ))
+    }
+
+    /// Sends a completion request with per-request options.
+    ///
+    /// If the options carry a cancellation token and it is triggered before the
+    /// response arrives, the HTTP request is aborted and [`LLMError::Cancelled`] is
+    /// returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The completion request parameters
+    /// * `options` - Per-request options such as a cancellation token
+    ///
+    /// # Returns
+    ///
+    /// The generated completion text or an error
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError>
+    where
+        Self: Sync,
+    {
+        crate::chat::with_cancellation(options, self.complete(req)).await
+    }
 }
 
 impl std::fmt::Display for CompletionResponse {
@@ -0,0 +1,315 @@
+//! Provider configuration loaded from files or the environment.
+//!
+//! An [`LLMConfig`] describes a provider — backend, model, sampling parameters, structured
+//! output schema and tools — so applications can switch providers without recompiling.
+//! Configurations are read from JSON or YAML files with [`LLMBuilder::from_config_path`],
+//! or from `LLM_*` environment variables with [`LLMBuilder::from_env`].
+//!
+//! [`LLMConfig`]: crate::config::LLMConfig
+//! [`LLMBuilder::from_config_path`]: crate::builder::LLMBuilder::from_config_path
+//! [`LLMBuilder::from_env`]: crate::builder::LLMBuilder::from_env
+//!
+//! # Example
+//!
+//! ```
+//! use llm::builder::LLMBuilder;
+//!
+//! let path = std::env::temp_dir().join("llm-doc-config.yaml");
+//! std::fs::write(
+//!     &path,
+//!     "backend: openai\n\
+//!      model: gpt-4o-mini\n\
+//!      api_key: sk-...\n\
+//!      temperature: 0.2\n\
+//!      system: You are a terse assistant.\n",
+//! )
+//! .unwrap();
+//!
+//! let llm = LLMBuilder::from_config_path(&path).unwrap().build().unwrap();
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    builder::{LLMBackend, LLMBuilder},
+    chat::{ReasoningEffort, StructuredOutputFormat, Tool},
+    error::LLMError,
+};
+
+/// Configuration of a provider, as read from a config file.
+///
+/// Every field but `backend` is optional; unset fields keep the builder's defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LLMConfig {
+    /// Backend name, as accepted by [`LLMBackend::from_str`] (e.g. "openai")
+    pub backend: String,
+    /// Model identifier
+    pub model: Option<String>,
+    /// API key
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key, used when `api_key` is not set
+    pub api_key_env: Option<String>,
+    /// Base URL for API requests
+    pub base_url: Option<String>,
+    /// Maximum tokens to generate
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Top-p (nucleus) sampling parameter
+    pub top_p: Option<f32>,
+    /// Top-k sampling parameter
+    pub top_k: Option<u32>,
+    /// Number of candidate replies to generate per request
+    pub n: Option<u32>,
+    /// System prompt
+    pub system: Option<String>,
+    /// Request timeout in seconds
+    pub timeout_seconds: Option<u64>,
+    /// Whether to stream responses
+    pub stream: Option<bool>,
+    /// Whether to enable reasoning
+    pub reasoning: Option<bool>,
+    /// Reasoning effort ("low", "medium" or "high")
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Token budget for reasoning
+    pub reasoning_budget_tokens: Option<u32>,
+    /// JSON schema for structured output
+    pub schema: Option<StructuredOutputFormat>,
+    /// Function tools, in OpenAI's tool format
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+    /// Extra HTTP headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// URL of the proxy requests are sent through
+    pub proxy: Option<String>,
+    /// Hosts reached without the proxy
+    pub no_proxy: Option<String>,
+    /// Extra parameters merged into the chat request bodies
+    #[serde(default)]
+    pub extra_params: Map<String, Value>,
+}
+
+impl LLMConfig {
+    /// Reads a configuration from a file.
+    ///
+    /// The format is chosen from the extension: `.json` for JSON, `.yaml` or `.yml` for
+    /// YAML.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, LLMError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            LLMError::InvalidRequest(format!(
+                "Failed to read config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => {
+                serde_json::from_str(&content).map_err(|e| LLMError::JsonError(e.to_string()))
+            }
+            Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(|e| {
+                LLMError::InvalidRequest(format!("Invalid YAML config {}: {}", path.display(), e))
+            }),
+            _ => Err(LLMError::InvalidRequest(format!(
+                "Unsupported config file {}: use a .json, .yaml or .yml file",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Reads a configuration from environment variables.
+    ///
+    /// | Variable | Setting |
+    /// |----------|---------|
+    /// | `LLM_BACKEND` | Backend name (required) |
+    /// | `LLM_MODEL` | Model identifier |
+    /// | `LLM_API_KEY` | API key, falling back to the provider's own variable (e.g. `OPENAI_API_KEY`) |
+    /// | `LLM_BASE_URL` | Base URL for API requests |
+    /// | `LLM_MAX_TOKENS` | Maximum tokens to generate |
+    /// | `LLM_TEMPERATURE` | Sampling temperature |
+    /// | `LLM_TOP_P` | Top-p sampling parameter |
+    /// | `LLM_TOP_K` | Top-k sampling parameter |
+    /// | `LLM_SYSTEM` | System prompt |
+    /// | `LLM_TIMEOUT_SECONDS` | Request timeout in seconds |
+    /// | `LLM_STREAM` | Whether to stream responses (`true` or `false`) |
+    /// | `LLM_PROXY` | URL of the proxy requests are sent through |
+    /// | `LLM_NO_PROXY` | Hosts reached without the proxy |
+    ///
+    /// Empty variables are treated as unset.
+    pub fn from_env() -> Result<Self, LLMError> {
+        let backend = env_var("LLM_BACKEND").ok_or_else(|| {
+            LLMError::InvalidRequest("Environment variable LLM_BACKEND is not set".to_string())
+        })?;
+        let api_key = env_var("LLM_API_KEY").or_else(|| {
+            LLMBackend::from_str(&backend)
+                .ok()
+                .and_then(provider_api_key_var)
+                .and_then(env_var)
+        });
+
+        Ok(Self {
+            backend,
+            model: env_var("LLM_MODEL"),
+            api_key,
+            base_url: env_var("LLM_BASE_URL"),
+            max_tokens: parse_env_var("LLM_MAX_TOKENS")?,
+            temperature: parse_env_var("LLM_TEMPERATURE")?,
+            top_p: parse_env_var("LLM_TOP_P")?,
+            top_k: parse_env_var("LLM_TOP_K")?,
+            system: env_var("LLM_SYSTEM"),
+            timeout_seconds: parse_env_var("LLM_TIMEOUT_SECONDS")?,
+            stream: parse_env_var("LLM_STREAM")?,
+            proxy: env_var("LLM_PROXY"),
+            no_proxy: env_var("LLM_NO_PROXY"),
+            ..Default::default()
+        })
+    }
+}
+
+/// Returns the variable a provider's API key is conventionally stored in.
+fn provider_api_key_var(backend: LLMBackend) -> Option<&'static str> {
+    match backend {
+        LLMBackend::OpenAI => Some("OPENAI_API_KEY"),
+        LLMBackend::Anthropic => Some("ANTHROPIC_API_KEY"),
+        LLMBackend::DeepSeek => Some("DEEPSEEK_API_KEY"),
+        LLMBackend::XAI => Some("XAI_API_KEY"),
+        LLMBackend::Google => Some("GOOGLE_API_KEY"),
+        LLMBackend::Groq => Some("GROQ_API_KEY"),
+        LLMBackend::AzureOpenAI => Some("AZURE_OPENAI_API_KEY"),
+        LLMBackend::Mistral => Some("MISTRAL_API_KEY"),
+        LLMBackend::Cohere => Some("COHERE_API_KEY"),
+        LLMBackend::Fireworks => Some("FIREWORKS_API_KEY"),
+        LLMBackend::Perplexity => Some("PERPLEXITY_API_KEY"),
+        LLMBackend::Nvidia => Some("NVIDIA_API_KEY"),
+        LLMBackend::OpenAICompatible => Some("OPENAI_COMPATIBLE_API_KEY"),
+        LLMBackend::Qwen => Some("DASHSCOPE_API_KEY"),
+        LLMBackend::Moonshot => Some("MOONSHOT_API_KEY"),
+        LLMBackend::Voyage => Some("VOYAGE_API_KEY"),
+        LLMBackend::Jina => Some("JINA_API_KEY"),
+        LLMBackend::Ollama | LLMBackend::Phind | LLMBackend::Mock | LLMBackend::LMStudio => None,
+    }
+}
+
+/// Reads an environment variable, treating empty values as unset.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Reads and parses an environment variable.
+fn parse_env_var<T: FromStr>(name: &str) -> Result<Option<T>, LLMError>
+where
+    T::Err: std::fmt::Display,
+{
+    env_var(name)
+        .map(|value| {
+            value.parse().map_err(|e| {
+                LLMError::InvalidRequest(format!("Invalid value '{}' for {}: {}", value, name, e))
+            })
+        })
+        .transpose()
+}
+
+impl LLMBuilder {
+    /// Creates a builder from a configuration.
+    ///
+    /// The builder can be refined further, e.g. with validators or tool handlers, before
+    /// calling [`LLMBuilder::build`].
+    pub fn from_config(config: LLMConfig) -> Result<Self, LLMError> {
+        let mut builder = LLMBuilder::new().backend(LLMBackend::from_str(&config.backend)?);
+
+        let api_key = match (config.api_key, config.api_key_env) {
+            (Some(key), _) => Some(key),
+            (None, Some(var)) => Some(std::env::var(&var).map_err(|_| {
+                LLMError::AuthError(format!("Environment variable {} is not set", var))
+            })?),
+            (None, None) => None,
+        };
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+        if let Some(model) = config.model {
+            builder = builder.model(model);
+        }
+        if let Some(base_url) = config.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(max_tokens) = config.max_tokens {
+            builder = builder.max_tokens(max_tokens);
+        }
+        if let Some(temperature) = config.temperature {
+            builder = builder.temperature(temperature);
+        }
+        if let Some(top_p) = config.top_p {
+            builder = builder.top_p(top_p);
+        }
+        if let Some(top_k) = config.top_k {
+            builder = builder.top_k(top_k);
+        }
+        if let Some(n) = config.n {
+            builder = builder.n(n);
+        }
+        if let Some(system) = config.system {
+            builder = builder.system(system);
+        }
+        if let Some(timeout_seconds) = config.timeout_seconds {
+            builder = builder.timeout_seconds(timeout_seconds);
+        }
+        if let Some(stream) = config.stream {
+            builder = builder.stream(stream);
+        }
+        if let Some(reasoning) = config.reasoning {
+            builder = builder.reasoning(reasoning);
+        }
+        if let Some(effort) = config.reasoning_effort {
+            builder = builder.reasoning_effort(effort);
+        }
+        if let Some(budget) = config.reasoning_budget_tokens {
+            builder = builder.reasoning_budget_tokens(budget);
+        }
+        if let Some(schema) = config.schema {
+            builder = builder.schema(schema);
+        }
+        if !config.tools.is_empty() {
+            builder = builder.tools(config.tools);
+        }
+        for (key, value) in config.headers {
+            builder = builder.header(key, value);
+        }
+        if let Some(proxy) = config.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(no_proxy) = config.no_proxy {
+            builder = builder.no_proxy(no_proxy);
+        }
+        for (key, value) in config.extra_params {
+            builder = builder.extra_param(key, value);
+        }
+        Ok(builder)
+    }
+
+    /// Creates a builder from a JSON or YAML config file. See [`LLMConfig::from_path`].
+    pub fn from_config_path(path: impl AsRef<Path>) -> Result<Self, LLMError> {
+        Self::from_config(LLMConfig::from_path(path)?)
+    }
+
+    /// Creates a builder from `LLM_*` environment variables. See [`LLMConfig::from_env`].
+    ///
+    /// ```no_run
+    /// use llm::builder::LLMBuilder;
+    ///
+    /// // LLM_BACKEND=anthropic LLM_MODEL=claude-3-5-haiku-latest ANTHROPIC_API_KEY=...
+    /// let llm = LLMBuilder::from_env()?.build()?;
+    /// # Ok::<(), llm::error::LLMError>(())
+    /// ```
+    pub fn from_env() -> Result<Self, LLMError> {
+        Self::from_config(LLMConfig::from_env()?)
+    }
+}
@@ -0,0 +1,227 @@
+//! A wrapper continuing chat replies cut short by the token limit.
+//!
+//! When a reply ends with [`FinishReason::Length`], a [`ContinuingLLM`] sends the partial
+//! reply back with a request to continue, and stitches the parts into a single response.
+//! Set it up with [`crate::builder::LLMBuilder::max_continuations`].
+//!
+//! [`FinishReason::Length`]: crate::chat::FinishReason::Length
+//! [`ContinuingLLM`]: crate::continuing_llm::ContinuingLLM
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::ChatMessage;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .max_tokens(1024)
+//!     .max_continuations(3)
+//!     .build()?;
+//!
+//! let essay = llm
+//!     .chat(&[ChatMessage::user().content("Write a 3000 word essay on rivers").build()])
+//!     .await?;
+//! println!("{}", essay);
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+
+use crate::chat::{
+    ChatChoice, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, FinishReason,
+    ResponseSnapshot, Tool, Usage,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// Message asking the model to resume a truncated reply.
+const CONTINUE_PROMPT: &str =
+    "Continue exactly where you left off, without repeating anything you already wrote.";
+
+/// A wrapper around an LLM provider re-issuing chat requests whose reply was truncated.
+///
+/// The combined response has the text of every part, the summed usage, and the finish
+/// reason, metadata and raw response of the last part. Replies requesting tool calls are
+/// not continued, nor are streams.
+pub struct ContinuingLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Most follow-up requests per reply
+    max_continuations: usize,
+}
+
+impl ContinuingLLM {
+    /// Wraps a provider, continuing truncated replies up to `max_continuations` times.
+    pub fn new(inner: Box<dyn LLMProvider>, max_continuations: usize) -> Self {
+        Self {
+            inner,
+            max_continuations,
+        }
+    }
+
+    /// Sends a chat request, continuing its reply while it is truncated.
+    async fn continued_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        // Responses are not `Send`, so each part is copied before the next request.
+        let mut last = {
+            let response = self
+                .inner
+                .chat_with_options(messages, tools, options)
+                .await?;
+            if !is_truncated(response.as_ref()) || self.max_continuations == 0 {
+                return Ok(response);
+            }
+            ResponseSnapshot::from_response(response.as_ref())
+        };
+        let mut text = last.text.clone().unwrap_or_default();
+        let mut usage = last.usage.clone();
+        let mut history = messages.to_vec();
+        for _ in 0..self.max_continuations {
+            if !is_truncated(&last) {
+                break;
+            }
+            history.push(
+                ChatMessage::assistant()
+                    .content(last.text.clone().unwrap_or_default())
+                    .build(),
+            );
+            history.push(ChatMessage::user().content(CONTINUE_PROMPT).build());
+            tracing::debug!(length = text.len(), "continuing truncated reply");
+
+            last = ResponseSnapshot::from_response(
+                self.inner
+                    .chat_with_options(&history, tools, options)
+                    .await?
+                    .as_ref(),
+            );
+            text.push_str(last.text.as_deref().unwrap_or_default());
+            usage = match (usage, last.usage.clone()) {
+                (Some(total), Some(part)) => Some(Usage {
+                    prompt_tokens: total.prompt_tokens + part.prompt_tokens,
+                    completion_tokens: total.completion_tokens + part.completion_tokens,
+                    total_tokens: total.total_tokens + part.total_tokens,
+                    cached_prompt_tokens: match (
+                        total.cached_prompt_tokens,
+                        part.cached_prompt_tokens,
+                    ) {
+                        (Some(total), Some(part)) => Some(total + part),
+                        (total, part) => total.or(part),
+                    },
+                }),
+                (total, part) => total.or(part),
+            };
+        }
+
+        Ok(Box::new(ResponseSnapshot {
+            choices: vec![ChatChoice {
+                text: Some(text.clone()),
+                tool_calls: None,
+                finish_reason: last.finish_reason.clone(),
+                refusal: last.refusal.clone(),
+                logprobs: None,
+            }],
+            display: text.clone(),
+            text: Some(text),
+            usage,
+            logprobs: None,
+            ..last
+        }))
+    }
+}
+
+/// Returns whether a reply was cut short by the token limit, with text to continue.
+fn is_truncated(response: &dyn ChatResponse) -> bool {
+    response.finish_reason() == Some(FinishReason::Length)
+        && response.tool_calls().is_none_or(|calls| calls.is_empty())
+        && response.text().is_some_and(|text| !text.is_empty())
+}
+
+#[async_trait]
+impl LLMProvider for ContinuingLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for ContinuingLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.continued_chat(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.continued_chat(messages, tools, options).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner.chat_stream_with_tools(messages, tools).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for ContinuingLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ContinuingLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
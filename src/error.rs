@@ -1,7 +1,8 @@
 use std::fmt;
+use std::time::Duration;
 
 /// Error types that can occur when interacting with LLM providers.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum LLMError {
     /// HTTP request/response errors
     HttpError(String),
@@ -13,6 +14,26 @@ pub enum LLMError {
     ProviderError(String),
     /// JSON serialization/deserialization errors
     JsonError(String),
+    /// The request was cancelled before it completed
+    Cancelled,
+    /// The provider rejected the request because a rate limit was exceeded
+    RateLimited {
+        /// The provider's error message
+        message: String,
+        /// How long the provider asked to wait before retrying, if it said
+        retry_after: Option<Duration>,
+    },
+    /// The model declined to answer or the provider blocked the request
+    Refused(String),
+    /// The response did not deserialize into the requested structured output
+    StructuredOutputError {
+        /// Why deserialization failed
+        message: String,
+        /// The response text as received
+        text: String,
+    },
+    /// A spending limit was reached
+    BudgetExceeded(String),
 }
 
 impl fmt::Display for LLMError {
@@ -23,12 +44,53 @@ impl fmt::Display for LLMError {
             LLMError::InvalidRequest(e) => write!(f, "Invalid Request: {}", e),
             LLMError::ProviderError(e) => write!(f, "Provider Error: {}", e),
             LLMError::JsonError(e) => write!(f, "JSON Parse Error: {}", e),
+            LLMError::Cancelled => write!(f, "Request cancelled"),
+            LLMError::RateLimited { message, .. } => write!(f, "Rate Limited: {}", message),
+            LLMError::Refused(e) => write!(f, "Refused: {}", e),
+            LLMError::StructuredOutputError { message, .. } => {
+                write!(f, "Structured Output Error: {}", message)
+            }
+            LLMError::BudgetExceeded(e) => write!(f, "Budget Exceeded: {}", e),
         }
     }
 }
 
 impl std::error::Error for LLMError {}
 
+impl LLMError {
+    /// Returns a short, stable name for the class of the error (e.g. "rate_limited"),
+    /// suitable as a metric label or trace attribute.
+    ///
+    /// ```
+    /// use llm::error::LLMError;
+    ///
+    /// assert_eq!(LLMError::AuthError("invalid key".to_string()).kind(), "auth");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LLMError::HttpError(_) => "http",
+            LLMError::AuthError(_) => "auth",
+            LLMError::InvalidRequest(_) => "invalid_request",
+            LLMError::ProviderError(_) => "provider",
+            LLMError::JsonError(_) => "json",
+            LLMError::Cancelled => "cancelled",
+            LLMError::RateLimited { .. } => "rate_limited",
+            LLMError::Refused(_) => "refused",
+            LLMError::StructuredOutputError { .. } => "structured_output",
+            LLMError::BudgetExceeded(_) => "budget_exceeded",
+        }
+    }
+
+    /// Returns whether the error shows the provider itself failing, rather than a problem
+    /// with the request.
+    pub(crate) fn is_provider_failure(&self) -> bool {
+        matches!(
+            self,
+            LLMError::HttpError(_) | LLMError::ProviderError(_) | LLMError::RateLimited { .. }
+        )
+    }
+}
+
 /// Converts reqwest HTTP errors into LlmErrors
 impl From<reqwest::Error> for LLMError {
     fn from(err: reqwest::Error) -> Self {
@@ -0,0 +1,392 @@
+//! Fallback across an ordered list of providers.
+//!
+//! A [`FallbackLLM`] sends each request to its first provider and moves on to the next one
+//! whenever a provider fails, be it for an authentication error, a rate limit or a
+//! timeout. Errors in the request itself, which every provider would reject alike, are
+//! returned right away. Chat responses report the name of the provider that answered in
+//! [`ResponseMetadata::provider`].
+//!
+//! [`FallbackLLM`]: crate::fallback::FallbackLLM
+//! [`ResponseMetadata::provider`]: crate::chat::ResponseMetadata::provider
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::fallback::FallbackLLM;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = FallbackLLM::new()
+//!     .provider(
+//!         "openai",
+//!         LLMBuilder::new()
+//!             .backend(LLMBackend::OpenAI)
+//!             .api_key("sk-...")
+//!             .build()?,
+//!     )
+//!     .provider(
+//!         "anthropic",
+//!         LLMBuilder::new()
+//!             .backend(LLMBackend::Anthropic)
+//!             .api_key("sk-ant-...")
+//!             .build()?,
+//!     );
+//!
+//! let reply = llm
+//!     .chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! println!("{} answered: {}", reply.metadata().provider.unwrap_or_default(), reply);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+
+use async_trait::async_trait;
+
+use crate::chat::{
+    AudioReply, ChatChoice, ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream,
+    Citation, FinishReason, Grounding, ResponseMetadata, SafetyRating, TokenLogprob, Tool, Usage,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::{LLMProvider, ToolCall};
+
+/// Returns whether another provider may succeed where one failed with this error.
+fn falls_back(error: &LLMError) -> bool {
+    error.is_provider_failure() || matches!(error, LLMError::AuthError(_))
+}
+
+/// A provider trying a list of providers in order until one of them succeeds.
+///
+/// HTTP errors, authentication errors, rate limits and provider errors move on to the next
+/// provider; if they all fail, the last error is returned. Any other error, such as an
+/// invalid request, a response that is not the requested structured output or a
+/// cancellation, is returned right away. Streams move on only if they fail to open.
+#[derive(Default)]
+pub struct FallbackLLM {
+    /// The providers to try, in order, with their names
+    providers: Vec<(String, Box<dyn LLMProvider>)>,
+}
+
+impl FallbackLLM {
+    /// Creates a fallback chain with no providers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a provider to the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name reported in the metadata of the responses it serves
+    /// * `provider` - The provider to try after the ones already added
+    pub fn provider(mut self, name: impl Into<String>, provider: Box<dyn LLMProvider>) -> Self {
+        self.providers.push((name.into(), provider));
+        self
+    }
+
+    /// Returns the names of the providers, in the order they are tried.
+    pub fn names(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Sends a request to each provider in turn, returning the first success and the name
+    /// of the provider that produced it.
+    async fn call<'a, T, F, Fut>(&'a self, request: F) -> Result<(&'a str, T), LLMError>
+    where
+        F: Fn(&'a dyn LLMProvider) -> Fut + Send,
+        Fut: Future<Output = Result<T, LLMError>> + Send + 'a,
+    {
+        let mut last_error = None;
+        for (name, provider) in &self.providers {
+            match request(provider.as_ref()).await {
+                Ok(value) => return Ok((name, value)),
+                Err(e) if !falls_back(&e) => return Err(e),
+                Err(e) => {
+                    tracing::warn!(provider = name.as_str(), error = %e, "provider failed");
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            LLMError::InvalidRequest("No providers in the fallback chain".to_string())
+        }))
+    }
+
+    /// Sends a chat request, tagging the response with the provider that answered.
+    async fn call_chat<'a, F, Fut>(&'a self, request: F) -> Result<Box<dyn ChatResponse>, LLMError>
+    where
+        F: Fn(&'a dyn LLMProvider) -> Fut + Send,
+        Fut: Future<Output = Result<Box<dyn ChatResponse>, LLMError>> + Send + 'a,
+    {
+        let (name, response) = self.call(request).await?;
+        Ok(Box::new(NamedResponse::new(response, name)))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for FallbackLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.providers.first()?.1.tools()
+    }
+
+    /// Returns the status of the first healthy provider, or of the last one if none is.
+    async fn validate(&self) -> HealthStatus {
+        let mut last_status = None;
+        for (_, provider) in &self.providers {
+            let status = provider.validate().await;
+            if status.is_healthy() {
+                return status;
+            }
+            last_status = Some(status);
+        }
+        last_status.unwrap_or_else(|| {
+            HealthStatus::Unavailable("No providers in the fallback chain".to_string())
+        })
+    }
+}
+
+#[async_trait]
+impl ChatProvider for FallbackLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call_chat(|llm| llm.chat(messages)).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call_chat(|llm| llm.chat_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call_chat(|llm| llm.chat_with_options(messages, tools, options))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        let (_, stream) = self
+            .call(|llm| llm.chat_stream_with_tools(messages, tools))
+            .await?;
+        Ok(stream)
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        let (_, stream) = self
+            .call(|llm| llm.chat_stream_with_options(messages, tools, options))
+            .await?;
+        Ok(stream)
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for FallbackLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        let (_, response) = self.call(|llm| llm.complete(req)).await?;
+        Ok(response)
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        let (_, stream) = self.call(|llm| llm.complete_stream(req)).await?;
+        Ok(stream)
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        let (_, response) = self
+            .call(|llm| llm.complete_with_options(req, options))
+            .await?;
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for FallbackLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        let (_, embeddings) = self.call(|llm| llm.embed(input.clone())).await?;
+        Ok(embeddings)
+    }
+}
+
+/// A chat response tagged with the name of the provider that served it.
+#[derive(Debug)]
+pub(crate) struct NamedResponse {
+    inner: Box<dyn ChatResponse>,
+    provider: String,
+}
+
+impl NamedResponse {
+    pub(crate) fn new(inner: Box<dyn ChatResponse>, provider: impl Into<String>) -> Self {
+        Self {
+            inner,
+            provider: provider.into(),
+        }
+    }
+}
+
+impl fmt::Display for NamedResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl ChatResponse for NamedResponse {
+    fn text(&self) -> Option<String> {
+        self.inner.text()
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        self.inner.tool_calls()
+    }
+
+    fn thinking(&self) -> Option<String> {
+        self.inner.thinking()
+    }
+
+    fn finish_reason(&self) -> Option<FinishReason> {
+        self.inner.finish_reason()
+    }
+
+    fn raw(&self) -> Option<&serde_json::Value> {
+        self.inner.raw()
+    }
+
+    fn refusal(&self) -> Option<String> {
+        self.inner.refusal()
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.inner.usage()
+    }
+
+    fn logprobs(&self) -> Option<Vec<TokenLogprob>> {
+        self.inner.logprobs()
+    }
+
+    fn citations(&self) -> Option<Vec<Citation>> {
+        self.inner.citations()
+    }
+
+    fn audio(&self) -> Option<AudioReply> {
+        self.inner.audio()
+    }
+
+    fn safety_ratings(&self) -> Option<Vec<SafetyRating>> {
+        self.inner.safety_ratings()
+    }
+
+    fn grounding(&self) -> Option<Grounding> {
+        self.inner.grounding()
+    }
+
+    fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: Some(self.provider.clone()),
+            ..self.inner.metadata()
+        }
+    }
+
+    fn choices(&self) -> Vec<ChatChoice> {
+        self.inner.choices()
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockLLM;
+
+    fn messages() -> Vec<ChatMessage> {
+        vec![ChatMessage::user().content("Hello").build()]
+    }
+
+    fn chain(primary: &MockLLM, secondary: &MockLLM) -> FallbackLLM {
+        FallbackLLM::new()
+            .provider("primary", Box::new(primary.clone()))
+            .provider("secondary", Box::new(secondary.clone()))
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_provider_failures() {
+        for error in [
+            LLMError::HttpError("timed out".to_string()),
+            LLMError::AuthError("invalid key".to_string()),
+            LLMError::ProviderError("overloaded".to_string()),
+            LLMError::RateLimited {
+                message: "slow down".to_string(),
+                retry_after: None,
+            },
+        ] {
+            let primary = MockLLM::new().fail(error);
+            let secondary = MockLLM::new().reply("from secondary");
+            let response = chain(&primary, &secondary).chat(&messages()).await.unwrap();
+            assert_eq!(response.text().as_deref(), Some("from secondary"));
+            assert_eq!(response.metadata().provider.as_deref(), Some("secondary"));
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_request_errors_without_falling_back() {
+        for error in [
+            LLMError::InvalidRequest("bad tool schema".to_string()),
+            LLMError::JsonError("unexpected token".to_string()),
+            LLMError::StructuredOutputError {
+                message: "missing field".to_string(),
+                text: "{}".to_string(),
+            },
+            LLMError::Cancelled,
+        ] {
+            let kind = error.kind();
+            let primary = MockLLM::new().fail(error);
+            let secondary = MockLLM::new().reply("from secondary");
+            let error = chain(&primary, &secondary)
+                .chat(&messages())
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(error.kind(), kind);
+            assert!(secondary.calls().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_error_when_every_provider_fails() {
+        let primary = MockLLM::new().fail(LLMError::HttpError("timed out".to_string()));
+        let secondary = MockLLM::new().fail(LLMError::ProviderError("overloaded".to_string()));
+        let error = chain(&primary, &secondary)
+            .chat(&messages())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), "provider");
+    }
+}
@@ -0,0 +1,65 @@
+//! Health checks verifying a provider's credentials and connectivity.
+//!
+//! [`crate::LLMProvider::validate`] sends a cheap authenticated request, such as listing
+//! the available models, so services can check their configuration at startup instead of
+//! failing on the first user request.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::health::HealthStatus;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .build()?;
+//!
+//! match llm.validate().await {
+//!     HealthStatus::Healthy { latency } => println!("OpenAI is up ({:?})", latency),
+//!     HealthStatus::Unauthorized(message) => panic!("Invalid OpenAI key: {}", message),
+//!     status => eprintln!("OpenAI is degraded: {:?}", status),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use crate::error::LLMError;
+
+/// Outcome of a provider health check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// The provider answered an authenticated request
+    Healthy {
+        /// How long the provider took to answer
+        latency: Duration,
+    },
+    /// The provider rejected the credentials
+    Unauthorized(String),
+    /// The credentials are valid but a rate limit was exceeded
+    RateLimited {
+        /// How long the provider asked to wait, if it said
+        retry_after: Option<Duration>,
+    },
+    /// The provider could not be reached, or failed to answer
+    Unavailable(String),
+}
+
+impl HealthStatus {
+    /// Returns whether the provider can serve requests right away.
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, HealthStatus::Healthy { .. })
+    }
+
+    /// Classifies the error a health check request failed with.
+    pub(crate) fn from_error(error: LLMError) -> Self {
+        match error {
+            LLMError::AuthError(message) => HealthStatus::Unauthorized(message),
+            LLMError::RateLimited { retry_after, .. } => HealthStatus::RateLimited { retry_after },
+            error => HealthStatus::Unavailable(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! Lifecycle hooks called around each chat request.
+//!
+//! [`Hooks`] hold callbacks run before each request is sent, after each response arrives
+//! and when a request fails. They receive the provider, model and messages of the request,
+//! and request hooks may rewrite the messages, which makes them suitable for audit logging,
+//! prompt rewriting or alerting without touching the backends. Register them when building
+//! a provider with [`crate::builder::LLMBuilder::on_request`],
+//! [`crate::builder::LLMBuilder::on_response`] and [`crate::builder::LLMBuilder::on_error`],
+//! or wrap an existing provider in a [`HookedLLM`].
+//!
+//! [`Hooks`]: crate::hooks::Hooks
+//! [`HookedLLM`]: crate::hooks::HookedLLM
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .on_request(|event| {
+//!         // Keep email addresses out of the prompts.
+//!         for message in event.messages.iter_mut() {
+//!             message.content = message.content.replace("alice@example.com", "[email]");
+//!         }
+//!     })
+//!     .on_response(|event| {
+//!         println!("{} answered in {:?}", event.model, event.latency);
+//!     })
+//!     .on_error(|event| eprintln!("{} failed: {}", event.provider, event.error))
+//!     .build()?;
+//!
+//! llm.chat(&[ChatMessage::user().content("Write to alice@example.com").build()])
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// A chat request about to be sent.
+#[derive(Debug)]
+pub struct RequestEvent<'a> {
+    /// Name of the provider (e.g. "openai")
+    pub provider: &'a str,
+    /// Model the provider was configured with
+    pub model: &'a str,
+    /// Messages of the request, which the hook may rewrite
+    pub messages: Vec<ChatMessage>,
+    /// Tools offered to the model
+    pub tools: Option<&'a [Tool]>,
+    /// Whether the reply is streamed
+    pub stream: bool,
+}
+
+/// A chat response that arrived.
+#[derive(Debug)]
+pub struct ResponseEvent<'a> {
+    /// Name of the provider (e.g. "openai")
+    pub provider: &'a str,
+    /// Model the provider was configured with
+    pub model: &'a str,
+    /// Messages the request was sent with
+    pub messages: &'a [ChatMessage],
+    /// The response
+    pub response: &'a dyn ChatResponse,
+    /// Time the request took
+    pub latency: Duration,
+}
+
+/// A chat request that failed.
+#[derive(Debug)]
+pub struct ErrorEvent<'a> {
+    /// Name of the provider (e.g. "openai")
+    pub provider: &'a str,
+    /// Model the provider was configured with
+    pub model: &'a str,
+    /// Messages the request was sent with
+    pub messages: &'a [ChatMessage],
+    /// The error the request failed with
+    pub error: &'a LLMError,
+    /// Time until the request failed
+    pub latency: Duration,
+}
+
+type RequestHook = Arc<dyn Fn(&mut RequestEvent) + Send + Sync>;
+type ResponseHook = Arc<dyn Fn(&ResponseEvent) + Send + Sync>;
+type ErrorHook = Arc<dyn Fn(&ErrorEvent) + Send + Sync>;
+
+/// Callbacks run around each chat request, in the order they were added.
+#[derive(Clone, Default)]
+pub struct Hooks {
+    on_request: Vec<RequestHook>,
+    on_response: Vec<ResponseHook>,
+    on_error: Vec<ErrorHook>,
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("on_request", &self.on_request.len())
+            .field("on_response", &self.on_response.len())
+            .field("on_error", &self.on_error.len())
+            .finish()
+    }
+}
+
+impl Hooks {
+    /// Creates an empty set of hooks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a callback run before each request is sent, which may rewrite its messages.
+    pub fn on_request(mut self, hook: impl Fn(&mut RequestEvent) + Send + Sync + 'static) -> Self {
+        self.on_request.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a callback run after each response arrives.
+    pub fn on_response(mut self, hook: impl Fn(&ResponseEvent) + Send + Sync + 'static) -> Self {
+        self.on_response.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a callback run when a request fails.
+    pub fn on_error(mut self, hook: impl Fn(&ErrorEvent) + Send + Sync + 'static) -> Self {
+        self.on_error.push(Arc::new(hook));
+        self
+    }
+
+    /// Returns whether no callback was added.
+    pub fn is_empty(&self) -> bool {
+        self.on_request.is_empty() && self.on_response.is_empty() && self.on_error.is_empty()
+    }
+}
+
+/// A wrapper around an LLM provider running hooks around each chat request.
+///
+/// Streams run the request hooks, and the error hooks if they fail to open; their chunks
+/// are not reported. Completions and embeddings are passed through.
+pub struct HookedLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Callbacks run around each request
+    hooks: Hooks,
+    /// Name of the wrapped provider
+    provider: String,
+    /// Model the provider was configured with
+    model: String,
+}
+
+impl HookedLLM {
+    /// Wraps a provider, running hooks around its chat requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to hook
+    /// * `hooks` - Callbacks run around each request
+    /// * `provider` - Name reported to the hooks (e.g. "openai")
+    /// * `model` - Model reported to the hooks
+    pub fn new(
+        inner: Box<dyn LLMProvider>,
+        hooks: Hooks,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            hooks,
+            provider: provider.into(),
+            model: model.into(),
+        }
+    }
+
+    /// Runs the request hooks, returning the messages to send.
+    fn before(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> Vec<ChatMessage> {
+        let mut event = RequestEvent {
+            provider: &self.provider,
+            model: &self.model,
+            messages: messages.to_vec(),
+            tools,
+            stream,
+        };
+        for hook in &self.hooks.on_request {
+            hook(&mut event);
+        }
+        event.messages
+    }
+
+    /// Runs the error hooks.
+    fn failed(&self, messages: &[ChatMessage], error: &LLMError, start: Instant) {
+        let event = ErrorEvent {
+            provider: &self.provider,
+            model: &self.model,
+            messages,
+            error,
+            latency: start.elapsed(),
+        };
+        for hook in &self.hooks.on_error {
+            hook(&event);
+        }
+    }
+
+    /// Sends a chat request between the hooks.
+    async fn hooked_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: Option<&ChatOptions>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let messages = self.before(messages, tools, false);
+        let start = Instant::now();
+        let result = match options {
+            Some(options) => {
+                self.inner
+                    .chat_with_options(&messages, tools, options)
+                    .await
+            }
+            None => self.inner.chat_with_tools(&messages, tools).await,
+        };
+        self.answered(&messages, &result, start);
+        result
+    }
+
+    /// Runs the response or error hooks on the result of a chat request.
+    fn answered(
+        &self,
+        messages: &[ChatMessage],
+        result: &Result<Box<dyn ChatResponse>, LLMError>,
+        start: Instant,
+    ) {
+        match result {
+            Ok(response) => {
+                let event = ResponseEvent {
+                    provider: &self.provider,
+                    model: &self.model,
+                    messages,
+                    response: response.as_ref(),
+                    latency: start.elapsed(),
+                };
+                for hook in &self.hooks.on_response {
+                    hook(&event);
+                }
+            }
+            Err(e) => self.failed(messages, e, start),
+        }
+    }
+
+    /// Opens a chat stream after the request hooks.
+    async fn hooked_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: Option<&ChatOptions>,
+    ) -> Result<ChatStream, LLMError> {
+        let messages = self.before(messages, tools, true);
+        let start = Instant::now();
+        let result = match options {
+            Some(options) => {
+                self.inner
+                    .chat_stream_with_options(&messages, tools, options)
+                    .await
+            }
+            None => self.inner.chat_stream_with_tools(&messages, tools).await,
+        };
+        if let Err(e) = &result {
+            self.failed(&messages, e, start);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl LLMProvider for HookedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for HookedLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let messages = self.before(messages, None, false);
+        let start = Instant::now();
+        let result = self.inner.chat(&messages).await;
+        self.answered(&messages, &result, start);
+        result
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.hooked_chat(messages, tools, None).await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.hooked_chat(messages, tools, Some(options)).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.hooked_stream(messages, tools, None).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.hooked_stream(messages, tools, Some(options)).await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for HookedLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HookedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
@@ -37,12 +37,89 @@ pub mod completion;
 /// Vector embeddings generation for text
 pub mod embedding;
 
+/// Reranking of documents by relevance to a query
+pub mod rerank;
+
+/// Screening of texts for harmful content
+pub mod moderation;
+
 /// Error types and handling
 pub mod error;
 
 /// Validation wrapper for LLM providers with retry capabilities
 pub mod validated_llm;
 
+/// Wrapper for LLM providers that executes tool calls automatically
+pub mod tool_enabled_llm;
+
+/// Wrapper keeping chat requests within the model's context window
+pub mod truncating_llm;
+
+/// Wrapper continuing chat replies cut short by the token limit
+pub mod continuing_llm;
+
+/// Lifecycle hooks called around each chat request
+pub mod hooks;
+
+/// Composable middleware for chat requests
+pub mod middleware;
+
+/// Recording and replay of provider interactions for tests
+pub mod replay;
+
+/// Token usage accounting across providers
+pub mod usage;
+
+/// Provider configuration loaded from JSON or YAML files
+pub mod config;
+
+/// Retry policies for transient request failures
+pub mod retry;
+
+/// Debug logging of HTTP exchanges with secrets redacted
+pub mod logging;
+
+/// Circuit breaker failing fast while a provider keeps failing
+pub mod circuit_breaker;
+
+/// Fallback across an ordered list of providers
+pub mod fallback;
+
+/// Load balancing across several instances of a provider
+pub mod load_balancer;
+
+/// Coalescing of identical concurrent chat requests
+pub mod coalesce;
+
+/// Caching of chat responses
+pub mod cache;
+
+/// Cost estimation from token usage
+pub mod pricing;
+
+/// Spending limits for providers
+pub mod budget;
+
+/// Local token counting
+pub mod tokens;
+
+/// Registry of known models and their capabilities
+pub mod models;
+
+/// Helpers for testing code that consumes chat responses
+pub mod testing;
+
+/// Tracing of provider requests following the OpenTelemetry GenAI semantic conventions
+#[cfg(feature = "otel")]
+pub mod telemetry;
+
+/// Prometheus metrics of provider requests
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+/// Health checks verifying a provider's credentials and connectivity
+pub mod health;
+
 /// Evaluator for LLM providers
 pub mod evaluator;
 
@@ -52,18 +129,50 @@ pub mod secret_store;
 #[cfg(feature = "api")]
 pub mod api;
 
+/// Model Context Protocol client exposing MCP server tools to every backend
+#[cfg(feature = "mcp")]
+pub mod mcp;
+
+/// Attribute macro declaring a tool from an async function
+#[cfg(feature = "macros")]
+pub use llm_macros::llm_tool;
+
+/// Re-exports used by code generated by the `llm-macros` crate
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+    pub use serde_json;
+}
+
 /// Core trait that all LLM providers must implement, combining chat, completion
 /// and embedding capabilities into a unified interface
+#[async_trait::async_trait]
 pub trait LLMProvider:
     chat::ChatProvider + completion::CompletionProvider + embedding::EmbeddingProvider
 {
     fn tools(&self) -> Option<&[Tool]> {
         None
     }
+
+    /// Checks that the provider is reachable and accepts the configured credentials.
+    ///
+    /// The built-in backends list the available models, which is free. The default
+    /// implementation asks for a one-token chat reply.
+    async fn validate(&self) -> health::HealthStatus {
+        let start = std::time::Instant::now();
+        let messages = [chat::ChatMessage::user().content("ping").build()];
+        let options = chat::ChatOptions::new().max_tokens(1);
+        match self.chat_with_options(&messages, None, &options).await {
+            Ok(_) => health::HealthStatus::Healthy {
+                latency: start.elapsed(),
+            },
+            Err(e) => health::HealthStatus::from_error(e),
+        }
+    }
 }
 
 /// Tool call from OpenAI's API.
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct ToolCall {
     /// The ID of the tool call.
     pub id: String,
@@ -74,10 +183,65 @@ pub struct ToolCall {
     pub function: FunctionCall,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct FunctionCall {
     /// The name of the function to call.
     pub name: String,
     /// The arguments to pass to the function.
     pub arguments: String,
 }
+
+impl ToolCall {
+    /// Deserializes the JSON-encoded call arguments into `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use llm::{FunctionCall, ToolCall};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct WeatherArgs {
+    ///     city: String,
+    /// }
+    ///
+    /// let call = ToolCall {
+    ///     id: "call_1".to_string(),
+    ///     call_type: "function".to_string(),
+    ///     function: FunctionCall {
+    ///         name: "get_weather".to_string(),
+    ///         arguments: r#"{"city": "Paris"}"#.to_string(),
+    ///     },
+    /// };
+    /// let args: WeatherArgs = call.parse_args().unwrap();
+    /// assert_eq!(args.city, "Paris");
+    /// ```
+    pub fn parse_args<T: serde::de::DeserializeOwned>(&self) -> Result<T, error::LLMError> {
+        serde_json::from_str(&self.function.arguments).map_err(|e| {
+            error::LLMError::JsonError(format!(
+                "Invalid arguments for tool '{}': {}",
+                self.function.name, e
+            ))
+        })
+    }
+}
+
+impl std::fmt::Display for ToolCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\n  \"id\": \"{}\",\n  \"type\": \"{}\",\n  \"function\": {}\n}}",
+            self.id, self.call_type, self.function
+        )
+    }
+}
+
+impl std::fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\n  \"name\": \"{}\",\n  \"arguments\": {}\n}}",
+            self.name, self.arguments
+        )
+    }
+}
@@ -0,0 +1,469 @@
+//! Load balancing across several instances of a provider.
+//!
+//! A [`LoadBalancedLLM`] spreads requests over several providers, such as the same backend
+//! configured with different API keys or regions, either in turn or to the one with the
+//! fewest requests in flight. Instances that keep failing are taken out of rotation for a
+//! cool-down period. Chat responses report the name of the instance that served them in
+//! [`ResponseMetadata::provider`].
+//!
+//! [`LoadBalancedLLM`]: crate::load_balancer::LoadBalancedLLM
+//! [`ResponseMetadata::provider`]: crate::chat::ResponseMetadata::provider
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::load_balancer::{BalanceStrategy, LoadBalancedLLM};
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let mut llm = LoadBalancedLLM::new(BalanceStrategy::LeastInFlight);
+//! for (name, key) in [("key-a", "sk-a..."), ("key-b", "sk-b...")] {
+//!     llm = llm.provider(
+//!         name,
+//!         LLMBuilder::new()
+//!             .backend(LLMBackend::OpenAI)
+//!             .api_key(key)
+//!             .build()?,
+//!     );
+//! }
+//!
+//! let reply = llm
+//!     .chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! println!("{} answered: {}", reply.metadata().provider.unwrap_or_default(), reply);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::fallback::NamedResponse;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// How a [`LoadBalancedLLM`] picks the instance serving a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalanceStrategy {
+    /// Each instance in turn
+    #[default]
+    RoundRobin,
+    /// The instance with the fewest requests in flight, streams included
+    LeastInFlight,
+}
+
+/// Health and load of one instance, as returned by [`LoadBalancedLLM::statuses`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceStatus {
+    /// Name the instance was added under
+    pub name: String,
+    /// Requests currently being served
+    pub in_flight: usize,
+    /// Consecutive failures of the instance
+    pub failures: u32,
+    /// Whether the instance is in rotation
+    pub healthy: bool,
+}
+
+/// A provider in the pool, with its load and health.
+struct Instance {
+    name: String,
+    provider: Box<dyn LLMProvider>,
+    in_flight: AtomicUsize,
+    health: Mutex<Health>,
+}
+
+#[derive(Default)]
+struct Health {
+    failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl Instance {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.health
+            .lock()
+            .unwrap()
+            .unhealthy_until
+            .is_none_or(|until| now >= until)
+    }
+}
+
+/// Counts a request as in flight on an instance until dropped.
+struct InFlight(Arc<Instance>);
+
+impl InFlight {
+    fn start(instance: &Arc<Instance>) -> Self {
+        instance.in_flight.fetch_add(1, Ordering::Relaxed);
+        Self(Arc::clone(instance))
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A provider distributing requests over a pool of providers.
+///
+/// HTTP errors, provider errors and rate limits count as failures of the instance that
+/// served the request; after `failure_threshold` consecutive failures (3 by default), the
+/// instance is skipped for `cooldown` (30 seconds by default). If every instance is
+/// unhealthy, requests are spread over all of them. Failed requests are not resent to
+/// another instance; wrap the pool in a [`crate::fallback::FallbackLLM`] for that.
+pub struct LoadBalancedLLM {
+    /// The providers in the pool
+    instances: Vec<Arc<Instance>>,
+    /// How instances are picked
+    strategy: BalanceStrategy,
+    /// Counter rotating through the instances
+    next: AtomicUsize,
+    /// Consecutive failures taking an instance out of rotation
+    failure_threshold: u32,
+    /// Time an unhealthy instance stays out of rotation
+    cooldown: Duration,
+}
+
+impl LoadBalancedLLM {
+    /// Creates an empty pool using a balancing strategy.
+    pub fn new(strategy: BalanceStrategy) -> Self {
+        Self {
+            instances: Vec::new(),
+            strategy,
+            next: AtomicUsize::new(0),
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+
+    /// Adds a provider to the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name reported in the metadata of the responses it serves
+    /// * `provider` - The provider to add
+    pub fn provider(mut self, name: impl Into<String>, provider: Box<dyn LLMProvider>) -> Self {
+        self.instances.push(Arc::new(Instance {
+            name: name.into(),
+            provider,
+            in_flight: AtomicUsize::new(0),
+            health: Mutex::new(Health::default()),
+        }));
+        self
+    }
+
+    /// Sets how many consecutive failures take an instance out of rotation, and for how
+    /// long.
+    pub fn health_policy(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Returns the load and health of every instance, in the order they were added.
+    pub fn statuses(&self) -> Vec<InstanceStatus> {
+        let now = Instant::now();
+        self.instances
+            .iter()
+            .map(|instance| {
+                let failures = instance.health.lock().unwrap().failures;
+                InstanceStatus {
+                    name: instance.name.clone(),
+                    in_flight: instance.in_flight.load(Ordering::Relaxed),
+                    failures,
+                    healthy: instance.is_healthy(now),
+                }
+            })
+            .collect()
+    }
+
+    /// Picks the instance serving the next request.
+    fn pick(&self) -> Result<&Arc<Instance>, LLMError> {
+        let now = Instant::now();
+        let healthy: Vec<&Arc<Instance>> = self
+            .instances
+            .iter()
+            .filter(|instance| instance.is_healthy(now))
+            .collect();
+        let candidates = if healthy.is_empty() {
+            self.instances.iter().collect()
+        } else {
+            healthy
+        };
+        if candidates.is_empty() {
+            return Err(LLMError::InvalidRequest(
+                "No providers in the load balancer".to_string(),
+            ));
+        }
+
+        // Rotate the starting point so ties are spread across instances.
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % candidates.len();
+        let rotated = candidates[start..].iter().chain(&candidates[..start]);
+        let instance = match self.strategy {
+            BalanceStrategy::RoundRobin => candidates[start],
+            BalanceStrategy::LeastInFlight => rotated
+                .min_by_key(|instance| instance.in_flight.load(Ordering::Relaxed))
+                .expect("candidates are not empty"),
+        };
+        Ok(instance)
+    }
+
+    /// Records the outcome of a request served by an instance.
+    fn record<T>(&self, instance: &Instance, result: &Result<T, LLMError>) {
+        let mut health = instance.health.lock().unwrap();
+        match result {
+            Err(e) if e.is_provider_failure() => {
+                health.failures += 1;
+                if health.failures >= self.failure_threshold {
+                    tracing::warn!(
+                        instance = instance.name.as_str(),
+                        failures = health.failures,
+                        "taking provider out of rotation"
+                    );
+                    health.unhealthy_until = Some(Instant::now() + self.cooldown);
+                }
+            }
+            _ => *health = Health::default(),
+        }
+    }
+
+    /// Sends a request to the picked instance, returning the result with the in-flight
+    /// guard of the instance.
+    async fn call<'a, T, F, Fut>(&'a self, request: F) -> Result<(InFlight, T), LLMError>
+    where
+        F: FnOnce(&'a dyn LLMProvider) -> Fut + Send,
+        Fut: Future<Output = Result<T, LLMError>> + Send + 'a,
+    {
+        let instance = self.pick()?;
+        let guard = InFlight::start(instance);
+        let result = request(instance.provider.as_ref()).await;
+        self.record(instance, &result);
+        result.map(|value| (guard, value))
+    }
+
+    /// Sends a chat request, tagging the response with the instance that served it.
+    async fn call_chat<'a, F, Fut>(&'a self, request: F) -> Result<Box<dyn ChatResponse>, LLMError>
+    where
+        F: FnOnce(&'a dyn LLMProvider) -> Fut + Send,
+        Fut: Future<Output = Result<Box<dyn ChatResponse>, LLMError>> + Send + 'a,
+    {
+        let (guard, response) = self.call(request).await?;
+        Ok(Box::new(NamedResponse::new(response, guard.0.name.clone())))
+    }
+
+    /// Sends a chat stream request, keeping it in flight until the stream is dropped.
+    async fn call_stream<'a, F, Fut>(&'a self, request: F) -> Result<ChatStream, LLMError>
+    where
+        F: FnOnce(&'a dyn LLMProvider) -> Fut + Send,
+        Fut: Future<Output = Result<ChatStream, LLMError>> + Send + 'a,
+    {
+        let (guard, stream) = self.call(request).await?;
+        Ok(Box::pin(stream.map(move |chunk| {
+            let _ = &guard;
+            chunk
+        })))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for LoadBalancedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.instances.first()?.provider.tools()
+    }
+
+    /// Returns the status of the first healthy instance, or of the last one if none is.
+    async fn validate(&self) -> HealthStatus {
+        let mut last_status = None;
+        for instance in &self.instances {
+            let status = instance.provider.validate().await;
+            if status.is_healthy() {
+                return status;
+            }
+            last_status = Some(status);
+        }
+        last_status.unwrap_or_else(|| {
+            HealthStatus::Unavailable("No providers in the load balancer".to_string())
+        })
+    }
+}
+
+#[async_trait]
+impl ChatProvider for LoadBalancedLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call_chat(|llm| llm.chat(messages)).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call_chat(|llm| llm.chat_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.call_chat(|llm| llm.chat_with_options(messages, tools, options))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.call_stream(|llm| llm.chat_stream_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.call_stream(|llm| llm.chat_stream_with_options(messages, tools, options))
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for LoadBalancedLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        let (_, response) = self.call(|llm| llm.complete(req)).await?;
+        Ok(response)
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        let (guard, stream) = self.call(|llm| llm.complete_stream(req)).await?;
+        Ok(Box::pin(stream.map(move |text| {
+            let _ = &guard;
+            text
+        })))
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        let (_, response) = self
+            .call(|llm| llm.complete_with_options(req, options))
+            .await?;
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LoadBalancedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        let (_, embeddings) = self.call(|llm| llm.embed(input)).await?;
+        Ok(embeddings)
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockLLM;
+
+    fn messages() -> Vec<ChatMessage> {
+        vec![ChatMessage::user().content("Hello").build()]
+    }
+
+    fn overloaded() -> LLMError {
+        LLMError::ProviderError("overloaded".to_string())
+    }
+
+    async fn served_by(llm: &LoadBalancedLLM) -> Option<String> {
+        llm.chat(&messages()).await.ok()?.metadata().provider
+    }
+
+    fn health(llm: &LoadBalancedLLM, name: &str) -> (u32, bool) {
+        let status = llm
+            .statuses()
+            .into_iter()
+            .find(|status| status.name == name)
+            .unwrap();
+        (status.failures, status.healthy)
+    }
+
+    #[tokio::test]
+    async fn rotates_a_failing_instance_out_until_its_cooldown_ends() {
+        let flaky = MockLLM::new()
+            .fail(overloaded())
+            .fail(overloaded())
+            .fallback_reply("recovered");
+        let steady = MockLLM::new().fallback_reply("steady");
+        let llm = LoadBalancedLLM::new(BalanceStrategy::RoundRobin)
+            .provider("flaky", Box::new(flaky.clone()))
+            .provider("steady", Box::new(steady.clone()))
+            .health_policy(2, Duration::from_millis(80));
+
+        assert_eq!(served_by(&llm).await, None);
+        assert_eq!(served_by(&llm).await.as_deref(), Some("steady"));
+        assert_eq!(health(&llm, "flaky"), (1, true));
+        assert_eq!(served_by(&llm).await, None);
+        assert_eq!(health(&llm, "flaky"), (2, false));
+
+        for _ in 0..3 {
+            assert_eq!(served_by(&llm).await.as_deref(), Some("steady"));
+        }
+        assert_eq!(flaky.calls().len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(health(&llm, "flaky").1);
+        let mut served = Vec::new();
+        for _ in 0..2 {
+            served.push(served_by(&llm).await.unwrap());
+        }
+        assert!(served.contains(&"flaky".to_string()));
+        assert_eq!(health(&llm, "flaky"), (0, true));
+    }
+
+    #[tokio::test]
+    async fn keeps_serving_when_every_instance_is_unhealthy() {
+        let failing = MockLLM::new().fail(overloaded()).fallback_reply("back");
+        let llm = LoadBalancedLLM::new(BalanceStrategy::RoundRobin)
+            .provider("only", Box::new(failing.clone()))
+            .health_policy(1, Duration::from_secs(60));
+
+        assert!(llm.chat(&messages()).await.is_err());
+        assert_eq!(health(&llm, "only"), (1, false));
+        assert_eq!(served_by(&llm).await.as_deref(), Some("only"));
+        assert_eq!(health(&llm, "only"), (0, true));
+    }
+
+    #[tokio::test]
+    async fn does_not_count_request_errors_as_failures() {
+        let mock = MockLLM::new()
+            .fail(LLMError::InvalidRequest("bad request".to_string()))
+            .fail(LLMError::InvalidRequest("bad request".to_string()));
+        let llm = LoadBalancedLLM::new(BalanceStrategy::LeastInFlight)
+            .provider("only", Box::new(mock))
+            .health_policy(1, Duration::from_secs(60));
+
+        for _ in 0..2 {
+            assert!(llm.chat(&messages()).await.is_err());
+        }
+        assert_eq!(health(&llm, "only"), (0, true));
+    }
+}
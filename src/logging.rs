@@ -0,0 +1,353 @@
+//! Debug logging of the HTTP exchanges with providers.
+//!
+//! A [`RequestLogging`] makes the backends log each request they send and each response
+//! they receive as `debug` events of the `llm::http` [`tracing`] target: the method, URL,
+//! headers and JSON body of the request, and the status and body of the response. API
+//! keys — in authentication headers or in the URL — are always redacted, and so are the
+//! body fields named with [`RequestLogging::redact_field`], such as the message contents.
+//! Attach it when building a provider with [`crate::builder::LLMBuilder::log_requests`].
+//!
+//! Streamed response bodies are not logged, so streams are not held back.
+//!
+//! To see what was actually sent when a provider rejects a request, an [`ExchangeCapture`]
+//! keeps the last exchange instead: the exact request body, the headers with credentials
+//! redacted, the status and the raw response body. Attach one with
+//! [`crate::builder::LLMBuilder::capture_exchanges`] and keep a clone to read it.
+//!
+//! [`RequestLogging`]: crate::logging::RequestLogging
+//! [`RequestLogging::redact_field`]: crate::logging::RequestLogging::redact_field
+//! [`ExchangeCapture`]: crate::logging::ExchangeCapture
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::logging::RequestLogging;
+//!
+//! # fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .log_requests(RequestLogging::new().redact_field("content"))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+// Without a backend, no request is ever sent.
+#![cfg_attr(not(feature = "http-backend"), allow(dead_code))]
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use reqwest::{header::HeaderMap, ResponseBuilderExt};
+use serde_json::Value;
+
+/// Placeholder replacing redacted values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Headers carrying credentials.
+const SECRET_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "api-key",
+    "x-goog-api-key",
+    "cookie",
+];
+
+/// URL query parameters carrying credentials.
+const SECRET_PARAMS: &[&str] = &["key", "api_key", "api-key"];
+
+/// Configuration of the debug logging of HTTP exchanges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestLogging {
+    /// Names of the JSON fields whose values are redacted, at any depth
+    pub redact_fields: Vec<String>,
+    /// Where the last exchange is kept, if it is captured
+    pub capture: Option<ExchangeCapture>,
+    /// Whether the exchanges are only captured, not logged
+    pub(crate) silent: bool,
+}
+
+impl RequestLogging {
+    /// Logs exchanges with only credentials redacted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redacts the values of a JSON field, wherever it appears in request and response
+    /// bodies (e.g. "content" to hide the messages).
+    pub fn redact_field(mut self, name: impl Into<String>) -> Self {
+        self.redact_fields.push(name.into());
+        self
+    }
+
+    /// Also keeps the last exchange in a capture.
+    pub fn capture(mut self, capture: ExchangeCapture) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Redacts the values of the redacted fields in a JSON document.
+    ///
+    /// ```
+    /// use llm::logging::RequestLogging;
+    /// use serde_json::json;
+    ///
+    /// let logging = RequestLogging::new().redact_field("content");
+    /// let mut body = json!({"messages": [{"role": "user", "content": "secret plans"}]});
+    /// logging.redact(&mut body);
+    /// assert_eq!(body["messages"][0]["content"], "[REDACTED]");
+    /// ```
+    pub fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if self.redact_fields.iter().any(|name| name == key) {
+                        *field = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact(item)),
+            _ => {}
+        }
+    }
+
+    /// Formats a body for the log, redacted if it is JSON.
+    pub(crate) fn body(&self, bytes: &[u8]) -> String {
+        match serde_json::from_slice::<Value>(bytes) {
+            Ok(mut json) => {
+                self.redact(&mut json);
+                json.to_string()
+            }
+            Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+
+    /// Sends a request with a client, logging and capturing the exchange.
+    pub(crate) async fn send(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::Request,
+    ) -> reqwest::Result<reqwest::Response> {
+        if !self.silent {
+            self.log_request(&request);
+        }
+        let mut exchange = self.capture.as_ref().map(|capture| {
+            let exchange = HttpExchange::from_request(&request);
+            capture.set(exchange.clone());
+            exchange
+        });
+        let resp = client.execute(request).await?;
+        if exchange.is_none() && self.silent {
+            return Ok(resp);
+        }
+        let streamed = is_stream(resp.headers());
+        let (resp, bytes) = if streamed {
+            (resp, None)
+        } else {
+            let (resp, bytes) = buffer(resp).await?;
+            (resp, Some(bytes))
+        };
+        if !self.silent {
+            self.log_response(&resp, bytes.as_deref());
+        }
+        if let (Some(capture), Some(exchange)) = (&self.capture, exchange.as_mut()) {
+            exchange.status = Some(resp.status().as_u16());
+            exchange.response_headers = redact_headers(resp.headers());
+            exchange.response_body =
+                bytes.map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+            capture.set(exchange.clone());
+        }
+        Ok(resp)
+    }
+
+    /// Logs a request about to be sent.
+    fn log_request(&self, request: &reqwest::Request) {
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(|bytes| self.body(bytes))
+            .unwrap_or_default();
+        tracing::debug!(
+            target: "llm::http",
+            method = %request.method(),
+            url = %redact_url(request.url()),
+            headers = ?redact_headers(request.headers()),
+            body = %body,
+            "sending request"
+        );
+    }
+
+    /// Logs a response, with its body unless it is streamed.
+    fn log_response(&self, resp: &reqwest::Response, body: Option<&[u8]>) {
+        match body {
+            Some(bytes) => tracing::debug!(
+                target: "llm::http",
+                status = resp.status().as_u16(),
+                headers = ?redact_headers(resp.headers()),
+                body = %self.body(bytes),
+                "received response"
+            ),
+            None => tracing::debug!(
+                target: "llm::http",
+                status = resp.status().as_u16(),
+                headers = ?redact_headers(resp.headers()),
+                "received streamed response"
+            ),
+        }
+    }
+}
+
+/// An HTTP exchange with a provider, as it was sent and received.
+///
+/// Credentials are redacted from the headers and the URL; the bodies are kept as is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpExchange {
+    /// Method of the request
+    pub method: String,
+    /// URL the request was sent to
+    pub url: String,
+    /// Headers of the request
+    pub request_headers: Vec<(String, String)>,
+    /// Serialized body of the request
+    pub request_body: Option<String>,
+    /// Status of the response, or `None` if no response arrived
+    pub status: Option<u16>,
+    /// Headers of the response
+    pub response_headers: Vec<(String, String)>,
+    /// Raw body of the response, or `None` if none arrived or it was streamed
+    pub response_body: Option<String>,
+}
+
+impl HttpExchange {
+    /// Captures a request about to be sent.
+    fn from_request(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: redact_url(request.url()).to_string(),
+            request_headers: redact_headers(request.headers()),
+            request_body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Shared slot keeping the last HTTP exchange of the providers it is attached to.
+///
+/// Clones share the same slot. With concurrent requests, the request sent or answered
+/// last wins.
+#[derive(Clone, Default)]
+pub struct ExchangeCapture {
+    last: Arc<Mutex<Option<HttpExchange>>>,
+}
+
+impl fmt::Debug for ExchangeCapture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExchangeCapture")
+            .field("last", &self.last())
+            .finish()
+    }
+}
+
+impl PartialEq for ExchangeCapture {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.last, &other.last)
+    }
+}
+
+impl Eq for ExchangeCapture {}
+
+impl ExchangeCapture {
+    /// Creates an empty capture.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the last exchange, or `None` if no request was sent yet.
+    pub fn last(&self) -> Option<HttpExchange> {
+        self.last.lock().unwrap().clone()
+    }
+
+    /// Forgets the last exchange.
+    pub fn clear(&self) {
+        *self.last.lock().unwrap() = None;
+    }
+
+    fn set(&self, exchange: HttpExchange) {
+        *self.last.lock().unwrap() = Some(exchange);
+    }
+}
+
+/// Returns whether a response body is a stream of events.
+pub(crate) fn is_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value.starts_with("text/event-stream") || value.starts_with("application/x-ndjson")
+        })
+}
+
+/// Reads a response body, returning an equivalent response to read it from again.
+pub(crate) async fn buffer(
+    resp: reqwest::Response,
+) -> reqwest::Result<(reqwest::Response, Vec<u8>)> {
+    let mut rebuilt = http::Response::builder()
+        .status(resp.status())
+        .version(resp.version())
+        .url(resp.url().clone());
+    if let Some(headers) = rebuilt.headers_mut() {
+        *headers = resp.headers().clone();
+    }
+    let bytes = resp.bytes().await?.to_vec();
+    let rebuilt = rebuilt
+        .body(bytes.clone())
+        .expect("parts of a received response are valid");
+    Ok((reqwest::Response::from(rebuilt), bytes))
+}
+
+/// Returns a copy of headers with the credentials redacted.
+pub(crate) fn redact_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// Returns a URL with the credentials in its query string redacted.
+pub(crate) fn redact_url(url: &reqwest::Url) -> reqwest::Url {
+    if !url
+        .query_pairs()
+        .any(|(key, _)| SECRET_PARAMS.contains(&&*key))
+    {
+        return url.clone();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let value = if SECRET_PARAMS.contains(&&*key) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (key.into_owned(), value)
+        })
+        .collect();
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url
+}
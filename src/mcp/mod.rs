@@ -0,0 +1,286 @@
+//! Model Context Protocol (MCP) client.
+//!
+//! This module connects to MCP servers, lists the tools they expose as [`Tool`] definitions
+//! usable with every backend, and dispatches the model's [`ToolCall`]s back to the server.
+//! Servers can be reached over stdio, by spawning them as a child process, or over HTTP
+//! with Server-Sent Events.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::ChatMessage;
+//! use llm::mcp::{McpClient, StdioTransport};
+//! use llm::tool_enabled_llm::ToolEnabledLLM;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let transport = StdioTransport::spawn("npx", ["-y", "@modelcontextprotocol/server-everything"])?;
+//! let mcp = Arc::new(McpClient::connect(transport).await?);
+//!
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .build()?;
+//! let mut llm = ToolEnabledLLM::new(llm);
+//! for tool in mcp.executable_tools().await? {
+//!     llm = llm.register(tool);
+//! }
+//!
+//! let messages = vec![ChatMessage::user().content("Add 2 and 3").build()];
+//! println!("{}", llm.chat_auto(&messages).await?);
+//! # Ok(())
+//! # }
+//! ```
+
+mod transport;
+
+pub use transport::{McpTransport, SseTransport, StdioTransport};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    chat::{FunctionTool, ParameterProperty, ParametersSchema, Tool},
+    error::LLMError,
+    tool_enabled_llm::ExecutableTool,
+    ToolCall,
+};
+
+/// MCP protocol revision requested during the handshake.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// A tool exposed by an MCP server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpTool {
+    /// Unique name of the tool
+    pub name: String,
+    /// Human-readable description of the tool
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON schema of the tool arguments
+    #[serde(rename = "inputSchema", default)]
+    pub input_schema: Value,
+}
+
+impl McpTool {
+    /// Converts the tool into a chat tool definition.
+    ///
+    /// Argument properties that cannot be represented by [`ParameterProperty`], such as
+    /// ones without a `type`, are left out of the schema.
+    pub fn to_tool(&self) -> Tool {
+        let parameters = serde_json::from_value(self.input_schema.clone()).unwrap_or_else(|_| {
+            let properties = self.input_schema["properties"]
+                .as_object()
+                .map(|properties| {
+                    properties
+                        .iter()
+                        .filter_map(|(name, property)| {
+                            serde_json::from_value::<ParameterProperty>(property.clone())
+                                .ok()
+                                .map(|property| (name.clone(), property))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let required =
+                serde_json::from_value(self.input_schema["required"].clone()).unwrap_or_default();
+            ParametersSchema {
+                properties,
+                required,
+                ..Default::default()
+            }
+        });
+
+        Tool {
+            tool_type: "function".to_string(),
+            function: FunctionTool {
+                name: self.name.clone(),
+                description: self.description.clone().unwrap_or_default(),
+                parameters,
+            },
+            settings: Default::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListToolsResult {
+    tools: Vec<McpTool>,
+    #[serde(rename = "nextCursor")]
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CallToolResult {
+    #[serde(default)]
+    content: Vec<Value>,
+    #[serde(rename = "isError", default)]
+    is_error: bool,
+}
+
+/// Client connected to a single MCP server.
+pub struct McpClient {
+    transport: Box<dyn McpTransport>,
+    next_id: AtomicU64,
+    server_info: Value,
+}
+
+impl McpClient {
+    /// Connects to a server over the given transport and performs the MCP handshake.
+    pub async fn connect(transport: impl McpTransport + 'static) -> Result<Self, LLMError> {
+        let mut client = Self {
+            transport: Box::new(transport),
+            next_id: AtomicU64::new(1),
+            server_info: Value::Null,
+        };
+
+        let result = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }),
+            )
+            .await?;
+        client.server_info = result.get("serverInfo").cloned().unwrap_or_default();
+
+        client
+            .transport
+            .notify(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/initialized",
+            }))
+            .await?;
+
+        Ok(client)
+    }
+
+    /// Returns the server's name and version, as reported during the handshake.
+    pub fn server_info(&self) -> &Value {
+        &self.server_info
+    }
+
+    /// Sends a JSON-RPC request and returns its result.
+    async fn request(&self, method: &str, params: Value) -> Result<Value, LLMError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self
+            .transport
+            .request(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            }))
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(LLMError::ProviderError(format!(
+                "MCP error {}: {}",
+                error["code"],
+                error["message"].as_str().unwrap_or_default()
+            )));
+        }
+        Ok(response.get("result").cloned().unwrap_or_default())
+    }
+
+    /// Lists every tool exposed by the server, following pagination.
+    pub async fn list_tools(&self) -> Result<Vec<McpTool>, LLMError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result: ListToolsResult =
+                serde_json::from_value(self.request("tools/list", params).await?)
+                    .map_err(|e| LLMError::JsonError(e.to_string()))?;
+            tools.extend(result.tools);
+
+            match result.next_cursor {
+                Some(next) if !next.is_empty() => cursor = Some(next),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Lists the server's tools as chat tool definitions.
+    pub async fn tools(&self) -> Result<Vec<Tool>, LLMError> {
+        Ok(self
+            .list_tools()
+            .await?
+            .iter()
+            .map(McpTool::to_tool)
+            .collect())
+    }
+
+    /// Calls a tool on the server and returns its text output.
+    ///
+    /// Tool failures reported by the server are returned as [`LLMError::ProviderError`].
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<String, LLMError> {
+        let result: CallToolResult = serde_json::from_value(
+            self.request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?,
+        )
+        .map_err(|e| LLMError::JsonError(e.to_string()))?;
+
+        let output = result
+            .content
+            .iter()
+            .map(|content| match content["type"].as_str() {
+                Some("text") => content["text"].as_str().unwrap_or_default().to_string(),
+                _ => content.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if result.is_error {
+            Err(LLMError::ProviderError(output))
+        } else {
+            Ok(output)
+        }
+    }
+
+    /// Dispatches a tool call requested by the model to the server.
+    pub async fn call(&self, tool_call: &ToolCall) -> Result<String, LLMError> {
+        let arguments = if tool_call.function.arguments.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str(&tool_call.function.arguments)
+                .map_err(|e| LLMError::JsonError(e.to_string()))?
+        };
+        self.call_tool(&tool_call.function.name, arguments).await
+    }
+
+    /// Lists the server's tools with handlers that call them on the server, ready to be
+    /// registered on a [`crate::tool_enabled_llm::ToolEnabledLLM`].
+    pub async fn executable_tools(self: &Arc<Self>) -> Result<Vec<ExecutableTool>, LLMError> {
+        Ok(self
+            .list_tools()
+            .await?
+            .into_iter()
+            .map(|tool| {
+                let client = Arc::clone(self);
+                let name = tool.name.clone();
+                ExecutableTool::new(tool.to_tool(), move |args| {
+                    let client = Arc::clone(&client);
+                    let name = name.clone();
+                    async move { client.call_tool(&name, args).await }
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,277 @@
+//! Transports carrying JSON-RPC messages between the client and an MCP server.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::{backends::sse::sse_stream, error::LLMError};
+
+/// A channel to an MCP server.
+///
+/// Transports only move JSON-RPC messages; the client builds requests, assigns their IDs
+/// and interprets responses.
+#[async_trait]
+pub trait McpTransport: Send + Sync {
+    /// Sends a request and waits for the response carrying the same ID.
+    async fn request(&self, request: Value) -> Result<Value, LLMError>;
+
+    /// Sends a notification, which gets no response.
+    async fn notify(&self, notification: Value) -> Result<(), LLMError>;
+}
+
+/// Returns the ID of a JSON-RPC message, if it is a response to one of our requests.
+fn response_id(message: &Value) -> Option<u64> {
+    if message.get("method").is_some() {
+        return None;
+    }
+    message.get("id").and_then(Value::as_u64)
+}
+
+/// The reading and writing halves of a server process.
+struct StdioPipes {
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+/// Transport talking to an MCP server spawned as a child process.
+///
+/// Messages are exchanged as newline-delimited JSON over the process's stdin and stdout.
+/// The process is killed when the transport is dropped.
+pub struct StdioTransport {
+    pipes: Mutex<StdioPipes>,
+    _child: Child,
+}
+
+impl StdioTransport {
+    /// Spawns a server process from a program and its arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The server executable (e.g. "npx")
+    /// * `args` - Arguments passed to the executable
+    pub fn spawn<I, S>(program: impl AsRef<OsStr>, args: I) -> Result<Self, LLMError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = Command::new(program);
+        command.args(args);
+        Self::from_command(command)
+    }
+
+    /// Spawns a server process from a configured command, e.g. one with extra environment
+    /// variables. Its stdin and stdout are replaced by pipes.
+    pub fn from_command(mut command: Command) -> Result<Self, LLMError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| LLMError::ProviderError(format!("Failed to start MCP server: {}", e)))?;
+
+        let stdin = child.stdin.take().ok_or_else(|| {
+            LLMError::ProviderError("MCP server stdin is not available".to_string())
+        })?;
+        let stdout = child.stdout.take().ok_or_else(|| {
+            LLMError::ProviderError("MCP server stdout is not available".to_string())
+        })?;
+
+        Ok(Self {
+            pipes: Mutex::new(StdioPipes {
+                stdin,
+                stdout: BufReader::new(stdout).lines(),
+            }),
+            _child: child,
+        })
+    }
+}
+
+impl StdioPipes {
+    async fn write(&mut self, message: &Value) -> Result<(), LLMError> {
+        let mut line =
+            serde_json::to_vec(message).map_err(|e| LLMError::JsonError(e.to_string()))?;
+        line.push(b'\n');
+        self.stdin.write_all(&line).await.map_err(|e| {
+            LLMError::ProviderError(format!("Failed to write to MCP server: {}", e))
+        })?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| LLMError::ProviderError(format!("Failed to write to MCP server: {}", e)))
+    }
+}
+
+#[async_trait]
+impl McpTransport for StdioTransport {
+    async fn request(&self, request: Value) -> Result<Value, LLMError> {
+        let id = request.get("id").and_then(Value::as_u64);
+        let mut pipes = self.pipes.lock().await;
+        pipes.write(&request).await?;
+
+        loop {
+            let line = pipes
+                .stdout
+                .next_line()
+                .await
+                .map_err(|e| {
+                    LLMError::ProviderError(format!("Failed to read from MCP server: {}", e))
+                })?
+                .ok_or_else(|| LLMError::ProviderError("MCP server closed stdout".to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let message: Value =
+                serde_json::from_str(&line).map_err(|e| LLMError::JsonError(e.to_string()))?;
+            if response_id(&message).is_some() && response_id(&message) == id {
+                return Ok(message);
+            }
+
+            // The client exposes no capabilities, so refuse requests from the server.
+            if let (Some(server_id), Some(_)) = (message.get("id"), message.get("method")) {
+                let refusal = json!({
+                    "jsonrpc": "2.0",
+                    "id": server_id,
+                    "error": { "code": -32601, "message": "Method not found" },
+                });
+                pipes.write(&refusal).await?;
+            }
+        }
+    }
+
+    async fn notify(&self, notification: Value) -> Result<(), LLMError> {
+        self.pipes.lock().await.write(&notification).await
+    }
+}
+
+/// Requests waiting for their response, keyed by ID.
+type PendingRequests = Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<Value>>>>;
+
+/// Transport talking to an MCP server over HTTP with Server-Sent Events.
+///
+/// The server streams its messages over a long-lived `GET` request. Its first event
+/// names the endpoint the client `POST`s its own messages to.
+pub struct SseTransport {
+    client: Client,
+    endpoint: Url,
+    pending: PendingRequests,
+    reader: JoinHandle<()>,
+}
+
+impl SseTransport {
+    /// Opens the event stream and waits for the server to announce its message endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the server's SSE endpoint (e.g. "http://localhost:8000/sse")
+    pub async fn connect(url: impl AsRef<str>) -> Result<Self, LLMError> {
+        let url = Url::parse(url.as_ref())
+            .map_err(|e| LLMError::InvalidRequest(format!("Invalid MCP server URL: {}", e)))?;
+        let client = Client::new();
+
+        let response = client
+            .get(url.clone())
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut events = Box::pin(sse_stream(response));
+
+        let endpoint = loop {
+            match events.next().await {
+                Some(Ok(event)) if event.event.as_deref() == Some("endpoint") => {
+                    break url.join(event.data.trim()).map_err(|e| {
+                        LLMError::ProviderError(format!("Invalid MCP message endpoint: {}", e))
+                    })?;
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => {
+                    return Err(LLMError::ProviderError(
+                        "MCP server closed the event stream before sending its endpoint"
+                            .to_string(),
+                    ))
+                }
+            }
+        };
+
+        let pending = PendingRequests::default();
+        let reader_pending = pending.clone();
+        let reader = tokio::spawn(async move {
+            while let Some(Ok(event)) = events.next().await {
+                if event.event.as_deref().unwrap_or("message") != "message" {
+                    continue;
+                }
+                let Ok(message) = serde_json::from_str::<Value>(&event.data) else {
+                    continue;
+                };
+                if let Some(id) = response_id(&message) {
+                    let sender = reader_pending.lock().unwrap().remove(&id);
+                    if let Some(sender) = sender {
+                        let _ = sender.send(message);
+                    }
+                }
+            }
+            // Dropping the senders wakes every request still waiting.
+            reader_pending.lock().unwrap().clear();
+        });
+
+        Ok(Self {
+            client,
+            endpoint,
+            pending,
+            reader,
+        })
+    }
+
+    async fn post(&self, message: &Value) -> Result<(), LLMError> {
+        self.client
+            .post(self.endpoint.clone())
+            .json(message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl McpTransport for SseTransport {
+    async fn request(&self, request: Value) -> Result<Value, LLMError> {
+        let id = request
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| LLMError::InvalidRequest("MCP request has no ID".to_string()))?;
+
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, sender);
+        if let Err(e) = self.post(&request).await {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+
+        receiver
+            .await
+            .map_err(|_| LLMError::ProviderError("MCP server closed the event stream".to_string()))
+    }
+
+    async fn notify(&self, notification: Value) -> Result<(), LLMError> {
+        self.post(&notification).await
+    }
+}
+
+impl Drop for SseTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
@@ -0,0 +1,431 @@
+//! Prometheus metrics of provider requests.
+//!
+//! A [`Metrics`] registry counts requests, errors by class and tokens, and keeps a latency
+//! histogram, per provider and model. Attach it when building a provider with
+//! [`crate::builder::LLMBuilder::metrics`], or wrap an existing provider in a
+//! [`MetricsLLM`]; one registry can be shared by any number of providers. Its
+//! [`Metrics::render`] output is in the Prometheus text exposition format, and the API
+//! server can serve it on `/metrics` with `Server::with_metrics`.
+//!
+//! [`Metrics`]: crate::metrics::Metrics
+//! [`Metrics::render`]: crate::metrics::Metrics::render
+//! [`MetricsLLM`]: crate::metrics::MetricsLLM
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::metrics::Metrics;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let metrics = Metrics::new();
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .model("gpt-4o-mini")
+//!     .metrics(metrics.clone())
+//!     .build()?;
+//!
+//! llm.chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! print!("{}", metrics.render());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, StreamChunk, Tool, Usage,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: [f64; 11] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+
+/// Metrics of one provider and model.
+#[derive(Debug, Default, Clone)]
+struct Series {
+    requests: u64,
+    /// Failed requests, by [`LLMError::kind`]
+    errors: BTreeMap<&'static str, u64>,
+    input_tokens: u64,
+    output_tokens: u64,
+    /// Requests per latency bucket, the last one counting those slower than every bound
+    latency_buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    latency_sum: f64,
+}
+
+/// Registry of request metrics, keyed by provider and model.
+///
+/// Cloning a registry is cheap and every clone shares the same metrics.
+#[derive(Debug, Default, Clone)]
+pub struct Metrics {
+    series: Arc<Mutex<BTreeMap<(String, String), Series>>>,
+}
+
+impl Metrics {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a request and how long it took.
+    pub fn record_request(&self, provider: &str, model: &str, latency_seconds: f64) {
+        self.update(provider, model, |series| {
+            series.requests += 1;
+            let bucket = LATENCY_BUCKETS
+                .iter()
+                .position(|bound| latency_seconds <= *bound)
+                .unwrap_or(LATENCY_BUCKETS.len());
+            series.latency_buckets[bucket] += 1;
+            series.latency_sum += latency_seconds;
+        });
+    }
+
+    /// Records the failure of a request, counted under the error's [`LLMError::kind`].
+    pub fn record_error(&self, provider: &str, model: &str, error: &LLMError) {
+        self.update(provider, model, |series| {
+            *series.errors.entry(error.kind()).or_default() += 1;
+        });
+    }
+
+    /// Records the tokens a request used.
+    pub fn record_usage(&self, provider: &str, model: &str, usage: &Usage) {
+        self.update(provider, model, |series| {
+            series.input_tokens += u64::from(usage.prompt_tokens);
+            series.output_tokens += u64::from(usage.completion_tokens);
+        });
+    }
+
+    /// Returns the number of requests recorded for a provider and model.
+    pub fn requests(&self, provider: &str, model: &str) -> u64 {
+        self.series
+            .lock()
+            .unwrap()
+            .get(&(provider.to_string(), model.to_string()))
+            .map_or(0, |series| series.requests)
+    }
+
+    /// Clears every metric.
+    pub fn reset(&self) {
+        self.series.lock().unwrap().clear();
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    ///
+    /// ```
+    /// use llm::metrics::Metrics;
+    ///
+    /// let metrics = Metrics::new();
+    /// metrics.record_request("openai", "gpt-4o", 0.8);
+    /// assert!(metrics
+    ///     .render()
+    ///     .contains(r#"llm_requests_total{provider="openai",model="gpt-4o"} 1"#));
+    /// ```
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap().clone();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "llm_requests_total",
+            "counter",
+            "Requests sent to providers",
+        );
+        for ((provider, model), series) in &series {
+            let labels = labels(provider, model);
+            let _ = writeln!(out, "llm_requests_total{{{}}} {}", labels, series.requests);
+        }
+
+        header(
+            &mut out,
+            "llm_errors_total",
+            "counter",
+            "Failed requests, by error class",
+        );
+        for ((provider, model), series) in &series {
+            let labels = labels(provider, model);
+            for (kind, count) in &series.errors {
+                let _ = writeln!(
+                    out,
+                    "llm_errors_total{{{},kind=\"{}\"}} {}",
+                    labels, kind, count
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "llm_tokens_total",
+            "counter",
+            "Tokens used, by direction",
+        );
+        for ((provider, model), series) in &series {
+            let labels = labels(provider, model);
+            let _ = writeln!(
+                out,
+                "llm_tokens_total{{{},type=\"input\"}} {}",
+                labels, series.input_tokens
+            );
+            let _ = writeln!(
+                out,
+                "llm_tokens_total{{{},type=\"output\"}} {}",
+                labels, series.output_tokens
+            );
+        }
+
+        header(
+            &mut out,
+            "llm_request_duration_seconds",
+            "histogram",
+            "Latency of requests",
+        );
+        for ((provider, model), series) in &series {
+            let labels = labels(provider, model);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&series.latency_buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "llm_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "llm_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, series.requests
+            );
+            let _ = writeln!(
+                out,
+                "llm_request_duration_seconds_sum{{{}}} {}",
+                labels, series.latency_sum
+            );
+            let _ = writeln!(
+                out,
+                "llm_request_duration_seconds_count{{{}}} {}",
+                labels, series.requests
+            );
+        }
+        out
+    }
+
+    /// Applies a change to the series of a provider and model.
+    fn update(&self, provider: &str, model: &str, change: impl FnOnce(&mut Series)) {
+        let mut series = self.series.lock().unwrap();
+        change(
+            series
+                .entry((provider.to_string(), model.to_string()))
+                .or_default(),
+        );
+    }
+}
+
+/// Writes the `HELP` and `TYPE` lines of a metric.
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Formats the provider and model labels of a series.
+fn labels(provider: &str, model: &str) -> String {
+    format!(
+        "provider=\"{}\",model=\"{}\"",
+        escape(provider),
+        escape(model)
+    )
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// A wrapper around an LLM provider recording the metrics of each request.
+///
+/// Metrics are keyed by the configured model. Streams count as one request, timed until
+/// they open, and record the usage of their final chunk.
+pub struct MetricsLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Registry receiving the metrics
+    metrics: Metrics,
+    /// Name of the wrapped provider
+    provider: String,
+    /// Model the provider was configured with
+    model: String,
+}
+
+impl MetricsLLM {
+    /// Wraps a provider, recording its metrics in a registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to measure
+    /// * `metrics` - Registry receiving the metrics
+    /// * `provider` - Value of the `provider` label (e.g. "openai")
+    /// * `model` - Value of the `model` label
+    pub fn new(
+        inner: Box<dyn LLMProvider>,
+        metrics: Metrics,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            metrics,
+            provider: provider.into(),
+            model: model.into(),
+        }
+    }
+
+    /// Records the request count, latency and failure of a request.
+    async fn measured<T, F>(&self, request: F) -> Result<T, LLMError>
+    where
+        F: Future<Output = Result<T, LLMError>> + Send,
+    {
+        let start = Instant::now();
+        let result = request.await;
+        self.metrics
+            .record_request(&self.provider, &self.model, start.elapsed().as_secs_f64());
+        if let Err(e) = &result {
+            self.metrics.record_error(&self.provider, &self.model, e);
+        }
+        result
+    }
+
+    /// Records the metrics of a chat request, including its usage.
+    async fn measured_chat<F>(&self, request: F) -> Result<Box<dyn ChatResponse>, LLMError>
+    where
+        F: Future<Output = Result<Box<dyn ChatResponse>, LLMError>> + Send,
+    {
+        let result = self.measured(request).await;
+        if let Some(usage) = result.as_ref().ok().and_then(|response| response.usage()) {
+            self.metrics
+                .record_usage(&self.provider, &self.model, &usage);
+        }
+        result
+    }
+
+    /// Records the metrics of a chat stream, whose usage arrives with its final chunk.
+    async fn measured_stream<F>(&self, request: F) -> Result<ChatStream, LLMError>
+    where
+        F: Future<Output = Result<ChatStream, LLMError>> + Send,
+    {
+        let stream = self.measured(request).await?;
+        let metrics = self.metrics.clone();
+        let provider = self.provider.clone();
+        let model = self.model.clone();
+        Ok(Box::pin(stream.inspect(move |chunk| match chunk {
+            Ok(StreamChunk::Done {
+                usage: Some(usage), ..
+            }) => metrics.record_usage(&provider, &model, usage),
+            Err(e) => metrics.record_error(&provider, &model, e),
+            _ => {}
+        })))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for MetricsLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for MetricsLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.measured_chat(self.inner.chat(messages)).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.measured_chat(self.inner.chat_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.measured_chat(self.inner.chat_with_options(messages, tools, options))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.measured_stream(self.inner.chat_stream_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.measured_stream(
+            self.inner
+                .chat_stream_with_options(messages, tools, options),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for MetricsLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.measured(self.inner.complete(req)).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.measured(self.inner.complete_stream(req)).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.measured(self.inner.complete_with_options(req, options))
+            .await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MetricsLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.measured(self.inner.embed(input)).await
+    }
+}
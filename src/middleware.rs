@@ -0,0 +1,326 @@
+//! Composable middleware for chat requests.
+//!
+//! An [`LLMMiddleware`] wraps chat calls: it receives each request with a [`Next`] handle
+//! to the rest of the stack, and may change the request, answer it without calling the
+//! provider, or change the response before returning it. A [`MiddlewareStack`] layers
+//! any number of them over a provider, so cross-cutting concerns such as caching, retries,
+//! guardrails or logging stack uniformly. Add middleware when building a provider with
+//! [`crate::builder::LLMBuilder::middleware`], or stack them over an existing provider.
+//!
+//! [`LLMMiddleware`]: crate::middleware::LLMMiddleware
+//! [`Next`]: crate::middleware::Next
+//! [`MiddlewareStack`]: crate::middleware::MiddlewareStack
+//!
+//! # Example
+//!
+//! ```no_run
+//! use async_trait::async_trait;
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider, ChatResponse};
+//! use llm::error::LLMError;
+//! use llm::middleware::{ChatRequest, LLMMiddleware, Next};
+//!
+//! /// Refuses prompts mentioning a forbidden word.
+//! struct Guardrail;
+//!
+//! #[async_trait]
+//! impl LLMMiddleware for Guardrail {
+//!     async fn chat(
+//!         &self,
+//!         request: ChatRequest,
+//!         next: Next<'_>,
+//!     ) -> Result<Box<dyn ChatResponse>, LLMError> {
+//!         if request.messages.iter().any(|m| m.content.contains("forbidden")) {
+//!             return Err(LLMError::Refused("Prompt rejected by guardrail".to_string()));
+//!         }
+//!         next.chat(request).await
+//!     }
+//! }
+//!
+//! # async fn run() -> Result<(), LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .middleware(Guardrail)
+//!     .build()?;
+//!
+//! llm.chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// A chat request passing through a middleware stack.
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    /// Messages of the conversation
+    pub messages: Vec<ChatMessage>,
+    /// Tools offered to the model
+    pub tools: Option<Vec<Tool>>,
+    /// Per-request overrides, or `None` to use the provider's configuration
+    pub options: Option<ChatOptions>,
+}
+
+impl ChatRequest {
+    /// Creates a request from borrowed parts.
+    pub fn new(
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: Option<&ChatOptions>,
+    ) -> Self {
+        Self {
+            messages: messages.to_vec(),
+            tools: tools.map(<[Tool]>::to_vec),
+            options: options.cloned(),
+        }
+    }
+}
+
+/// Handle to the rest of a middleware stack, ending with the provider.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    /// The provider at the bottom of the stack
+    provider: &'a dyn LLMProvider,
+    /// The middleware below the current one, outermost first
+    middleware: &'a [Arc<dyn LLMMiddleware>],
+}
+
+impl<'a> Next<'a> {
+    /// Sends a chat request through the rest of the stack.
+    pub async fn chat(self, request: ChatRequest) -> Result<Box<dyn ChatResponse>, LLMError> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => {
+                first
+                    .chat(
+                        request,
+                        Next {
+                            provider: self.provider,
+                            middleware: rest,
+                        },
+                    )
+                    .await
+            }
+            None => {
+                let tools = request.tools.as_deref();
+                match &request.options {
+                    Some(options) => {
+                        self.provider
+                            .chat_with_options(&request.messages, tools, options)
+                            .await
+                    }
+                    None => {
+                        self.provider
+                            .chat_with_tools(&request.messages, tools)
+                            .await
+                    }
+                }
+            }
+        }
+    }
+
+    /// Opens a chat stream through the rest of the stack.
+    pub async fn chat_stream(self, request: ChatRequest) -> Result<ChatStream, LLMError> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => {
+                first
+                    .chat_stream(
+                        request,
+                        Next {
+                            provider: self.provider,
+                            middleware: rest,
+                        },
+                    )
+                    .await
+            }
+            None => {
+                let tools = request.tools.as_deref();
+                match &request.options {
+                    Some(options) => {
+                        self.provider
+                            .chat_stream_with_options(&request.messages, tools, options)
+                            .await
+                    }
+                    None => {
+                        self.provider
+                            .chat_stream_with_tools(&request.messages, tools)
+                            .await
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A layer of a middleware stack, wrapping each chat call.
+///
+/// Both methods pass the request on unchanged by default, so a middleware only implements
+/// the calls it handles.
+#[async_trait]
+pub trait LLMMiddleware: Send + Sync {
+    /// Handles a chat request, calling `next` to pass it down the stack.
+    async fn chat(
+        &self,
+        request: ChatRequest,
+        next: Next<'_>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        next.chat(request).await
+    }
+
+    /// Handles a streaming chat request, calling `next` to pass it down the stack.
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        next: Next<'_>,
+    ) -> Result<ChatStream, LLMError> {
+        next.chat_stream(request).await
+    }
+}
+
+/// A provider with middleware layered over its chat requests.
+///
+/// The first middleware added is the outermost: it sees each request first and each
+/// response last. Completions and embeddings are passed through.
+pub struct MiddlewareStack {
+    /// The provider at the bottom of the stack
+    inner: Box<dyn LLMProvider>,
+    /// The middleware, outermost first
+    middleware: Vec<Arc<dyn LLMMiddleware>>,
+}
+
+impl MiddlewareStack {
+    /// Creates a stack with no middleware over a provider.
+    pub fn new(inner: Box<dyn LLMProvider>) -> Self {
+        Self {
+            inner,
+            middleware: Vec::new(),
+        }
+    }
+
+    /// Adds a middleware below the ones already added.
+    pub fn with(mut self, middleware: impl LLMMiddleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Adds shared middleware below the ones already added.
+    pub fn with_all(
+        mut self,
+        middleware: impl IntoIterator<Item = Arc<dyn LLMMiddleware>>,
+    ) -> Self {
+        self.middleware.extend(middleware);
+        self
+    }
+
+    /// Returns the number of middleware in the stack.
+    pub fn len(&self) -> usize {
+        self.middleware.len()
+    }
+
+    /// Returns whether the stack has no middleware.
+    pub fn is_empty(&self) -> bool {
+        self.middleware.is_empty()
+    }
+
+    /// Returns the handle to the top of the stack.
+    fn next(&self) -> Next<'_> {
+        Next {
+            provider: self.inner.as_ref(),
+            middleware: &self.middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for MiddlewareStack {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for MiddlewareStack {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.next()
+            .chat(ChatRequest::new(messages, tools, None))
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.next()
+            .chat(ChatRequest::new(messages, tools, Some(options)))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.next()
+            .chat_stream(ChatRequest::new(messages, tools, None))
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.next()
+            .chat_stream(ChatRequest::new(messages, tools, Some(options)))
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for MiddlewareStack {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MiddlewareStack {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
@@ -0,0 +1,329 @@
+//! Registry of known models and their capabilities.
+//!
+//! The registry describes the models of the built-in backends: their context window,
+//! output limit, supported features and deprecation status, so applications and other
+//! parts of the crate, such as truncation, can adapt to the model in use. Register
+//! custom entries for fine-tunes, self-hosted models or models released since; they take
+//! precedence over the built-in ones.
+//!
+//! Models are matched by name, and dated snapshots or size tags by their base model:
+//! "gpt-4o-2024-08-06" is described by "gpt-4o", and "llama3.1:8b" by "llama3.1".
+//!
+//! # Example
+//!
+//! ```
+//! use llm::models::{self, ModelInfo};
+//!
+//! let info = models::lookup("claude-3-5-haiku-20241022").unwrap();
+//! assert_eq!(info.provider, "anthropic");
+//! assert!(info.tools);
+//!
+//! models::register(ModelInfo::new("my-fine-tune", "openai", 16_384).tools(true));
+//! assert_eq!(models::lookup("my-fine-tune").unwrap().context_window, 16_384);
+//! ```
+
+use std::sync::{OnceLock, RwLock};
+
+/// Description of a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// Name of the model, or of the base model of its snapshots
+    pub name: String,
+    /// Backend serving the model (e.g. "openai")
+    pub provider: String,
+    /// Context window, in tokens
+    pub context_window: usize,
+    /// Most tokens a reply may take, if limited below the context window
+    pub max_output_tokens: Option<u32>,
+    /// Whether the model accepts images
+    pub vision: bool,
+    /// Whether the model can call tools
+    pub tools: bool,
+    /// Whether the model supports JSON mode or structured output
+    pub json_mode: bool,
+    /// Whether replies can be streamed
+    pub streaming: bool,
+    /// Whether the provider has deprecated the model
+    pub deprecated: bool,
+}
+
+impl ModelInfo {
+    /// Describes a model supporting streaming and no other feature.
+    pub fn new(
+        name: impl Into<String>,
+        provider: impl Into<String>,
+        context_window: usize,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            provider: provider.into(),
+            context_window,
+            max_output_tokens: None,
+            vision: false,
+            tools: false,
+            json_mode: false,
+            streaming: true,
+            deprecated: false,
+        }
+    }
+
+    /// Sets the most tokens a reply may take.
+    pub fn max_output_tokens(mut self, tokens: u32) -> Self {
+        self.max_output_tokens = Some(tokens);
+        self
+    }
+
+    /// Sets whether the model accepts images.
+    pub fn vision(mut self, vision: bool) -> Self {
+        self.vision = vision;
+        self
+    }
+
+    /// Sets whether the model can call tools.
+    pub fn tools(mut self, tools: bool) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Sets whether the model supports JSON mode or structured output.
+    pub fn json_mode(mut self, json_mode: bool) -> Self {
+        self.json_mode = json_mode;
+        self
+    }
+
+    /// Sets whether replies can be streamed.
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Sets whether the provider has deprecated the model.
+    pub fn deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+}
+
+/// Features of a built-in model.
+const VISION: u8 = 1;
+const TOOLS: u8 = 1 << 1;
+const JSON: u8 = 1 << 2;
+const STREAM: u8 = 1 << 3;
+const DEPRECATED: u8 = 1 << 4;
+
+/// A built-in registry entry.
+struct Known {
+    name: &'static str,
+    provider: &'static str,
+    context_window: usize,
+    max_output_tokens: Option<u32>,
+    features: u8,
+}
+
+const fn known(
+    name: &'static str,
+    provider: &'static str,
+    context_window: usize,
+    max_output_tokens: Option<u32>,
+    features: u8,
+) -> Known {
+    Known {
+        name,
+        provider,
+        context_window,
+        max_output_tokens,
+        features,
+    }
+}
+
+impl Known {
+    fn info(&self) -> ModelInfo {
+        ModelInfo {
+            name: self.name.to_string(),
+            provider: self.provider.to_string(),
+            context_window: self.context_window,
+            max_output_tokens: self.max_output_tokens,
+            vision: self.features & VISION != 0,
+            tools: self.features & TOOLS != 0,
+            json_mode: self.features & JSON != 0,
+            streaming: self.features & STREAM != 0,
+            deprecated: self.features & DEPRECATED != 0,
+        }
+    }
+}
+
+/// Every feature of current chat models.
+const ALL: u8 = VISION | TOOLS | JSON | STREAM;
+
+/// Models of the built-in backends. Family names such as "claude" describe the models of
+/// the family without an entry of their own.
+#[rustfmt::skip]
+const BUILT_IN: &[Known] = &[
+    // OpenAI
+    known("gpt-5", "openai", 400_000, Some(128_000), ALL),
+    known("gpt-5-mini", "openai", 400_000, Some(128_000), ALL),
+    known("gpt-5-nano", "openai", 400_000, Some(128_000), ALL),
+    known("gpt-4.1", "openai", 1_047_576, Some(32_768), ALL),
+    known("gpt-4.1-mini", "openai", 1_047_576, Some(32_768), ALL),
+    known("gpt-4.1-nano", "openai", 1_047_576, Some(32_768), ALL),
+    known("gpt-4o", "openai", 128_000, Some(16_384), ALL),
+    known("gpt-4o-mini", "openai", 128_000, Some(16_384), ALL),
+    known("gpt-4-turbo", "openai", 128_000, Some(4_096), ALL),
+    known("gpt-4", "openai", 8_192, Some(8_192), TOOLS | STREAM),
+    known("gpt-3.5-turbo", "openai", 16_385, Some(4_096), TOOLS | JSON | STREAM),
+    known("o1", "openai", 200_000, Some(100_000), ALL),
+    known("o1-mini", "openai", 128_000, Some(65_536), STREAM | DEPRECATED),
+    known("o3", "openai", 200_000, Some(100_000), ALL),
+    known("o3-mini", "openai", 200_000, Some(100_000), TOOLS | JSON | STREAM),
+    known("o4-mini", "openai", 200_000, Some(100_000), ALL),
+    // Anthropic
+    known("claude", "anthropic", 200_000, None, VISION | TOOLS | STREAM),
+    known("claude-opus-4", "anthropic", 200_000, Some(32_000), VISION | TOOLS | STREAM),
+    known("claude-opus-4-1", "anthropic", 200_000, Some(32_000), VISION | TOOLS | STREAM),
+    known("claude-sonnet-4", "anthropic", 200_000, Some(64_000), VISION | TOOLS | STREAM),
+    known("claude-sonnet-4-5", "anthropic", 200_000, Some(64_000), VISION | TOOLS | STREAM),
+    known("claude-haiku-4-5", "anthropic", 200_000, Some(64_000), VISION | TOOLS | STREAM),
+    known("claude-3-7-sonnet", "anthropic", 200_000, Some(64_000), VISION | TOOLS | STREAM),
+    known("claude-3-5-sonnet", "anthropic", 200_000, Some(8_192), VISION | TOOLS | STREAM),
+    known("claude-3-5-haiku", "anthropic", 200_000, Some(8_192), VISION | TOOLS | STREAM),
+    known("claude-3-opus", "anthropic", 200_000, Some(4_096), VISION | TOOLS | STREAM | DEPRECATED),
+    known("claude-3-haiku", "anthropic", 200_000, Some(4_096), VISION | TOOLS | STREAM),
+    // Google
+    known("gemini-2.5-pro", "google", 1_048_576, Some(65_536), ALL),
+    known("gemini-2.5-flash", "google", 1_048_576, Some(65_536), ALL),
+    known("gemini-2.5-flash-lite", "google", 1_048_576, Some(65_536), ALL),
+    known("gemini-2.0", "google", 1_048_576, Some(8_192), ALL),
+    known("gemini-2.0-flash", "google", 1_048_576, Some(8_192), ALL),
+    known("gemini-1.5-pro", "google", 2_097_152, Some(8_192), ALL | DEPRECATED),
+    known("gemini-1.5-flash", "google", 1_048_576, Some(8_192), ALL | DEPRECATED),
+    // xAI
+    known("grok", "xai", 131_072, None, TOOLS | JSON | STREAM),
+    known("grok-4", "xai", 256_000, None, ALL),
+    // DeepSeek
+    known("deepseek", "deepseek", 65_536, Some(8_192), TOOLS | JSON | STREAM),
+    known("deepseek-reasoner", "deepseek", 65_536, Some(8_192), JSON | STREAM),
+    // Groq
+    known("llama-3.1", "groq", 131_072, None, TOOLS | JSON | STREAM),
+    known("llama-3.2", "groq", 131_072, None, TOOLS | JSON | STREAM),
+    known("llama-3.3", "groq", 131_072, None, TOOLS | JSON | STREAM),
+    known("mixtral-8x7b", "groq", 32_768, None, TOOLS | JSON | STREAM | DEPRECATED),
+    // Mistral
+    known("mistral-large", "mistral", 131_072, None, TOOLS | JSON | STREAM),
+    known("mistral-medium", "mistral", 131_072, None, ALL),
+    known("mistral-small", "mistral", 131_072, None, ALL),
+    known("magistral-medium", "mistral", 40_000, None, TOOLS | JSON | STREAM),
+    known("magistral-small", "mistral", 40_000, None, TOOLS | JSON | STREAM),
+    known("codestral", "mistral", 256_000, None, TOOLS | JSON | STREAM),
+    known("pixtral-large", "mistral", 131_072, None, ALL),
+    known("mistral-embed", "mistral", 8_192, None, 0),
+    // Cohere
+    known("command-a", "cohere", 256_000, Some(8_000), TOOLS | JSON | STREAM),
+    known("command-r-plus", "cohere", 128_000, Some(4_000), TOOLS | JSON | STREAM),
+    known("command-r", "cohere", 128_000, Some(4_000), TOOLS | JSON | STREAM),
+    known("embed-english-v3.0", "cohere", 512, None, 0),
+    known("embed-multilingual-v3.0", "cohere", 512, None, 0),
+    // Fireworks
+    known("accounts/fireworks/models/llama-v3p1", "fireworks", 131_072, None, TOOLS | JSON | STREAM),
+    known("accounts/fireworks/models/qwen2p5-72b-instruct", "fireworks", 32_768, None, TOOLS | JSON | STREAM),
+    known("accounts/fireworks/models/deepseek-v3", "fireworks", 131_072, None, TOOLS | JSON | STREAM),
+    // Perplexity
+    known("sonar", "perplexity", 128_000, None, JSON | STREAM),
+    known("sonar-pro", "perplexity", 200_000, Some(8_000), JSON | STREAM),
+    known("sonar-reasoning", "perplexity", 128_000, None, JSON | STREAM),
+    known("sonar-reasoning-pro", "perplexity", 128_000, None, JSON | STREAM),
+    known("sonar-deep-research", "perplexity", 128_000, None, JSON | STREAM),
+    // NVIDIA NIM
+    known("meta/llama-3.1-8b-instruct", "nvidia", 131_072, None, TOOLS | JSON | STREAM),
+    known("meta/llama-3.1-70b-instruct", "nvidia", 131_072, None, TOOLS | JSON | STREAM),
+    known("meta/llama-3.1-405b-instruct", "nvidia", 131_072, None, TOOLS | JSON | STREAM),
+    known("nvidia/llama-3.1-nemotron-70b-instruct", "nvidia", 131_072, None, TOOLS | JSON | STREAM),
+    known("nvidia/nv-embedqa-e5-v5", "nvidia", 512, None, 0),
+    // Qwen
+    known("qwen-max", "qwen", 32_768, Some(8_192), TOOLS | JSON | STREAM),
+    known("qwen-plus", "qwen", 131_072, Some(16_384), TOOLS | JSON | STREAM),
+    known("qwen-turbo", "qwen", 1_000_000, Some(16_384), TOOLS | JSON | STREAM),
+    known("qwen-vl-max", "qwen", 131_072, Some(8_192), ALL),
+    known("qwen-vl-plus", "qwen", 131_072, Some(8_192), ALL),
+    known("text-embedding-v3", "qwen", 8_192, None, 0),
+    // Moonshot
+    known("moonshot-v1-8k", "moonshot", 8_192, None, TOOLS | JSON | STREAM),
+    known("moonshot-v1-32k", "moonshot", 32_768, None, TOOLS | JSON | STREAM),
+    known("moonshot-v1-128k", "moonshot", 131_072, None, TOOLS | JSON | STREAM),
+    known("moonshot-v1-auto", "moonshot", 131_072, None, TOOLS | JSON | STREAM),
+    known("kimi-latest", "moonshot", 131_072, None, ALL),
+    known("kimi-k2-0905-preview", "moonshot", 262_144, None, TOOLS | JSON | STREAM),
+    // Voyage
+    known("voyage-3.5", "voyage", 32_000, None, 0),
+    known("voyage-3.5-lite", "voyage", 32_000, None, 0),
+    known("voyage-3-large", "voyage", 32_000, None, 0),
+    known("voyage-code-3", "voyage", 32_000, None, 0),
+    known("voyage-finance-2", "voyage", 32_000, None, 0),
+    known("voyage-law-2", "voyage", 16_000, None, 0),
+    // Jina
+    known("jina-embeddings-v3", "jina", 8_192, None, 0),
+    known("jina-embeddings-v4", "jina", 32_768, None, 0),
+    known("jina-clip-v2", "jina", 8_192, None, 0),
+    known("jina-reranker-v2-base-multilingual", "jina", 1_024, None, 0),
+    known("jina-reranker-m0", "jina", 10_240, None, 0),
+    // Ollama
+    known("llama3", "ollama", 8_192, None, STREAM),
+    known("llama3.1", "ollama", 131_072, None, TOOLS | JSON | STREAM),
+    known("llama3.2", "ollama", 131_072, None, TOOLS | JSON | STREAM),
+    known("llama3.3", "ollama", 131_072, None, TOOLS | JSON | STREAM),
+];
+
+/// Entries added with [`register`], which take precedence over the built-in ones.
+fn custom() -> &'static RwLock<Vec<ModelInfo>> {
+    static CUSTOM: OnceLock<RwLock<Vec<ModelInfo>>> = OnceLock::new();
+    CUSTOM.get_or_init(Default::default)
+}
+
+/// Adds a model to the registry, replacing any custom entry of the same name.
+pub fn register(info: ModelInfo) {
+    let mut custom = custom().write().unwrap();
+    custom.retain(|entry| entry.name != info.name);
+    custom.push(info);
+}
+
+/// Describes a model, or returns `None` if it is unknown.
+pub fn lookup(model: &str) -> Option<ModelInfo> {
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    let custom = custom().read().unwrap();
+    best_match(model, custom.iter().map(|info| (info.name.as_str(), info)))
+        .cloned()
+        .or_else(|| {
+            best_match(model, BUILT_IN.iter().map(|known| (known.name, known))).map(Known::info)
+        })
+}
+
+/// Lists the models of a backend (e.g. "openai"), custom entries first.
+pub fn list(provider: &str) -> Vec<ModelInfo> {
+    let mut models: Vec<ModelInfo> = custom()
+        .read()
+        .unwrap()
+        .iter()
+        .filter(|info| info.provider == provider)
+        .cloned()
+        .collect();
+    let built_in: Vec<ModelInfo> = BUILT_IN
+        .iter()
+        .filter(|known| known.provider == provider)
+        .filter(|known| !models.iter().any(|info| info.name == known.name))
+        .map(Known::info)
+        .collect();
+    models.extend(built_in);
+    models
+}
+
+/// Finds the entry with the longest name that is the model itself or the base of one of
+/// its snapshots.
+fn best_match<'a, T>(model: &str, entries: impl Iterator<Item = (&'a str, T)>) -> Option<T> {
+    entries
+        .filter(|(name, _)| {
+            model
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '@', ':']))
+        })
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, entry)| entry)
+}
@@ -0,0 +1,68 @@
+//! Screening of texts for harmful content.
+//!
+//! A moderation model scores a text against categories of harmful content (harassment,
+//! hate, self-harm, sexual content, violence...) and flags it when a category applies, so
+//! applications can screen what users send before generating a reply, and the reply
+//! before showing it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::LLMBuilder;
+//! use llm::moderation::ModerationProvider;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let openai = LLMBuilder::new().api_key("sk-...").build_openai()?;
+//! let results = openai
+//!     .moderate(&["I will find you and hurt you.".to_string()])
+//!     .await?;
+//! if results[0].flagged {
+//!     println!("Flagged for {:?}", results[0].flagged_categories());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::LLMError;
+
+/// Verdict of the moderation model on one of the texts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModerationResult {
+    /// Whether any category applies to the text
+    pub flagged: bool,
+    /// Whether each category applies, by category name (e.g. "harassment/threatening")
+    pub categories: BTreeMap<String, bool>,
+    /// Confidence that each category applies, between 0 and 1, by category name
+    pub category_scores: BTreeMap<String, f32>,
+}
+
+impl ModerationResult {
+    /// Returns the names of the categories applying to the text.
+    pub fn flagged_categories(&self) -> Vec<&str> {
+        self.categories
+            .iter()
+            .filter(|(_, flagged)| **flagged)
+            .map(|(category, _)| category.as_str())
+            .collect()
+    }
+}
+
+/// Trait for providers screening texts for harmful content.
+#[async_trait]
+pub trait ModerationProvider {
+    /// Screens texts for harmful content.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The texts to screen
+    ///
+    /// # Returns
+    ///
+    /// One verdict per text, in the order of `input`
+    async fn moderate(&self, input: &[String]) -> Result<Vec<ModerationResult>, LLMError>;
+}
@@ -0,0 +1,243 @@
+//! Cost estimation from token usage.
+//!
+//! The built-in table holds the list prices of common OpenAI, Anthropic and Google
+//! models, in US dollars per million tokens. Prices change, so treat estimates as
+//! indicative and keep the table current with [`set_price`], which also adds models the
+//! table doesn't know.
+//!
+//! Dated snapshots are priced as their base model: "gpt-4o-2024-08-06" uses the price of
+//! "gpt-4o".
+//!
+//! [`set_price`]: crate::pricing::set_price
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::ChatMessage;
+//! use llm::pricing::{self, ModelPrice};
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! // Negotiated prices take precedence over the built-in ones.
+//! pricing::set_price("gpt-4o", ModelPrice::new(2.0, 8.0));
+//!
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .model("gpt-4o")
+//!     .build()?;
+//! let reply = llm.chat(&[ChatMessage::user().content("Hello!").build()]).await?;
+//! if let Some(cost) = reply.cost() {
+//!     println!("This reply cost ${:.6}", cost.total_cost);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use crate::chat::Usage;
+
+/// Price of a model, in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    /// Price of a million prompt tokens
+    pub input_per_million: f64,
+    /// Price of a million generated tokens
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    /// Creates a price from the costs of a million prompt and generated tokens.
+    pub const fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Returns the cost of the tokens of a request.
+    pub fn estimate(&self, usage: &Usage) -> CostEstimate {
+        let input_cost = f64::from(usage.prompt_tokens) * self.input_per_million / 1e6;
+        let output_cost = f64::from(usage.completion_tokens) * self.output_per_million / 1e6;
+        CostEstimate {
+            input_cost,
+            output_cost,
+            total_cost: input_cost + output_cost,
+        }
+    }
+}
+
+/// Estimated cost of a request, in US dollars.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostEstimate {
+    /// Cost of the prompt tokens
+    pub input_cost: f64,
+    /// Cost of the generated tokens
+    pub output_cost: f64,
+    /// Cost of the whole request
+    pub total_cost: f64,
+}
+
+/// List prices of known models, in US dollars per million prompt and generated tokens.
+const BUILT_IN_PRICES: &[(&str, ModelPrice)] = &[
+    // OpenAI
+    ("gpt-5", ModelPrice::new(1.25, 10.0)),
+    ("gpt-5-mini", ModelPrice::new(0.25, 2.0)),
+    ("gpt-5-nano", ModelPrice::new(0.05, 0.4)),
+    ("gpt-4.1", ModelPrice::new(2.0, 8.0)),
+    ("gpt-4.1-mini", ModelPrice::new(0.4, 1.6)),
+    ("gpt-4.1-nano", ModelPrice::new(0.1, 0.4)),
+    ("gpt-4o", ModelPrice::new(2.5, 10.0)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.6)),
+    ("gpt-4-turbo", ModelPrice::new(10.0, 30.0)),
+    ("gpt-4", ModelPrice::new(30.0, 60.0)),
+    ("gpt-3.5-turbo", ModelPrice::new(0.5, 1.5)),
+    ("o1", ModelPrice::new(15.0, 60.0)),
+    ("o1-mini", ModelPrice::new(1.1, 4.4)),
+    ("o3", ModelPrice::new(2.0, 8.0)),
+    ("o3-mini", ModelPrice::new(1.1, 4.4)),
+    ("o4-mini", ModelPrice::new(1.1, 4.4)),
+    // Anthropic
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0)),
+    ("claude-opus-4-1", ModelPrice::new(15.0, 75.0)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0)),
+    ("claude-sonnet-4-5", ModelPrice::new(3.0, 15.0)),
+    ("claude-haiku-4-5", ModelPrice::new(1.0, 5.0)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0)),
+    ("claude-3-opus", ModelPrice::new(15.0, 75.0)),
+    ("claude-3-haiku", ModelPrice::new(0.25, 1.25)),
+    // Google
+    ("gemini-2.5-pro", ModelPrice::new(1.25, 10.0)),
+    ("gemini-2.5-flash", ModelPrice::new(0.3, 2.5)),
+    ("gemini-2.5-flash-lite", ModelPrice::new(0.1, 0.4)),
+    ("gemini-2.0-flash", ModelPrice::new(0.1, 0.4)),
+    ("gemini-2.0-flash-lite", ModelPrice::new(0.075, 0.3)),
+    ("gemini-1.5-pro", ModelPrice::new(1.25, 5.0)),
+    ("gemini-1.5-flash", ModelPrice::new(0.075, 0.3)),
+];
+
+/// Prices set with [`set_price`], which take precedence over the built-in ones.
+fn overrides() -> &'static RwLock<HashMap<String, ModelPrice>> {
+    static OVERRIDES: OnceLock<RwLock<HashMap<String, ModelPrice>>> = OnceLock::new();
+    OVERRIDES.get_or_init(Default::default)
+}
+
+/// Sets the price of a model, replacing its built-in price if it has one.
+///
+/// The price also applies to the dated snapshots of the model.
+pub fn set_price(model: impl Into<String>, price: ModelPrice) {
+    overrides().write().unwrap().insert(model.into(), price);
+}
+
+/// Returns the price of a model, or `None` if it is unknown.
+///
+/// ```
+/// use llm::pricing::{self, ModelPrice};
+///
+/// assert_eq!(
+///     pricing::price("claude-3-5-haiku-20241022"),
+///     pricing::price("claude-3-5-haiku")
+/// );
+/// assert_eq!(pricing::price("my-fine-tune"), None);
+///
+/// pricing::set_price("my-fine-tune", ModelPrice::new(1.0, 2.0));
+/// assert_eq!(pricing::price("my-fine-tune"), Some(ModelPrice::new(1.0, 2.0)));
+/// ```
+pub fn price(model: &str) -> Option<ModelPrice> {
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    let overrides = overrides().read().unwrap();
+    lookup(
+        model,
+        overrides
+            .iter()
+            .map(|(name, price)| (name.as_str(), *price)),
+    )
+    .or_else(|| lookup(model, BUILT_IN_PRICES.iter().copied()))
+}
+
+/// Returns the estimated cost of a request to a model, or `None` if the model is unknown.
+pub fn estimate(model: &str, usage: &Usage) -> Option<CostEstimate> {
+    Some(price(model)?.estimate(usage))
+}
+
+/// Finds the price of the longest model name that is the model itself or the base of one
+/// of its snapshots.
+fn lookup<'a>(
+    model: &str,
+    prices: impl Iterator<Item = (&'a str, ModelPrice)>,
+) -> Option<ModelPrice> {
+    prices
+        .filter(|(name, _)| {
+            model
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '@']))
+        })
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, price)| price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt_tokens: u32, completion_tokens: u32) -> Usage {
+        Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cached_prompt_tokens: None,
+        }
+    }
+
+    #[test]
+    fn estimates_cost_per_million_tokens() {
+        let cost = ModelPrice::new(2.0, 8.0).estimate(&usage(500_000, 250_000));
+        assert_eq!(cost.input_cost, 1.0);
+        assert_eq!(cost.output_cost, 2.0);
+        assert_eq!(cost.total_cost, 3.0);
+    }
+
+    #[test]
+    fn snapshots_use_the_price_of_their_model() {
+        assert_eq!(price("gpt-4o-2024-08-06"), price("gpt-4o"));
+        assert_eq!(
+            price("claude-3-5-haiku@20241022"),
+            price("claude-3-5-haiku")
+        );
+        assert_eq!(price("models/gemini-2.5-pro"), price("gemini-2.5-pro"));
+    }
+
+    #[test]
+    fn longest_matching_name_wins() {
+        assert_eq!(price("gpt-4o-mini-2024-07-18"), price("gpt-4o-mini"));
+        assert_ne!(price("gpt-4o-mini"), price("gpt-4o"));
+        assert_eq!(price("gpt-4-0613"), Some(ModelPrice::new(30.0, 60.0)));
+    }
+
+    #[test]
+    fn names_only_match_at_a_separator() {
+        assert_eq!(price("o1"), Some(ModelPrice::new(15.0, 60.0)));
+        assert_eq!(price("o1x"), None);
+        assert_eq!(estimate("unknown-model", &usage(1, 1)), None);
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        set_price("pricing-test-model", ModelPrice::new(1.0, 2.0));
+        assert_eq!(
+            price("pricing-test-model-2025"),
+            Some(ModelPrice::new(1.0, 2.0))
+        );
+
+        set_price("gpt-3.5-turbo-pricing-test", ModelPrice::new(0.0, 0.0));
+        assert_eq!(
+            price("gpt-3.5-turbo-pricing-test"),
+            Some(ModelPrice::new(0.0, 0.0))
+        );
+        assert_eq!(price("gpt-3.5-turbo"), Some(ModelPrice::new(0.5, 1.5)));
+    }
+}
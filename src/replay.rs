@@ -0,0 +1,458 @@
+//! Recording of provider interactions to fixture files, and their replay.
+//!
+//! A [`ReplayLLM`] records the chat requests, streams, completions and embeddings a
+//! provider serves into a JSON fixture file, and replays them later without network
+//! access, so applications built on this crate can run integration tests in CI. Requests
+//! are matched on everything that affects the reply: messages, tools and sampling
+//! overrides. Identical requests are replayed in the order they were recorded.
+//!
+//! Set up replay when building a provider with [`crate::builder::LLMBuilder::replay`],
+//! usually with the mode read from the `LLM_REPLAY` environment variable by
+//! [`ReplayMode::from_env`]: record fixtures locally with `LLM_REPLAY=record`, and run
+//! CI with `LLM_REPLAY=replay`.
+//!
+//! [`ReplayLLM`]: crate::replay::ReplayLLM
+//! [`ReplayMode::from_env`]: crate::replay::ReplayMode::from_env
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::replay::ReplayMode;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     // Any key works when replaying.
+//!     .api_key(std::env::var("OPENAI_API_KEY").unwrap_or_default())
+//!     .model("gpt-4o-mini")
+//!     .replay("tests/fixtures/greeting.json", ReplayMode::from_env())
+//!     .build()?;
+//!
+//! let reply = llm
+//!     .chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! assert!(reply.text().is_some());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, ResponseSnapshot,
+    StreamChunk, Tool,
+};
+use crate::coalesce::request_key;
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// Environment variable read by [`ReplayMode::from_env`].
+pub const REPLAY_ENV_VAR: &str = "LLM_REPLAY";
+
+/// How a [`ReplayLLM`] uses its fixture file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Sends every request to the provider and records it, replacing the fixture file
+    Record,
+    /// Serves every request from the fixture file, failing for requests it lacks
+    Replay,
+    /// Serves the requests the fixture file has and records the others
+    Auto,
+}
+
+impl ReplayMode {
+    /// Reads the mode from the `LLM_REPLAY` environment variable: "record", "replay" or
+    /// "auto", which is also the mode when the variable is unset or unrecognized.
+    pub fn from_env() -> Self {
+        match std::env::var(REPLAY_ENV_VAR).as_deref() {
+            Ok("record") => ReplayMode::Record,
+            Ok("replay") => ReplayMode::Replay,
+            _ => ReplayMode::Auto,
+        }
+    }
+}
+
+/// Layout of a fixture file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Fixture {
+    interactions: Vec<Interaction>,
+}
+
+/// A recorded request and what the provider answered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: Value,
+    reply: Reply,
+}
+
+/// What the provider answered a request with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Reply {
+    Chat(Box<ResponseSnapshot>),
+    Stream(Vec<StreamChunk>),
+    Completion(String),
+    Embeddings(Vec<Vec<f32>>),
+}
+
+/// The fixture of a provider, shared with the streams it records.
+#[derive(Debug)]
+struct Recorder {
+    path: PathBuf,
+    mode: ReplayMode,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Debug)]
+struct RecorderState {
+    fixture: Fixture,
+    /// Whether each interaction was replayed
+    served: Vec<bool>,
+}
+
+impl Recorder {
+    /// Returns the reply recorded for a request, preferring interactions not yet replayed.
+    fn find(&self, request: &Value) -> Option<Reply> {
+        let mut state = self.state.lock().unwrap();
+        let matching: Vec<usize> = (0..state.fixture.interactions.len())
+            .filter(|&i| state.fixture.interactions[i].request == *request)
+            .collect();
+        let index = matching
+            .iter()
+            .copied()
+            .find(|&i| !state.served[i])
+            .or_else(|| matching.last().copied())?;
+        state.served[index] = true;
+        Some(state.fixture.interactions[index].reply.clone())
+    }
+
+    /// Appends an interaction and saves the fixture.
+    fn record(&self, request: Value, reply: Reply) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .fixture
+            .interactions
+            .push(Interaction { request, reply });
+        state.served.push(true);
+        if let Err(e) = save(&self.path, &state.fixture) {
+            tracing::warn!(path = %self.path.display(), error = %e, "failed to save fixture");
+        }
+    }
+}
+
+/// Writes a fixture file, through a temporary file so readers never see a partial one.
+fn save(path: &Path, fixture: &Fixture) -> Result<(), LLMError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| LLMError::ProviderError(e.to_string()))?;
+    }
+    let json =
+        serde_json::to_vec_pretty(fixture).map_err(|e| LLMError::JsonError(e.to_string()))?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json).map_err(|e| LLMError::ProviderError(e.to_string()))?;
+    fs::rename(&tmp, path).map_err(|e| LLMError::ProviderError(e.to_string()))
+}
+
+/// A wrapper around an LLM provider recording its interactions to a fixture file, or
+/// replaying them from it.
+///
+/// Only successful requests are recorded; streams are recorded once they end. Completion
+/// streams are passed through.
+pub struct ReplayLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// The fixture being recorded or replayed
+    recorder: Arc<Recorder>,
+}
+
+impl ReplayLLM {
+    /// Wraps a provider, recording to or replaying from a fixture file.
+    ///
+    /// Fails if the fixture cannot be read, or does not exist in [`ReplayMode::Replay`].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to record
+    /// * `path` - Path of the JSON fixture file
+    /// * `mode` - Whether requests are recorded, replayed, or replayed when recorded
+    pub fn new(
+        inner: Box<dyn LLMProvider>,
+        path: impl Into<PathBuf>,
+        mode: ReplayMode,
+    ) -> Result<Self, LLMError> {
+        let path = path.into();
+        let fixture = match mode {
+            ReplayMode::Record => Fixture::default(),
+            ReplayMode::Auto if !path.exists() => Fixture::default(),
+            ReplayMode::Replay | ReplayMode::Auto => {
+                let json = fs::read(&path).map_err(|e| {
+                    LLMError::InvalidRequest(format!(
+                        "Cannot read fixture '{}': {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                serde_json::from_slice(&json).map_err(|e| LLMError::JsonError(e.to_string()))?
+            }
+        };
+        let served = vec![false; fixture.interactions.len()];
+        Ok(Self {
+            inner,
+            recorder: Arc::new(Recorder {
+                path,
+                mode,
+                state: Mutex::new(RecorderState { fixture, served }),
+            }),
+        })
+    }
+
+    /// Returns the mode the fixture is used in.
+    pub fn mode(&self) -> ReplayMode {
+        self.recorder.mode
+    }
+
+    /// Returns the recorded reply, unless the request must go to the provider.
+    fn replayed(&self, request: &Value) -> Result<Option<Reply>, LLMError> {
+        if self.recorder.mode == ReplayMode::Record {
+            return Ok(None);
+        }
+        match self.recorder.find(request) {
+            Some(reply) => Ok(Some(reply)),
+            None if self.recorder.mode == ReplayMode::Replay => {
+                Err(LLMError::InvalidRequest(format!(
+                    "No recorded interaction in '{}' matches the request",
+                    self.recorder.path.display()
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Serves a chat request from the fixture, or sends it with `send` and records it.
+    async fn replay_chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: Option<&ChatOptions>,
+        send: impl Future<Output = Result<Box<dyn ChatResponse>, LLMError>> + Send,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let request = chat_request("chat", messages, tools, options)?;
+        if let Some(reply) = self.replayed(&request)? {
+            return match reply {
+                Reply::Chat(snapshot) => Ok(snapshot),
+                _ => Err(mismatched_reply()),
+            };
+        }
+        let snapshot = ResponseSnapshot::from_response(send.await?.as_ref());
+        self.recorder
+            .record(request, Reply::Chat(Box::new(snapshot.clone())));
+        Ok(Box::new(snapshot))
+    }
+
+    /// Serves a chat stream from the fixture, or opens it and records its chunks.
+    async fn replay_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: Option<&ChatOptions>,
+    ) -> Result<ChatStream, LLMError> {
+        let request = chat_request("stream", messages, tools, options)?;
+        if let Some(reply) = self.replayed(&request)? {
+            return match reply {
+                Reply::Stream(chunks) => Ok(Box::pin(stream::iter(chunks.into_iter().map(Ok)))),
+                _ => Err(mismatched_reply()),
+            };
+        }
+        let stream = match options {
+            Some(options) => {
+                self.inner
+                    .chat_stream_with_options(messages, tools, options)
+                    .await?
+            }
+            None => self.inner.chat_stream_with_tools(messages, tools).await?,
+        };
+        let recorder = Arc::clone(&self.recorder);
+        let mut chunks = Vec::new();
+        let mut request = Some(request);
+        Ok(Box::pin(stream.inspect(move |chunk| match chunk {
+            Ok(chunk) => {
+                chunks.push(chunk.clone());
+                if matches!(chunk, StreamChunk::Done { .. }) {
+                    if let Some(request) = request.take() {
+                        recorder.record(request, Reply::Stream(std::mem::take(&mut chunks)));
+                    }
+                }
+            }
+            // A failed stream is not worth replaying.
+            Err(_) => request = None,
+        })))
+    }
+
+    /// Serves a completion from the fixture, or sends and records it.
+    async fn replay_completion(
+        &self,
+        req: &CompletionRequest,
+        options: Option<&ChatOptions>,
+    ) -> Result<CompletionResponse, LLMError> {
+        let request = json!({
+            "operation": "complete",
+            "prompt": req.prompt,
+            "max_tokens": req.max_tokens,
+            "temperature": req.temperature,
+            "options": options.map(|options| request_key(&[], None, options)).transpose()?,
+        });
+        if let Some(reply) = self.replayed(&request)? {
+            return match reply {
+                Reply::Completion(text) => Ok(CompletionResponse { text }),
+                _ => Err(mismatched_reply()),
+            };
+        }
+        let response = match options {
+            Some(options) => self.inner.complete_with_options(req, options).await?,
+            None => self.inner.complete(req).await?,
+        };
+        self.recorder
+            .record(request, Reply::Completion(response.text.clone()));
+        Ok(response)
+    }
+}
+
+/// Identifies a chat request in a fixture.
+fn chat_request(
+    operation: &str,
+    messages: &[ChatMessage],
+    tools: Option<&[Tool]>,
+    options: Option<&ChatOptions>,
+) -> Result<Value, LLMError> {
+    let key = request_key(messages, tools, options.unwrap_or(&ChatOptions::default()))?;
+    let mut request: Value =
+        serde_json::from_str(&key).map_err(|e| LLMError::JsonError(e.to_string()))?;
+    request["operation"] = json!(operation);
+    Ok(request)
+}
+
+/// The error for a fixture recording another kind of reply than requested.
+fn mismatched_reply() -> LLMError {
+    LLMError::InvalidRequest("The recorded interaction is of another kind".to_string())
+}
+
+#[async_trait]
+impl LLMProvider for ReplayLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        match self.recorder.mode {
+            // Replays never reach the provider.
+            ReplayMode::Replay => HealthStatus::Healthy {
+                latency: Default::default(),
+            },
+            ReplayMode::Record | ReplayMode::Auto => self.inner.validate().await,
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for ReplayLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.replay_chat(messages, None, None, self.inner.chat(messages))
+            .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.replay_chat(
+            messages,
+            tools,
+            None,
+            self.inner.chat_with_tools(messages, tools),
+        )
+        .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.replay_chat(
+            messages,
+            tools,
+            Some(options),
+            self.inner.chat_with_options(messages, tools, options),
+        )
+        .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.replay_stream(messages, tools, None).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.replay_stream(messages, tools, Some(options)).await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for ReplayLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.replay_completion(req, None).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.replay_completion(req, Some(options)).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ReplayLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        let request = json!({"operation": "embed", "input": input});
+        if let Some(reply) = self.replayed(&request)? {
+            return match reply {
+                Reply::Embeddings(embeddings) => Ok(embeddings),
+                _ => Err(mismatched_reply()),
+            };
+        }
+        let embeddings = self.inner.embed(input).await?;
+        self.recorder
+            .record(request, Reply::Embeddings(embeddings.clone()));
+        Ok(embeddings)
+    }
+}
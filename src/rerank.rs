@@ -0,0 +1,56 @@
+//! Reranking of documents by relevance to a query.
+//!
+//! A reranker scores each document against the query with a cross-encoder, which ranks
+//! more accurately than comparing embeddings but costs a request per query, so retrieval
+//! pipelines usually rerank the few dozen candidates an embedding search returned.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::backends::{jina::Jina, HttpOptions};
+//! use llm::rerank::RerankProvider;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let jina = Jina::new("jina_...", None, None, None, None, None, HttpOptions::default());
+//! let documents = vec![
+//!     "The Eiffel Tower is in Paris.".to_string(),
+//!     "Rust guarantees memory safety without a garbage collector.".to_string(),
+//! ];
+//! for result in jina.rerank("Which language is memory safe?", &documents, Some(1)).await? {
+//!     println!("{:.3} {}", result.relevance_score, documents[result.index]);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::LLMError;
+
+/// Relevance of one of the reranked documents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RerankResult {
+    /// Position of the document in the list given to the reranker
+    pub index: usize,
+    /// Relevance of the document to the query, higher meaning more relevant
+    pub relevance_score: f32,
+}
+
+/// Trait for providers ranking documents by relevance to a query.
+#[async_trait]
+pub trait RerankProvider {
+    /// Ranks documents by relevance to a query, most relevant first.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query the documents are compared with
+    /// * `documents` - The documents to rank
+    /// * `top_n` - How many of the most relevant documents to return (all by default)
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: Option<usize>,
+    ) -> Result<Vec<RerankResult>, LLMError>;
+}
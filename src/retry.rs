@@ -0,0 +1,210 @@
+//! Retrying of transient request failures.
+//!
+//! A [`RetryPolicy`] makes the backends resend requests that failed with a transport error,
+//! a server error (5xx) or a rate limit (429), waiting an exponentially growing delay
+//! between attempts. Attach it when building a provider with
+//! [`crate::builder::LLMBuilder::retry`].
+//!
+//! [`RetryPolicy`]: crate::retry::RetryPolicy
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::retry::{RetryOn, RetryPolicy};
+//!
+//! # fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .retry(
+//!         RetryPolicy::new(5)
+//!             .base_delay(Duration::from_millis(250))
+//!             .retry_on([RetryOn::Transport, RetryOn::RateLimit]),
+//!     )
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Class of transient failures a [`RetryPolicy`] can retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryOn {
+    /// Connection failures and timeouts
+    Transport,
+    /// Responses with a 5xx status
+    ServerError,
+    /// Responses with a 429 status
+    RateLimit,
+}
+
+/// Policy deciding which failed requests are resent, and how long to wait in between.
+///
+/// The delay before the n-th retry is `base_delay * 2^(n-1)`, capped at `max_delay`. With
+/// jitter, each delay is shortened by a random amount of up to half, so clients failing
+/// together do not retry together. Rate-limited requests wait for the delay given by the
+/// provider's `Retry-After` or rate-limit reset headers instead, and are not retried if it
+/// is longer than `max_delay`.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use llm::retry::RetryPolicy;
+///
+/// let policy = RetryPolicy::new(4)
+///     .base_delay(Duration::from_secs(1))
+///     .jitter(false);
+/// assert_eq!(policy.delay(1), Duration::from_secs(1));
+/// assert_eq!(policy.delay(3), Duration::from_secs(4));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Longest delay between two attempts
+    pub max_delay: Duration,
+    /// Whether delays are randomized
+    pub jitter: bool,
+    /// Failures that are retried
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, starting at 500 ms with jitter, retrying every failure class.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_on: vec![RetryOn::Transport, RetryOn::ServerError, RetryOn::RateLimit],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default policy with a maximum number of attempts.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Sets the longest delay between two attempts.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Enables or disables randomized delays.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the failures that are retried.
+    pub fn retry_on(mut self, classes: impl IntoIterator<Item = RetryOn>) -> Self {
+        self.retry_on = classes.into_iter().collect();
+        self
+    }
+
+    /// Returns whether failures of a class are retried.
+    pub fn retries(&self, class: RetryOn) -> bool {
+        self.retry_on.contains(&class)
+    }
+
+    /// Returns the delay before a retry, counted from 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if self.jitter {
+            delay.mul_f64(1.0 - random_fraction() / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Returns a random number in `[0, 1)`, from the randomly seeded standard hasher.
+fn random_fraction() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_doubles_with_each_retry() {
+        let policy = RetryPolicy::new(5)
+            .base_delay(Duration::from_millis(100))
+            .jitter(false);
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(4), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn delay_is_capped_at_max_delay() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(5))
+            .jitter(false);
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(4), Duration::from_secs(5));
+        assert_eq!(policy.delay(9), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn delay_saturates_instead_of_overflowing() {
+        let policy = RetryPolicy::new(u32::MAX)
+            .base_delay(Duration::from_secs(u64::MAX / 2))
+            .max_delay(Duration::MAX)
+            .jitter(false);
+        assert_eq!(policy.delay(40), Duration::MAX);
+        assert_eq!(policy.delay(u32::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn jitter_shortens_delay_by_up_to_half() {
+        let policy = RetryPolicy::new(5).base_delay(Duration::from_secs(2));
+        for _ in 0..100 {
+            let delay = policy.delay(2);
+            assert!(delay > Duration::from_secs(2));
+            assert!(delay <= Duration::from_secs(4));
+        }
+    }
+
+    #[test]
+    fn random_fraction_is_in_unit_interval() {
+        for _ in 0..100 {
+            let fraction = random_fraction();
+            assert!((0.0..1.0).contains(&fraction));
+        }
+    }
+
+    #[test]
+    fn retries_only_selected_failures() {
+        let policy = RetryPolicy::new(3).retry_on([RetryOn::RateLimit]);
+        assert!(policy.retries(RetryOn::RateLimit));
+        assert!(!policy.retries(RetryOn::Transport));
+        assert!(!policy.retries(RetryOn::ServerError));
+    }
+}
@@ -0,0 +1,310 @@
+//! Tracing of provider requests following the OpenTelemetry GenAI semantic conventions.
+//!
+//! With the `otel` feature, every provider built by [`crate::builder::LLMBuilder`] is
+//! wrapped in a [`TracedLLM`], which opens a [`tracing`] span per request carrying the
+//! attributes of the [GenAI semantic conventions]: `gen_ai.system`,
+//! `gen_ai.request.model`, the sampling parameters, the response model and ID, the finish
+//! reasons and the token counts. Forward the spans to OpenTelemetry with
+//! `tracing-opentelemetry` and they show up as GenAI client spans in tools such as
+//! Langfuse or Grafana.
+//!
+//! [`TracedLLM`]: crate::telemetry::TracedLLM
+//! [GenAI semantic conventions]: https://opentelemetry.io/docs/specs/semconv/gen-ai/
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::chat::{ChatMessage, ChatProvider};
+//! use llm::telemetry::TracedLLM;
+//!
+//! # async fn run(provider: Box<dyn llm::LLMProvider>) -> Result<(), llm::error::LLMError> {
+//! // Providers from the builder are already traced; wrap others explicitly.
+//! let llm = TracedLLM::new(provider, "openai", "gpt-4o-mini");
+//! let reply = llm
+//!     .chat(&[ChatMessage::user().content("Hello!").build()])
+//!     .await?;
+//! println!("{}", reply);
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, StreamChunk, Tool, Usage,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// Returns the `gen_ai.system` value of a backend name (e.g. "gcp.gemini" for "google").
+pub fn system_name(backend: &str) -> &str {
+    match backend {
+        "google" => "gcp.gemini",
+        "mistral" => "mistral_ai",
+        "azure" => "az.ai.openai",
+        other => other,
+    }
+}
+
+/// A wrapper around an LLM provider tracing each request in a GenAI client span.
+///
+/// Chat requests, completions and embeddings get the `chat`, `text_completion` and
+/// `embeddings` operations. A stream's span stays open until the stream is dropped and
+/// records the usage of its final chunk. Failed requests set `error.type` to the
+/// [`LLMError::kind`] of the error.
+pub struct TracedLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Value of `gen_ai.system`
+    system: String,
+    /// Model the provider was configured with
+    model: String,
+}
+
+impl TracedLLM {
+    /// Wraps a provider, tracing its requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to trace
+    /// * `backend` - Name of the backend (e.g. "openai"), mapped with [`system_name`]
+    /// * `model` - Model the provider was configured with
+    pub fn new(
+        inner: Box<dyn LLMProvider>,
+        backend: impl AsRef<str>,
+        model: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            system: system_name(backend.as_ref()).to_string(),
+            model: model.into(),
+        }
+    }
+
+    /// Opens the span of a request.
+    fn span(&self, operation: &'static str, options: Option<&ChatOptions>) -> Span {
+        let span = tracing::info_span!(
+            "gen_ai",
+            otel.name = %format!("{} {}", operation, self.model).trim_end(),
+            otel.kind = "client",
+            otel.status_code = Empty,
+            gen_ai.operation.name = operation,
+            gen_ai.system = self.system.as_str(),
+            gen_ai.request.model = self.model.as_str(),
+            gen_ai.request.max_tokens = Empty,
+            gen_ai.request.temperature = Empty,
+            gen_ai.request.top_p = Empty,
+            gen_ai.request.seed = Empty,
+            gen_ai.response.model = Empty,
+            gen_ai.response.id = Empty,
+            gen_ai.response.finish_reasons = Empty,
+            gen_ai.usage.input_tokens = Empty,
+            gen_ai.usage.output_tokens = Empty,
+            error.type = Empty,
+        );
+        if let Some(options) = options {
+            if let Some(max_tokens) = options.max_tokens {
+                span.record("gen_ai.request.max_tokens", max_tokens);
+            }
+            if let Some(temperature) = options.temperature {
+                span.record("gen_ai.request.temperature", f64::from(temperature));
+            }
+            if let Some(top_p) = options.top_p {
+                span.record("gen_ai.request.top_p", f64::from(top_p));
+            }
+            if let Some(seed) = options.seed {
+                span.record("gen_ai.request.seed", seed);
+            }
+        }
+        span
+    }
+
+    /// Sends a chat request inside its span, recording the response.
+    async fn traced_chat<F>(
+        &self,
+        options: Option<&ChatOptions>,
+        request: F,
+    ) -> Result<Box<dyn ChatResponse>, LLMError>
+    where
+        F: std::future::Future<Output = Result<Box<dyn ChatResponse>, LLMError>> + Send,
+    {
+        let span = self.span("chat", options);
+        let result = request.instrument(span.clone()).await;
+        match &result {
+            Ok(response) => {
+                let metadata = response.metadata();
+                if let Some(model) = &metadata.model {
+                    span.record("gen_ai.response.model", model.as_str());
+                }
+                if let Some(id) = &metadata.response_id {
+                    span.record("gen_ai.response.id", id.as_str());
+                }
+                if let Some(reason) = response.finish_reason() {
+                    span.record(
+                        "gen_ai.response.finish_reasons",
+                        format!("{:?}", [reason.to_string()]),
+                    );
+                }
+                if let Some(usage) = response.usage() {
+                    record_usage(&span, &usage);
+                }
+            }
+            Err(e) => record_error(&span, e),
+        }
+        result
+    }
+
+    /// Sends a request other than a chat inside its span, recording its failure.
+    async fn traced<T, F>(&self, operation: &'static str, request: F) -> Result<T, LLMError>
+    where
+        F: std::future::Future<Output = Result<T, LLMError>> + Send,
+    {
+        let span = self.span(operation, None);
+        let result = request.instrument(span.clone()).await;
+        if let Err(e) = &result {
+            record_error(&span, e);
+        }
+        result
+    }
+
+    /// Opens a chat stream inside its span, which records the stream's final usage.
+    async fn traced_stream<F>(
+        &self,
+        options: Option<&ChatOptions>,
+        request: F,
+    ) -> Result<ChatStream, LLMError>
+    where
+        F: std::future::Future<Output = Result<ChatStream, LLMError>> + Send,
+    {
+        let span = self.span("chat", options);
+        let stream = match request.instrument(span.clone()).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                record_error(&span, &e);
+                return Err(e);
+            }
+        };
+        Ok(Box::pin(stream.inspect(move |chunk| match chunk {
+            Ok(StreamChunk::Done {
+                usage: Some(usage), ..
+            }) => record_usage(&span, usage),
+            Err(e) => record_error(&span, e),
+            _ => {}
+        })))
+    }
+}
+
+/// Records the token counts of a response.
+fn record_usage(span: &Span, usage: &Usage) {
+    span.record("gen_ai.usage.input_tokens", usage.prompt_tokens);
+    span.record("gen_ai.usage.output_tokens", usage.completion_tokens);
+}
+
+/// Marks a span as failed.
+fn record_error(span: &Span, error: &LLMError) {
+    span.record("error.type", error.kind());
+    span.record("otel.status_code", "ERROR");
+}
+
+#[async_trait]
+impl LLMProvider for TracedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for TracedLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.traced_chat(None, self.inner.chat(messages)).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.traced_chat(None, self.inner.chat_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.traced_chat(
+            Some(options),
+            self.inner.chat_with_options(messages, tools, options),
+        )
+        .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.traced_stream(None, self.inner.chat_stream_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.traced_stream(
+            Some(options),
+            self.inner
+                .chat_stream_with_options(messages, tools, options),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for TracedLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.traced("text_completion", self.inner.complete(req))
+            .await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.traced("text_completion", self.inner.complete_stream(req))
+            .await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.traced(
+            "text_completion",
+            self.inner.complete_with_options(req, options),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for TracedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.traced("embeddings", self.inner.embed(input)).await
+    }
+}
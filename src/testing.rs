@@ -0,0 +1,387 @@
+//! Helpers for testing code built on this crate.
+//!
+//! [`FakeResponse`] builds the `Box<dyn ChatResponse>` a provider would return, so code
+//! consuming responses can be tested without a provider. [`ToolCallMatcher`] checks tool
+//! calls by name and arguments, ignoring their IDs and the formatting of the arguments,
+//! and the `assert_*` functions check conversations and responses with readable failure
+//! messages. To script a whole provider, see the `mock` backend.
+//!
+//! [`FakeResponse`]: crate::testing::FakeResponse
+//! [`ToolCallMatcher`]: crate::testing::ToolCallMatcher
+//!
+//! # Example
+//!
+//! ```
+//! use llm::chat::{ChatMessage, ChatRole};
+//! use llm::testing::{assert_contains_message, assert_tool_called, FakeResponse, ToolCallMatcher};
+//! use serde_json::json;
+//!
+//! let response = FakeResponse::new()
+//!     .tool_call("get_weather", json!({"city": "Paris", "unit": "celsius"}))
+//!     .boxed();
+//! assert_tool_called(response.as_ref(), &ToolCallMatcher::named("get_weather").arg("city", "Paris"));
+//!
+//! let messages = [ChatMessage::user().content("What's the weather in Paris?").build()];
+//! assert_contains_message(&messages, ChatRole::User, "weather");
+//! ```
+
+use serde_json::Value;
+
+use crate::chat::{
+    AudioReply, ChatChoice, ChatMessage, ChatResponse, ChatRole, Citation, FinishReason,
+    ResponseMetadata, ResponseSnapshot, Usage,
+};
+use crate::{FunctionCall, ToolCall};
+
+/// Builder of chat responses, as a provider would return them.
+///
+/// The finish reason defaults to [`FinishReason::ToolCalls`] if the response has tool
+/// calls, and to [`FinishReason::Stop`] otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct FakeResponse {
+    text: Option<String>,
+    tool_calls: Vec<ToolCall>,
+    thinking: Option<String>,
+    finish_reason: Option<FinishReason>,
+    refusal: Option<String>,
+    usage: Option<Usage>,
+    raw: Option<Value>,
+    citations: Vec<Citation>,
+    audio: Option<AudioReply>,
+    metadata: ResponseMetadata,
+}
+
+impl FakeResponse {
+    /// Creates an empty response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the reply text.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Adds a tool call with arguments, given an ID from its position ("call_0", ...).
+    pub fn tool_call(self, name: impl Into<String>, arguments: Value) -> Self {
+        let id = format!("call_{}", self.tool_calls.len());
+        self.with_tool_call(tool_call(id, name, arguments))
+    }
+
+    /// Adds a tool call as is.
+    pub fn with_tool_call(mut self, call: ToolCall) -> Self {
+        self.tool_calls.push(call);
+        self
+    }
+
+    /// Sets the model's reasoning.
+    pub fn thinking(mut self, thinking: impl Into<String>) -> Self {
+        self.thinking = Some(thinking.into());
+        self
+    }
+
+    /// Sets why the model stopped generating.
+    pub fn finish_reason(mut self, reason: FinishReason) -> Self {
+        self.finish_reason = Some(reason);
+        self
+    }
+
+    /// Sets the model's refusal, finishing with [`FinishReason::ContentFilter`] unless a
+    /// finish reason is set.
+    pub fn refusal(mut self, reason: impl Into<String>) -> Self {
+        self.refusal = Some(reason.into());
+        self.finish_reason
+            .get_or_insert(FinishReason::ContentFilter);
+        self
+    }
+
+    /// Sets the token usage.
+    pub fn usage(mut self, prompt_tokens: u32, completion_tokens: u32) -> Self {
+        self.usage = Some(Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cached_prompt_tokens: None,
+        });
+        self
+    }
+
+    /// Sets the provider's response document.
+    pub fn raw(mut self, raw: Value) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+
+    /// Adds a source the reply cites.
+    pub fn citation(mut self, url: impl Into<String>, title: Option<&str>) -> Self {
+        self.citations.push(Citation {
+            url: url.into(),
+            title: title.map(str::to_string),
+            date: None,
+        });
+        self
+    }
+
+    /// Sets the spoken reply, with its transcript.
+    pub fn audio(mut self, data: Vec<u8>, transcript: Option<&str>) -> Self {
+        self.audio = Some(AudioReply {
+            id: None,
+            data,
+            transcript: transcript.map(str::to_string),
+            expires_at: None,
+        });
+        self
+    }
+
+    /// Sets the provider and model reported as having served the request.
+    pub fn served_by(mut self, provider: impl Into<String>, model: impl Into<String>) -> Self {
+        self.metadata.provider = Some(provider.into());
+        self.metadata.model = Some(model.into());
+        self
+    }
+
+    /// Builds the response.
+    pub fn build(self) -> ResponseSnapshot {
+        let tool_calls = (!self.tool_calls.is_empty()).then_some(self.tool_calls);
+        let finish_reason = self.finish_reason.unwrap_or(if tool_calls.is_some() {
+            FinishReason::ToolCalls
+        } else {
+            FinishReason::Stop
+        });
+        ResponseSnapshot {
+            display: self.text.clone().unwrap_or_default(),
+            choices: vec![ChatChoice {
+                text: self.text.clone(),
+                tool_calls: tool_calls.clone(),
+                finish_reason: Some(finish_reason.clone()),
+                refusal: self.refusal.clone(),
+                logprobs: None,
+            }],
+            text: self.text,
+            tool_calls,
+            thinking: self.thinking,
+            finish_reason: Some(finish_reason),
+            raw: self.raw,
+            refusal: self.refusal,
+            usage: self.usage,
+            logprobs: None,
+            citations: (!self.citations.is_empty()).then_some(self.citations),
+            audio: self.audio,
+            safety_ratings: None,
+            grounding: None,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Builds the response, boxed as providers return it.
+    pub fn boxed(self) -> Box<dyn ChatResponse> {
+        Box::new(self.build())
+    }
+}
+
+/// Creates a function tool call.
+pub fn tool_call(id: impl Into<String>, name: impl Into<String>, arguments: Value) -> ToolCall {
+    ToolCall {
+        id: id.into(),
+        call_type: "function".to_string(),
+        function: FunctionCall {
+            name: name.into(),
+            arguments: arguments.to_string(),
+        },
+    }
+}
+
+/// Matches tool calls by name and arguments.
+///
+/// Arguments are compared as JSON, so whitespace and key order don't matter. Only the
+/// arguments named with [`ToolCallMatcher::arg`] are checked, unless the whole arguments
+/// are given with [`ToolCallMatcher::args`].
+///
+/// ```
+/// use llm::testing::{tool_call, ToolCallMatcher};
+/// use serde_json::json;
+///
+/// let call = tool_call("call_1", "search", json!({"query": "rust", "limit": 5}));
+/// assert!(ToolCallMatcher::named("search").arg("limit", 5).matches(&call));
+/// assert!(!ToolCallMatcher::named("search").args(json!({"query": "rust"})).matches(&call));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCallMatcher {
+    name: String,
+    args: Vec<(String, Value)>,
+    exact: Option<Value>,
+}
+
+impl ToolCallMatcher {
+    /// Matches calls of a function, whatever their arguments.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            args: Vec::new(),
+            exact: None,
+        }
+    }
+
+    /// Also requires an argument to have a value.
+    pub fn arg(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.args.push((name.into(), value.into()));
+        self
+    }
+
+    /// Also requires the arguments to be exactly these.
+    pub fn args(mut self, args: Value) -> Self {
+        self.exact = Some(args);
+        self
+    }
+
+    /// Returns whether a tool call matches.
+    pub fn matches(&self, call: &ToolCall) -> bool {
+        self.mismatch(call).is_none()
+    }
+
+    /// Describes why a tool call doesn't match, or returns `None` if it does.
+    pub fn mismatch(&self, call: &ToolCall) -> Option<String> {
+        if call.function.name != self.name {
+            return Some(format!(
+                "called '{}' instead of '{}'",
+                call.function.name, self.name
+            ));
+        }
+        if self.args.is_empty() && self.exact.is_none() {
+            return None;
+        }
+        let args: Value = match serde_json::from_str(&call.function.arguments) {
+            Ok(args) => args,
+            Err(e) => {
+                return Some(format!(
+                    "arguments of '{}' are not JSON ({}): {}",
+                    self.name, e, call.function.arguments
+                ))
+            }
+        };
+        if let Some(exact) = &self.exact {
+            if &args != exact {
+                return Some(format!(
+                    "'{}' was called with {} instead of {}",
+                    self.name, args, exact
+                ));
+            }
+        }
+        self.args
+            .iter()
+            .find_map(|(name, expected)| match args.get(name) {
+                Some(actual) if actual == expected => None,
+                Some(actual) => Some(format!(
+                    "argument '{}' of '{}' is {} instead of {}",
+                    name, self.name, actual, expected
+                )),
+                None => Some(format!(
+                    "'{}' was called without argument '{}'",
+                    self.name, name
+                )),
+            })
+    }
+}
+
+/// Asserts that a conversation has a message with a role whose content contains a text,
+/// and returns the first such message.
+///
+/// # Panics
+///
+/// Panics, listing the conversation, if no message matches.
+#[track_caller]
+pub fn assert_contains_message<'a>(
+    messages: &'a [ChatMessage],
+    role: ChatRole,
+    needle: &str,
+) -> &'a ChatMessage {
+    match messages
+        .iter()
+        .find(|message| message.role == role && message.content.contains(needle))
+    {
+        Some(message) => message,
+        None => panic!(
+            "no {:?} message contains {:?} in the conversation:\n{}",
+            role,
+            needle,
+            describe(messages)
+        ),
+    }
+}
+
+/// Asserts that a response has a text reply and returns it.
+///
+/// # Panics
+///
+/// Panics if the response has no text.
+#[track_caller]
+pub fn assert_text(response: &dyn ChatResponse) -> String {
+    match response.text() {
+        Some(text) => text,
+        None => panic!("the response has no text: {:?}", response),
+    }
+}
+
+/// Asserts that a response requests a tool call matching a matcher, and returns the first
+/// such call.
+///
+/// # Panics
+///
+/// Panics, explaining why each call doesn't match, if none does.
+#[track_caller]
+pub fn assert_tool_called(response: &dyn ChatResponse, matcher: &ToolCallMatcher) -> ToolCall {
+    let calls = response.tool_calls().unwrap_or_default();
+    assert_tool_calls_contain(&calls, matcher).clone()
+}
+
+/// Asserts that a list of tool calls has one matching a matcher, and returns it.
+///
+/// # Panics
+///
+/// Panics, explaining why each call doesn't match, if none does.
+#[track_caller]
+pub fn assert_tool_calls_contain<'a>(
+    calls: &'a [ToolCall],
+    matcher: &ToolCallMatcher,
+) -> &'a ToolCall {
+    if let Some(call) = calls.iter().find(|call| matcher.matches(call)) {
+        return call;
+    }
+    if calls.is_empty() {
+        panic!("no tool was called, expected '{}'", matcher.name);
+    }
+    let reasons: Vec<String> = calls
+        .iter()
+        .filter_map(|call| matcher.mismatch(call))
+        .collect();
+    panic!("no tool call matches:\n  {}", reasons.join("\n  "))
+}
+
+/// Asserts that a response requests no tool call.
+///
+/// # Panics
+///
+/// Panics, listing the calls, if it requests any.
+#[track_caller]
+pub fn assert_no_tool_calls(response: &dyn ChatResponse) {
+    if let Some(calls) = response.tool_calls().filter(|calls| !calls.is_empty()) {
+        let names: Vec<&str> = calls
+            .iter()
+            .map(|call| call.function.name.as_str())
+            .collect();
+        panic!("expected no tool call, got {}", names.join(", "));
+    }
+}
+
+/// Lists the messages of a conversation, one per line.
+fn describe(messages: &[ChatMessage]) -> String {
+    if messages.is_empty() {
+        return "  (empty)".to_string();
+    }
+    messages
+        .iter()
+        .map(|message| format!("  {:?}: {:?}", message.role, message.content))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,359 @@
+//! Local, approximate token counting.
+//!
+//! Counting tokens before sending a request lets applications check that a prompt fits
+//! the context window, or estimate its cost, without a round trip to the provider.
+//!
+//! The counts are approximations, not tiktoken-compatible counts: tiktoken's vocabularies
+//! are not bundled. OpenAI models are approximated by splitting text the way tiktoken does
+//! before applying its byte-pair encoding, then estimating the tokens of each piece, which
+//! is usually close for English text and code but can drift for other scripts. Other
+//! models are approximated at about four characters per token. Implement [`Tokenizer`]
+//! with an exact tokenizer and pass it to [`count_messages_with`] when exact counts matter.
+//!
+//! [`count_messages_with`]: crate::tokens::count_messages_with
+//!
+//! [`Tokenizer`]: crate::tokens::Tokenizer
+//!
+//! # Example
+//!
+//! ```
+//! use llm::chat::ChatMessage;
+//! use llm::tokens::{self, Encoding};
+//!
+//! let encoding = Encoding::for_model("gpt-4o-mini");
+//! assert_eq!(encoding, Encoding::O200kBase);
+//! assert_eq!(tokens::count_tokens("gpt-4o-mini", "Hello world"), 2);
+//!
+//! let messages = vec![
+//!     ChatMessage::user().content("What is the capital of France?").build(),
+//! ];
+//! let prompt_tokens = tokens::count_messages(&messages);
+//! assert!(prompt_tokens > 7 && prompt_tokens < 20);
+//! ```
+
+use crate::chat::{ChatMessage, ContentPart, MessageType};
+
+/// Tokens added to each message by the chat format, around its role and content.
+const TOKENS_PER_MESSAGE: usize = 4;
+
+/// Tokens priming the model's reply, added once per request.
+const REPLY_PRIMING_TOKENS: usize = 3;
+
+/// Approximate token cost of an image, that of a 1024×1024 image in high detail.
+const IMAGE_TOKENS: usize = 765;
+
+/// Approximate token cost of an audio clip or document attachment.
+const ATTACHMENT_TOKENS: usize = 1000;
+
+/// Counts the tokens of a text.
+pub trait Tokenizer: Send + Sync {
+    /// Returns the number of tokens a text encodes to.
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Tokenization whose token counts are approximated for a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// The encoding of GPT-4o, GPT-4.1, GPT-5 and the o-series models
+    O200kBase,
+    /// The encoding of GPT-4 and GPT-3.5 models
+    Cl100kBase,
+    /// About four characters per token, for models of other providers
+    Approximate,
+}
+
+impl Encoding {
+    /// Returns the encoding of a model, by name.
+    pub fn for_model(model: &str) -> Self {
+        const O200K_PREFIXES: &[&str] = &["gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "chatgpt-"];
+        const CL100K_PREFIXES: &[&str] = &["gpt-4", "gpt-3.5", "text-embedding-"];
+        let is_o_series =
+            model.starts_with('o') && model[1..].starts_with(|c: char| c.is_ascii_digit());
+        if is_o_series
+            || O200K_PREFIXES
+                .iter()
+                .any(|prefix| model.starts_with(prefix))
+        {
+            Encoding::O200kBase
+        } else if CL100K_PREFIXES
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Encoding::Cl100kBase
+        } else {
+            Encoding::Approximate
+        }
+    }
+
+    /// Estimates the tokens of one piece of pre-tokenized text.
+    fn piece_tokens(&self, piece: &Piece) -> usize {
+        match piece.class {
+            Class::Letters if piece.non_ascii > 0 => {
+                let ascii = piece.chars - piece.non_ascii;
+                // The larger vocabulary merges non-Latin scripts more often.
+                let non_ascii = match self {
+                    Encoding::O200kBase => (piece.non_ascii * 2).div_ceil(3),
+                    _ => piece.non_ascii,
+                };
+                ascii.div_ceil(6) + non_ascii
+            }
+            Class::Letters => piece.chars.div_ceil(6),
+            Class::Digits => piece.chars.div_ceil(3),
+            Class::Whitespace => 1,
+            Class::Punctuation => piece.chars.div_ceil(2),
+        }
+    }
+}
+
+impl Tokenizer for Encoding {
+    fn count_tokens(&self, text: &str) -> usize {
+        match self {
+            Encoding::Approximate => text.chars().count().div_ceil(4),
+            _ => pieces(text)
+                .iter()
+                .map(|piece| self.piece_tokens(piece))
+                .sum(),
+        }
+    }
+}
+
+/// Kind of characters making up a piece of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Letters,
+    Digits,
+    Whitespace,
+    Punctuation,
+}
+
+impl Class {
+    fn of(c: char) -> Self {
+        if c.is_alphabetic() {
+            Class::Letters
+        } else if c.is_numeric() {
+            Class::Digits
+        } else if c.is_whitespace() {
+            Class::Whitespace
+        } else {
+            Class::Punctuation
+        }
+    }
+}
+
+/// A run of characters of one class, which tiktoken encodes separately.
+#[derive(Debug)]
+struct Piece {
+    class: Class,
+    chars: usize,
+    non_ascii: usize,
+}
+
+/// Splits text the way tiktoken does before encoding: into words with their leading
+/// space, numbers, punctuation runs and whitespace runs.
+fn pieces(text: &str) -> Vec<Piece> {
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut class = Class::of(c);
+        let mut count = 1;
+        let mut non_ascii = usize::from(!c.is_ascii());
+        // A single space is merged into the word or punctuation that follows it.
+        if c == ' ' {
+            if let Some(&next) = chars.peek() {
+                let next_class = Class::of(next);
+                if matches!(next_class, Class::Letters | Class::Punctuation) {
+                    class = next_class;
+                    count = 0;
+                }
+            }
+        }
+        while let Some(&next) = chars.peek() {
+            if Class::of(next) != class || (class == Class::Digits && count == 3) {
+                break;
+            }
+            // A whitespace run leaves its last space to the next word.
+            if class == Class::Whitespace && next == ' ' {
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                if lookahead.peek().is_some_and(|c| !c.is_whitespace()) {
+                    break;
+                }
+            }
+            chars.next();
+            count += 1;
+            non_ascii += usize::from(!next.is_ascii());
+        }
+        pieces.push(Piece {
+            class,
+            chars: count,
+            non_ascii,
+        });
+    }
+    pieces
+}
+
+/// Approximates the tokens of a text for a model.
+pub fn count_tokens(model: &str, text: &str) -> usize {
+    Encoding::for_model(model).count_tokens(text)
+}
+
+/// Approximates the prompt tokens of a chat request, chat formatting included, with the
+/// encoding of the current OpenAI models.
+///
+/// The count is an approximation of tiktoken's, not an exact one; see the
+/// [module documentation](self).
+pub fn count_messages(messages: &[ChatMessage]) -> usize {
+    count_messages_with(&Encoding::O200kBase, messages)
+}
+
+/// Approximates the prompt tokens of a chat request to a model, chat formatting included.
+pub fn count_messages_for(model: &str, messages: &[ChatMessage]) -> usize {
+    count_messages_with(&Encoding::for_model(model), messages)
+}
+
+/// Counts the prompt tokens of a chat request with a tokenizer, chat formatting included.
+///
+/// Attachments are counted at a fixed approximate cost.
+pub fn count_messages_with(tokenizer: &dyn Tokenizer, messages: &[ChatMessage]) -> usize {
+    messages
+        .iter()
+        .map(|message| TOKENS_PER_MESSAGE + message_tokens(tokenizer, message))
+        .sum::<usize>()
+        + REPLY_PRIMING_TOKENS
+}
+
+fn message_tokens(tokenizer: &dyn Tokenizer, message: &ChatMessage) -> usize {
+    let payload = match &message.message_type {
+        MessageType::Image(_) | MessageType::ImageURL(_) => IMAGE_TOKENS,
+        MessageType::Pdf(_) | MessageType::Audio(_) => ATTACHMENT_TOKENS,
+        MessageType::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text(text) => tokenizer.count_tokens(text),
+                ContentPart::Image(_) | ContentPart::ImageURL(_) => IMAGE_TOKENS,
+                ContentPart::Pdf(_) | ContentPart::Audio(_) | ContentPart::File(_) => {
+                    ATTACHMENT_TOKENS
+                }
+            })
+            .sum(),
+        MessageType::ToolUse(calls) => calls
+            .iter()
+            .map(|call| {
+                tokenizer.count_tokens(&call.function.name)
+                    + tokenizer.count_tokens(&call.function.arguments)
+            })
+            .sum(),
+        MessageType::ToolResult { parts, .. } => parts
+            .iter()
+            .map(|part| match part {
+                // The text of the output is counted with the message's content.
+                ContentPart::Text(_) => 0,
+                ContentPart::Image(_) | ContentPart::ImageURL(_) => IMAGE_TOKENS,
+                ContentPart::Pdf(_) | ContentPart::Audio(_) | ContentPart::File(_) => {
+                    ATTACHMENT_TOKENS
+                }
+            })
+            .sum(),
+        MessageType::Text => 0,
+    };
+    tokenizer.count_tokens(&message.content) + payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classes(text: &str) -> Vec<(Class, usize)> {
+        pieces(text)
+            .into_iter()
+            .map(|piece| (piece.class, piece.chars))
+            .collect()
+    }
+
+    #[test]
+    fn merges_a_leading_space_into_the_next_word() {
+        assert_eq!(
+            classes("Hello world!"),
+            vec![
+                (Class::Letters, 5),
+                (Class::Letters, 5),
+                (Class::Punctuation, 1)
+            ]
+        );
+        assert_eq!(classes(" ("), vec![(Class::Punctuation, 1)]);
+    }
+
+    #[test]
+    fn splits_numbers_into_groups_of_three_digits() {
+        assert_eq!(
+            classes("1234567"),
+            vec![(Class::Digits, 3), (Class::Digits, 3), (Class::Digits, 1)]
+        );
+        assert_eq!(Encoding::O200kBase.count_tokens("1234567"), 3);
+    }
+
+    #[test]
+    fn leaves_the_last_space_of_a_run_to_the_next_word() {
+        assert_eq!(
+            classes("a   b"),
+            vec![
+                (Class::Letters, 1),
+                (Class::Whitespace, 2),
+                (Class::Letters, 1)
+            ]
+        );
+        assert_eq!(
+            classes("a\n\n"),
+            vec![(Class::Letters, 1), (Class::Whitespace, 2)]
+        );
+    }
+
+    #[test]
+    fn counts_non_latin_scripts_by_encoding() {
+        assert_eq!(Encoding::O200kBase.count_tokens("日本語"), 2);
+        assert_eq!(Encoding::Cl100kBase.count_tokens("日本語"), 3);
+        assert_eq!(Encoding::Approximate.count_tokens("日本語"), 1);
+    }
+
+    #[test]
+    fn picks_the_encoding_of_a_model() {
+        assert_eq!(Encoding::for_model("gpt-4o-mini"), Encoding::O200kBase);
+        assert_eq!(Encoding::for_model("o3-mini"), Encoding::O200kBase);
+        assert_eq!(Encoding::for_model("gpt-4-turbo"), Encoding::Cl100kBase);
+        assert_eq!(Encoding::for_model("omni"), Encoding::Approximate);
+        assert_eq!(
+            Encoding::for_model("claude-3-5-sonnet"),
+            Encoding::Approximate
+        );
+        assert_eq!(count_tokens("claude-3-5-sonnet", "abcdefgh"), 2);
+    }
+
+    #[test]
+    fn adds_the_chat_formatting_overhead() {
+        struct Chars;
+        impl Tokenizer for Chars {
+            fn count_tokens(&self, text: &str) -> usize {
+                text.chars().count()
+            }
+        }
+
+        assert_eq!(count_messages(&[]), REPLY_PRIMING_TOKENS);
+        let messages = vec![
+            ChatMessage::user().content("ab").build(),
+            ChatMessage::assistant().content("cde").build(),
+        ];
+        assert_eq!(
+            count_messages_with(&Chars, &messages),
+            2 * TOKENS_PER_MESSAGE + 5 + REPLY_PRIMING_TOKENS
+        );
+
+        let image = ChatMessage::user()
+            .content("ab")
+            .image_url("https://example.com/cat.png")
+            .build();
+        assert_eq!(
+            count_messages_with(&Chars, &[image]),
+            TOKENS_PER_MESSAGE + 2 + IMAGE_TOKENS + REPLY_PRIMING_TOKENS
+        );
+    }
+}
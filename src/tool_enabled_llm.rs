@@ -0,0 +1,334 @@
+//! A module providing automatic tool execution for LLM providers.
+//!
+//! This module wraps any LLM provider in a `ToolEnabledLLM` that owns a set of tools, each
+//! registered with an async handler. When the model asks for tool calls, the wrapper runs the
+//! matching handlers, feeds their output back to the model and repeats until the model answers
+//! with text.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{FunctionBuilder, LLMBackend, LLMBuilder, ParamBuilder};
+//! use llm::chat::ChatMessage;
+//! use llm::tool_enabled_llm::ToolEnabledLLM;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .build()?;
+//!
+//! let llm = ToolEnabledLLM::new(llm)
+//!     .tool(
+//!         FunctionBuilder::new("get_weather")
+//!             .description("Get the current weather in a city")
+//!             .param(ParamBuilder::new("city").type_of("string"))
+//!             .required(vec!["city".to_string()]),
+//!         |args| async move { Ok(format!("Sunny in {}", args["city"])) },
+//!     )
+//!     .max_iterations(5);
+//!
+//! let messages = vec![ChatMessage::user().content("Weather in Paris?").build()];
+//! let answer = llm.chat_auto(&messages).await?;
+//! println!("{}", answer);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::builder::FunctionBuilder;
+use crate::chat::{ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, Tool};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// Future returned by a tool handler, resolving to the tool output sent back to the model.
+pub type ToolHandlerFuture = Pin<Box<dyn Future<Output = Result<String, LLMError>> + Send>>;
+
+/// An async function run when the model calls a tool. It receives the parsed call arguments.
+pub type ToolHandler = dyn Fn(Value) -> ToolHandlerFuture + Send + Sync + 'static;
+
+/// A tool definition paired with the handler that executes it.
+///
+/// Functions annotated with `#[llm_tool]` generate a constructor returning this type.
+pub struct ExecutableTool {
+    /// Definition sent to the model
+    pub tool: Tool,
+    /// Handler run when the model calls the tool
+    pub handler: Box<ToolHandler>,
+}
+
+impl ExecutableTool {
+    /// Pairs a tool definition with its async handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool` - Definition sent to the model
+    /// * `handler` - Async function receiving the call arguments and returning the tool output
+    pub fn new<F, Fut>(tool: Tool, handler: F) -> Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, LLMError>> + Send + 'static,
+    {
+        Self {
+            tool,
+            handler: Box::new(move |args| Box::pin(handler(args))),
+        }
+    }
+}
+
+/// A wrapper around an LLM provider that executes tool calls automatically.
+///
+/// The wrapper runs the tool loop by:
+/// 1. Sending the conversation and the registered tools to the underlying provider
+/// 2. Running the handlers of every tool the model calls, concurrently when it requests
+///    several at once
+/// 3. Sending the tool outputs back, until the model answers without calling tools or the
+///    iteration cap is reached
+///
+/// Handler errors and calls to unknown tools are reported back to the model as tool output,
+/// so it can recover instead of aborting the conversation.
+pub struct ToolEnabledLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Tool definitions sent to the model
+    tools: Vec<Tool>,
+    /// Handlers keyed by function name
+    handlers: HashMap<String, Box<ToolHandler>>,
+    /// Maximum number of model calls in a single conversation turn
+    max_iterations: usize,
+}
+
+impl ToolEnabledLLM {
+    /// Creates a new ToolEnabledLLM wrapper with no tools and a cap of 10 iterations.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to wrap
+    pub fn new(inner: Box<dyn LLMProvider>) -> Self {
+        Self {
+            inner,
+            tools: Vec::new(),
+            handlers: HashMap::new(),
+            max_iterations: 10,
+        }
+    }
+
+    /// Registers a tool and the async handler that executes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - Definition of the function exposed to the model
+    /// * `handler` - Async function receiving the call arguments and returning the tool output
+    pub fn tool<F, Fut>(self, function: FunctionBuilder, handler: F) -> Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, LLMError>> + Send + 'static,
+    {
+        self.register(ExecutableTool::new(function.build(), handler))
+    }
+
+    /// Registers a tool that already carries its handler, such as one generated by
+    /// `#[llm_tool]`.
+    pub fn register(mut self, tool: ExecutableTool) -> Self {
+        self.handlers
+            .insert(tool.tool.function.name.clone(), tool.handler);
+        self.tools.push(tool.tool);
+        self
+    }
+
+    /// Sets the maximum number of model calls made by [`ToolEnabledLLM::chat_auto`].
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Runs the tool loop until the model produces a final answer.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation history as a slice of chat messages
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Box<dyn ChatResponse>)` - The first response without tool calls
+    /// * `Err(LLMError)` - If a request fails or the iteration cap is reached
+    pub async fn chat_auto(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let mut local_messages = messages.to_vec();
+
+        for _ in 0..self.max_iterations {
+            // Responses are not `Send`, so keep only what the next turn needs before awaiting tools.
+            let (text, tool_calls) = {
+                let response = self
+                    .inner
+                    .chat_with_tools(&local_messages, Some(&self.tools))
+                    .await?;
+                match response.tool_calls() {
+                    Some(calls) if !calls.is_empty() => (response.text(), calls),
+                    _ => return Ok(response),
+                }
+            };
+
+            local_messages.push(
+                ChatMessage::assistant()
+                    .content(text.unwrap_or_default())
+                    .tool_use(tool_calls.clone())
+                    .build(),
+            );
+
+            // Run parallel calls concurrently, but send their results back in call order.
+            let outputs = futures::future::join_all(
+                tool_calls
+                    .iter()
+                    .map(|call| self.run_tool(&call.function.name, &call.function.arguments)),
+            )
+            .await;
+            for (call, output) in tool_calls.into_iter().zip(outputs) {
+                local_messages.push(
+                    ChatMessage::tool()
+                        .tool_result(call.id, call.function.name, output)
+                        .build(),
+                );
+            }
+        }
+
+        Err(LLMError::ProviderError(format!(
+            "Tool loop did not finish after {} iterations",
+            self.max_iterations
+        )))
+    }
+
+    /// Runs the handler for a tool call and returns the output to send back to the model.
+    async fn run_tool(&self, name: &str, arguments: &str) -> String {
+        let Some(handler) = self.handlers.get(name) else {
+            return format!("Error: unknown tool '{}'", name);
+        };
+
+        let args = if arguments.trim().is_empty() {
+            Value::Object(Default::default())
+        } else {
+            match serde_json::from_str(arguments) {
+                Ok(args) => args,
+                Err(e) => return format!("Error: invalid arguments: {}", e),
+            }
+        };
+
+        match handler(args).await {
+            Ok(output) => output,
+            Err(e) => format!("Error: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for ToolEnabledLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        Some(&self.tools)
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for ToolEnabledLLM {
+    /// Sends a chat request and runs the tool loop on the registered tools.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The chat messages to send to the model
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Box<dyn ChatResponse>)` - The model's final answer
+    /// * `Err(LLMError)` - If a request fails or the iteration cap is reached
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_auto(messages).await
+    }
+
+    /// Passes a single chat request through to the inner provider, without running tools.
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.inner.chat_with_tools(messages, tools).await
+    }
+
+    /// Passes streaming requests through to the inner provider, without running tools.
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner.chat_stream_with_tools(messages, tools).await
+    }
+
+    /// Passes a single chat request with options through to the inner provider, without
+    /// running tools.
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.inner.chat_with_options(messages, tools, options).await
+    }
+
+    /// Passes streaming requests with options through to the inner provider, without
+    /// running tools.
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        self.inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for ToolEnabledLLM {
+    /// Passes through completion requests to the inner provider.
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    /// Passes through streaming completion requests to the inner provider.
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    /// Passes through completion requests with per-request options to the inner provider.
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ToolEnabledLLM {
+    /// Passes through embedding requests to the inner provider.
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
@@ -0,0 +1,211 @@
+//! A wrapper shortening chat histories that would overflow the model's context window.
+//!
+//! A [`TruncatingLLM`] estimates the tokens of each chat request before sending it. When
+//! the messages and the reply would not fit the context window together, it drops or
+//! summarizes the oldest messages first, instead of letting the provider reject the
+//! request. Set it up with [`crate::builder::LLMBuilder::truncation`].
+//!
+//! [`TruncatingLLM`]: crate::truncating_llm::TruncatingLLM
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::truncating_llm::Truncation;
+//!
+//! # fn run() -> Result<(), llm::error::LLMError> {
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .model("gpt-4o-mini")
+//!     .max_tokens(1024)
+//!     .truncation(Truncation::SummarizeOlder { keep_last: 6 })
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, SummarizeOlder, TokenBudget,
+    Tool, TrimStrategy,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// How a [`TruncatingLLM`] shortens a history that doesn't fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Truncation {
+    /// Drop the oldest messages
+    DropOldest,
+    /// Replace the oldest messages with a summary written by the model, keeping the last
+    /// `keep_last` messages verbatim
+    SummarizeOlder {
+        /// Number of recent messages kept verbatim
+        keep_last: usize,
+    },
+}
+
+/// A wrapper around an LLM provider keeping chat requests within the context window.
+///
+/// The room left for the reply is the request's `max_tokens`, then the configured one,
+/// and a quarter of the window if neither is set. System messages are always kept, and
+/// tool results stay with the call that requested them.
+pub struct TruncatingLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// How histories are shortened
+    truncation: Truncation,
+    /// The model's context window, in tokens
+    context_length: usize,
+    /// Tokens the reply may take, as configured on the provider
+    max_output_tokens: Option<u32>,
+}
+
+impl TruncatingLLM {
+    /// Wraps a provider, shortening the histories that overflow its context window.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to wrap
+    /// * `truncation` - How histories are shortened
+    /// * `context_length` - The model's context window, in tokens
+    /// * `max_output_tokens` - Tokens the reply may take, as configured on the provider
+    pub fn new(
+        inner: Box<dyn LLMProvider>,
+        truncation: Truncation,
+        context_length: usize,
+        max_output_tokens: Option<u32>,
+    ) -> Self {
+        Self {
+            inner,
+            truncation,
+            context_length,
+            max_output_tokens,
+        }
+    }
+
+    /// Shortens a history to leave room for the reply.
+    async fn truncate(
+        &self,
+        messages: &[ChatMessage],
+        options: &ChatOptions,
+    ) -> Result<Vec<ChatMessage>, LLMError> {
+        let reserved = options
+            .max_tokens
+            .or(self.max_output_tokens)
+            .map_or(self.context_length / 4, |tokens| tokens as usize);
+        let budget = self.context_length.saturating_sub(reserved);
+        let strategy: Box<dyn TrimStrategy> = match self.truncation {
+            Truncation::DropOldest => Box::new(TokenBudget::new(budget)),
+            Truncation::SummarizeOlder { keep_last } => {
+                Box::new(SummarizeOlder::new(keep_last).max_tokens(budget))
+            }
+        };
+
+        let trimmed = strategy
+            .trim(messages.to_vec(), self.inner.as_ref(), None)
+            .await?;
+        if trimmed.len() != messages.len() {
+            tracing::debug!(
+                before = messages.len(),
+                after = trimmed.len(),
+                "truncated chat history to fit the context window"
+            );
+        }
+        Ok(trimmed)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for TruncatingLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for TruncatingLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let messages = self.truncate(messages, &ChatOptions::default()).await?;
+        self.inner.chat(&messages).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let messages = self.truncate(messages, &ChatOptions::default()).await?;
+        self.inner.chat_with_tools(&messages, tools).await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let messages = self.truncate(messages, options).await?;
+        self.inner
+            .chat_with_options(&messages, tools, options)
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        let messages = self.truncate(messages, &ChatOptions::default()).await?;
+        self.inner.chat_stream_with_tools(&messages, tools).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        let messages = self.truncate(messages, options).await?;
+        self.inner
+            .chat_stream_with_options(&messages, tools, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for TruncatingLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for TruncatingLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
@@ -0,0 +1,479 @@
+//! Token usage accounting across providers.
+//!
+//! A [`UsageTracker`] accumulates the token counts of every request made through the
+//! providers it is attached to, keyed by provider and model. Attach it when building a
+//! provider with [`crate::builder::LLMBuilder::usage_tracker`], or wrap an existing
+//! provider in a [`TrackedLLM`].
+//!
+//! Give the tracker a [`UsageExporter`] to also get a structured record of each request,
+//! with its timestamp, latency and outcome, for billing or analytics. A [`JsonlExporter`]
+//! appends the records to a file; any closure can be an exporter too.
+//!
+//! [`UsageTracker`]: crate::usage::UsageTracker
+//! [`TrackedLLM`]: crate::usage::TrackedLLM
+//! [`UsageExporter`]: crate::usage::UsageExporter
+//! [`JsonlExporter`]: crate::usage::JsonlExporter
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm::builder::{LLMBackend, LLMBuilder};
+//! use llm::chat::ChatMessage;
+//! use llm::usage::UsageTracker;
+//!
+//! # async fn run() -> Result<(), llm::error::LLMError> {
+//! let tracker = UsageTracker::new();
+//! let llm = LLMBuilder::new()
+//!     .backend(LLMBackend::OpenAI)
+//!     .api_key("sk-...")
+//!     .model("gpt-4o-mini")
+//!     .usage_tracker(tracker.clone())
+//!     .build()?;
+//!
+//! llm.chat(&[ChatMessage::user().content("Hello!").build()]).await?;
+//!
+//! for (key, totals) in tracker.snapshot() {
+//!     println!("{}/{}: {} tokens", key.provider, key.model, totals.total_tokens);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatStream, StreamChunk, Tool, Usage,
+};
+use crate::completion::{
+    CompletionProvider, CompletionRequest, CompletionResponse, CompletionStream,
+};
+use crate::embedding::EmbeddingProvider;
+use crate::error::LLMError;
+use crate::health::HealthStatus;
+use crate::LLMProvider;
+
+/// Identifies the provider and model a usage total belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UsageKey {
+    /// Name of the provider (e.g. "openai")
+    pub provider: String,
+    /// The model that served the requests
+    pub model: String,
+}
+
+/// Accumulated token usage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    /// Number of requests that reported usage
+    pub requests: u64,
+    /// Tokens sent in prompts
+    pub prompt_tokens: u64,
+    /// Tokens generated by the model
+    pub completion_tokens: u64,
+    /// Tokens used overall
+    pub total_tokens: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, usage: &Usage) {
+        self.requests += 1;
+        self.prompt_tokens += u64::from(usage.prompt_tokens);
+        self.completion_tokens += u64::from(usage.completion_tokens);
+        self.total_tokens += u64::from(usage.total_tokens);
+    }
+
+    fn merge(&mut self, other: &UsageTotals) {
+        self.requests += other.requests;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// Whether a request succeeded, in a [`UsageRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageOutcome {
+    /// The provider answered
+    Success,
+    /// The request failed
+    Error,
+}
+
+/// Structured record of one request, as passed to a [`UsageExporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// When the request was sent, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Name of the provider (e.g. "openai")
+    pub provider: String,
+    /// The model that served the request
+    pub model: String,
+    /// Tokens sent in the prompt
+    pub prompt_tokens: u32,
+    /// Tokens generated by the model
+    pub completion_tokens: u32,
+    /// Tokens used overall
+    pub total_tokens: u32,
+    /// Time until the response was complete, in milliseconds
+    pub latency_ms: u64,
+    /// Whether the request succeeded
+    pub outcome: UsageOutcome,
+    /// The error the request failed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Receiver of a [`UsageRecord`] for every request made through a tracked provider.
+///
+/// Any `Fn(&UsageRecord)` closure is an exporter.
+pub trait UsageExporter: Send + Sync {
+    /// Handles the record of a finished request.
+    fn export(&self, record: &UsageRecord);
+}
+
+impl<F: Fn(&UsageRecord) + Send + Sync> UsageExporter for F {
+    fn export(&self, record: &UsageRecord) {
+        self(record)
+    }
+}
+
+/// Exporter appending each record as a line of JSON to a file.
+#[derive(Debug)]
+pub struct JsonlExporter {
+    file: Mutex<File>,
+}
+
+impl JsonlExporter {
+    /// Opens a file for appending, creating it if needed.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, LLMError> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                LLMError::InvalidRequest(format!(
+                    "Failed to open usage log {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl UsageExporter for JsonlExporter {
+    fn export(&self, record: &UsageRecord) {
+        let mut line = match serde_json::to_vec(record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to serialize usage record");
+                return;
+            }
+        };
+        line.push(b'\n');
+        if let Err(e) = self.file.lock().unwrap().write_all(&line) {
+            tracing::warn!(error = %e, "failed to write usage record");
+        }
+    }
+}
+
+/// Shared tracker accumulating token usage per provider and model.
+///
+/// Clones share the same counts, so one tracker can be attached to several providers and
+/// read from anywhere. Trackers with an exporter also hand it a record of every request.
+#[derive(Clone, Default)]
+pub struct UsageTracker {
+    totals: Arc<Mutex<BTreeMap<UsageKey, UsageTotals>>>,
+    exporter: Option<Arc<dyn UsageExporter>>,
+}
+
+impl fmt::Debug for UsageTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageTracker")
+            .field("totals", &self.totals)
+            .field("exporter", &self.exporter.is_some())
+            .finish()
+    }
+}
+
+impl UsageTracker {
+    /// Creates a tracker with no recorded usage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hands a record of every request made through the tracked providers to an exporter.
+    ///
+    /// ```no_run
+    /// use llm::usage::{JsonlExporter, UsageTracker};
+    ///
+    /// let tracker = UsageTracker::new().exporter(JsonlExporter::new("usage.jsonl").unwrap());
+    /// let metered = UsageTracker::new().exporter(|record: &llm::usage::UsageRecord| {
+    ///     println!("{} tokens in {} ms", record.total_tokens, record.latency_ms);
+    /// });
+    /// ```
+    pub fn exporter(mut self, exporter: impl UsageExporter + 'static) -> Self {
+        self.exporter = Some(Arc::new(exporter));
+        self
+    }
+
+    /// Hands the record of a finished request to the exporter, if there is one.
+    pub(crate) fn export(&self, record: &UsageRecord) {
+        if let Some(exporter) = &self.exporter {
+            exporter.export(record);
+        }
+    }
+
+    /// Adds the usage of one request.
+    pub fn record(&self, provider: &str, model: &str, usage: &Usage) {
+        let key = UsageKey {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        };
+        self.totals
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .add(usage);
+    }
+
+    /// Returns the usage recorded so far, per provider and model.
+    pub fn snapshot(&self) -> BTreeMap<UsageKey, UsageTotals> {
+        self.totals.lock().unwrap().clone()
+    }
+
+    /// Returns the usage recorded so far across every provider and model.
+    pub fn total(&self) -> UsageTotals {
+        let mut total = UsageTotals::default();
+        for totals in self.totals.lock().unwrap().values() {
+            total.merge(totals);
+        }
+        total
+    }
+
+    /// Clears the recorded usage.
+    pub fn reset(&self) {
+        self.totals.lock().unwrap().clear();
+    }
+}
+
+/// A wrapper around an LLM provider recording the usage of each chat request in a
+/// [`UsageTracker`].
+///
+/// Usage is keyed by the model reported in the response, falling back to the configured
+/// model. Streams are recorded when their final chunk reports usage. Failed requests are
+/// handed to the tracker's exporter, with no tokens.
+pub struct TrackedLLM {
+    /// The wrapped LLM provider
+    inner: Box<dyn LLMProvider>,
+    /// Tracker receiving the usage
+    tracker: UsageTracker,
+    /// Name of the wrapped provider
+    provider: String,
+    /// Model the provider was configured with
+    model: String,
+}
+
+impl TrackedLLM {
+    /// Wraps a provider, recording its usage in a tracker.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The LLM provider to track
+    /// * `tracker` - Tracker receiving the usage
+    /// * `provider` - Name the usage is recorded under (e.g. "openai")
+    /// * `model` - Model the provider was configured with, used when responses don't
+    ///   report one
+    pub fn new(
+        inner: Box<dyn LLMProvider>,
+        tracker: UsageTracker,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            tracker,
+            provider: provider.into(),
+            model: model.into(),
+        }
+    }
+
+    /// Builds the record of a request sent at `started_at`.
+    fn request_record(
+        &self,
+        started_at: SystemTime,
+        start: Instant,
+        model: Option<String>,
+        usage: Option<&Usage>,
+        error: Option<&LLMError>,
+    ) -> UsageRecord {
+        let usage = usage.cloned().unwrap_or_default();
+        UsageRecord {
+            timestamp_ms: started_at
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_millis() as u64),
+            provider: self.provider.clone(),
+            model: model.unwrap_or_else(|| self.model.clone()),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            latency_ms: start.elapsed().as_millis() as u64,
+            outcome: match error {
+                Some(_) => UsageOutcome::Error,
+                None => UsageOutcome::Success,
+            },
+            error: error.map(ToString::to_string),
+        }
+    }
+
+    /// Records the outcome of a chat request.
+    async fn tracked<F>(&self, request: F) -> Result<Box<dyn ChatResponse>, LLMError>
+    where
+        F: Future<Output = Result<Box<dyn ChatResponse>, LLMError>> + Send,
+    {
+        let started_at = SystemTime::now();
+        let start = Instant::now();
+        let result = request.await;
+        let record = match &result {
+            Ok(response) => {
+                let model = response.metadata().model;
+                let usage = response.usage();
+                if let Some(usage) = &usage {
+                    let model = model.as_deref().unwrap_or(&self.model);
+                    self.tracker.record(&self.provider, model, usage);
+                }
+                self.request_record(started_at, start, model, usage.as_ref(), None)
+            }
+            Err(e) => self.request_record(started_at, start, None, None, Some(e)),
+        };
+        self.tracker.export(&record);
+        result
+    }
+}
+
+#[async_trait]
+impl LLMProvider for TrackedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
+
+#[async_trait]
+impl ChatProvider for TrackedLLM {
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.tracked(self.inner.chat(messages)).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.tracked(self.inner.chat_with_tools(messages, tools))
+            .await
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.tracked(self.inner.chat_with_options(messages, tools, options))
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+    ) -> Result<ChatStream, LLMError> {
+        self.chat_stream_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
+    ) -> Result<ChatStream, LLMError> {
+        let started_at = SystemTime::now();
+        let start = Instant::now();
+        let stream = match self
+            .inner
+            .chat_stream_with_options(messages, tools, options)
+            .await
+        {
+            Ok(stream) => stream,
+            Err(e) => {
+                let record = self.request_record(started_at, start, None, None, Some(&e));
+                self.tracker.export(&record);
+                return Err(e);
+            }
+        };
+        let tracker = self.tracker.clone();
+        let record = self.request_record(started_at, start, None, None, None);
+        Ok(Box::pin(stream.inspect(move |chunk| {
+            if let Ok(StreamChunk::Done {
+                usage: Some(usage), ..
+            }) = chunk
+            {
+                tracker.record(&record.provider, &record.model, usage);
+                tracker.export(&UsageRecord {
+                    prompt_tokens: usage.prompt_tokens,
+                    completion_tokens: usage.completion_tokens,
+                    total_tokens: usage.total_tokens,
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    ..record.clone()
+                });
+            }
+        })))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for TrackedLLM {
+    async fn complete(&self, req: &CompletionRequest) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req).await
+    }
+
+    async fn complete_stream(&self, req: &CompletionRequest) -> Result<CompletionStream, LLMError> {
+        self.inner.complete_stream(req).await
+    }
+
+    async fn complete_with_options(
+        &self,
+        req: &CompletionRequest,
+        options: &ChatOptions,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete_with_options(req, options).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for TrackedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
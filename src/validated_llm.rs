@@ -25,10 +25,13 @@
 
 use async_trait::async_trait;
 
-use crate::chat::{ChatMessage, ChatProvider, ChatResponse, ChatRole, MessageType, Tool};
+use crate::chat::{
+    ChatMessage, ChatOptions, ChatProvider, ChatResponse, ChatRole, MessageType, Tool,
+};
 use crate::completion::{CompletionProvider, CompletionRequest, CompletionResponse};
 use crate::embedding::EmbeddingProvider;
 use crate::error::LLMError;
+use crate::health::HealthStatus;
 use crate::{builder::ValidatorFn, LLMProvider};
 
 /// A wrapper around an LLM provider that validates responses before returning them.
@@ -71,7 +74,16 @@ impl ValidatedLLM {
     }
 }
 
-impl LLMProvider for ValidatedLLM {}
+#[async_trait]
+impl LLMProvider for ValidatedLLM {
+    fn tools(&self) -> Option<&[Tool]> {
+        self.inner.tools()
+    }
+
+    async fn validate(&self) -> HealthStatus {
+        self.inner.validate().await
+    }
+}
 
 #[async_trait]
 impl ChatProvider for ValidatedLLM {
@@ -79,6 +91,7 @@ impl ChatProvider for ValidatedLLM {
     ///
     /// If validation fails, retries with feedback to the model about the validation error.
     /// The feedback is appended as a new user message to help guide the model.
+    /// Responses requesting tool calls are not final answers and are returned unvalidated.
     ///
     /// # Arguments
     ///
@@ -92,16 +105,36 @@ impl ChatProvider for ValidatedLLM {
         &self,
         messages: &[ChatMessage],
         tools: Option<&[Tool]>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.chat_with_options(messages, tools, &ChatOptions::default())
+            .await
+    }
+
+    /// Sends a chat request with per-request options and validates the response, retrying
+    /// like [`ValidatedLLM::chat_with_tools`]. The options apply to every attempt.
+    async fn chat_with_options(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[Tool]>,
+        options: &ChatOptions,
     ) -> Result<Box<dyn ChatResponse>, LLMError> {
         let mut local_messages = messages.to_vec();
         let mut remaining_attempts = self.attempts;
 
         loop {
-            let response = match self.inner.chat_with_tools(&local_messages, tools).await {
+            let response = match self
+                .inner
+                .chat_with_options(&local_messages, tools, options)
+                .await
+            {
                 Ok(resp) => resp,
                 Err(e) => return Err(e),
             };
 
+            if response.tool_calls().is_some_and(|calls| !calls.is_empty()) {
+                return Ok(response);
+            }
+
             match (self.validator)(&response.text().unwrap_or_default()) {
                 Ok(()) => {
                     return Ok(response);
@@ -123,6 +156,7 @@ impl ChatProvider for ValidatedLLM {
                              Please try again and produce a valid response.",
                             err
                         ),
+                        metadata: Default::default(),
                     });
                 }
             }
@@ -0,0 +1,119 @@
+//! Chat requests go through the providers the builder wraps around a backend (tracing,
+//! metrics, hooks, truncation...) and reach the backend's own chat implementation.
+//!
+//! The backends are pointed at a closed port, so every request fails with an error; a
+//! wrapper falling back to an unimplemented backend method panics instead.
+
+use llm::{
+    builder::{LLMBackend, LLMBuilder},
+    chat::ChatMessage,
+    replay::ReplayMode,
+    truncating_llm::Truncation,
+};
+
+/// Address nothing listens on.
+const UNREACHABLE: &str = "http://127.0.0.1:9";
+
+/// Every enabled backend with a network API.
+const BACKENDS: &[LLMBackend] = &[
+    #[cfg(feature = "openai")]
+    LLMBackend::OpenAI,
+    #[cfg(feature = "anthropic")]
+    LLMBackend::Anthropic,
+    #[cfg(feature = "ollama")]
+    LLMBackend::Ollama,
+    #[cfg(feature = "deepseek")]
+    LLMBackend::DeepSeek,
+    #[cfg(feature = "xai")]
+    LLMBackend::XAI,
+    #[cfg(feature = "phind")]
+    LLMBackend::Phind,
+    #[cfg(feature = "google")]
+    LLMBackend::Google,
+    #[cfg(feature = "groq")]
+    LLMBackend::Groq,
+    #[cfg(feature = "azure")]
+    LLMBackend::AzureOpenAI,
+    #[cfg(feature = "mistral")]
+    LLMBackend::Mistral,
+    #[cfg(feature = "cohere")]
+    LLMBackend::Cohere,
+    #[cfg(feature = "fireworks")]
+    LLMBackend::Fireworks,
+    #[cfg(feature = "perplexity")]
+    LLMBackend::Perplexity,
+    #[cfg(feature = "nvidia")]
+    LLMBackend::Nvidia,
+    #[cfg(feature = "openai-compatible")]
+    LLMBackend::OpenAICompatible,
+    #[cfg(feature = "lmstudio")]
+    LLMBackend::LMStudio,
+    #[cfg(feature = "qwen")]
+    LLMBackend::Qwen,
+    #[cfg(feature = "moonshot")]
+    LLMBackend::Moonshot,
+    #[cfg(feature = "voyage")]
+    LLMBackend::Voyage,
+    #[cfg(feature = "jina")]
+    LLMBackend::Jina,
+];
+
+/// Returns a builder for the backend, sending its requests to [`UNREACHABLE`].
+fn builder(backend: &LLMBackend) -> LLMBuilder {
+    LLMBuilder::new()
+        .backend(backend.clone())
+        .api_key("key")
+        .model("model")
+        .base_url(UNREACHABLE)
+}
+
+/// Builds a provider for every backend with `configure`, and checks that its chat
+/// requests fail without panicking.
+async fn assert_chat_fails(configure: impl Fn(LLMBuilder) -> LLMBuilder) {
+    let messages = vec![ChatMessage::user().content("Hello").build()];
+    for backend in BACKENDS {
+        let llm = configure(builder(backend))
+            .build()
+            .unwrap_or_else(|e| panic!("building {} failed: {}", backend, e));
+        assert!(
+            llm.chat(&messages).await.is_err(),
+            "{} chat reached a server",
+            backend
+        );
+    }
+}
+
+/// With the otel feature, every provider is wrapped in a tracing provider.
+#[tokio::test]
+async fn chat_reaches_every_backend() {
+    assert_chat_fails(|builder| builder).await;
+}
+
+#[tokio::test]
+async fn hooked_chat_reaches_every_backend() {
+    assert_chat_fails(|builder| builder.on_request(|_| {})).await;
+}
+
+#[tokio::test]
+async fn truncating_chat_reaches_every_backend() {
+    assert_chat_fails(|builder| {
+        builder
+            .truncation(Truncation::DropOldest)
+            .context_length(8000)
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn recorded_chat_reaches_every_backend() {
+    let path = std::env::temp_dir().join(format!("llm-wrapped-chat-{}.json", std::process::id()));
+    assert_chat_fails(|builder| builder.replay(&path, ReplayMode::Record)).await;
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn measured_chat_reaches_every_backend() {
+    let metrics = llm::metrics::Metrics::new();
+    assert_chat_fails(|builder| builder.metrics(metrics.clone())).await;
+}